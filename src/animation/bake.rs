@@ -0,0 +1,108 @@
+use crate::animation::project::AnimProject;
+use crate::core::animation::mixer::Pose;
+use crate::core::animation::skeleton::Skeleton;
+use crate::render::resample::ResampleMode;
+
+// 烘焙结果写入方式：每帧一个新图层，或拼成一张精灵表导出为 PNG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BakeTarget {
+    Layers,
+    SpriteSheet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BakeSettings {
+    pub fps: u32,
+    pub resample: ResampleMode,
+    pub target: BakeTarget,
+    // 精灵表每行的帧数，0 为自动 (接近正方形)
+    pub columns: u32,
+}
+
+impl Default for BakeSettings {
+    fn default() -> Self {
+        Self { fps: 12, resample: ResampleMode::RotSprite, target: BakeTarget::Layers, columns: 0 }
+    }
+}
+
+// 循环动画的最后一帧与第一帧相同，不重复烘焙；非循环动画额外取末尾姿势
+pub fn frame_times(duration: f32, fps: u32, looping: bool) -> Vec<f32> {
+    let fps = fps.max(1) as f32;
+    let count = ((duration * fps).round() as u32).max(1);
+    let mut times: Vec<f32> = (0..count).map(|i| i as f32 / fps).collect();
+    if !looping && duration > 0.0 {
+        times.push(duration);
+    }
+    times
+}
+
+// 逐帧采样骨架姿势；每帧从装配姿态开始，结果与当前编辑器中的姿态无关
+pub fn sample_frames(project: &AnimProject, anim_id: &str, fps: u32, looping: bool) -> Vec<Skeleton> {
+    let anim = match project.animations.get(anim_id) {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+    frame_times(anim.duration, fps, looping).into_iter().map(|t| {
+        let mut skeleton = project.skeleton.clone();
+        Pose::setup(&skeleton).apply_to(&mut skeleton);
+        anim.apply_at(&mut skeleton, t);
+        skeleton.update();
+        skeleton
+    }).collect()
+}
+
+// 把等大的帧按行排成精灵表，返回 (像素, 宽, 高)
+pub fn sprite_sheet(frames: &[Vec<u8>], frame_w: u32, frame_h: u32, columns: u32) -> (Vec<u8>, u32, u32) {
+    let count = frames.len() as u32;
+    if count == 0 { return (Vec::new(), 0, 0); }
+    let columns = if columns == 0 { (count as f32).sqrt().ceil() as u32 } else { columns.min(count) };
+    let rows = count.div_ceil(columns);
+    let (sheet_w, sheet_h) = (frame_w * columns, frame_h * rows);
+    let mut sheet = vec![0u8; (sheet_w * sheet_h * 4) as usize];
+    let row_bytes = (frame_w * 4) as usize;
+    for (i, frame) in frames.iter().enumerate() {
+        let (cx, cy) = (i as u32 % columns, i as u32 / columns);
+        for y in 0..frame_h {
+            let src = (y * frame_w * 4) as usize;
+            let dst = (((cy * frame_h + y) * sheet_w + cx * frame_w) * 4) as usize;
+            sheet[dst..dst + row_bytes].copy_from_slice(&frame[src..src + row_bytes]);
+        }
+    }
+    (sheet, sheet_w, sheet_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_times() {
+        assert_eq!(frame_times(1.0, 4, true), vec![0.0, 0.25, 0.5, 0.75]);
+        let times = frame_times(1.0, 4, false);
+        assert_eq!(times.len(), 5);
+        assert_eq!(times[4], 1.0, "非循环动画包含末尾姿势");
+        assert_eq!(frame_times(0.0, 12, true), vec![0.0], "空动画至少一帧");
+    }
+
+    #[test]
+    fn test_sample_frames_start_from_setup_pose() {
+        use crate::core::animation::bone::BoneData;
+        use crate::core::animation::timeline::{Animation, CurveType, KeyframeValue, Timeline, TimelineProperty};
+
+        let mut project = AnimProject::new();
+        project.skeleton.add_bone(BoneData::new("arm".into(), "arm".into()));
+        let mut anim = Animation::new("swing".into(), 1.0);
+        let mut tl = Timeline::new("root".into(), TimelineProperty::Rotation);
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(0.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(90.0), CurveType::Linear);
+        anim.timelines.push(tl);
+        project.animations.insert("swing".into(), anim);
+        // 编辑器中残留的、动画未记录的姿态
+        project.skeleton.bones[1].local_transform.rotation = 45.0;
+
+        let frames = sample_frames(&project, "swing", 2, false);
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|s| s.bones[1].local_transform.rotation == 0.0), "未记录的骨骼使用装配姿态");
+        assert_eq!(frames[2].bones[0].local_transform.rotation, 90.0, "末帧取 duration 处的姿势");
+    }
+}
@@ -1,8 +1,36 @@
 use crate::animation::state::AnimationState;
+use crate::core::animation::mixer::AnimationMixer;
 use std::time::Duration;
 
 pub struct AnimationController;
 
+const MAX_FIRED_EVENTS: usize = 16;
+
+// 过渡预览：start_time 之前播放 from，之后在 duration 内交叉淡入到 to，可随 current_time 拖动
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionPreview {
+    pub from_id: String,
+    pub to_id: String,
+    pub start_time: f32,
+    pub duration: f32,
+}
+
+impl TransitionPreview {
+    pub fn mixer_at(&self, time: f32) -> AnimationMixer {
+        let mut mixer = AnimationMixer::new();
+        mixer.set_animation(0, &self.from_id, true).time = time;
+
+        let elapsed = time - self.start_time;
+        if elapsed >= 0.0 {
+            let track = mixer.crossfade(0, &self.to_id, self.duration, true);
+            track.time = elapsed;
+            track.mix_time = elapsed;
+            if elapsed >= self.duration { track.mix_from = None; }
+        }
+        mixer
+    }
+}
+
 impl AnimationController {
     pub fn update(state: &mut AnimationState, delta: Duration) {
         if !state.is_playing {
@@ -10,12 +38,57 @@ impl AnimationController {
         }
 
         let dt = delta.as_secs_f32() * state.playback_speed;
+        let previous_time = state.current_time;
         state.current_time += dt;
 
+        Self::fire_events(state, previous_time, state.current_time);
+        Self::apply_current_pose(state);
+    }
+
+    // 触发当前动画在 [from, to) 内经过的事件，循环播放时处理回绕
+    pub fn fire_events(state: &mut AnimationState, from: f32, to: f32) {
+        let fired = match state.project.active_animation_id.as_ref().and_then(|id| state.project.animations.get(id)) {
+            Some(anim) => crate::core::animation::event::events_in_range(&anim.events, anim.duration, from, to, state.is_looping),
+            None => return,
+        };
+        for event in fired {
+            for listener in state.event_listeners.iter_mut() {
+                listener(&event);
+            }
+            state.fired_events.push(event);
+        }
+        let excess = state.fired_events.len().saturating_sub(MAX_FIRED_EVENTS);
+        state.fired_events.drain(..excess);
+    }
+
+    pub fn preview_transition(state: &mut AnimationState, to_id: &str, duration: f32) -> bool {
+        let from_id = match &state.project.active_animation_id {
+            Some(id) if state.project.animations.contains_key(id) && state.project.animations.contains_key(to_id) => id.clone(),
+            _ => return false,
+        };
+        state.transition_preview = Some(TransitionPreview {
+            from_id,
+            to_id: to_id.to_string(),
+            start_time: state.current_time,
+            duration: duration.max(0.0),
+        });
         Self::apply_current_pose(state);
+        true
+    }
+
+    pub fn stop_preview(state: &mut AnimationState) {
+        if state.transition_preview.take().is_some() {
+            Self::apply_current_pose(state);
+        }
     }
 
     pub fn apply_current_pose(state: &mut AnimationState) {
+        if let Some(preview) = &state.transition_preview {
+            preview.mixer_at(state.current_time).apply(&state.project.animations, &mut state.project.skeleton);
+            state.project.skeleton.update();
+            return;
+        }
+
         let project = &mut state.project;
         let active_anim_id = match &project.active_animation_id {
             Some(id) => id,
@@ -58,4 +131,34 @@ mod tests {
         let rot = state.project.skeleton.bones[0].local_transform.rotation;
         assert!((rot - 50.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_transition_preview_follows_time() {
+        let mut state = AnimationState::new();
+        for (id, value) in [("idle", 0.0), ("run", 90.0)] {
+            let mut anim = Animation::new(id.into(), 1.0);
+            let mut tl = Timeline::new("root".into(), TimelineProperty::Rotation);
+            tl.add_keyframe(0.0, KeyframeValue::Rotate(value), CurveType::Linear);
+            tl.add_keyframe(1.0, KeyframeValue::Rotate(value), CurveType::Linear);
+            anim.timelines.push(tl);
+            state.project.animations.insert(id.into(), anim);
+        }
+        state.project.active_animation_id = Some("idle".into());
+        state.current_time = 0.5;
+
+        assert!(!AnimationController::preview_transition(&mut state, "missing", 0.5));
+        assert!(AnimationController::preview_transition(&mut state, "run", 0.5));
+
+        state.is_playing = true;
+        AnimationController::update(&mut state, Duration::from_millis(250));
+        let rot = state.project.skeleton.bones[0].local_transform.rotation;
+        assert!((rot - 45.0).abs() < 0.01, "过渡进行一半时应为两个动画的平均姿态: {}", rot);
+
+        AnimationController::update(&mut state, Duration::from_millis(500));
+        assert!((state.project.skeleton.bones[0].local_transform.rotation - 90.0).abs() < 0.01);
+
+        AnimationController::stop_preview(&mut state);
+        assert!(state.transition_preview.is_none());
+        assert!(state.project.skeleton.bones[0].local_transform.rotation.abs() < 0.01, "停止预览后恢复当前动画");
+    }
 }
\ No newline at end of file
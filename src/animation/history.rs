@@ -10,17 +10,32 @@ pub enum AnimPatch {
         new: Option<crate::core::animation::timeline::Timeline> 
     },
     Skeleton { 
-        old: crate::core::animation::skeleton::Skeleton, 
-        new: crate::core::animation::skeleton::Skeleton 
+        old: Box<crate::core::animation::skeleton::Skeleton>, 
+        new: Box<crate::core::animation::skeleton::Skeleton> 
     },
     SlotBone {
         slot_id: String,
         old_bone: String,
         new_bone: String,
     },
+    FrameRate(Box<FrameRateChange>),
+    Events {
+        anim_id: String,
+        old: Vec<crate::core::animation::event::AnimationEvent>,
+        new: Vec<crate::core::animation::event::AnimationEvent>,
+    },
     Composite(Vec<AnimPatch>),
 }
 
+// 修改帧率时的整体快照 (重新定时会改动所有动画)
+#[derive(Clone)]
+pub struct FrameRateChange {
+    pub old_fps: u32,
+    pub new_fps: u32,
+    pub old_anims: std::collections::HashMap<String, crate::core::animation::timeline::Animation>,
+    pub new_anims: std::collections::HashMap<String, crate::core::animation::timeline::Animation>,
+}
+
 pub struct AnimHistory {
     pub undo_stack: Vec<AnimPatch>,
     pub redo_stack: Vec<AnimPatch>,
@@ -32,6 +47,17 @@ impl AnimHistory {
         self.undo_stack.push(patch);
         self.redo_stack.clear();
     }
+    // 连续拖动同一条轨道时合并为一步撤销：保留最早的 old，只更新 new
+    pub fn commit_merged(&mut self, patch: AnimPatch) {
+        if let (Some(AnimPatch::Timeline { anim_id, bone_id, prop, new, .. }), AnimPatch::Timeline { anim_id: a2, bone_id: b2, prop: p2, new: n2, .. }) = (self.undo_stack.last_mut(), &patch) {
+            if anim_id == a2 && bone_id == b2 && prop == p2 {
+                *new = n2.clone();
+                self.redo_stack.clear();
+                return;
+            }
+        }
+        self.commit(patch);
+    }
     pub fn undo(&mut self, project: &mut AnimProject) -> bool {
         if let Some(patch) = self.undo_stack.pop() {
             self.apply_patch(project, &patch, true);
@@ -63,12 +89,22 @@ impl AnimHistory {
                     anim.recalculate_duration();
                 }
             }
-            AnimPatch::Skeleton { old, new } => { project.skeleton = if is_undo { old.clone() } else { new.clone() }; }
+            AnimPatch::Skeleton { old, new } => { project.skeleton = if is_undo { (**old).clone() } else { (**new).clone() }; }
             AnimPatch::SlotBone { slot_id, old_bone, new_bone } => {
                 if let Some(slot) = project.skeleton.slots.iter_mut().find(|s| s.data.id == *slot_id) {
                     slot.data.bone_id = if is_undo { old_bone.clone() } else { new_bone.clone() };
                 }
             }
+            AnimPatch::FrameRate(change) => {
+                project.fps = if is_undo { change.old_fps } else { change.new_fps };
+                project.animations = if is_undo { change.old_anims.clone() } else { change.new_anims.clone() };
+            }
+            AnimPatch::Events { anim_id, old, new } => {
+                if let Some(anim) = project.animations.get_mut(anim_id) {
+                    anim.events = if is_undo { old.clone() } else { new.clone() };
+                    anim.recalculate_duration();
+                }
+            }
             AnimPatch::Composite(patches) => {
                 let iter: Box<dyn Iterator<Item = &AnimPatch>> = if is_undo { Box::new(patches.iter().rev()) } else { Box::new(patches.iter()) };
                 for p in iter { self.apply_patch(project, p, is_undo); }
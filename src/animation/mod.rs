@@ -1,4 +1,6 @@
 pub mod project;
 pub mod controller;
 pub mod state;
-pub mod history;
\ No newline at end of file
+pub mod history;
+pub mod onion_skin;
+pub mod bake;
\ No newline at end of file
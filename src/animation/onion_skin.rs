@@ -0,0 +1,203 @@
+use crate::animation::state::AnimationState;
+use crate::core::animation::mesh::DeformedMesh;
+use crate::core::animation::skeleton::Skeleton;
+use crate::core::blend_mode::BlendMode;
+use crate::core::layer_effect::LayerEffect;
+use crate::core::store::PixelStore;
+use std::collections::HashMap;
+
+// 前后残影帧数与帧间隔的上限，界面与读取文件时都按此限制
+pub const MAX_ONION_FRAMES: u32 = 10;
+pub const MAX_ONION_STEP: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnionSkinSettings {
+    pub enabled: bool,
+    pub frames_before: u32,
+    pub frames_after: u32,
+    pub step: u32,
+    pub tint_before: [u8; 3],
+    pub tint_after: [u8; 3],
+    pub opacity: u8,
+}
+
+impl Default for OnionSkinSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frames_before: 2,
+            frames_after: 2,
+            step: 1,
+            tint_before: [255, 70, 70],
+            tint_after: [70, 200, 90],
+            opacity: 128,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OnionGhost {
+    pub time: f32,
+    pub skeleton: Skeleton,
+    pub tint: [u8; 3],
+    pub opacity: u8,
+}
+
+impl OnionSkinSettings {
+    // 距离当前帧越远越淡：最近一帧为 opacity，最远一帧为 opacity / n
+    pub fn ghost_opacity(&self, distance: u32, count: u32) -> u8 {
+        if count == 0 || distance == 0 || distance > count { return 0; }
+        (self.opacity as u32 * (count + 1 - distance) / count) as u8
+    }
+}
+
+// 残影的图层姿态：(变换矩阵, 偏移, 网格)
+pub type GhostPose = (HashMap<String, [f32; 6]>, HashMap<String, (i32, i32)>, HashMap<String, DeformedMesh>);
+
+// onion_cache 对应的全部输入；时间、设置与骨架的变化都体现在各残影的姿态里，相同则无需重新合成
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnionCacheKey {
+    canvas: (u32, u32),
+    layers: Vec<LayerKey>,
+    ghosts: Vec<GhostKey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LayerKey {
+    id: String,
+    version: u64,
+    visible: bool,
+    offset: (i32, i32),
+    blend_mode: BlendMode,
+    opacity: u8,
+    effects: Vec<LayerEffect>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GhostKey {
+    pose: GhostPose,
+    effect_amounts: HashMap<String, f32>,
+    tint: [u8; 3],
+    opacity: u8,
+}
+
+impl OnionCacheKey {
+    pub fn new(store: &PixelStore, ghosts: &[OnionGhost], poses: &[GhostPose]) -> Self {
+        Self {
+            canvas: (store.canvas_width, store.canvas_height),
+            layers: store.layers.iter().map(|l| LayerKey {
+                id: l.id.clone(),
+                version: l.version,
+                visible: l.visible,
+                offset: (l.offset_x, l.offset_y),
+                blend_mode: l.blend_mode,
+                opacity: l.opacity,
+                effects: l.effects.clone(),
+            }).collect(),
+            ghosts: ghosts.iter().zip(poses).map(|(g, pose)| GhostKey {
+                pose: pose.clone(),
+                effect_amounts: g.skeleton.effect_amounts.clone(),
+                tint: g.tint,
+                opacity: g.opacity,
+            }).collect(),
+        }
+    }
+}
+
+pub fn sample_ghosts(state: &AnimationState) -> Vec<OnionGhost> {
+    let settings = &state.project.onion_skin;
+    if !settings.enabled || (settings.frames_before == 0 && settings.frames_after == 0) {
+        return Vec::new();
+    }
+    let anim = match state.project.active_animation_id.as_ref().and_then(|id| state.project.animations.get(id)) {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+
+    let frame_time = settings.step.max(1) as f32 * state.project.frame_duration();
+    let mut ghosts = Vec::new();
+
+    // 远的先画，近的后画，保证近帧叠在上层
+    let mut offsets: Vec<(i32, [u8; 3], u8)> = Vec::new();
+    for i in (1..=settings.frames_before).rev() {
+        offsets.push((-(i as i32), settings.tint_before, settings.ghost_opacity(i, settings.frames_before)));
+    }
+    for i in (1..=settings.frames_after).rev() {
+        offsets.push((i as i32, settings.tint_after, settings.ghost_opacity(i, settings.frames_after)));
+    }
+
+    for (offset, tint, opacity) in offsets {
+        let mut t = state.current_time + offset as f32 * frame_time;
+        if state.is_looping && anim.duration > 0.0 {
+            t = t.rem_euclid(anim.duration);
+        } else if t < 0.0 || t > anim.duration {
+            continue;
+        }
+
+        let mut skeleton = state.project.skeleton.clone();
+        anim.apply(&mut skeleton, t);
+        skeleton.update();
+        ghosts.push(OnionGhost { time: t, skeleton, tint, opacity });
+    }
+    ghosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::animation::timeline::{Animation, Timeline, TimelineProperty, KeyframeValue, CurveType};
+
+    fn setup_state() -> AnimationState {
+        let mut state = AnimationState::new();
+        let mut anim = Animation::new("walk".into(), 1.0);
+        let mut tl = Timeline::new("root".into(), TimelineProperty::Rotation);
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(0.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(30.0), CurveType::Linear);
+        anim.timelines.push(tl);
+        state.project.animations.insert("walk".into(), anim);
+        state.project.active_animation_id = Some("walk".into());
+        state
+    }
+
+    #[test]
+    fn test_ghosts_disabled_by_default() {
+        let state = setup_state();
+        assert!(sample_ghosts(&state).is_empty());
+    }
+
+    #[test]
+    fn test_ghosts_sample_offset_times() {
+        let mut state = setup_state();
+        state.project.onion_skin.enabled = true;
+        state.project.onion_skin.step = 3;
+        state.current_time = 0.5;
+
+        let ghosts = sample_ghosts(&state);
+        assert_eq!(ghosts.len(), 4);
+        let times: Vec<f32> = ghosts.iter().map(|g| g.time).collect();
+        let expected = [0.3, 0.4, 0.7, 0.6];
+        for (t, e) in times.iter().zip(expected.iter()) {
+            assert!((t - e).abs() < 1e-4, "ghost time {} != {}", t, e);
+        }
+        let rot = ghosts[0].skeleton.bones[0].local_transform.rotation;
+        assert!((rot - 9.0).abs() < 1e-3);
+        assert_eq!(ghosts[0].tint, state.project.onion_skin.tint_before);
+        assert!(ghosts[0].opacity < ghosts[1].opacity, "远端帧应更淡");
+    }
+
+    #[test]
+    fn test_ghosts_respect_loop_flag() {
+        let mut state = setup_state();
+        state.project.onion_skin.enabled = true;
+        state.project.onion_skin.frames_after = 0;
+        state.current_time = 0.0;
+
+        state.is_looping = true;
+        let ghosts = sample_ghosts(&state);
+        assert_eq!(ghosts.len(), 2);
+        assert!(ghosts.iter().all(|g| g.time > 0.9));
+
+        state.is_looping = false;
+        assert!(sample_ghosts(&state).is_empty());
+    }
+}
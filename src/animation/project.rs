@@ -1,12 +1,20 @@
 use crate::core::animation::skeleton::Skeleton;
 use crate::core::animation::timeline::Animation;
+use crate::animation::onion_skin::OnionSkinSettings;
 use std::collections::HashMap;
 
+pub const DEFAULT_FPS: u32 = 30;
+pub const FPS_PRESETS: [u32; 5] = [8, 12, 24, 30, 60];
+pub const MAX_FPS: u32 = 240;
+
 #[derive(Debug)]
 pub struct AnimProject {
     pub skeleton: Skeleton,
     pub animations: HashMap<String, Animation>,
     pub active_animation_id: Option<String>,
+    pub onion_skin: OnionSkinSettings,
+    pub fps: u32,
+    pub snap_to_frames: bool,
 }
 
 impl Default for AnimProject {
@@ -17,6 +25,9 @@ impl Default for AnimProject {
             skeleton,
             animations: HashMap::new(),
             active_animation_id: None,
+            onion_skin: OnionSkinSettings::default(),
+            fps: DEFAULT_FPS,
+            snap_to_frames: true,
         }
     }
 }
@@ -25,4 +36,33 @@ impl AnimProject {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn fps_f32(&self) -> f32 {
+        self.fps.max(1) as f32
+    }
+
+    pub fn frame_duration(&self) -> f32 {
+        1.0 / self.fps_f32()
+    }
+
+    // 开启吸附时将时间对齐到最近的帧边界
+    pub fn snap_time(&self, time: f32) -> f32 {
+        if self.snap_to_frames { crate::core::animation::timeline::snap_to_frame(time, self.fps_f32()) } else { time }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_time_to_frame_grid() {
+        let mut project = AnimProject::new();
+        project.fps = 12;
+        assert!((project.snap_time(0.1) - 1.0 / 12.0).abs() < 1e-6);
+        assert!((project.snap_time(0.5) - 0.5).abs() < 1e-6);
+
+        project.snap_to_frames = false;
+        assert_eq!(project.snap_time(0.1), 0.1);
+    }
 }
\ No newline at end of file
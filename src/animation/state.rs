@@ -1,6 +1,8 @@
 use crate::animation::project::AnimProject;
 use crate::animation::history::AnimHistory;
 
+pub type EventListener = Box<dyn FnMut(&crate::core::animation::event::AnimationEvent)>;
+
 pub struct AnimationState {
     pub project: AnimProject,
     pub history: AnimHistory,
@@ -11,6 +13,17 @@ pub struct AnimationState {
     pub playback_speed: f32,
     pub create_bone_tool: crate::tools::create_bone::CreateBoneTool,
     pub is_looping: bool,
+    pub transition_preview: Option<crate::animation::controller::TransitionPreview>,
+    // 播放经过事件时依次调用 (游戏逻辑的脚步声、攻击判定等)
+    pub event_listeners: Vec<EventListener>,
+    // 最近触发的事件，供界面显示
+    pub fired_events: Vec<crate::core::animation::event::AnimationEvent>,
+    // 复制的关键帧或姿势，可粘贴到其它时间、骨骼或动画
+    pub keyframe_clipboard: Option<crate::core::animation::keyframe_edit::KeyframeClipboard>,
+    // 从其它工程导入动画时等待确认的骨骼映射
+    pub pending_import: Option<crate::core::animation::retarget::AnimationImport>,
+    // 当前 onion_cache 对应的输入，未变化时跳过合成
+    pub onion_cache_key: Option<crate::animation::onion_skin::OnionCacheKey>,
 }
 
 impl AnimationState {
@@ -25,11 +38,69 @@ impl AnimationState {
             playback_speed: 1.0,
             create_bone_tool: crate::tools::create_bone::CreateBoneTool::new(),
             is_looping: true,
+            transition_preview: None,
+            event_listeners: Vec::new(),
+            fired_events: Vec::new(),
+            keyframe_clipboard: None,
+            pending_import: None,
+            onion_cache_key: None,
         }
     }
     
+    // 在当前时间为任意目标写入关键帧 (IK、插槽显示图层、绘制顺序等非骨骼变换属性)
+    pub fn key_value(&mut self, target_id: &str, property: crate::core::animation::timeline::TimelineProperty, value: crate::core::animation::timeline::KeyframeValue) {
+        use crate::core::animation::timeline::{Timeline, TimelineProperty, CurveType};
+        let time = self.project.snap_time(self.current_time);
+        let active_id = match &self.project.active_animation_id {
+            Some(id) => id.clone(),
+            None => return,
+        };
+
+        if let Some(anim) = self.project.animations.get_mut(&active_id) {
+            let idx = anim.timelines.iter().position(|t| t.target_id == target_id && t.property == property)
+                .unwrap_or_else(|| {
+                    anim.timelines.push(Timeline::new(target_id.to_string(), property.clone()));
+                    anim.timelines.len() - 1
+                });
+            let curve = match property {
+                TimelineProperty::Attachment | TimelineProperty::DrawOrder => CurveType::Stepped,
+                _ => CurveType::Linear,
+            };
+            anim.timelines[idx].add_keyframe(time, value, curve);
+            anim.recalculate_duration();
+        }
+    }
+
+    pub fn auto_key_ik(&mut self, constraint_id: &str) {
+        use crate::core::animation::timeline::{TimelineProperty, KeyframeValue};
+        let (mix, bend) = match self.project.skeleton.ik_constraints.iter().find(|c| c.data.id == constraint_id) {
+            Some(c) => (c.mix, c.bend_positive),
+            None => return,
+        };
+        self.key_value(constraint_id, TimelineProperty::Ik, KeyframeValue::Ik(mix, bend));
+    }
+
+    // 以网格当前的顶点偏移写入变形关键帧，target 为网格所属图层 id
+    pub fn auto_key_deform(&mut self, layer_id: &str) {
+        use crate::core::animation::timeline::{TimelineProperty, KeyframeValue};
+        let offsets = match self.project.skeleton.mesh(layer_id) {
+            Some(mesh) => (0..mesh.vertices.len()).map(|i| mesh.deform_offset(i)).collect(),
+            None => return,
+        };
+        self.key_value(layer_id, TimelineProperty::Deform, KeyframeValue::Deform(offsets));
+    }
+
+    // 骨骼已有切变或当前动画已有切变轨道时，整体打帧才包含切变，避免为每根骨骼生成空切变轨道
+    pub fn bone_uses_shear(&self, bone_id: &str) -> bool {
+        let sheared = self.project.skeleton.bones.iter()
+            .any(|b| b.data.id == bone_id && (b.local_transform.shear_x != 0.0 || b.local_transform.shear_y != 0.0));
+        sheared || self.project.active_animation_id.as_ref()
+            .and_then(|id| self.project.animations.get(id))
+            .is_some_and(|a| a.timelines.iter().any(|t| t.target_id == bone_id && t.property == crate::core::animation::timeline::TimelineProperty::Shear))
+    }
+
     pub fn auto_key_bone(&mut self, bone_id: &str, property: crate::core::animation::timeline::TimelineProperty) {
-        let time = self.current_time;
+        let time = self.project.snap_time(self.current_time);
         let active_id = match &self.project.active_animation_id {
             Some(id) => id.clone(),
             None => return,
@@ -56,6 +127,8 @@ impl AnimationState {
                     timeline.add_keyframe(time, KeyframeValue::Translate(transform.x, transform.y), CurveType::Linear),
                 crate::core::animation::timeline::TimelineProperty::Scale => 
                     timeline.add_keyframe(time, KeyframeValue::Scale(transform.scale_x, transform.scale_y), CurveType::Linear),
+                crate::core::animation::timeline::TimelineProperty::Shear => 
+                    timeline.add_keyframe(time, KeyframeValue::Shear(transform.shear_x, transform.shear_y), CurveType::Linear),
                 _ => {}
             }
             anim.recalculate_duration();
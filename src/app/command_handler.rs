@@ -5,7 +5,9 @@ use crate::history::patch::ActionPatch;
 use crate::tools::pen::PenTool;
 use rust_i18n::t;
 use crate::app::state::AppMode;
-use crate::app::handlers::{anim_handler, layer_handler};
+use crate::app::handlers::{anim_handler, filter_handler, layer_handler, palette_handler};
+use crate::animation::history::AnimPatch;
+use crate::animation::project::AnimProject;
 
 pub struct CommandHandler;
 
@@ -14,20 +16,38 @@ impl CommandHandler {
         match cmd {
             AppCommand::ToggleLayerLock(_) | AppCommand::SetLayerOpacity(_, _) |
             AppCommand::SetLayerBlendMode(_, _) | AppCommand::MoveLayerUp(_) |
+            AppCommand::AddLayerEffect(_, _) | AppCommand::SetLayerEffects(_, _) |
             AppCommand::MoveLayerDown(_) | AppCommand::MoveLayerToIndex(_, _) |
             AppCommand::RenameLayer(_, _) | AppCommand::DuplicateLayer(_) |
-            AppCommand::MergeSelected(_) => {
+            AppCommand::MergeSelected(_) | AppCommand::TransformLayer(_, _) => {
                 layer_handler::execute(app_state, cmd);
                 return;
             }
+            AppCommand::AddColorToPalette(_) | AppCommand::RemovePaletteColor(_) | AppCommand::SetPalette(_) |
+            AppCommand::RenamePaletteColor(_, _) | AppCommand::AddPaletteRamp { .. } | AppCommand::AssignPaletteRamp { .. } |
+            AppCommand::RenamePaletteRamp(_, _) | AppCommand::RemovePaletteRamp(_) | AppCommand::SortPalette(_) |
+            AppCommand::DedupePalette | AppCommand::MergePalette(_) | AppCommand::CreatePaletteFromUsedColors |
+            AppCommand::QuantizeLayer { .. } | AppCommand::GeneratePaletteFromLayer { .. } => palette_handler::execute(app_state, cmd),
+            AppCommand::BeginFilter(_) | AppCommand::UpdateFilterPreview(_) | AppCommand::ApplyFilter | AppCommand::CancelFilter |
+            AppCommand::ApplyEffect { .. } => filter_handler::execute(app_state, cmd),
             AppCommand::CreateAnimation(_) | AppCommand::SelectAnimation(_) |
+            AppCommand::ConfirmAnimationImport | AppCommand::CancelAnimationImport | AppCommand::BakeAnimation(_) |
             AppCommand::DeleteKeyframe(_, _, _) | AppCommand::UpdateKeyframeCurve(_, _, _, _) |
-            AppCommand::MoveSelectedKeyframes(_) | AppCommand::BeginOffsetSnapshot |
+            AppCommand::MoveSelectedKeyframes(_) | AppCommand::SetKeyframeTangentMode { .. } |
+            AppCommand::SetKeyframeHandle { .. } | AppCommand::ScaleSelectedKeyframes { .. } |
+            AppCommand::CopyKeyframes | AppCommand::CopyPose | AppCommand::PasteKeyframes { .. } | AppCommand::ReverseSelectedKeyframes |
+            AppCommand::SaveCurvePreset { .. } | AppCommand::DeleteCurvePreset(_) | AppCommand::BeginOffsetSnapshot |
             AppCommand::CommitOffsetSnapshot | AppCommand::OffsetSelectedKeyframes(_) |
             AppCommand::ApplySpineOffset { .. } | AppCommand::InsertManualKeyframe(_) |
             AppCommand::TogglePlayback | AppCommand::StepFrame(_) | AppCommand::SetTime(_) |
             AppCommand::SetPlaybackSpeed(_) | AppCommand::ToggleLoop | AppCommand::ToggleTimelineFilter(_) |
-            AppCommand::BindLayerToBone(_, _) | AppCommand::DeleteBone(_) => {
+            AppCommand::SetOnionSkin(_) | AppCommand::SetFrameRate { .. } | AppCommand::ToggleFrameSnap |
+            AppCommand::AddIkConstraint { .. } | AppCommand::RemoveIkConstraint(_) | AppCommand::SetIkConstraint { .. } |
+            AppCommand::SetSlotAttachment { .. } | AppCommand::MoveSlotDrawOrder { .. } |
+            AppCommand::AddAnimationEvent(_) | AppCommand::UpdateAnimationEvent(_, _) | AppCommand::DeleteAnimationEvent(_) |
+            AppCommand::PreviewTransition(_, _) | AppCommand::StopTransitionPreview |
+            AppCommand::BindLayerToBone(_, _) | AppCommand::DeleteBone(_) | AppCommand::SetBoneTransformMode { .. } |
+            AppCommand::CreateMesh { .. } | AppCommand::DeleteMesh(_) | AppCommand::AutoWeightMesh(_) => {
                 anim_handler::execute(app_state, cmd);
                 return;
             }
@@ -41,11 +61,19 @@ impl CommandHandler {
             AppCommand::ConfirmExit => app_state.enqueue_command(AppCommand::WindowClose),
             AppCommand::CancelExit => app_state.ui.show_exit_modal = false,
             AppCommand::SaveProject => app_state.save_project_to_pxad(),
-            AppCommand::LoadProject => app_state.load_project_from_pxad(),
+            AppCommand::LoadProject => {
+                filter_handler::cancel_preview(app_state);
+                app_state.load_project_from_pxad();
+            }
+            AppCommand::ImportAnimations => app_state.import_animations_from_pxad(),
             AppCommand::ImportImage => app_state.import_image(),
-            AppCommand::ExportPng => app_state.export_to_png(),
+            AppCommand::ExportPng => {
+                filter_handler::cancel_preview(app_state);
+                app_state.export_to_png();
+            }
             
             AppCommand::Undo => {
+                filter_handler::cancel_preview(app_state);
                 if app_state.mode == AppMode::Animation {
                     if app_state.animation.history.undo(&mut app_state.animation.project) {
                         app_state.is_dirty = true; app_state.view.needs_full_redraw = true;
@@ -57,6 +85,7 @@ impl CommandHandler {
                 }
             }
             AppCommand::Redo => {
+                filter_handler::cancel_preview(app_state);
                 if app_state.mode == AppMode::Animation {
                     if app_state.animation.history.redo(&mut app_state.animation.project) {
                         app_state.is_dirty = true; app_state.view.needs_full_redraw = true;
@@ -67,21 +96,12 @@ impl CommandHandler {
                     else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
                 }
             }
-            AppCommand::AddColorToPalette(color) => {
-                app_state.engine.add_color_to_palette(color);
-                app_state.is_dirty = true;
-            }
-            AppCommand::RemovePaletteColor(idx) => {
-                app_state.engine.remove_palette_color(idx);
-                app_state.is_dirty = true;
-            }
             AppCommand::ImportPalette => app_state.import_palette(),
             AppCommand::ExportPalette => app_state.export_palette(),
-            AppCommand::SetPalette(palette) => {
-                app_state.engine.set_palette(palette);
-                app_state.is_dirty = true;
-            }
+            AppCommand::MergePaletteFromFile => app_state.merge_palette_from_file(),
             AppCommand::SetPrimaryColor(color) => app_state.engine.set_primary_color(color),
+            AppCommand::SetSecondaryColor(color) => app_state.engine.set_secondary_color(color),
+            AppCommand::SwapColors => app_state.engine.parts_mut().0.swap_colors(),
             
             AppCommand::ClearSelection => {
                 if app_state.engine.store().selection.is_active {
@@ -195,6 +215,32 @@ impl CommandHandler {
                 if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
                 else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
             }
+            AppCommand::ToggleTilingMode => {
+                let (store, _, _) = app_state.engine.parts_mut();
+                store.tiling_mode = !store.tiling_mode;
+                app_state.view.needs_full_redraw = true;
+            }
+            AppCommand::WrapOffsetCanvas(dx, dy) => {
+                if dx == 0 && dy == 0 { return; }
+
+                let w = app_state.engine.store().canvas_width;
+                let h = app_state.engine.store().canvas_height;
+                let old_layers = app_state.engine.store().layers.clone();
+                let selection = app_state.engine.store().selection.clone();
+
+                let mut new_layers = old_layers.clone();
+                for layer in &mut new_layers {
+                    layer.shift_wrapped(dx, dy, w, h);
+                }
+
+                let patch = ActionPatch::new_canvas_resize(
+                    id_gen::gen_id(), w, h, w, h,
+                    old_layers, new_layers, selection.clone(), selection
+                );
+
+                if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+                else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+            }
             AppCommand::CommitCurrentTool => app_state.commit_current_tool(),
             AppCommand::CancelCurrentTool => app_state.cancel_current_tool(),
             AppCommand::SetLanguage(lang) => {
@@ -268,7 +314,126 @@ impl CommandHandler {
             AppCommand::ToggleTransformCoordinateSystem => {
                 app_state.ui.show_world_transform = !app_state.ui.show_world_transform;
             }
+            AppCommand::SetTransformResample(mode) => {
+                if let Err(e) = app_state.engine.set_transform_resample(mode) { app_state.ui.error_message = Some(e.to_string()); }
+                else { app_state.view.needs_full_redraw = true; }
+            }
+            AppCommand::SetTransformHandleMode(mode) => {
+                let _ = app_state.engine.update_transform_tool(|tool, _| { tool.handle_mode = mode; Ok(()) });
+            }
+            AppCommand::SetTransformParams(params) => {
+                if let Err(e) = app_state.engine.update_transform_tool(|tool, store| tool.set_params(params, store)) { app_state.ui.error_message = Some(e.to_string()); }
+                else { app_state.view.needs_full_redraw = true; }
+            }
+            AppCommand::FlipTransform { horizontal } => {
+                if let Err(e) = app_state.engine.update_transform_tool(|tool, store| tool.flip(horizontal, store)) { app_state.ui.error_message = Some(e.to_string()); }
+                else { app_state.view.needs_full_redraw = true; }
+            }
+            AppCommand::ResetTransformDistort => {
+                if let Err(e) = app_state.engine.update_transform_tool(|tool, store| tool.reset_distort(store)) { app_state.ui.error_message = Some(e.to_string()); }
+                else { app_state.view.needs_full_redraw = true; }
+            }
+            AppCommand::TransformCanvas(op) => {
+                let old_w = app_state.engine.store().canvas_width;
+                let old_h = app_state.engine.store().canvas_height;
+                let (new_w, new_h) = if op.swaps_axes() { (old_h, old_w) } else { (old_w, old_h) };
+
+                let old_layers = app_state.engine.store().layers.clone();
+                let old_selection = app_state.engine.store().selection.clone();
+
+                let mut new_layers = old_layers.clone();
+                for layer in &mut new_layers {
+                    let (w, h) = if op.swaps_axes() { (layer.height, layer.width) } else { (layer.width, layer.height) };
+                    layer.apply_lossless(op, 0, 0, w, h);
+                }
+
+                let mut new_selection = old_selection.clone();
+                new_selection.apply_lossless(op, 0, 0, new_w, new_h);
+
+                let layer_sizes = old_layers.iter().map(|l| (l.id.clone(), (l.width, l.height))).collect();
+                let patch = ActionPatch::new_canvas_resize(
+                    id_gen::gen_id(), old_w, old_h, new_w, new_h,
+                    old_layers, new_layers, old_selection, new_selection
+                );
+
+                if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+                else {
+                    commit_rig_change(app_state, |project| {
+                        project.skeleton.apply_lossless(op, old_w, old_h, &layer_sizes);
+                        let skeleton = &project.skeleton;
+                        for anim in project.animations.values_mut() {
+                            anim.apply_lossless(op, old_w, old_h, skeleton);
+                        }
+                    });
+                    app_state.is_dirty = true;
+                    app_state.view.needs_full_redraw = true;
+                }
+            }
+            AppCommand::ConfirmImageImport => app_state.confirm_image_import(),
+            AppCommand::CancelImageImport => app_state.ui.pending_image_import = None,
+            AppCommand::UpscaleCanvas(mode) => {
+                let factor = mode.factor();
+                let old_w = app_state.engine.store().canvas_width;
+                let old_h = app_state.engine.store().canvas_height;
+                let (new_w, new_h) = (old_w * factor, old_h * factor);
+                if new_w > 16384 || new_h > 16384 {
+                    app_state.ui.error_message = Some(t!("error.canvas_size_limit", max = 16384).to_string());
+                    return;
+                }
+
+                let old_layers = app_state.engine.store().layers.clone();
+                let old_selection = app_state.engine.store().selection.clone();
+
+                let mut new_layers = old_layers.clone();
+                for layer in &mut new_layers {
+                    let (pixels, w, h) = crate::render::upscale::upscale(&layer.get_rect_data(0, 0, layer.width, layer.height), layer.width, layer.height, mode);
+                    layer.replace_rgba(w, h, &pixels);
+                    layer.offset_x *= factor as i32;
+                    layer.offset_y *= factor as i32;
+                }
+
+                let mut new_selection = old_selection.clone();
+                new_selection.scale_nearest(factor);
+
+                let patch = ActionPatch::new_canvas_resize(
+                    id_gen::gen_id(), old_w, old_h, new_w, new_h,
+                    old_layers, new_layers, old_selection, new_selection
+                );
+
+                if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+                else {
+                    commit_rig_change(app_state, |project| {
+                        project.skeleton.scale_positions(factor as f32);
+                        for anim in project.animations.values_mut() {
+                            anim.scale_positions(factor as f32);
+                        }
+                    });
+                    app_state.is_dirty = true;
+                    app_state.view.needs_full_redraw = true;
+                }
+            }
             _ => {}
         }
     }
+}
+
+// 画布整体变换后让骨架、网格与关键帧跟随，作为一步记入动画历史
+fn commit_rig_change(app_state: &mut AppState, edit: impl FnOnce(&mut AnimProject)) {
+    let old_skeleton = app_state.animation.project.skeleton.clone();
+    let old_anims = app_state.animation.project.animations.clone();
+    edit(&mut app_state.animation.project);
+
+    let project = &app_state.animation.project;
+    let mut patches = vec![AnimPatch::Skeleton { old: Box::new(old_skeleton), new: Box::new(project.skeleton.clone()) }];
+    for (anim_id, anim) in &project.animations {
+        let Some(old_anim) = old_anims.get(anim_id) else { continue };
+        for (old_tl, new_tl) in old_anim.timelines.iter().zip(&anim.timelines) {
+            if old_tl != new_tl {
+                patches.push(AnimPatch::Timeline { anim_id: anim_id.clone(), bone_id: new_tl.target_id.clone(), prop: new_tl.property.clone(), old: Some(old_tl.clone()), new: Some(new_tl.clone()) });
+            }
+        }
+    }
+    app_state.animation.history.commit(AnimPatch::Composite(patches));
+    crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
+    app_state.sync_animation_to_layers();
 }
\ No newline at end of file
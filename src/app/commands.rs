@@ -13,15 +13,37 @@ pub enum AppCommand {
     AddColorToPalette(Color),
     RemovePaletteColor(usize),
     SetPrimaryColor(Color),
+    SetSecondaryColor(Color),
+    // 交换主色与副色
+    SwapColors,
     ToggleLayerLock(String),
     SetLayerOpacity(String, u8),
     MoveLayerUp(String),
     MoveLayerDown(String),
     RenameLayer(String, String),
     SetLayerBlendMode(String, BlendMode),
+    // 图层效果栈：添加一个效果，或整体替换 (修改参数、开关、排序、删除)
+    AddLayerEffect(String, crate::core::layer_effect::LayerEffectKind),
+    SetLayerEffects(String, Vec<crate::core::layer_effect::LayerEffect>),
     ImportPalette,
     ExportPalette,
     SetPalette(crate::core::palette::Palette),
+    // 调色板整理：以下操作与添加 / 删除颜色一样可撤销
+    RenamePaletteColor(usize, String),
+    AddPaletteRamp { name: String, colors: Vec<Color> },
+    AssignPaletteRamp { indices: Vec<usize>, ramp: Option<u32> },
+    RenamePaletteRamp(u32, String),
+    RemovePaletteRamp(u32),
+    SortPalette(crate::core::palette::PaletteSortKey),
+    DedupePalette,
+    MergePalette(crate::core::palette::Palette),
+    MergePaletteFromFile,
+    // 以文档中用到的颜色 (按使用次数排序) 替换调色板
+    CreatePaletteFromUsedColors,
+    // 把图层 (选区激活时只处理选区内的像素) 映射到当前调色板
+    QuantizeLayer { layer_id: String, settings: crate::render::quantize::QuantizeSettings },
+    // 从图层 (或选区内) 生成 count 色调色板替换当前调色板；remap 为 Some 时同时把图层映射到新调色板，一步撤销
+    GeneratePaletteFromLayer { layer_id: String, method: crate::render::quantize::PaletteGenMethod, count: usize, remap: Option<crate::render::quantize::QuantizeSettings> },
     ClearSelection,
     InvertSelection,
     StrokeSelection(u32),
@@ -38,11 +60,19 @@ pub enum AppCommand {
     WindowMaximize,
     SaveProject,
     LoadProject,
+    // 从其它 .pxad 工程导入动画，确认骨骼映射后再写入
+    ImportAnimations,
+    ConfirmAnimationImport,
+    CancelAnimationImport,
+    // 把当前动画逐帧栅格化为新图层或精灵表
+    BakeAnimation(crate::animation::bake::BakeSettings),
     ImportImage,
     ExportPng,
     Undo,
     Redo,
     ResizeCanvas(u32, u32, ResizeAnchor),
+    ToggleTilingMode,
+    WrapOffsetCanvas(i32, i32),
     CommitCurrentTool,
     CancelCurrentTool,
     SetLanguage(String),
@@ -53,6 +83,15 @@ pub enum AppCommand {
     DeleteKeyframe(String, Option<TimelineProperty>, f32),
     UpdateKeyframeCurve(String, TimelineProperty, f32, CurveType),
     MoveSelectedKeyframes(f32),
+    SetKeyframeTangentMode { keys: Vec<(String, TimelineProperty, f32)>, mode: crate::core::animation::timeline::TangentMode },
+    SetKeyframeHandle { target_id: String, prop: TimelineProperty, time: f32, side: crate::core::animation::graph::TangentSide, x: f32, y: f32, merge: bool },
+    ScaleSelectedKeyframes { time_scale: f32, value_scale: f32 },
+    CopyKeyframes,
+    CopyPose,
+    PasteKeyframes { mirrored: bool },
+    ReverseSelectedKeyframes,
+    SaveCurvePreset { name: String, handles: [f32; 4] },
+    DeleteCurvePreset(String),
     BeginOffsetSnapshot,
     CommitOffsetSnapshot,
     OffsetSelectedKeyframes(f32),
@@ -62,8 +101,30 @@ pub enum AppCommand {
     TogglePathNodeType(usize),
     DeletePathNode(usize),
     ToggleTransformCoordinateSystem,
+    SetTransformResample(crate::render::resample::ResampleMode),
+    SetTransformHandleMode(crate::tools::transform::HandleMode),
+    // 数值面板输入的偏移、角度、缩放、轴心与切变
+    SetTransformParams(crate::tools::transform::TransformParams),
+    FlipTransform { horizontal: bool },
+    ResetTransformDistort,
+    // 整个图层 / 画布的 90° 倍数旋转与镜像，不经过取样
+    TransformLayer(String, crate::core::layer::LosslessTransform),
+    TransformCanvas(crate::core::layer::LosslessTransform),
+    // 导入图片对话框的确认 / 取消
+    ConfirmImageImport,
+    CancelImageImport,
+    // 用像素画放大算法放大整个画布
+    UpscaleCanvas(crate::render::upscale::Upscaler),
+    // 对当前图层 (或选区) 打开调整预览；确认时作为一步撤销提交，取消时恢复原图
+    BeginFilter(crate::render::filters::Adjustment),
+    UpdateFilterPreview(crate::render::filters::FilterSettings),
+    ApplyFilter,
+    CancelFilter,
+    // 对图层 (或选区) 应用描边、投影等像素画效果，作为一步撤销
+    ApplyEffect { layer_id: String, effect: crate::render::effects::PixelEffect },
     BindLayerToBone(String, String),
     DeleteBone(String),
+    SetBoneTransformMode { bone_id: String, mode: crate::core::animation::bone::TransformMode },
     InsertManualKeyframe(String),
     TogglePlayback,
     StepFrame(i32),
@@ -71,4 +132,25 @@ pub enum AppCommand {
     SetPlaybackSpeed(f32),
     ToggleLoop,
     ToggleTimelineFilter(TimelineProperty),
+    SetOnionSkin(crate::animation::onion_skin::OnionSkinSettings),
+    SetFrameRate { fps: u32, retime: bool },
+    ToggleFrameSnap,
+    // 以 tip 骨骼为链尾向上取 chain_length 根骨骼，追踪 target 骨骼
+    AddIkConstraint { tip_bone_id: String, target_bone_id: String, chain_length: usize },
+    RemoveIkConstraint(String),
+    SetIkConstraint { id: String, mix: f32, bend_positive: bool },
+    SetSlotAttachment { slot_id: String, attachment: Option<String> },
+    // delta > 0 向上层移动
+    MoveSlotDrawOrder { slot_id: String, delta: i32 },
+    // 在当前时间添加事件
+    AddAnimationEvent(String),
+    // 按索引替换事件 (改名、移动、修改负载)
+    UpdateAnimationEvent(usize, crate::core::animation::event::AnimationEvent),
+    DeleteAnimationEvent(usize),
+    PreviewTransition(String, f32),
+    StopTransitionPreview,
+    // 按 cell_size 网格三角化图层的不透明区域并自动分配权重 (已有网格时重新生成)
+    CreateMesh { layer_id: String, cell_size: u32 },
+    DeleteMesh(String),
+    AutoWeightMesh(String),
 }
\ No newline at end of file
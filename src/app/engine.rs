@@ -69,6 +69,10 @@ impl PxaEngine {
         self.store.primary_color = color;
     }
 
+    pub fn set_secondary_color(&mut self, color: Color) {
+        self.store.secondary_color = color;
+    }
+
     pub fn set_palette(&mut self, palette: crate::core::palette::Palette) {
         self.store.palette = palette;
     }
@@ -213,6 +217,27 @@ impl PxaEngine {
         }
     }
 
+    pub fn set_transform_resample(&mut self, mode: crate::render::resample::ResampleMode) -> crate::core::error::Result<()> {
+        if let Some(tool) = self.tool_manager.tools.get_mut(&crate::app::state::ToolType::Transform)
+            .and_then(|t| t.as_any_mut().downcast_mut::<crate::tools::transform::TransformTool>()) {
+            tool.set_resample(mode, &mut self.store)?;
+        }
+        self.process_dirty_rect();
+        Ok(())
+    }
+
+    // 面板、翻转等非指针操作：仅在变换工具为当前工具时生效，随后刷新预览
+    pub fn update_transform_tool<F>(&mut self, f: F) -> crate::core::error::Result<()>
+    where F: FnOnce(&mut crate::tools::transform::TransformTool, &mut PixelStore) -> crate::core::error::Result<()> {
+        if self.tool_manager.active_type != crate::app::state::ToolType::Transform { return Ok(()); }
+        if let Some(tool) = self.tool_manager.tools.get_mut(&crate::app::state::ToolType::Transform)
+            .and_then(|t| t.as_any_mut().downcast_mut::<crate::tools::transform::TransformTool>()) {
+            f(tool, &mut self.store)?;
+        }
+        self.process_dirty_rect();
+        Ok(())
+    }
+
     fn process_dirty_rect(&mut self) -> EngineEffect {
         let active_type = self.tool_manager.active_type;
         if let Some(rect) = self.tool_manager.tools.get_mut(&active_type).and_then(|t| t.take_dirty_rect()) {
@@ -1,6 +1,7 @@
 use crate::app::state::AppState;
 use crate::app::commands::AppCommand;
-use crate::animation::history::AnimPatch;
+use crate::animation::history::{AnimPatch, FrameRateChange};
+use crate::core::animation::timeline::{TimelineProperty, KeyframeValue, DRAW_ORDER_TARGET};
 
 pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
     match cmd {
@@ -10,12 +11,94 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
             anim.initialize_tracks(&app_state.animation.project.skeleton);
             app_state.animation.project.animations.insert(id.clone(), anim);
             app_state.animation.project.active_animation_id = Some(id);
+            app_state.animation.transition_preview = None;
             app_state.animation.current_time = 0.0;
             app_state.is_dirty = true;
         }
+        AppCommand::ConfirmAnimationImport => {
+            let import = match &app_state.animation.pending_import {
+                Some(import) => import,
+                None => return,
+            };
+            if !import.animations.iter().any(|a| a.2) {
+                app_state.ui.error_message = Some("没有选择要导入的动画".to_string());
+                return;
+            }
+            let import = match app_state.animation.pending_import.take() {
+                Some(import) => import,
+                None => return,
+            };
+            let project = &mut app_state.animation.project;
+            let mut first_id = None;
+            for (_, anim, selected) in &import.animations {
+                if !selected { continue; }
+                let mut retargeted = crate::core::animation::retarget::retarget_animation(anim, &import.skeleton, &project.skeleton, &import.mapping, import.rescale_translation);
+                // 与现有动画重名时追加序号
+                let mut n = 2;
+                while project.animations.values().any(|a| a.name == retargeted.name) {
+                    retargeted.name = format!("{} ({})", anim.name, n);
+                    n += 1;
+                }
+                let id = crate::core::id_gen::gen_id();
+                project.animations.insert(id.clone(), retargeted);
+                first_id.get_or_insert(id);
+            }
+            project.active_animation_id = first_id;
+            app_state.animation.transition_preview = None;
+            app_state.animation.current_time = 0.0;
+            crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
+            app_state.sync_animation_to_layers();
+            app_state.is_dirty = true;
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::CancelAnimationImport => app_state.animation.pending_import = None,
+        AppCommand::BakeAnimation(settings) => {
+            let anim_name = match app_state.animation.project.active_animation_id.as_ref().and_then(|id| app_state.animation.project.animations.get(id)) {
+                Some(anim) => anim.name.clone(),
+                None => {
+                    app_state.ui.error_message = Some("没有可烘焙的动画".to_string());
+                    return;
+                }
+            };
+            crate::app::handlers::filter_handler::cancel_preview(app_state);
+            let frames = app_state.bake_animation_frames(&settings);
+            let (w, h) = (app_state.engine.store().canvas_width, app_state.engine.store().canvas_height);
+            match settings.target {
+                crate::animation::bake::BakeTarget::Layers => {
+                    // 烘焙出的图层默认隐藏，避免与骨骼动画叠在一起显示；整批一步撤销
+                    let store = app_state.engine.store();
+                    let base_index = store.layers.len();
+                    let mut active_id = store.active_layer_id.clone();
+                    let mut patches = Vec::new();
+                    for (i, pixels) in frames.iter().enumerate() {
+                        let id = format!("layer_{}", crate::core::id_gen::gen_id());
+                        let mut layer = crate::core::layer::Layer::new(id.clone(), format!("{}_{:03}", anim_name, i), w, h);
+                        layer.set_rect_data(0, 0, w, h, pixels);
+                        layer.visible = false;
+                        patches.push(crate::history::patch::ActionPatch::new_layer_add(format!("bake_{}", id), id.clone(), layer, base_index + i, active_id.replace(id)));
+                    }
+                    let patch = crate::history::patch::ActionPatch::new_composite(format!("bake_{}", crate::core::id_gen::gen_id()), patches);
+                    match app_state.engine.commit_patch(patch) {
+                        Ok(()) => { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+                        Err(e) => app_state.ui.error_message = Some(e.to_string()),
+                    }
+                }
+                crate::animation::bake::BakeTarget::SpriteSheet => {
+                    let path = match crate::app::io_service::IoService::pick_sprite_sheet_path() {
+                        Some(path) => path,
+                        None => return,
+                    };
+                    let (sheet, sheet_w, sheet_h) = crate::animation::bake::sprite_sheet(&frames, w, h, settings.columns);
+                    if let Err(e) = crate::app::io_service::IoService::save_rgba_png(path, &sheet, sheet_w, sheet_h) {
+                        app_state.ui.error_message = Some(e.to_string());
+                    }
+                }
+            }
+        }
         AppCommand::SelectAnimation(id) => {
             if app_state.animation.project.animations.contains_key(&id) {
                 app_state.animation.project.active_animation_id = Some(id);
+                app_state.animation.transition_preview = None;
                 app_state.animation.current_time = 0.0;
                 crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
                 app_state.view.needs_full_redraw = true;
@@ -57,8 +140,12 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                     anim.timelines.retain(|tl| tl.target_id != bone_id);
                 }
             }
+            for mesh in &mut app_state.animation.project.skeleton.meshes {
+                mesh.replace_bone(&bone_id, &bind_target);
+            }
             app_state.animation.project.skeleton.bones.retain(|b| b.data.id != bone_id);
-            app_state.animation.history.commit(AnimPatch::Skeleton { old: old_skel, new: app_state.animation.project.skeleton.clone() });
+            app_state.animation.project.skeleton.ik_constraints.retain(|c| c.data.target_id != bone_id && !c.data.bone_ids.contains(&bone_id));
+            app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
             
             if app_state.ui.selected_bone_id.as_deref() == Some(&bone_id) {
                 app_state.ui.selected_bone_id = None;
@@ -141,10 +228,11 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                         }
                     }
                 }
+                let (fps, snap) = (app_state.animation.project.fps_f32(), app_state.animation.project.snap_to_frames);
                 if let Some(anim) = app_state.animation.project.animations.get_mut(&active_id) {
                     let mut new_selection = Vec::new();
                     for (bone_id, prop_opt, t) in &app_state.ui.selected_keyframes {
-                        let new_time = *t + actual_dt;
+                        let new_time = if snap { crate::core::animation::timeline::snap_to_frame(*t + actual_dt, fps).max(0.0) } else { *t + actual_dt };
                         new_selection.push((bone_id.clone(), prop_opt.clone(), new_time));
                         for tl in &mut anim.timelines {
                             if &tl.target_id == bone_id {
@@ -170,6 +258,155 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                 if !patches.is_empty() { app_state.animation.history.commit(AnimPatch::Composite(patches)); }
             }
         }
+        AppCommand::SetKeyframeTangentMode { keys, mode } => {
+            let active_id = match app_state.animation.project.active_animation_id.clone() {
+                Some(id) => id,
+                None => return,
+            };
+            let mut patches = Vec::new();
+            if let Some(anim) = app_state.animation.project.animations.get_mut(&active_id) {
+                for tl in &mut anim.timelines {
+                    if !crate::core::animation::graph::is_graph_property(&tl.property) { continue; }
+                    let times: Vec<f32> = keys.iter().filter(|k| k.0 == tl.target_id && k.1 == tl.property).map(|k| k.2).collect();
+                    if times.is_empty() { continue; }
+                    let old = tl.clone();
+                    for t in times {
+                        if let Some(idx) = tl.keyframes.iter().position(|k| (k.time - t).abs() < 0.001) {
+                            crate::core::animation::graph::set_tangent_mode(tl, idx, mode);
+                        }
+                    }
+                    if *tl != old {
+                        patches.push(AnimPatch::Timeline { anim_id: active_id.clone(), bone_id: tl.target_id.clone(), prop: tl.property.clone(), old: Some(old), new: Some(tl.clone()) });
+                    }
+                }
+            }
+            if !patches.is_empty() {
+                app_state.animation.history.commit(AnimPatch::Composite(patches));
+                app_state.is_dirty = true;
+                app_state.view.needs_full_redraw = true;
+            }
+        }
+        AppCommand::SetKeyframeHandle { target_id, prop, time, side, x, y, merge } => {
+            let active_id = match app_state.animation.project.active_animation_id.clone() {
+                Some(id) => id,
+                None => return,
+            };
+            let tl = match app_state.animation.project.animations.get_mut(&active_id)
+                .and_then(|a| a.timelines.iter_mut().find(|t| t.target_id == target_id && t.property == prop)) {
+                Some(tl) => tl,
+                None => return,
+            };
+            let idx = match tl.keyframes.iter().position(|k| (k.time - time).abs() < 0.001) {
+                Some(idx) => idx,
+                None => return,
+            };
+            let old = tl.clone();
+            crate::core::animation::graph::set_handle(tl, idx, side, x, y);
+            if *tl == old { return; }
+            let patch = AnimPatch::Timeline { anim_id: active_id, bone_id: target_id, prop, old: Some(old), new: Some(tl.clone()) };
+            if merge { app_state.animation.history.commit_merged(patch); } else { app_state.animation.history.commit(patch); }
+            app_state.is_dirty = true;
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::ScaleSelectedKeyframes { time_scale, value_scale } => {
+            if time_scale <= 0.0 || !time_scale.is_finite() || !value_scale.is_finite() {
+                app_state.ui.error_message = Some("时间缩放系数必须大于 0".to_string());
+                return;
+            }
+            let active_id = match app_state.animation.project.active_animation_id.clone() {
+                Some(id) => id,
+                None => return,
+            };
+            let pivot = app_state.animation.current_time;
+            let snap = if app_state.animation.project.snap_to_frames { Some(app_state.animation.project.fps_f32()) } else { None };
+            let selection = app_state.ui.selected_keyframes.clone();
+            let mut new_selection = Vec::new();
+            let mut patches = Vec::new();
+            if let Some(anim) = app_state.animation.project.animations.get_mut(&active_id) {
+                for tl in &mut anim.timelines {
+                    // 数值缩放来自曲线编辑器，只作用于其中显示的轨道；仅缩放时间 (摄影表) 时作用于所有轨道
+                    if value_scale != 1.0 && !crate::core::animation::graph::is_graph_property(&tl.property) { continue; }
+                    let times: Vec<f32> = selection.iter()
+                        .filter(|k| k.0 == tl.target_id && k.1.as_ref().is_none_or(|p| p == &tl.property))
+                        .map(|k| k.2).collect();
+                    if times.is_empty() { continue; }
+                    let old = tl.clone();
+                    for (_, new_time) in crate::core::animation::graph::scale_keys(tl, &times, pivot, time_scale, value_scale, snap) {
+                        new_selection.push((tl.target_id.clone(), Some(tl.property.clone()), new_time));
+                    }
+                    if *tl != old {
+                        patches.push(AnimPatch::Timeline { anim_id: active_id.clone(), bone_id: tl.target_id.clone(), prop: tl.property.clone(), old: Some(old), new: Some(tl.clone()) });
+                    }
+                }
+                anim.recalculate_duration();
+            }
+            if !patches.is_empty() {
+                app_state.ui.selected_keyframes = new_selection;
+                app_state.animation.history.commit(AnimPatch::Composite(patches));
+                app_state.is_dirty = true;
+                app_state.view.needs_full_redraw = true;
+            }
+        }
+        AppCommand::CopyKeyframes => {
+            let clipboard = app_state.animation.project.active_animation_id.as_ref()
+                .and_then(|id| app_state.animation.project.animations.get(id))
+                .and_then(|anim| crate::core::animation::keyframe_edit::KeyframeClipboard::from_selection(anim, &app_state.ui.selected_keyframes));
+            if clipboard.is_some() { app_state.animation.keyframe_clipboard = clipboard; }
+        }
+        AppCommand::CopyPose => {
+            app_state.animation.keyframe_clipboard = Some(crate::core::animation::keyframe_edit::KeyframeClipboard::from_pose(&app_state.animation.project.skeleton));
+        }
+        AppCommand::PasteKeyframes { mirrored } => {
+            let mut clipboard = match app_state.animation.keyframe_clipboard.clone() {
+                Some(c) => c,
+                None => return,
+            };
+            let skeleton = &app_state.animation.project.skeleton;
+            if mirrored {
+                clipboard = clipboard.mirrored(skeleton);
+            } else if let Some(bone_id) = &app_state.ui.selected_bone_id {
+                // 单根骨骼的关键帧粘贴到当前选中的骨骼上
+                let sources = clipboard.target_ids();
+                if sources.len() == 1 && skeleton.bones.iter().any(|b| b.data.id == sources[0]) && skeleton.bones.iter().any(|b| &b.data.id == bone_id) {
+                    clipboard.retarget(&bone_id.clone());
+                }
+            }
+            let time = app_state.animation.project.snap_time(app_state.animation.current_time);
+            edit_active_timelines(app_state, |anim| Some(clipboard.paste(anim, time)));
+        }
+        AppCommand::ReverseSelectedKeyframes => {
+            let selection = app_state.ui.selected_keyframes.clone();
+            let start = selection.iter().map(|k| k.2).fold(f32::MAX, f32::min);
+            let end = selection.iter().map(|k| k.2).fold(f32::MIN, f32::max);
+            if selection.is_empty() || end - start < 0.0001 { return; }
+            edit_active_timelines(app_state, |anim| {
+                let mut new_selection = Vec::new();
+                for tl in &mut anim.timelines {
+                    for (_, new_time) in crate::core::animation::keyframe_edit::reverse_keys(tl, &selection, start, end) {
+                        new_selection.push((tl.target_id.clone(), Some(tl.property.clone()), new_time));
+                    }
+                }
+                Some(new_selection)
+            });
+        }
+        AppCommand::SaveCurvePreset { name, handles } => {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                app_state.ui.error_message = Some("预设名称不能为空".to_string());
+                return;
+            }
+            let mut presets = app_state.ui.curve_presets.clone();
+            match presets.iter_mut().find(|p| p.name == name) {
+                Some(existing) => existing.handles = handles,
+                None => presets.push(crate::core::animation::easing::CurvePreset { name, handles }),
+            }
+            save_curve_presets(app_state, presets);
+        }
+        AppCommand::DeleteCurvePreset(name) => {
+            let mut presets = app_state.ui.curve_presets.clone();
+            presets.retain(|p| p.name != name);
+            save_curve_presets(app_state, presets);
+        }
         AppCommand::BeginOffsetSnapshot => {
             if let Some(active_id) = &app_state.animation.project.active_animation_id {
                 if let Some(anim) = app_state.animation.project.animations.get(active_id) {
@@ -258,8 +495,8 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
 
                     if let Some(val) = sample_val {
                         new_kfs.retain(|k| k.time > 0.001 && (duration - k.time) > 0.001);
-                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: 0.0, value: val.clone(), curve: crate::core::animation::timeline::CurveType::Linear });
-                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: duration, value: val, curve: crate::core::animation::timeline::CurveType::Linear });
+                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: 0.0, value: val.clone(), curve: crate::core::animation::timeline::CurveType::Linear, tangent: crate::core::animation::timeline::TangentMode::Broken });
+                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: duration, value: val, curve: crate::core::animation::timeline::CurveType::Linear, tangent: crate::core::animation::timeline::TangentMode::Broken });
                     }
 
                     new_kfs.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
@@ -284,7 +521,7 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                     return;
                 }
 
-                let fps = 30.0;
+                let fps = app_state.animation.project.fps_f32();
                 let n_sec = fixed_frames as f32 / fps;
 
                 let mut bone_order = Vec::new();
@@ -358,8 +595,8 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
 
                     if let Some(val) = sample_val {
                         new_kfs.retain(|k| k.time > 0.001 && (duration - k.time) > 0.001);
-                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: 0.0, value: val.clone(), curve: crate::core::animation::timeline::CurveType::Linear });
-                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: duration, value: val, curve: crate::core::animation::timeline::CurveType::Linear });
+                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: 0.0, value: val.clone(), curve: crate::core::animation::timeline::CurveType::Linear, tangent: crate::core::animation::timeline::TangentMode::Broken });
+                        new_kfs.push(crate::core::animation::timeline::Keyframe { time: duration, value: val, curve: crate::core::animation::timeline::CurveType::Linear, tangent: crate::core::animation::timeline::TangentMode::Broken });
                     }
 
                     new_kfs.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
@@ -383,18 +620,15 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
         AppCommand::InsertManualKeyframe(bone_id) => {
             if let Some(active_id) = app_state.animation.project.active_animation_id.clone() {
                 let mut patches = Vec::new();
-                let props = [
-                    crate::core::animation::timeline::TimelineProperty::Translation,
-                    crate::core::animation::timeline::TimelineProperty::Rotation,
-                    crate::core::animation::timeline::TimelineProperty::Scale,
-                ];
+                // IK 约束、插槽、绘制顺序与骨骼共用该命令，按 id 区分要记录的属性
+                let props = key_properties(app_state, &bone_id);
 
                 for prop in props {
                     let old_tl = app_state.animation.project.animations.get(&active_id)
                         .and_then(|a| a.timelines.iter().find(|t| t.target_id == bone_id && t.property == prop))
                         .cloned();
 
-                    app_state.animation.auto_key_bone(&bone_id, prop.clone());
+                    key_property(app_state, &bone_id, prop.clone());
 
                     let new_tl = app_state.animation.project.animations.get(&active_id)
                         .and_then(|a| a.timelines.iter().find(|t| t.target_id == bone_id && t.property == prop))
@@ -415,7 +649,9 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
         }
         AppCommand::TogglePlayback => app_state.animation.is_playing = !app_state.animation.is_playing,
         AppCommand::StepFrame(frames) => {
-            app_state.animation.current_time = (app_state.animation.current_time + frames as f32 / 30.0).max(0.0);
+            let fps = app_state.animation.project.fps_f32();
+            let frame = (app_state.animation.current_time * fps).round() + frames as f32;
+            app_state.animation.current_time = (frame / fps).max(0.0);
             crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
             app_state.sync_animation_to_layers();
             app_state.is_dirty = true;
@@ -435,6 +671,380 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                 app_state.ui.timeline_filter.push(prop);
             }
         }
+        AppCommand::SetFrameRate { fps, retime } => {
+            let old_fps = app_state.animation.project.fps;
+            if fps == 0 || fps == old_fps { return; }
+
+            // retime: 保持帧号不变，按比例缩放关键帧时间；否则保持秒数，按需对齐到新帧格
+            let old_anims = app_state.animation.project.animations.clone();
+            let snap = app_state.animation.project.snap_to_frames;
+            for anim in app_state.animation.project.animations.values_mut() {
+                if retime {
+                    anim.scale_time(old_fps as f32 / fps as f32);
+                } else if snap {
+                    anim.snap_keyframes(fps as f32);
+                }
+            }
+            app_state.animation.project.fps = fps;
+            if retime {
+                app_state.animation.current_time *= old_fps as f32 / fps as f32;
+            }
+            app_state.animation.current_time = app_state.animation.project.snap_time(app_state.animation.current_time);
+            app_state.ui.selected_keyframes.clear();
+
+            app_state.animation.history.commit(AnimPatch::FrameRate(Box::new(FrameRateChange {
+                old_fps,
+                new_fps: fps,
+                old_anims,
+                new_anims: app_state.animation.project.animations.clone(),
+            })));
+            crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
+            app_state.sync_animation_to_layers();
+            app_state.is_dirty = true;
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::ToggleFrameSnap => {
+            app_state.animation.project.snap_to_frames = !app_state.animation.project.snap_to_frames;
+            app_state.is_dirty = true;
+        }
+        AppCommand::PreviewTransition(to_id, duration) => {
+            if crate::animation::controller::AnimationController::preview_transition(&mut app_state.animation, &to_id, duration) {
+                app_state.animation.is_playing = true;
+                app_state.sync_animation_to_layers();
+                app_state.view.needs_full_redraw = true;
+            } else {
+                app_state.ui.error_message = Some("无法预览过渡：请先选择当前动画和目标动画。".to_string());
+            }
+        }
+        AppCommand::StopTransitionPreview => {
+            crate::animation::controller::AnimationController::stop_preview(&mut app_state.animation);
+            app_state.sync_animation_to_layers();
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::AddIkConstraint { tip_bone_id, target_bone_id, chain_length } => {
+            let skeleton = &app_state.animation.project.skeleton;
+            let mut chain = vec![tip_bone_id.clone()];
+            while chain.len() < chain_length.max(1) {
+                let parent = skeleton.bones.iter().find(|b| Some(&b.data.id) == chain.first()).and_then(|b| b.data.parent_id.clone());
+                match parent {
+                    Some(p) => chain.insert(0, p),
+                    None => break,
+                }
+            }
+            let valid = skeleton.bones.iter().any(|b| b.data.id == tip_bone_id)
+                && skeleton.bones.iter().any(|b| b.data.id == target_bone_id)
+                && !chain.contains(&target_bone_id);
+            if !valid {
+                app_state.ui.error_message = Some("无法创建 IK：目标骨骼不能位于骨骼链中。".to_string());
+                return;
+            }
+
+            let old_skel = skeleton.clone();
+            let id = format!("ik_{}", crate::core::id_gen::gen_id());
+            let name = format!("IK {}", app_state.animation.project.skeleton.ik_constraints.len() + 1);
+            let data = crate::core::animation::ik::IkConstraintData::new(id, name, chain, target_bone_id);
+            app_state.animation.project.skeleton.ik_constraints.push(crate::core::animation::ik::IkConstraint::new(data));
+            app_state.animation.project.skeleton.update();
+            app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
+            app_state.sync_animation_to_layers();
+            app_state.is_dirty = true;
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::RemoveIkConstraint(id) if app_state.animation.project.skeleton.ik_constraints.iter().any(|c| c.data.id == id) => {
+            let old_skel = app_state.animation.project.skeleton.clone();
+            // 保留对应的关键帧轨道，撤销删除后动画依然完整
+            app_state.animation.project.skeleton.ik_constraints.retain(|c| c.data.id != id);
+            app_state.animation.project.skeleton.update();
+            app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
+            app_state.sync_animation_to_layers();
+            app_state.is_dirty = true;
+            app_state.view.needs_full_redraw = true;
+        }
+        AppCommand::SetBoneTransformMode { bone_id, mode } => {
+            let old_skel = app_state.animation.project.skeleton.clone();
+            if let Some(bone) = app_state.animation.project.skeleton.bones.iter_mut().find(|b| b.data.id == bone_id) {
+                if bone.data.transform_mode() == mode { return; }
+                bone.data.set_transform_mode(mode);
+                app_state.animation.project.skeleton.update();
+                app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
+                app_state.sync_animation_to_layers();
+                app_state.is_dirty = true;
+                app_state.view.needs_full_redraw = true;
+            }
+        }
+        AppCommand::SetIkConstraint { id, mix, bend_positive } => {
+            if let Some(c) = app_state.animation.project.skeleton.ik_constraints.iter_mut().find(|c| c.data.id == id) {
+                let mix = mix.clamp(0.0, 1.0);
+                c.mix = mix;
+                c.bend_positive = bend_positive;
+                c.data.mix = mix;
+                c.data.bend_positive = bend_positive;
+                if app_state.ui.auto_keyframe {
+                    app_state.animation.auto_key_ik(&id);
+                }
+                app_state.animation.project.skeleton.update();
+                app_state.sync_animation_to_layers();
+                app_state.is_dirty = true;
+                app_state.view.needs_full_redraw = true;
+            }
+        }
+        AppCommand::SetSlotAttachment { slot_id, attachment } => {
+            let keying = app_state.ui.auto_keyframe && app_state.animation.project.active_animation_id.is_some();
+            let old_skel = app_state.animation.project.skeleton.clone();
+            if let Some(slot) = app_state.animation.project.skeleton.slots.iter_mut().find(|s| s.data.id == slot_id) {
+                slot.current_attachment = attachment.clone();
+                if keying {
+                    key_with_undo(app_state, &slot_id, TimelineProperty::Attachment, |app_state| {
+                        app_state.animation.key_value(&slot_id, TimelineProperty::Attachment, KeyframeValue::Attachment(attachment));
+                    });
+                } else {
+                    // 未开启自动关键帧时修改装配姿态下显示的图层
+                    slot.data.attachment = attachment;
+                    app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
+                }
+                app_state.sync_animation_to_layers();
+                app_state.is_dirty = true;
+                app_state.view.needs_full_redraw = true;
+            }
+        }
+        AppCommand::MoveSlotDrawOrder { slot_id, delta } => {
+            // 绘制顺序只存在于动画轨道中，必须记录为关键帧
+            if !app_state.ui.auto_keyframe || app_state.animation.project.active_animation_id.is_none() {
+                app_state.ui.error_message = Some("调整绘制顺序需要选中动画并开启自动关键帧。".to_string());
+                return;
+            }
+            let mut order = AppState::slot_draw_order(&app_state.animation.project.skeleton, &app_state.engine.store().layers);
+            if let Some(idx) = order.iter().position(|id| *id == slot_id) {
+                let new_idx = (idx as i32 + delta).clamp(0, order.len() as i32 - 1) as usize;
+                if new_idx != idx {
+                    let id = order.remove(idx);
+                    order.insert(new_idx, id);
+                    app_state.animation.project.skeleton.draw_order = Some(order);
+                    key_with_undo(app_state, DRAW_ORDER_TARGET, TimelineProperty::DrawOrder, |app_state| {
+                        key_property(app_state, DRAW_ORDER_TARGET, TimelineProperty::DrawOrder);
+                    });
+                    app_state.sync_animation_to_layers();
+                    app_state.is_dirty = true;
+                    app_state.view.needs_full_redraw = true;
+                }
+            }
+        }
+        AppCommand::AddAnimationEvent(name) => {
+            let time = app_state.animation.project.snap_time(app_state.animation.current_time);
+            edit_events(app_state, |events| {
+                let idx = events.iter().position(|e| e.time > time).unwrap_or(events.len());
+                events.insert(idx, crate::core::animation::event::AnimationEvent::new(name, time));
+                Some(idx)
+            });
+        }
+        AppCommand::UpdateAnimationEvent(index, mut event) => {
+            event.time = app_state.animation.project.snap_time(event.time.max(0.0));
+            edit_events(app_state, |events| {
+                if index >= events.len() || events[index] == event { return None; }
+                events.remove(index);
+                let idx = events.iter().position(|e| e.time > event.time).unwrap_or(events.len());
+                events.insert(idx, event);
+                Some(idx)
+            });
+        }
+        AppCommand::DeleteAnimationEvent(index) => {
+            edit_events(app_state, |events| {
+                if index >= events.len() { return None; }
+                events.remove(index);
+                Some(index)
+            });
+            app_state.ui.selected_event = None;
+        }
+        AppCommand::CreateMesh { layer_id, cell_size } => {
+            let mesh = app_state.engine.store().get_layer(&layer_id).and_then(|l| crate::core::animation::mesh::MeshAttachment::from_layer(l, cell_size));
+            match mesh {
+                Some(mut mesh) => {
+                    let old_skel = app_state.animation.project.skeleton.clone();
+                    mesh.auto_weight(&mesh_weight_bones(&app_state.animation.project.skeleton));
+                    let skeleton = &mut app_state.animation.project.skeleton;
+                    skeleton.meshes.retain(|m| m.layer_id != layer_id);
+                    skeleton.meshes.push(mesh);
+                    commit_mesh_edit(app_state, old_skel);
+                }
+                None => app_state.ui.error_message = Some("图层没有不透明像素，无法生成网格".to_string()),
+            }
+        }
+        AppCommand::DeleteMesh(layer_id) if app_state.animation.project.skeleton.mesh(&layer_id).is_some() => {
+            let old_skel = app_state.animation.project.skeleton.clone();
+            // 保留变形轨道，撤销删除后动画依然完整
+            app_state.animation.project.skeleton.meshes.retain(|m| m.layer_id != layer_id);
+            app_state.ui.selected_mesh_vertex = None;
+            commit_mesh_edit(app_state, old_skel);
+        }
+        AppCommand::AutoWeightMesh(layer_id) if app_state.animation.project.skeleton.mesh(&layer_id).is_some() => {
+            let old_skel = app_state.animation.project.skeleton.clone();
+            let bones = mesh_weight_bones(&app_state.animation.project.skeleton);
+            if let Some(mesh) = app_state.animation.project.skeleton.mesh_mut(&layer_id) {
+                mesh.auto_weight(&bones);
+            }
+            commit_mesh_edit(app_state, old_skel);
+        }
+        AppCommand::SetOnionSkin(settings) if app_state.animation.project.onion_skin != settings => {
+            app_state.animation.project.onion_skin = settings;
+            app_state.is_dirty = true;
+        }
         _ => {}
     }
-}
\ No newline at end of file
+}
+
+// 装配姿态下各骨骼的 (id, 世界矩阵, 长度)，用于自动权重
+fn mesh_weight_bones(skeleton: &crate::core::animation::skeleton::Skeleton) -> Vec<(String, [f32; 6], f32)> {
+    skeleton.bones.iter().zip(skeleton.setup_world_matrices())
+        .map(|(b, m)| (b.data.id.clone(), m, b.data.length))
+        .collect()
+}
+
+fn commit_mesh_edit(app_state: &mut AppState, old_skel: crate::core::animation::skeleton::Skeleton) {
+    app_state.animation.history.commit(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app_state.animation.project.skeleton.clone()) });
+    app_state.sync_animation_to_layers();
+    app_state.is_dirty = true;
+    app_state.view.needs_full_redraw = true;
+}
+
+fn key_properties(app_state: &AppState, id: &str) -> Vec<TimelineProperty> {
+    let skeleton = &app_state.animation.project.skeleton;
+    if id == DRAW_ORDER_TARGET {
+        vec![TimelineProperty::DrawOrder]
+    } else if layer_effect_amount(app_state, id).is_some() {
+        vec![TimelineProperty::EffectAmount]
+    } else if skeleton.ik_constraints.iter().any(|c| c.data.id == id) {
+        vec![TimelineProperty::Ik]
+    } else if skeleton.slots.iter().any(|s| s.data.id == id) && !skeleton.bones.iter().any(|b| b.data.id == id) {
+        // 插槽 id 与其网格图层 id 相同时一并记录变形
+        let mut props = vec![TimelineProperty::Attachment];
+        if skeleton.mesh(id).is_some() { props.push(TimelineProperty::Deform); }
+        props
+    } else if skeleton.mesh(id).is_some() && !skeleton.bones.iter().any(|b| b.data.id == id) {
+        vec![TimelineProperty::Deform]
+    } else {
+        let mut props = vec![TimelineProperty::Translation, TimelineProperty::Rotation, TimelineProperty::Scale];
+        if app_state.animation.bone_uses_shear(id) { props.push(TimelineProperty::Shear); }
+        props
+    }
+}
+
+// 图层效果当前的数值参数，id 不是带数值参数的效果时为 None
+fn layer_effect_amount(app_state: &AppState, effect_id: &str) -> Option<f32> {
+    app_state.engine.store().layers.iter()
+        .flat_map(|l| l.effects.iter())
+        .find(|e| e.id == effect_id)
+        .and_then(|e| e.kind.amount())
+}
+
+// 以当前运行时状态为值在当前时间写入关键帧
+fn key_property(app_state: &mut AppState, id: &str, prop: TimelineProperty) {
+    match prop {
+        TimelineProperty::Ik => app_state.animation.auto_key_ik(id),
+        TimelineProperty::Deform => app_state.animation.auto_key_deform(id),
+        TimelineProperty::Attachment => {
+            let attachment = app_state.animation.project.skeleton.slots.iter().find(|s| s.data.id == id).map(|s| s.current_attachment.clone());
+            if let Some(attachment) = attachment {
+                app_state.animation.key_value(id, TimelineProperty::Attachment, KeyframeValue::Attachment(attachment));
+            }
+        }
+        TimelineProperty::EffectAmount => {
+            if let Some(value) = layer_effect_amount(app_state, id) {
+                app_state.animation.key_value(id, TimelineProperty::EffectAmount, KeyframeValue::EffectAmount(value));
+            }
+        }
+        TimelineProperty::DrawOrder => {
+            let order = AppState::slot_draw_order(&app_state.animation.project.skeleton, &app_state.engine.store().layers);
+            app_state.animation.key_value(DRAW_ORDER_TARGET, TimelineProperty::DrawOrder, KeyframeValue::DrawOrder(order));
+        }
+        _ => app_state.animation.auto_key_bone(id, prop),
+    }
+}
+
+// 在当前动画上记录关键帧，并把目标轨道的变化作为一步撤销
+fn key_with_undo<F>(app_state: &mut AppState, target_id: &str, prop: TimelineProperty, key: F)
+where
+    F: FnOnce(&mut AppState),
+{
+    let anim_id = match app_state.animation.project.active_animation_id.clone() {
+        Some(id) => id,
+        None => return,
+    };
+    let find = |app_state: &AppState| app_state.animation.project.animations.get(&anim_id)
+        .and_then(|a| a.timelines.iter().find(|t| t.target_id == target_id && t.property == prop))
+        .cloned();
+    let old = find(app_state);
+    key(app_state);
+    let new = find(app_state);
+    if old != new {
+        app_state.animation.history.commit(AnimPatch::Timeline { anim_id: anim_id.clone(), bone_id: target_id.to_string(), prop, old, new });
+    }
+}
+
+// 修改当前动画的事件列表并记录撤销；edit 返回 None 表示未修改，否则返回需选中的事件索引
+fn edit_events<F>(app_state: &mut AppState, edit: F)
+where
+    F: FnOnce(&mut Vec<crate::core::animation::event::AnimationEvent>) -> Option<usize>,
+{
+    let anim_id = match app_state.animation.project.active_animation_id.clone() {
+        Some(id) => id,
+        None => return,
+    };
+    if let Some(anim) = app_state.animation.project.animations.get_mut(&anim_id) {
+        let old = anim.events.clone();
+        if let Some(selected) = edit(&mut anim.events) {
+            anim.recalculate_duration();
+            let new = anim.events.clone();
+            app_state.animation.history.commit(AnimPatch::Events { anim_id, old, new });
+            app_state.ui.selected_event = Some(selected);
+            app_state.is_dirty = true;
+        }
+    }
+}
+
+// 预设写入用户文件成功后才更新内存中的列表
+fn save_curve_presets(app_state: &mut AppState, presets: Vec<crate::core::animation::easing::CurvePreset>) {
+    match crate::format::curve_presets::save_presets(&app_state.ui.curve_presets_path, &presets) {
+        Ok(()) => app_state.ui.curve_presets = presets,
+        Err(e) => app_state.ui.error_message = Some(format!("保存曲线预设失败: {}", e)),
+    }
+}
+
+// 编辑当前动画的轨道并把所有变化记为一步撤销；edit 返回 None 表示未修改，否则返回新的关键帧选区
+fn edit_active_timelines<F>(app_state: &mut AppState, edit: F)
+where
+    F: FnOnce(&mut crate::core::animation::timeline::Animation) -> Option<Vec<(String, Option<TimelineProperty>, f32)>>,
+{
+    let anim_id = match app_state.animation.project.active_animation_id.clone() {
+        Some(id) => id,
+        None => return,
+    };
+    let anim = match app_state.animation.project.animations.get_mut(&anim_id) {
+        Some(anim) => anim,
+        None => return,
+    };
+    let old_timelines = anim.timelines.clone();
+    let selection = match edit(anim) {
+        Some(selection) => selection,
+        None => return,
+    };
+    let mut patches = Vec::new();
+    for new_tl in &anim.timelines {
+        let old_tl = old_timelines.iter().find(|t| t.target_id == new_tl.target_id && t.property == new_tl.property);
+        if old_tl != Some(new_tl) {
+            patches.push(AnimPatch::Timeline {
+                anim_id: anim_id.clone(),
+                bone_id: new_tl.target_id.clone(),
+                prop: new_tl.property.clone(),
+                old: old_tl.cloned(),
+                new: Some(new_tl.clone()),
+            });
+        }
+    }
+    if patches.is_empty() { return; }
+    app_state.animation.history.commit(AnimPatch::Composite(patches));
+    app_state.ui.selected_keyframes = selection;
+    crate::animation::controller::AnimationController::apply_current_pose(&mut app_state.animation);
+    app_state.sync_animation_to_layers();
+    app_state.is_dirty = true;
+    app_state.view.needs_full_redraw = true;
+}
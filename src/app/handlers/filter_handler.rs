@@ -0,0 +1,140 @@
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::app::handlers::palette_handler::mask_bounds;
+use crate::core::error::CoreError;
+use crate::core::id_gen;
+use crate::core::layer::Layer;
+use crate::core::store::PixelPreview;
+use crate::history::patch::ActionPatch;
+use crate::render::filters::{self, FilterSettings};
+
+// 滤镜对话框打开期间，合成结果显示调整后的图层 (PixelStore::pixel_preview)，图层像素不变
+#[derive(Debug, Clone)]
+pub struct FilterPreview {
+    pub layer_id: String,
+    // 图层坐标下的选区标记，None 表示整个图层
+    pub mask: Option<Vec<bool>>,
+    pub bounds: (u32, u32, u32, u32),
+    pub settings: FilterSettings,
+}
+
+impl FilterPreview {
+    fn filtered(&self, layer: &Layer, palette: &[crate::core::color::Color]) -> Layer {
+        let mut layer = layer.clone();
+        filters::apply_filter(&mut layer, self.mask.as_deref(), self.bounds, &self.settings, palette);
+        layer
+    }
+}
+
+// 预览期间图层被修改 (绘制、撤销等) 时需要基于新的像素重新计算
+pub fn preview_is_stale(app_state: &AppState) -> bool {
+    let Some(preview) = &app_state.ui.filter_preview else { return false };
+    let store = app_state.engine.store();
+    let version = store.get_layer(&preview.layer_id).map(|l| l.version);
+    store.pixel_preview.as_ref().map(|p| p.source_version) != version
+}
+
+fn set_pixel_preview(app_state: &mut AppState, preview: Option<PixelPreview>) {
+    let (store, _, _) = app_state.engine.parts_mut();
+    store.pixel_preview = preview;
+    app_state.engine.update_render_cache(None);
+    app_state.view.needs_full_redraw = true;
+}
+
+// 关闭预览，合成恢复为图层本身的像素；撤销 / 重做等操作之前也会调用
+pub fn cancel_preview(app_state: &mut AppState) {
+    if app_state.ui.filter_preview.take().is_some() {
+        set_pixel_preview(app_state, None);
+    }
+}
+
+fn refresh_preview(app_state: &mut AppState) {
+    let Some(preview) = &app_state.ui.filter_preview else { return };
+    let (store, _, _) = app_state.engine.parts_mut();
+    let Some(layer) = store.get_layer(&preview.layer_id) else { return };
+    let mut filtered = preview.filtered(layer, &store.palette.colors);
+    let source_version = layer.version;
+    // 预览结果的 version 与图层及效果缓存都不同，保证 GPU 纹理与效果缓存随预览更新
+    store.effect_cache_serial += 1;
+    filtered.version = (1 << 62) | store.effect_cache_serial;
+    set_pixel_preview(app_state, Some(PixelPreview { source_version, layer: filtered }));
+}
+
+pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
+    match cmd {
+        AppCommand::BeginFilter(adjustment) => {
+            cancel_preview(app_state);
+            let store = app_state.engine.store();
+            let Some(layer) = store.active_layer_id.as_ref().and_then(|id| store.get_layer(id)) else { return };
+            if layer.locked {
+                app_state.ui.error_message = Some(CoreError::LayerLocked.to_string());
+                return;
+            }
+            let mask = store.selection.layer_mask(layer);
+            let Some(bounds) = mask_bounds(mask.as_deref(), layer.width, layer.height) else { return };
+            app_state.ui.filter_preview = Some(FilterPreview {
+                layer_id: layer.id.clone(),
+                mask,
+                bounds,
+                settings: FilterSettings { adjustment, snap_to_palette: None },
+            });
+            refresh_preview(app_state);
+        }
+        AppCommand::UpdateFilterPreview(settings) => {
+            let stale = preview_is_stale(app_state);
+            let Some(preview) = app_state.ui.filter_preview.as_mut() else { return };
+            if preview.settings == settings && !stale { return; }
+            preview.settings = settings;
+            refresh_preview(app_state);
+        }
+        AppCommand::ApplyFilter => {
+            let Some(preview) = app_state.ui.filter_preview.take() else { return };
+            set_pixel_preview(app_state, None);
+            let store = app_state.engine.store();
+            let Some(layer) = store.get_layer(&preview.layer_id) else { return };
+            let filtered = preview.filtered(layer, &store.palette.colors);
+
+            // 只记录包围盒内的像素，作为一步撤销
+            let (x, y, w, h) = preview.bounds;
+            let old_data = layer.get_rect_data(x, y, w, h);
+            let new_data = filtered.get_rect_data(x, y, w, h);
+            if old_data == new_data { return; }
+            let patch = ActionPatch::new_region_diff(id_gen::gen_id(), preview.layer_id, x, y, w, h, old_data, new_data);
+            if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+            else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+        }
+        AppCommand::CancelFilter => cancel_preview(app_state),
+        AppCommand::ApplyEffect { layer_id, effect } => {
+            cancel_preview(app_state);
+            let store = app_state.engine.store();
+            let Some(layer) = store.get_layer(&layer_id) else { return };
+            if layer.locked {
+                app_state.ui.error_message = Some(CoreError::LayerLocked.to_string());
+                return;
+            }
+            // 效果读取整个图层的形状，但只改动选区内的像素
+            let (width, height) = (layer.width, layer.height);
+            let mask = store.selection.layer_mask(layer);
+            let old_full = layer.get_rect_data(0, 0, width, height);
+            let mut new_full = effect.apply(&old_full, width, height);
+            if let Some(mask) = &mask {
+                for (i, _) in mask.iter().enumerate().filter(|(_, &m)| !m) {
+                    new_full[i * 4..i * 4 + 4].copy_from_slice(&old_full[i * 4..i * 4 + 4]);
+                }
+            }
+            let changed: Vec<bool> = old_full.chunks_exact(4).zip(new_full.chunks_exact(4)).map(|(a, b)| a != b).collect();
+            let Some((x, y, w, h)) = mask_bounds(Some(&changed), width, height) else { return };
+
+            let crop = |data: &[u8]| -> Vec<u8> {
+                (y..y + h).flat_map(|row| {
+                    let start = ((row * width + x) * 4) as usize;
+                    data[start..start + (w * 4) as usize].to_vec()
+                }).collect()
+            };
+            let patch = ActionPatch::new_region_diff(id_gen::gen_id(), layer_id, x, y, w, h, crop(&old_full), crop(&new_full));
+            if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+            else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+        }
+        _ => {}
+    }
+}
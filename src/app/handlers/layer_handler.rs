@@ -3,6 +3,14 @@ use crate::app::commands::AppCommand;
 use crate::core::id_gen;
 use crate::history::patch::ActionPatch;
 
+fn set_layer_effects(app_state: &mut AppState, id: String, effects: Vec<crate::core::layer_effect::LayerEffect>) {
+    let Some(layer) = app_state.engine.store().get_layer(&id) else { return };
+    if layer.effects == effects { return; }
+    let patch = ActionPatch::new_layer_effects(id_gen::gen_id(), id, layer.effects.clone(), effects);
+    if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+    else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+}
+
 pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
     match cmd {
         AppCommand::ToggleLayerLock(id) => {
@@ -33,6 +41,14 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                 }
             }
         }
+        AppCommand::AddLayerEffect(id, kind) => {
+            if let Some(layer) = app_state.engine.store().get_layer(&id) {
+                let mut effects = layer.effects.clone();
+                effects.push(crate::core::layer_effect::LayerEffect::new(id_gen::gen_id(), kind));
+                set_layer_effects(app_state, id, effects);
+            }
+        }
+        AppCommand::SetLayerEffects(id, effects) => set_layer_effects(app_state, id, effects),
         AppCommand::MoveLayerUp(id) => {
             if let Some(idx) = app_state.engine.store().layers.iter().position(|l| l.id == id) {
                 if idx + 1 < app_state.engine.store().layers.len() {
@@ -61,6 +77,28 @@ pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
                 }
             }
         }
+        AppCommand::TransformLayer(id, op) => {
+            let old_layers = app_state.engine.store().layers.clone();
+            let index = match old_layers.iter().position(|l| l.id == id) { Some(i) => i, None => return };
+            if old_layers[index].locked {
+                app_state.ui.error_message = Some(crate::core::error::CoreError::LayerLocked.to_string());
+                return;
+            }
+
+            // 图层尺寸不变：非正方形图层旋转 90° 时以中心对齐，超出的部分被裁掉
+            let mut new_layers = old_layers.clone();
+            let layer = &mut new_layers[index];
+            let (w, h) = (layer.width, layer.height);
+            let (dx, dy) = if op.swaps_axes() { ((w as i32 - h as i32) / 2, (h as i32 - w as i32) / 2) } else { (0, 0) };
+            layer.apply_lossless(op, dx, dy, w, h);
+
+            let store = app_state.engine.store();
+            let (cw, ch) = (store.canvas_width, store.canvas_height);
+            let selection = store.selection.clone();
+            let patch = ActionPatch::new_canvas_resize(id_gen::gen_id(), cw, ch, cw, ch, old_layers, new_layers, selection.clone(), selection);
+            if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+            else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+        }
         AppCommand::RenameLayer(id, new_name) => {
             if let Some(layer) = app_state.engine.store().get_layer(&id) {
                 let trimmed_name = new_name.trim().to_string();
@@ -1,2 +1,4 @@
 pub mod anim_handler;
-pub mod layer_handler;
\ No newline at end of file
+pub mod layer_handler;
+pub mod palette_handler;
+pub mod filter_handler;
\ No newline at end of file
@@ -0,0 +1,131 @@
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::core::id_gen;
+use crate::core::palette::{self, Palette};
+use crate::core::error::CoreError;
+use crate::history::patch::ActionPatch;
+use crate::render::quantize;
+
+// 在当前调色板的副本上修改，有变化时作为一步可撤销的操作提交
+fn update_palette<F: FnOnce(&mut Palette)>(app_state: &mut AppState, f: F) {
+    let old = app_state.engine.store().palette.clone();
+    let mut new = old.clone();
+    f(&mut new);
+    if new == old { return; }
+    let patch = ActionPatch::new_palette_change(id_gen::gen_id(), old, new);
+    if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+    else { app_state.is_dirty = true; }
+}
+
+// 图层中要处理的矩形 (图层坐标)：选区激活时为选区包围盒与图层的交集，mask 标记其中被选中的像素
+struct LayerRegion {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    mask: Vec<bool>,
+}
+
+fn layer_region(app_state: &AppState, layer_id: &str) -> Option<LayerRegion> {
+    let store = app_state.engine.store();
+    let layer = store.get_layer(layer_id)?;
+    let layer_mask = store.selection.layer_mask(layer);
+    let (x, y, width, height) = mask_bounds(layer_mask.as_deref(), layer.width, layer.height)?;
+    let mask = match &layer_mask {
+        Some(m) => (y..y + height).flat_map(|row| (x..x + width).map(move |col| m[(row * layer.width + col) as usize])).collect(),
+        None => vec![true; (width * height) as usize],
+    };
+    Some(LayerRegion { x, y, width, height, data: layer.get_rect_data(x, y, width, height), mask })
+}
+
+// 选中像素的包围盒 (x, y, w, h)，没有选中任何像素时返回 None
+pub fn mask_bounds(mask: Option<&[bool]>, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+    let Some(mask) = mask else { return (width > 0 && height > 0).then_some((0, 0, width, height)) };
+    let (mut x0, mut y0, mut x1, mut y1) = (u32::MAX, u32::MAX, 0, 0);
+    for (i, _) in mask.iter().enumerate().filter(|(_, &m)| m) {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1));
+    }
+    (x0 < x1 && y0 < y1).then(|| (x0, y0, x1 - x0, y1 - y0))
+}
+
+fn is_locked(app_state: &mut AppState, layer_id: &str) -> bool {
+    let locked = app_state.engine.store().get_layer(layer_id).is_some_and(|l| l.locked);
+    if locked { app_state.ui.error_message = Some(CoreError::LayerLocked.to_string()); }
+    locked
+}
+
+// 把区域映射到调色板，像素有变化时返回 RegionDiffPatch
+fn remap_patch(layer_id: &str, region: &LayerRegion, colors: &[crate::core::color::Color], settings: quantize::QuantizeSettings) -> Option<ActionPatch> {
+    let mut new_data = region.data.clone();
+    quantize::remap_to_palette(&mut new_data, region.width, region.height, Some(&region.mask), colors, settings);
+    if new_data == region.data { return None; }
+    Some(ActionPatch::new_region_diff(
+        id_gen::gen_id(), layer_id.to_string(), region.x, region.y, region.width, region.height,
+        region.data.clone(), new_data,
+    ))
+}
+
+fn commit_pixels(app_state: &mut AppState, patch: ActionPatch) {
+    if let Err(e) = app_state.engine.commit_patch(patch) { app_state.ui.error_message = Some(e.to_string()); }
+    else { app_state.is_dirty = true; app_state.view.needs_full_redraw = true; }
+}
+
+pub fn execute(app_state: &mut AppState, cmd: AppCommand) {
+    match cmd {
+        AppCommand::AddColorToPalette(color) => update_palette(app_state, |p| p.add_color(color)),
+        AppCommand::RemovePaletteColor(idx) => update_palette(app_state, |p| p.remove_color(idx)),
+        AppCommand::SetPalette(palette) => update_palette(app_state, |p| *p = palette),
+        AppCommand::RenamePaletteColor(idx, name) => update_palette(app_state, |p| p.set_color_name(idx, name)),
+        AppCommand::AddPaletteRamp { name, colors } => update_palette(app_state, |p| { p.add_ramp(name, &colors); }),
+        AppCommand::AssignPaletteRamp { indices, ramp } => update_palette(app_state, |p| p.assign_ramp(&indices, ramp)),
+        AppCommand::RenamePaletteRamp(id, name) => update_palette(app_state, |p| p.rename_ramp(id, name)),
+        AppCommand::RemovePaletteRamp(id) => update_palette(app_state, |p| p.remove_ramp(id)),
+        AppCommand::SortPalette(key) => update_palette(app_state, |p| p.sort(key)),
+        AppCommand::DedupePalette => update_palette(app_state, |p| { p.dedupe(); }),
+        AppCommand::MergePalette(other) => update_palette(app_state, |p| p.merge(&other)),
+        AppCommand::CreatePaletteFromUsedColors => {
+            let used = palette::used_colors(&app_state.engine.store().layers);
+            if used.is_empty() {
+                app_state.ui.error_message = Some(rust_i18n::t!("error.no_used_colors").to_string());
+                return;
+            }
+            if used.len() > 4096 {
+                app_state.ui.error_message = Some(rust_i18n::t!("error.too_many_colors").to_string());
+                return;
+            }
+            // 使用次数记在颜色名称中
+            let mut new = Palette::from_colors(rust_i18n::t!("palette.used_colors_name").to_string(), used.iter().map(|(c, _)| *c).collect());
+            new.names = used.iter().map(|(_, count)| format!("{} px", count)).collect();
+            update_palette(app_state, |p| *p = new);
+        }
+        AppCommand::QuantizeLayer { layer_id, settings } => {
+            let colors = app_state.engine.store().palette.colors.clone();
+            if colors.is_empty() {
+                app_state.ui.error_message = Some(rust_i18n::t!("error.palette_empty").to_string());
+                return;
+            }
+            if is_locked(app_state, &layer_id) { return; }
+            let Some(region) = layer_region(app_state, &layer_id) else { return };
+            if let Some(patch) = remap_patch(&layer_id, &region, &colors, settings) { commit_pixels(app_state, patch); }
+        }
+        AppCommand::GeneratePaletteFromLayer { layer_id, method, count, remap } => {
+            if remap.is_some() && is_locked(app_state, &layer_id) { return; }
+            let Some(region) = layer_region(app_state, &layer_id) else { return };
+            let colors = quantize::generate_palette(&region.data, Some(&region.mask), count, method);
+            if colors.is_empty() {
+                app_state.ui.error_message = Some(rust_i18n::t!("error.no_opaque_pixels").to_string());
+                return;
+            }
+
+            let old = app_state.engine.store().palette.clone();
+            let layer_name = app_state.engine.store().get_layer(&layer_id).map(|l| l.name.clone()).unwrap_or_default();
+            let new = Palette::from_colors(rust_i18n::t!("palette.generated_name", name = layer_name, count = colors.len()).to_string(), colors.clone());
+            let mut patches = vec![ActionPatch::new_palette_change(id_gen::gen_id(), old, new)];
+            if let Some(patch) = remap.and_then(|settings| remap_patch(&layer_id, &region, &colors, settings)) { patches.push(patch); }
+            commit_pixels(app_state, ActionPatch::new_composite(id_gen::gen_id(), patches));
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,142 @@
+use crate::core::layer::Layer;
+use crate::render::pixel_grid::{self, PixelGrid};
+
+// 导入图片的尺寸处理：保持原始像素尺寸，或等比缩放到画布内
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSizing {
+    Keep,
+    Fit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportTarget {
+    NewLayer,
+    NewCanvas,
+}
+
+// RGBA 数据与宽高
+pub type ImportImage = (Vec<u8>, u32, u32);
+
+// 生成放置结果的参数：还原网格、缩放方式与目标画布宽高
+type PlacedKey = (Option<PixelGrid>, ImportSizing, u32, u32);
+
+// 还原网格与缩放的结果，按生成它们的参数缓存：对话框每帧读取尺寸时不重复计算
+#[derive(Debug, Clone, Default)]
+pub struct ImportCache {
+    source: Option<(Option<PixelGrid>, ImportImage)>,
+    placed: Option<(PlacedKey, ImportImage)>,
+}
+
+// 导入图片时等待用户确认的内容
+#[derive(Debug, Clone)]
+pub struct ImageImport {
+    pub source_path: String,
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    // 自动检测到的放大网格，以及用户确认后实际使用的网格 (None 为不还原)
+    pub detected_grid: Option<PixelGrid>,
+    pub snap_grid: Option<PixelGrid>,
+    pub sizing: ImportSizing,
+    // 作为新图层时左上角在画布上的位置
+    pub offset_x: i32,
+    pub offset_y: i32,
+    pub target: ImportTarget,
+    pub cache: ImportCache,
+}
+
+impl ImageImport {
+    // 检测到放大网格时默认还原为原生像素
+    pub fn new(source_path: String, rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        let detected_grid = pixel_grid::detect_grid(&rgba, width, height);
+        Self {
+            source_path,
+            rgba,
+            width,
+            height,
+            detected_grid,
+            snap_grid: detected_grid,
+            sizing: ImportSizing::Keep,
+            offset_x: 0,
+            offset_y: 0,
+            target: ImportTarget::NewLayer,
+            cache: ImportCache::default(),
+        }
+    }
+
+    // 还原网格后的图像
+    pub fn source_image(&mut self) -> &ImportImage {
+        if self.cache.source.as_ref().is_none_or(|(grid, _)| *grid != self.snap_grid) {
+            let image = match self.snap_grid {
+                Some(grid) if grid.cell > 1 => pixel_grid::snap_to_grid(&self.rgba, self.width, self.height, grid),
+                _ => (self.rgba.clone(), self.width, self.height),
+            };
+            self.cache.source = Some((self.snap_grid, image));
+        }
+        let Some((_, image)) = &self.cache.source else { unreachable!() };
+        image
+    }
+
+    // 放到 canvas_width x canvas_height 的画布上之前的最终图像。
+    // Fit 时放大只用整数倍 (不产生半个像素)，缩小则按比例最近邻取样
+    pub fn placed_image(&mut self, canvas_width: u32, canvas_height: u32) -> &ImportImage {
+        let key = (self.snap_grid, self.sizing, canvas_width, canvas_height);
+        if self.cache.placed.as_ref().is_none_or(|(k, _)| *k != key) {
+            let sizing = self.sizing;
+            let (rgba, w, h) = self.source_image();
+            let (w, h) = (*w, *h);
+            let image = if sizing == ImportSizing::Keep || w == 0 || h == 0 {
+                (rgba.clone(), w, h)
+            } else if w <= canvas_width && h <= canvas_height {
+                let factor = (canvas_width / w).min(canvas_height / h).max(1);
+                scale_nearest(rgba, w, h, w * factor, h * factor)
+            } else {
+                let ratio = (canvas_width as f64 / w as f64).min(canvas_height as f64 / h as f64);
+                let new_w = ((w as f64 * ratio).round() as u32).clamp(1, canvas_width);
+                let new_h = ((h as f64 * ratio).round() as u32).clamp(1, canvas_height);
+                scale_nearest(rgba, w, h, new_w, new_h)
+            };
+            self.cache.placed = Some((key, image));
+        }
+        let Some((_, image)) = &self.cache.placed else { unreachable!() };
+        image
+    }
+
+    pub fn centered_offset(&mut self, canvas_width: u32, canvas_height: u32) -> (i32, i32) {
+        let &(_, w, h) = self.placed_image(canvas_width, canvas_height);
+        ((canvas_width as i32 - w as i32) / 2, (canvas_height as i32 - h as i32) / 2)
+    }
+
+    // 画布大小的新图层，超出画布的部分被裁掉
+    pub fn to_layer(&mut self, canvas_width: u32, canvas_height: u32, id: String, name: String) -> Layer {
+        let (offset_x, offset_y) = (self.offset_x, self.offset_y);
+        let (rgba, w, h) = self.placed_image(canvas_width, canvas_height);
+        let (w, h) = (*w, *h);
+        let mut layer = Layer::new(id, name, canvas_width, canvas_height);
+        for y in 0..h {
+            for x in 0..w {
+                let (cx, cy) = (x as i32 + offset_x, y as i32 + offset_y);
+                if cx < 0 || cy < 0 || cx >= canvas_width as i32 || cy >= canvas_height as i32 { continue; }
+                let idx = ((y * w + x) * 4) as usize;
+                if rgba[idx + 3] == 0 { continue; }
+                let color = crate::core::color::Color::new(rgba[idx], rgba[idx + 1], rgba[idx + 2], rgba[idx + 3]);
+                let _ = layer.set_pixel(cx as u32, cy as u32, color);
+            }
+        }
+        layer
+    }
+}
+
+fn scale_nearest(rgba: &[u8], width: u32, height: u32, new_width: u32, new_height: u32) -> (Vec<u8>, u32, u32) {
+    let mut out = vec![0u8; (new_width * new_height * 4) as usize];
+    for y in 0..new_height {
+        let sy = (y as u64 * height as u64 / new_height as u64) as u32;
+        for x in 0..new_width {
+            let sx = (x as u64 * width as u64 / new_width as u64) as u32;
+            let src = ((sy * width + sx) * 4) as usize;
+            let dst = ((y * new_width + x) * 4) as usize;
+            out[dst..dst + 4].copy_from_slice(&rgba[src..src + 4]);
+        }
+    }
+    (out, new_width, new_height)
+}
@@ -18,6 +18,11 @@ impl InputHandler {
                 }
             }
             let click_res = Self::handle_animation_click(app, x, y);
+            match app.engine.tool_manager().active_type {
+                ToolType::MeshEdit => Self::pick_mesh_vertex(app, x, y),
+                ToolType::WeightPaint => Self::paint_mesh_weight(app, x, y),
+                _ => {}
+            }
             if let Some(tool) = app.engine.tool_manager_mut().tools.get_mut(&ToolType::CreateBone) {
                 if let Some(bone_tool) = tool.as_any_mut().downcast_mut::<crate::tools::create_bone::CreateBoneTool>() {
                     bone_tool.parent_bone_id = app.ui.selected_bone_id.clone();
@@ -46,11 +51,30 @@ impl InputHandler {
             EngineEffect::Error(e) => Err(e.clone()),
             _ => Ok(()),
         };
+        if result.is_ok() { Self::record_recent_color(app); }
 
         Self::handle_engine_effect(app, effect);
         result
     }
 
+    // 右键绘制：本次笔画 (直到松开) 使用副色
+    pub fn on_secondary_mouse_down(app: &mut AppState, x: u32, y: u32) -> Result<(), CoreError> {
+        app.engine.parts_mut().0.paint_with_secondary = true;
+        Self::on_mouse_down(app, x, y)
+    }
+
+    // 用颜色绘制时记入最近使用
+    fn record_recent_color(app: &mut AppState) {
+        let tool = app.engine.tool_manager().active_type;
+        let store = app.engine.parts_mut().0;
+        let paints = match tool {
+            ToolType::Pencil | ToolType::Bucket => true,
+            ToolType::Eraser => store.paint_with_secondary,
+            _ => false,
+        };
+        if paints { store.push_recent_color(store.paint_color()); }
+    }
+
     pub fn on_mouse_move(app: &mut AppState, x: u32, y: u32) -> Result<(), CoreError> {
         let last_pos = app.last_mouse_pos.unwrap_or((x, y));
         let dx = (x as i32).wrapping_sub(last_pos.0 as i32) as f32;
@@ -64,6 +88,11 @@ impl InputHandler {
 
             if app.engine.tool_manager().is_drawing {
                 let tool = app.engine.tool_manager().active_type;
+                match tool {
+                    ToolType::MeshEdit => { Self::drag_mesh_vertex(app, dx, dy); return Ok(()); }
+                    ToolType::WeightPaint => { Self::paint_mesh_weight(app, x, y); return Ok(()); }
+                    _ => {}
+                }
                 if let Some(bone_id) = app.ui.selected_bone_id.clone() {
                     let skeleton = &mut app.animation.project.skeleton;
                     if let Some(bone_idx) = skeleton.bones.iter().position(|b| b.data.id == bone_id) {
@@ -139,6 +168,7 @@ impl InputHandler {
     pub fn on_mouse_up(app: &mut AppState) -> Result<(), CoreError> {
         let was_drawing = app.engine.tool_manager().is_drawing;
         app.last_mouse_pos = None;
+        app.engine.parts_mut().0.paint_with_secondary = false;
         
         if app.mode == AppMode::Animation {
             app.engine.tool_manager_mut().is_drawing = false;
@@ -159,7 +189,7 @@ impl InputHandler {
             }
             if let Some(old_skel) = app.animation.drag_start_skeleton.take() {
                 let mut patches = Vec::new();
-                patches.push(AnimPatch::Skeleton { old: old_skel, new: app.animation.project.skeleton.clone() });
+                patches.push(AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(app.animation.project.skeleton.clone()) });
 
                 if let Some(old_anim) = app.animation.drag_start_animation.take() {
                     if let Some(id) = &app.animation.project.active_animation_id {
@@ -209,7 +239,89 @@ impl InputHandler {
         result
     }
 
+    // 网格工具作用于活动图层的网格附件
+    fn active_mesh_layer(app: &AppState) -> Option<String> {
+        let layer_id = app.engine.store().active_layer_id.clone()?;
+        app.animation.project.skeleton.mesh(&layer_id).map(|_| layer_id)
+    }
+
+    fn pick_mesh_vertex(app: &mut AppState, x: u32, y: u32) {
+        app.ui.selected_mesh_vertex = None;
+        let mesh = match Self::active_mesh_layer(app).and_then(|id| app.animation.project.skeleton.deformed_mesh(&id)) {
+            Some(m) => m,
+            None => return,
+        };
+        // 命中半径约为 8 个屏幕像素
+        let radius = (8.0 / app.view.zoom_level as f32).max(1.0);
+        let (fx, fy) = (x as f32 + 0.5, y as f32 + 0.5);
+        app.ui.selected_mesh_vertex = mesh.positions.iter().enumerate()
+            .map(|(i, p)| (i, (p[0] - fx).powi(2) + (p[1] - fy).powi(2)))
+            .filter(|(_, d)| *d <= radius * radius)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i);
+    }
+
+    // 开启自动关键帧时拖动写入变形偏移并记录关键帧，否则修改装配姿态下的顶点位置
+    fn drag_mesh_vertex(app: &mut AppState, dx: f32, dy: f32) {
+        let (layer_id, index) = match (Self::active_mesh_layer(app), app.ui.selected_mesh_vertex) {
+            (Some(id), Some(index)) => (id, index),
+            _ => return,
+        };
+        if dx == 0.0 && dy == 0.0 { return; }
+        let keying = app.ui.auto_keyframe && app.animation.project.active_animation_id.is_some();
+        let skeleton = &mut app.animation.project.skeleton;
+        let skin = match skeleton.mesh(&layer_id).and_then(|m| skeleton.vertex_skin_matrices(m).get(index).copied()) {
+            Some(m) => m,
+            None => return,
+        };
+        // 画布上的位移经蒙皮矩阵线性部分的逆换算回装配空间
+        let inv = match crate::core::animation::transform::invert_matrix(&[skin[0], skin[1], skin[2], skin[3], 0.0, 0.0]) {
+            Some(inv) => inv,
+            None => return,
+        };
+        let (bx, by) = (inv[0] * dx + inv[2] * dy, inv[1] * dx + inv[3] * dy);
+        if let Some(mesh) = skeleton.mesh_mut(&layer_id) {
+            if keying {
+                mesh.deform.resize(mesh.vertices.len(), (0.0, 0.0));
+                mesh.deform[index].0 += bx;
+                mesh.deform[index].1 += by;
+            } else {
+                mesh.vertices[index].x += bx;
+                mesh.vertices[index].y += by;
+            }
+        }
+        if keying { app.animation.auto_key_deform(&layer_id); }
+        app.sync_animation_to_layers();
+        app.is_dirty = true;
+        app.view.needs_full_redraw = true;
+    }
+
+    fn paint_mesh_weight(app: &mut AppState, x: u32, y: u32) {
+        let (layer_id, bone_id) = match (Self::active_mesh_layer(app), app.ui.selected_bone_id.clone()) {
+            (Some(layer_id), Some(bone_id)) => (layer_id, bone_id),
+            _ => return,
+        };
+        let skeleton = &mut app.animation.project.skeleton;
+        let positions = match skeleton.deformed_mesh(&layer_id) {
+            Some(m) => m.positions,
+            None => return,
+        };
+        let strength = if app.ui.weight_brush_erase { -app.ui.weight_brush_strength } else { app.ui.weight_brush_strength };
+        let center = (x as f32 + 0.5, y as f32 + 0.5);
+        let changed = skeleton.mesh_mut(&layer_id)
+            .is_some_and(|m| m.paint_weight(&positions, &bone_id, center, app.ui.weight_brush_radius, strength));
+        if changed {
+            app.sync_animation_to_layers();
+            app.is_dirty = true;
+            app.view.needs_full_redraw = true;
+        }
+    }
+
     pub fn handle_animation_click(app: &mut AppState, x: u32, y: u32) -> Result<(), CoreError> {
+        // 网格工具操作的是顶点，保持当前选中的骨骼 (权重绘制的目标)
+        if matches!(app.engine.tool_manager().active_type, ToolType::MeshEdit | ToolType::WeightPaint) {
+            return Ok(());
+        }
         let mut clicked_bone_id = None;
         for bone in &app.animation.project.skeleton.bones {
             let bx = bone.world_matrix[4];
@@ -1,4 +1,3 @@
-use crate::core::layer::Layer;
 use crate::render::compositor::{Compositor, Viewport};
 use crate::core::store::PixelStore;
 use std::path::PathBuf;
@@ -6,6 +5,7 @@ use crate::core::symmetry::SymmetryConfig;
 use crate::format::header::PxadHeader;
 use crate::format::stream::{PxadReader, PxadWriter};
 use crate::app::view_state::ViewState;
+use crate::animation::project::AnimProject;
 use crate::format::block::{read_block, write_block};
 use crate::format::payload::*;
 use std::fs::File;
@@ -29,26 +29,45 @@ impl IoService {
             .save_file()
     }
 
+    // 实际格式由 format::palette_io 按文件头与扩展名判断
     pub fn pick_palette_import_path() -> Option<PathBuf> {
         rfd::FileDialog::new()
+            .add_filter("Palette", &["hex", "txt", "gpl", "act", "pal", "ase", "png", "gif", "bmp"])
             .add_filter("HEX Palette", &["hex", "txt"])
+            .add_filter("GIMP Palette", &["gpl"])
+            .add_filter("Photoshop Color Table", &["act"])
+            .add_filter("JASC / RIFF Palette", &["pal"])
+            .add_filter("Adobe Swatch Exchange", &["ase"])
+            .add_filter("Image", &["png", "gif", "bmp"])
             .pick_file()
     }
 
+    // 按所选扩展名导出，.pal 为 JASC 格式，.png 为 1 像素高的色条
     pub fn pick_palette_export_path() -> Option<PathBuf> {
         rfd::FileDialog::new()
             .set_file_name("palette.hex")
             .add_filter("HEX Palette", &["hex", "txt"])
+            .add_filter("GIMP Palette", &["gpl"])
+            .add_filter("Photoshop Color Table", &["act"])
+            .add_filter("JASC Palette", &["pal"])
+            .add_filter("Adobe Swatch Exchange", &["ase"])
+            .add_filter("PNG", &["png"])
             .save_file()
     }
 
-    pub fn load_as_layer(path: PathBuf, target_width: u32, target_height: u32, id: String, name: String) -> Result<Layer> {
-        let img = image::open(path)?;
-        let resized = img.resize_exact(target_width, target_height, image::imageops::FilterType::Nearest);
-        let rgba = resized.to_rgba8();
-        let mut layer = Layer::new(id, name, target_width, target_height);
-        layer.set_rect_data(0, 0, target_width, target_height, &rgba.into_vec());
-        Ok(layer)
+    // 用户级数据 (如曲线预设) 存放在 %APPDATA%/PXA 下，无该变量时退回 ~/.config/PXA
+    pub fn user_data_path(file_name: &str) -> PathBuf {
+        let base = std::env::var_os("APPDATA").map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+            .unwrap_or_else(std::env::temp_dir);
+        base.join("PXA").join(file_name)
+    }
+
+    // 按原始尺寸读取图片，返回 (像素, 宽, 高)
+    pub fn load_rgba(path: PathBuf) -> Result<(Vec<u8>, u32, u32)> {
+        let rgba = image::open(path)?.to_rgba8();
+        let (w, h) = rgba.dimensions();
+        Ok((rgba.into_vec(), w, h))
     }
 
     pub fn save_png(path: PathBuf, store: &PixelStore) -> Result<()> {
@@ -61,6 +80,18 @@ impl IoService {
         image::save_buffer(path, &pixels, width, height, image::ColorType::Rgba8)?;
         Ok(())
     }
+    pub fn pick_sprite_sheet_path() -> Option<PathBuf> {
+        rfd::FileDialog::new()
+            .set_file_name("sheet.png")
+            .add_filter("PNG", &["png"])
+            .save_file()
+    }
+
+    pub fn save_rgba_png(path: PathBuf, pixels: &[u8], width: u32, height: u32) -> Result<()> {
+        image::save_buffer(path, pixels, width, height, image::ColorType::Rgba8)?;
+        Ok(())
+    }
+
     pub fn pick_project_save_path() -> Option<PathBuf> {
         rfd::FileDialog::new()
             .set_file_name("project.pxad")
@@ -74,18 +105,27 @@ impl IoService {
             .pick_file()
     }
 
-    pub fn save_project(path: PathBuf, store: &PixelStore, symmetry: &SymmetryConfig, view: &ViewState) -> Result<()> {
+    pub fn save_project(path: PathBuf, store: &PixelStore, symmetry: &SymmetryConfig, view: &ViewState, anim: &AnimProject) -> Result<()> {
         let file = File::create(path)?;
         let mut writer = PxadWriter::new(BufWriter::new(file));
 
         let mut header = PxadHeader::new();
-        header.block_count = 4 + store.layers.len() as u64;
+        header.block_count = 7 + store.layers.len() as u64 + anim.animations.len() as u64;
         header.write_to(&mut writer)?;
 
         write_block(&mut writer, *b"CANV", &serialize_canvas(store, view.pan_x, view.pan_y, view.zoom_level))?;
         write_block(&mut writer, *b"SYMM", &serialize_symmetry(symmetry))?;
         write_block(&mut writer, *b"PALT", &serialize_palette(&store.palette))?;
         write_block(&mut writer, *b"SELE", &crate::format::payload::serialize_selection(&store.selection))?;
+        write_block(&mut writer, *b"ONIO", &serialize_onion_skin(&anim.onion_skin))?;
+        write_block(&mut writer, *b"FRMR", &serialize_frame_rate(anim))?;
+        write_block(&mut writer, *b"SKEL", &crate::format::payload::serialize_skeleton(&anim.skeleton))?;
+        let mut anim_ids: Vec<&String> = anim.animations.keys().collect();
+        anim_ids.sort();
+        for id in anim_ids {
+            let is_active = anim.active_animation_id.as_ref() == Some(id);
+            write_block(&mut writer, *b"ANIM", &crate::format::payload::serialize_animation(id, &anim.animations[id], is_active))?;
+        }
 
         for layer in &store.layers {
             write_block(&mut writer, *b"LAYR", &serialize_layer(layer))?;
@@ -95,7 +135,7 @@ impl IoService {
         Ok(())
     }
 
-    pub fn load_project(path: PathBuf) -> Result<(PixelStore, SymmetryConfig, f32, f32, f64)> {
+    pub fn load_project(path: PathBuf) -> Result<(PixelStore, SymmetryConfig, f32, f32, f64, AnimProject)> {
         let file = File::open(path)?;
         let mut reader = PxadReader::new(BufReader::new(file));
 
@@ -113,6 +153,7 @@ impl IoService {
         let mut pan_x = 0.0;
         let mut pan_y = 0.0;
         let mut zoom_level = 1.0;
+        let mut anim = AnimProject::new();
 
         for _ in 0..header.block_count {
             let (b_type, payload) = read_block(&mut reader)?;
@@ -131,6 +172,14 @@ impl IoService {
                 },
                 b"SYMM" => symmetry = deserialize_symmetry(&payload)?,
                 b"SELE" => store.selection = deserialize_selection(&payload)?,
+                b"ONIO" => anim.onion_skin = deserialize_onion_skin(&payload)?,
+                b"FRMR" => deserialize_frame_rate(&payload, &mut anim)?,
+                b"SKEL" => anim.skeleton = crate::format::payload::deserialize_skeleton(&payload)?,
+                b"ANIM" => {
+                    let (id, animation, is_active) = crate::format::payload::deserialize_animation(&payload)?;
+                    if is_active { anim.active_animation_id = Some(id.clone()); }
+                    anim.animations.insert(id, animation);
+                },
                 b"LAYR" => {
                     let layer = deserialize_layer(&payload, header.minor_version)?;
                     if layer.width != store.canvas_width || layer.height != store.canvas_height {
@@ -151,6 +200,6 @@ impl IoService {
             store.active_layer_id = Some(last_layer.id.clone());
         }
 
-        Ok((store, symmetry, pan_x, pan_y, zoom_level))
+        Ok((store, symmetry, pan_x, pan_y, zoom_level, anim))
     }
 }
\ No newline at end of file
@@ -83,6 +83,8 @@ impl LayerService {
             let old_active_id = ctx.store.active_layer_id.clone();
             new_layer.id = format!("layer_{}", id_gen::gen_id());
             new_layer.name = t!("layer.copy_name", name = new_layer.name).to_string();
+            // 效果 id 是动画轨道的目标，副本使用新的 id
+            for effect in &mut new_layer.effects { effect.id = id_gen::gen_id(); }
             
             let patch = ActionPatch::new_layer_add(
                 format!("patch_{}", new_layer.id),
@@ -119,7 +121,8 @@ impl LayerService {
                     let l = &ctx.store.layers[idx];
                     let lx = x as i32 - l.offset_x; let ly = y as i32 - l.offset_y;
                     if lx >= 0 && ly >= 0 && lx < l.width as i32 && ly < l.height as i32 {
-                        if let Some(c) = l.get_pixel(lx as u32, ly as u32) {
+                        // 合并结果包含各图层的效果
+                        if let Some(c) = ctx.store.rendered_layer(l).get_pixel(lx as u32, ly as u32) {
                             let src = [c.r, c.g, c.b, c.a];
                             current_color = blend_pixels(current_color, src, l.blend_mode, l.opacity);
                         }
@@ -6,6 +6,7 @@ pub mod ui_state;
 pub mod view_state;
 pub mod layer_service; 
 pub mod io_service;
+pub mod image_import;
 pub mod tool_manager;
 pub mod context;
 pub mod commands;
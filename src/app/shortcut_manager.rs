@@ -51,6 +51,9 @@ impl ShortcutManager {
         self.bind_pixel_shortcut("t", AppCommand::SelectTool(ToolType::Transform));
         self.bind_pixel_shortcut("c", AppCommand::SelectTool(ToolType::Pen));
 
+        // 交换主色与副色
+        self.bind_pixel_shortcut("x", AppCommand::SwapColors);
+
         self.bind_anim_shortcut("c", AppCommand::SelectTool(ToolType::BoneRotate));
         self.bind_anim_shortcut("v", AppCommand::SelectTool(ToolType::BoneTranslate));
     }
@@ -11,11 +11,21 @@ use std::collections::VecDeque;
 use crate::core::error::CoreError;
 use rust_i18n::t;
 use crate::animation::state::AnimationState;
+use crate::app::image_import::{ImportSizing, ImportTarget};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ToolType { 
     Pencil, Eraser, Bucket, Eyedropper, RectSelect, EllipseSelect, 
-    Move, Transform, Pen, CreateBone, BoneRotate, BoneTranslate 
+    Move, Transform, Pen, CreateBone, BoneRotate, BoneTranslate,
+    // 动画模式：拖动网格顶点 / 为选中骨骼绘制权重
+    MeshEdit, WeightPaint
+}
+
+impl ToolType {
+    // 按右键时改用副色的工具
+    pub fn uses_paint_color(&self) -> bool {
+        matches!(self, ToolType::Pencil | ToolType::Eraser | ToolType::Bucket | ToolType::Eyedropper)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -24,6 +34,9 @@ pub enum AppMode {
     Animation,
 }
 
+// 图层的动画变换矩阵与整数像素偏移
+pub type LayerPoses = (std::collections::HashMap<String, [f32; 6]>, std::collections::HashMap<String, (i32, i32)>);
+
 pub struct AppState {
     pub engine: PxaEngine,
     pub command_queue: VecDeque<AppCommand>,
@@ -59,6 +72,8 @@ impl AppState {
                 state.animation.project.skeleton.slots.push(crate::core::animation::slot::RuntimeSlot::new(slot));
             }
         }
+        // 预设文件不存在时视为空
+        state.ui.curve_presets = crate::format::curve_presets::load_presets(&state.ui.curve_presets_path).unwrap_or_default();
         
         state
     }
@@ -91,6 +106,10 @@ impl AppState {
         crate::app::input_handler::InputHandler::on_mouse_down(self, x, y)
     }
 
+    pub fn on_secondary_mouse_down(&mut self, x: u32, y: u32) -> Result<(), CoreError> {
+        crate::app::input_handler::InputHandler::on_secondary_mouse_down(self, x, y)
+    }
+
     pub fn on_mouse_move(&mut self, x: u32, y: u32) -> Result<(), CoreError> {
         crate::app::input_handler::InputHandler::on_mouse_move(self, x, y)
     }
@@ -128,7 +147,7 @@ impl AppState {
                     slot.attachment = Some(id.clone());
                     let old_skel = self.animation.project.skeleton.clone();
                     self.animation.project.skeleton.slots.push(crate::core::animation::slot::RuntimeSlot::new(slot));
-                    self.animation.history.commit(crate::animation::history::AnimPatch::Skeleton { old: old_skel, new: self.animation.project.skeleton.clone() });
+                    self.animation.history.commit(crate::animation::history::AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(self.animation.project.skeleton.clone()) });
                 }
             }
             self.is_dirty = true;
@@ -144,7 +163,7 @@ impl AppState {
             if let Some(id) = id_to_delete {
                 let old_skel = self.animation.project.skeleton.clone();
                 self.animation.project.skeleton.slots.retain(|s| s.data.id != id);
-                self.animation.history.commit(crate::animation::history::AnimPatch::Skeleton { old: old_skel, new: self.animation.project.skeleton.clone() });
+                self.animation.history.commit(crate::animation::history::AnimPatch::Skeleton { old: Box::new(old_skel), new: Box::new(self.animation.project.skeleton.clone()) });
             }
             self.is_dirty = true;
             self.view.needs_full_redraw = true;
@@ -161,25 +180,70 @@ impl AppState {
 
     pub fn import_image(&mut self) {
         if let Some(path) = IoService::pick_import_path() {
-            let id = format!("layer_imp_{}", id_gen::gen_id());
-            let name = t!("layer.import_name", num = self.engine.store().layers.len() + 1).to_string();
-            let w = self.engine.store().canvas_width;
-            let h = self.engine.store().canvas_height;
-            let old_active_id = self.engine.store().active_layer_id.clone();
-            
-            match IoService::load_as_layer(path, w, h, id.clone(), name) {
-                Ok(layer) => {
-                    let index = self.engine.store().layers.len();
-                    let patch = ActionPatch::new_layer_add(format!("patch_{}", id), id.clone(), layer, index, old_active_id);
-                    if let Err(e) = self.engine.commit_patch(patch) {
-                        self.ui.error_message = Some(e.to_string());
-                    } else {
-                        self.engine.set_active_layer(id);
-                        self.is_dirty = true;
-                        self.view.needs_full_redraw = true;
-                    }
+            self.begin_image_import(path);
+        }
+    }
+
+    // 按原始尺寸读取图片并检测放大网格，等待用户在导入对话框中确认
+    pub fn begin_image_import(&mut self, path: std::path::PathBuf) {
+        match IoService::load_rgba(path.clone()) {
+            Ok((rgba, w, h)) => {
+                let mut import = crate::app::image_import::ImageImport::new(path.display().to_string(), rgba, w, h);
+                let (cw, ch) = (self.engine.store().canvas_width, self.engine.store().canvas_height);
+                (import.offset_x, import.offset_y) = import.centered_offset(cw, ch);
+                self.ui.pending_image_import = Some(import);
+            }
+            Err(e) => self.ui.error_message = Some(t!("error.import_image_failed", err = e.to_string()).to_string()),
+        }
+    }
+
+    pub fn confirm_image_import(&mut self) {
+        let mut import = match self.ui.pending_image_import.take() {
+            Some(import) => import,
+            None => return,
+        };
+        let id = format!("layer_imp_{}", id_gen::gen_id());
+        match import.target {
+            ImportTarget::NewLayer => {
+                let name = t!("layer.import_name", num = self.engine.store().layers.len() + 1).to_string();
+                let (w, h) = (self.engine.store().canvas_width, self.engine.store().canvas_height);
+                let layer = import.to_layer(w, h, id.clone(), name);
+                let old_active_id = self.engine.store().active_layer_id.clone();
+                let index = self.engine.store().layers.len();
+                let patch = ActionPatch::new_layer_add(format!("patch_{}", id), id.clone(), layer, index, old_active_id);
+                if let Err(e) = self.engine.commit_patch(patch) {
+                    self.ui.error_message = Some(e.to_string());
+                } else {
+                    self.engine.set_active_layer(id);
+                    self.is_dirty = true;
+                    self.view.needs_full_redraw = true;
+                }
+            }
+            ImportTarget::NewCanvas => {
+                // 以图片 (还原网格后) 的尺寸新建画布，保留当前调色板；与打开工程一样清空历史与动画
+                let &(_, w, h) = import.source_image();
+                if w == 0 || h == 0 {
+                    self.ui.error_message = Some(t!("error.canvas_size_zero").to_string());
+                    return;
+                }
+                if w > 16384 || h > 16384 {
+                    self.ui.error_message = Some(t!("error.canvas_size_limit", max = 16384).to_string());
+                    return;
                 }
-                Err(e) => self.ui.error_message = Some(t!("error.import_image_failed", err = e.to_string()).to_string()),
+                let name = t!("layer.import_name", num = 1).to_string();
+                let mut store = crate::core::store::PixelStore::new(w, h);
+                store.palette = self.engine.store().palette.clone();
+                (import.sizing, import.offset_x, import.offset_y) = (ImportSizing::Keep, 0, 0);
+                store.add_layer(import.to_layer(w, h, id.clone(), name.clone()));
+                self.engine.replace_store_and_symmetry(store, crate::core::symmetry::SymmetryConfig::new(w, h));
+
+                self.animation = AnimationState::new();
+                let mut slot = crate::core::animation::slot::SlotData::new(id.clone(), name, "root".to_string());
+                slot.attachment = Some(id);
+                self.animation.project.skeleton.slots.push(crate::core::animation::slot::RuntimeSlot::new(slot));
+                self.ui.selected_bone_id = None;
+                self.is_dirty = true;
+                self.view.needs_full_redraw = true;
             }
         }
     }
@@ -194,23 +258,33 @@ impl AppState {
 
     pub fn import_palette(&mut self) {
         if let Some(path) = IoService::pick_palette_import_path() {
-            match crate::format::hex_palette::load_from_hex(&path) {
+            match crate::format::palette_io::load_palette(&path) {
                 Ok(palette) => self.enqueue_command(AppCommand::SetPalette(palette)),
                 Err(e) => self.ui.error_message = Some(t!("error.load_palette_failed", err = e.to_string()).to_string()),
             }
         }
     }
 
+    // 读取另一个调色板文件，把其中尚未出现的颜色追加到当前调色板
+    pub fn merge_palette_from_file(&mut self) {
+        if let Some(path) = IoService::pick_palette_import_path() {
+            match crate::format::palette_io::load_palette(&path) {
+                Ok(palette) => self.enqueue_command(AppCommand::MergePalette(palette)),
+                Err(e) => self.ui.error_message = Some(t!("error.load_palette_failed", err = e.to_string()).to_string()),
+            }
+        }
+    }
+
     pub fn export_palette(&mut self) {
         if let Some(path) = IoService::pick_palette_export_path() {
-            if let Err(e) = crate::format::hex_palette::save_to_hex(&path, &self.engine.store().palette) {
+            if let Err(e) = crate::format::palette_io::save_palette(&path, &self.engine.store().palette) {
                 self.ui.error_message = Some(t!("error.export_palette_failed", err = e.to_string()).to_string());
             }
         }
     }
     pub fn save_project_to_pxad(&mut self) {
         if let Some(path) = IoService::pick_project_save_path() {
-            if let Err(e) = IoService::save_project(path, self.engine.store(), self.engine.symmetry(), &self.view) {
+            if let Err(e) = IoService::save_project(path, self.engine.store(), self.engine.symmetry(), &self.view, &self.animation.project) {
                 self.ui.error_message = Some(t!("error.save_project_failed", err = e.to_string()).to_string());
             } else {
                 self.is_dirty = false;
@@ -221,8 +295,26 @@ impl AppState {
     pub fn load_project_from_pxad(&mut self) {
         if let Some(path) = IoService::pick_project_load_path() {
             match IoService::load_project(path) {
-                Ok((new_store, new_sym, px, py, zl)) => {
+                Ok((new_store, new_sym, px, py, zl, anim)) => {
                     self.engine.replace_store_and_symmetry(new_store, new_sym);
+                    self.animation.project.onion_skin = anim.onion_skin;
+                    self.animation.project.fps = anim.fps;
+                    self.animation.project.snap_to_frames = anim.snap_to_frames;
+                    self.animation.project.skeleton = anim.skeleton;
+                    self.animation.project.animations = anim.animations;
+                    self.animation.project.active_animation_id = anim.active_animation_id;
+                    self.animation.current_time = 0.0;
+                    self.animation.history = crate::animation::history::AnimHistory::new();
+                    // 旧版工程没有 SKEL 块，为每个图层补建绑定到 root 的插槽
+                    if self.animation.project.skeleton.slots.is_empty() {
+                        for layer in &self.engine.store().layers {
+                            let mut slot = crate::core::animation::slot::SlotData::new(layer.id.clone(), layer.name.clone(), "root".to_string());
+                            slot.attachment = Some(layer.id.clone());
+                            self.animation.project.skeleton.slots.push(crate::core::animation::slot::RuntimeSlot::new(slot));
+                        }
+                    }
+                    self.animation.transition_preview = None;
+                    self.ui.selected_bone_id = None;
                     self.view.pan_x = px;
                     self.view.pan_y = py;
                     self.view.zoom_level = zl;
@@ -233,83 +325,181 @@ impl AppState {
             }
         }
     }
-    pub fn sync_animation_to_layers(&mut self) {
-        let mut changes = false;
-        let is_anim_mode = self.mode == AppMode::Animation;
-        let skeleton = &self.animation.project.skeleton;
+    pub fn import_animations_from_pxad(&mut self) {
+        if let Some(path) = IoService::pick_project_load_path() {
+            self.begin_animation_import(path);
+        }
+    }
+
+    // 读取另一个工程的骨架与动画，按骨骼名称自动匹配后等待用户确认
+    pub fn begin_animation_import(&mut self, path: std::path::PathBuf) {
+        match IoService::load_project(path.clone()) {
+            Ok((_, _, _, _, _, source)) => {
+                if source.animations.is_empty() {
+                    self.ui.error_message = Some("该工程没有可导入的动画".to_string());
+                    return;
+                }
+                let mapping = crate::core::animation::retarget::auto_map(&source.skeleton, &self.animation.project.skeleton);
+                let mut animations: Vec<_> = source.animations.into_iter().map(|(id, anim)| (id, anim, true)).collect();
+                animations.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+                self.animation.pending_import = Some(crate::core::animation::retarget::AnimationImport {
+                    source_path: path.display().to_string(),
+                    skeleton: source.skeleton,
+                    animations,
+                    mapping,
+                    rescale_translation: false,
+                });
+            }
+            Err(e) => self.ui.error_message = Some(t!("error.load_project_failed", err = e.to_string()).to_string()),
+        }
+    }
+
+    pub fn compute_layer_poses(skeleton: &crate::core::animation::skeleton::Skeleton) -> LayerPoses {
         let mut new_transforms = std::collections::HashMap::new();
         let mut new_offsets = std::collections::HashMap::new();
 
-        if is_anim_mode {
-            // 1. 动态计算所有骨骼的“Setup/Bind Pose(装配姿态)”的世界矩阵
-            let mut setup_matrices = vec![[1.0, 0.0, 0.0, 1.0, 0.0, 0.0]; skeleton.bones.len()];
-            for i in 0..skeleton.bones.len() {
-                let bone = &skeleton.bones[i];
-                let local_matrix = bone.data.local_transform.to_matrix();
-                let parent_matrix = bone.parent_index.map(|p_idx| setup_matrices[p_idx]);
-                setup_matrices[i] = match parent_matrix {
-                    None => local_matrix,
-                    Some(pm) => {
-                        let pa = pm[0]; let pb = pm[1]; let pc = pm[2]; let pd = pm[3]; let px = pm[4]; let py = pm[5];
-                        let la = local_matrix[0]; let lb = local_matrix[1]; let lc = local_matrix[2]; let ld = local_matrix[3]; let lx = local_matrix[4]; let ly = local_matrix[5];
-                        [
-                            pa * la + pc * lb, pb * la + pd * lb,
-                            pa * lc + pc * ld, pb * lc + pd * ld,
-                            pa * lx + pc * ly + px, pb * lx + pd * ly + py
-                        ]
-                    }
-                };
-            }
+        // 1. 动态计算所有骨骼的“Setup/Bind Pose(装配姿态)”的世界矩阵
+        let setup_matrices = skeleton.setup_world_matrices();
+
+        // 2. 根据 M_bind(绑定状态) 和 M_curr(当前状态) 计算逆矩阵
+        for slot in &skeleton.slots {
+            // 网格附件由 compute_layer_meshes 逐顶点变形
+            if let Some(layer_id) = slot.current_attachment.as_ref().filter(|id| skeleton.mesh(id).is_none()) {
+                if let Some(bone_idx) = skeleton.bones.iter().position(|b| b.data.id == slot.data.bone_id) {
+                    let m_bind = setup_matrices[bone_idx];
+                    let m_curr = skeleton.bones[bone_idx].world_matrix;
+
+                    // [核心修复] 提取纯位移差，保持严格的像素网格对齐
+                    let dx = (m_curr[4] - m_bind[4]).round() as i32;
+                    let dy = (m_curr[5] - m_bind[5]).round() as i32;
+                    new_offsets.insert(layer_id.clone(), (dx, dy));
 
-            // 2. 根据 M_bind(绑定状态) 和 M_curr(当前状态) 计算逆矩阵
-            for slot in &skeleton.slots {
-                if let Some(layer_id) = &slot.current_attachment {
-                    if let Some(bone_idx) = skeleton.bones.iter().position(|b| b.data.id == slot.data.bone_id) {
-                        let m_bind = setup_matrices[bone_idx];
-                        let m_curr = skeleton.bones[bone_idx].world_matrix;
-
-                        // [核心修复] 提取纯位移差，保持严格的像素网格对齐
-                        let dx = (m_curr[4] - m_bind[4]).round() as i32;
-                        let dy = (m_curr[5] - m_bind[5]).round() as i32;
-                        new_offsets.insert(layer_id.clone(), (dx, dy));
-
-                        // [智能降级] 仅在含有旋转或缩放时，才启用浮点矩阵渲染
-                        let has_rotation_or_scale = (m_curr[0] - m_bind[0]).abs() > 1e-4 ||
-                                                    (m_curr[1] - m_bind[1]).abs() > 1e-4 ||
-                                                    (m_curr[2] - m_bind[2]).abs() > 1e-4 ||
-                                                    (m_curr[3] - m_bind[3]).abs() > 1e-4;
-
-                        if has_rotation_or_scale {
-                            let det = m_curr[0] * m_curr[3] - m_curr[1] * m_curr[2];
-                            if det.abs() > 1e-6 {
-                                let inv_det = 1.0 / det;
-                                let i_a = m_curr[3] * inv_det;
-                                let i_b = -m_curr[1] * inv_det;
-                                let i_c = -m_curr[2] * inv_det;
-                                let i_d = m_curr[0] * inv_det;
-                                let i_tx = (m_curr[2]*m_curr[5] - m_curr[3]*m_curr[4]) * inv_det;
-                                let i_ty = (m_curr[1]*m_curr[4] - m_curr[0]*m_curr[5]) * inv_det;
-
-                                let f_a = m_bind[0]*i_a + m_bind[2]*i_b;
-                                let f_b = m_bind[1]*i_a + m_bind[3]*i_b;
-                                let f_c = m_bind[0]*i_c + m_bind[2]*i_d;
-                                let f_d = m_bind[1]*i_c + m_bind[3]*i_d;
-                                let f_tx = m_bind[0]*i_tx + m_bind[2]*i_ty + m_bind[4];
-                                let f_ty = m_bind[1]*i_tx + m_bind[3]*i_ty + m_bind[5];
-
-                                new_transforms.insert(layer_id.clone(), [f_a, f_b, f_c, f_d, f_tx, f_ty]);
-                            }
+                    // [智能降级] 仅在含有旋转或缩放时，才启用浮点矩阵渲染
+                    let has_rotation_or_scale = (m_curr[0] - m_bind[0]).abs() > 1e-4 ||
+                                                (m_curr[1] - m_bind[1]).abs() > 1e-4 ||
+                                                (m_curr[2] - m_bind[2]).abs() > 1e-4 ||
+                                                (m_curr[3] - m_bind[3]).abs() > 1e-4;
+
+                    if has_rotation_or_scale {
+                        let det = m_curr[0] * m_curr[3] - m_curr[1] * m_curr[2];
+                        if det.abs() > 1e-6 {
+                            let inv_det = 1.0 / det;
+                            let i_a = m_curr[3] * inv_det;
+                            let i_b = -m_curr[1] * inv_det;
+                            let i_c = -m_curr[2] * inv_det;
+                            let i_d = m_curr[0] * inv_det;
+                            let i_tx = (m_curr[2]*m_curr[5] - m_curr[3]*m_curr[4]) * inv_det;
+                            let i_ty = (m_curr[1]*m_curr[4] - m_curr[0]*m_curr[5]) * inv_det;
+
+                            let f_a = m_bind[0]*i_a + m_bind[2]*i_b;
+                            let f_b = m_bind[1]*i_a + m_bind[3]*i_b;
+                            let f_c = m_bind[0]*i_c + m_bind[2]*i_d;
+                            let f_d = m_bind[1]*i_c + m_bind[3]*i_d;
+                            let f_tx = m_bind[0]*i_tx + m_bind[2]*i_ty + m_bind[4];
+                            let f_ty = m_bind[1]*i_tx + m_bind[3]*i_ty + m_bind[5];
+
+                            new_transforms.insert(layer_id.clone(), [f_a, f_b, f_c, f_d, f_tx, f_ty]);
                         }
                     }
                 }
             }
         }
+
+        (new_transforms, new_offsets)
+    }
+
+    // 当前姿态下各网格附件蒙皮后的顶点 (按图层 id)
+    pub fn compute_layer_meshes(skeleton: &crate::core::animation::skeleton::Skeleton) -> std::collections::HashMap<String, crate::core::animation::mesh::DeformedMesh> {
+        // 与刚性附件一致：只有插槽当前显示 (或未被任何插槽引用) 的图层参与
+        let shown = |layer_id: &str| {
+            skeleton.slots.iter().any(|s| s.current_attachment.as_deref() == Some(layer_id))
+                || !skeleton.slots.iter().any(|s| s.data.id == layer_id || s.data.attachment.as_deref() == Some(layer_id))
+        };
+        skeleton.meshes.iter()
+            .filter(|m| shown(&m.layer_id))
+            .filter_map(|m| skeleton.deformed_mesh(&m.layer_id).map(|d| (m.layer_id.clone(), d)))
+            .collect()
+    }
+
+    // 插槽的装配层级：绑定图层在图层列表中的位置
+    fn slot_setup_key(slot: &crate::core::animation::slot::RuntimeSlot, layers: &[crate::core::layer::Layer]) -> usize {
+        let index_of = |id: &str| layers.iter().position(|l| l.id == id);
+        index_of(&slot.data.id)
+            .or_else(|| slot.data.attachment.as_deref().and_then(index_of))
+            .unwrap_or(layers.len())
+    }
+
+    // 当前插槽绘制顺序 (从下到上)：有动画设置的顺序时使用之，否则按装配图层的层级
+    pub fn slot_draw_order(skeleton: &crate::core::animation::skeleton::Skeleton, layers: &[crate::core::layer::Layer]) -> Vec<String> {
+        if skeleton.draw_order.is_some() {
+            return skeleton.draw_order_slots().iter().map(|s| s.data.id.clone()).collect();
+        }
+        let mut slots: Vec<_> = skeleton.slots.iter().collect();
+        slots.sort_by_key(|s| Self::slot_setup_key(s, layers));
+        slots.iter().map(|s| s.data.id.clone()).collect()
+    }
+
+    // 插槽按绘制顺序依次占用各插槽装配图层的层级并显示当前附件；未被插槽引用的图层保持原位
+    pub fn compute_draw_order(skeleton: &crate::core::animation::skeleton::Skeleton, layers: &[crate::core::layer::Layer]) -> Vec<String> {
+        let mut keys: Vec<usize> = skeleton.slots.iter().map(|s| Self::slot_setup_key(s, layers)).collect();
+        keys.sort();
+
+        let mut entries: Vec<(usize, String)> = Vec::new();
+        for (key, slot_id) in keys.into_iter().zip(Self::slot_draw_order(skeleton, layers)) {
+            if let Some(layer_id) = skeleton.slots.iter().find(|s| s.data.id == slot_id).and_then(|s| s.current_attachment.clone()) {
+                entries.push((key, layer_id));
+            }
+        }
+        for (i, layer) in layers.iter().enumerate() {
+            let claimed = skeleton.slots.iter().any(|s| {
+                s.data.id == layer.id || s.data.attachment.as_deref() == Some(layer.id.as_str()) || s.current_attachment.as_deref() == Some(layer.id.as_str())
+            });
+            if !claimed { entries.push((i, layer.id.clone())); }
+        }
+        entries.sort_by_key(|(key, _)| *key);
+        entries.into_iter().map(|(_, id)| id).collect()
+    }
+
+    pub fn sync_animation_to_layers(&mut self) {
+        let mut changes = false;
+        let (new_transforms, new_offsets) = if self.mode == AppMode::Animation {
+            Self::compute_layer_poses(&self.animation.project.skeleton)
+        } else {
+            (std::collections::HashMap::new(), std::collections::HashMap::new())
+        };
+        let new_meshes = if self.mode == AppMode::Animation {
+            Self::compute_layer_meshes(&self.animation.project.skeleton)
+        } else {
+            std::collections::HashMap::new()
+        };
+        let new_order = if self.mode == AppMode::Animation {
+            Some(Self::compute_draw_order(&self.animation.project.skeleton, &self.engine.store().layers))
+        } else {
+            None
+        };
+        let new_effect_amounts = if self.mode == AppMode::Animation {
+            self.animation.project.skeleton.effect_amounts.clone()
+        } else {
+            std::collections::HashMap::new()
+        };
         
         let (store, _, _) = self.engine.parts_mut();
         if store.layer_anim_transforms != new_transforms {
             store.layer_anim_transforms = new_transforms;
             changes = true;
         }
+        if store.layer_meshes != new_meshes {
+            store.layer_meshes = new_meshes;
+            changes = true;
+        }
+        if store.layer_draw_order != new_order {
+            store.layer_draw_order = new_order;
+            changes = true;
+        }
+        if store.effect_amounts != new_effect_amounts {
+            store.effect_amounts = new_effect_amounts;
+            changes = true;
+        }
 
         for layer in &mut store.layers {
             let (target_tx, target_ty) = new_offsets.get(&layer.id).copied().unwrap_or((0, 0));
@@ -322,4 +512,78 @@ impl AppState {
         
         if changes { self.engine.update_render_cache(None); }
     }
+
+    pub fn update_onion_skin(&mut self) -> Vec<crate::animation::onion_skin::OnionGhost> {
+        let ghosts = if self.mode == AppMode::Animation {
+            crate::animation::onion_skin::sample_ghosts(&self.animation)
+        } else {
+            Vec::new()
+        };
+
+        let (store, _, _) = self.engine.parts_mut();
+        if ghosts.is_empty() {
+            if !store.onion_cache.is_empty() { store.onion_cache.clear(); }
+            self.animation.onion_cache_key = None;
+            return ghosts;
+        }
+
+        let poses: Vec<crate::animation::onion_skin::GhostPose> = ghosts.iter().map(|ghost| {
+            let (transforms, offsets) = Self::compute_layer_poses(&ghost.skeleton);
+            (transforms, offsets, Self::compute_layer_meshes(&ghost.skeleton))
+        }).collect();
+        let key = crate::animation::onion_skin::OnionCacheKey::new(store, &ghosts, &poses);
+        let size = (store.canvas_width * store.canvas_height * 4) as usize;
+        if store.onion_cache.len() == size && self.animation.onion_cache_key.as_ref() == Some(&key) {
+            return ghosts;
+        }
+
+        let mut cache = vec![0u8; size];
+        for (ghost, (transforms, offsets, meshes)) in ghosts.iter().zip(&poses) {
+            let effect_layers = crate::render::effects::evaluate_layers(store, &ghost.skeleton.effect_amounts);
+            crate::render::compositor::Compositor::composite_onion_ghost(store, transforms, offsets, meshes, &effect_layers, (ghost.tint, ghost.opacity), &mut cache);
+        }
+        store.onion_cache = cache;
+        self.animation.onion_cache_key = Some(key);
+        ghosts
+    }
+
+    // 按设置的帧率逐帧合成当前动画，每帧为画布大小的 RGBA
+    pub fn bake_animation_frames(&self, settings: &crate::animation::bake::BakeSettings) -> Vec<Vec<u8>> {
+        let anim_id = match &self.animation.project.active_animation_id {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        let store = self.engine.store();
+        let skeletons = crate::animation::bake::sample_frames(&self.animation.project, anim_id, settings.fps, self.animation.is_looping);
+
+        // 放大后的取样源由图层像素与该帧解析后的效果参数决定，参数相同的帧共用
+        let mut sources: std::collections::HashMap<String, Vec<(Vec<crate::core::layer_effect::LayerEffectKind>, crate::render::resample::SampleSource)>> = std::collections::HashMap::new();
+        let mut frames = Vec::with_capacity(skeletons.len());
+        for skeleton in &skeletons {
+            let (transforms, offsets) = Self::compute_layer_poses(skeleton);
+            let meshes = Self::compute_layer_meshes(skeleton);
+            let order = Self::compute_draw_order(skeleton, &store.layers);
+            let effect_layers = crate::render::effects::evaluate_layers(store, &skeleton.effect_amounts);
+            let resolved: Vec<(&crate::core::layer::Layer, Vec<crate::core::layer_effect::LayerEffectKind>)> = store.layers.iter().filter(|l| l.visible)
+                .map(|l| (l, crate::core::layer_effect::resolve_effects(&l.effects, &skeleton.effect_amounts)))
+                .collect();
+            if settings.resample != crate::render::resample::ResampleMode::Nearest {
+                for (layer, effects) in &resolved {
+                    let cached = sources.entry(layer.id.clone()).or_default();
+                    if !cached.iter().any(|(e, _)| e == effects) {
+                        let source = crate::render::resample::SampleSource::from_layer(effect_layers.get(&layer.id).unwrap_or(layer), settings.resample);
+                        cached.push((effects.clone(), source));
+                    }
+                }
+            }
+            let frame_sources: std::collections::HashMap<String, &crate::render::resample::SampleSource> = resolved.iter()
+                .filter_map(|(layer, effects)| {
+                    let (_, source) = sources.get(&layer.id)?.iter().find(|(e, _)| e == effects)?;
+                    Some((layer.id.clone(), source))
+                })
+                .collect();
+            frames.push(crate::render::compositor::Compositor::composite_pose(store, &order, &transforms, &offsets, &meshes, &frame_sources, &effect_layers));
+        }
+        frames
+    }
 }
\ No newline at end of file
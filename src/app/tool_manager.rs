@@ -77,4 +77,23 @@ impl ToolManager {
         let tool = self.tools.get(&ToolType::Transform)?;
         tool.as_any().downcast_ref::<TransformTool>().and_then(|t| t.get_transform_params())
     }
+
+    pub fn get_transform_corners(&self) -> Option<[(f32, f32); 4]> {
+        if self.active_type != ToolType::Transform { return None; }
+        let tool = self.tools.get(&ToolType::Transform)?;
+        tool.as_any().downcast_ref::<TransformTool>().and_then(|t| t.corners())
+    }
+
+    // 变换进行中时的参数与控制柄模式，供数值面板显示
+    pub fn transform_state(&self) -> Option<(crate::tools::transform::TransformParams, crate::tools::transform::HandleMode, bool)> {
+        let tool = self.tools.get(&ToolType::Transform)?.as_any().downcast_ref::<TransformTool>()?;
+        Some((tool.params(), tool.handle_mode, tool.is_active))
+    }
+
+    pub fn transform_resample(&self) -> crate::render::resample::ResampleMode {
+        self.tools.get(&ToolType::Transform)
+            .and_then(|t| t.as_any().downcast_ref::<TransformTool>())
+            .map(|t| t.resample)
+            .unwrap_or_default()
+    }
 }
\ No newline at end of file
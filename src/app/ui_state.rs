@@ -9,6 +9,9 @@ pub struct UiState {
     pub resize_new_width: String,
     pub resize_new_height: String,
     pub resize_anchor: ResizeAnchor,
+    pub show_wrap_offset_modal: bool,
+    pub wrap_offset_x: String,
+    pub wrap_offset_y: String,
     pub selected_layer_ids: Vec<String>,
     pub last_clicked_layer_id: Option<String>,
     pub dragging_layer_id: Option<String>,
@@ -26,11 +29,31 @@ pub struct UiState {
     pub show_curve_editor: bool,
     pub graph_pan: egui::Vec2,
     pub graph_zoom: egui::Vec2,
+    // 曲线编辑器：只显示某一类轨道 (None 为全部)、归一化显示、框选起点与缩放系数
+    pub graph_channel_filter: Option<crate::core::animation::timeline::TimelineProperty>,
+    pub graph_normalized: bool,
+    pub graph_box_start: Option<egui::Pos2>,
+    pub graph_time_scale: f32,
+    pub graph_value_scale: f32,
+    // 缓动预设：阶梯级数、用户保存的贝塞尔预设及其文件位置
+    pub curve_steps: u32,
+    pub curve_presets: Vec<crate::core::animation::easing::CurvePreset>,
+    pub curve_presets_path: std::path::PathBuf,
+    pub new_curve_preset_name: String,
     pub timeline_zoom: f32,
     pub show_offset_modal: bool,
     pub offset_fixed_frames: i32,
     pub offset_step_frames: i32,
     pub offset_mode: usize,
+    pub show_bake_modal: bool,
+    pub bake_settings: crate::animation::bake::BakeSettings,
+    pub retime_on_fps_change: bool,
+    pub transition_target_id: Option<String>,
+    pub transition_frames: i32,
+    pub ik_target_bone_id: Option<String>,
+    pub ik_chain_length: usize,
+    pub selected_event: Option<usize>,
+    pub new_event_name: String,
     pub selected_node_idx: Option<usize>,
     pub show_world_transform: bool,
     pub auto_keyframe: bool,
@@ -41,6 +64,31 @@ pub struct UiState {
     pub is_offset_mode_active: bool,
     pub expanded_bones: std::collections::HashSet<String>,
     pub drag_target_bone_id: Option<String>,
+    pub mesh_cell_size: u32,
+    pub selected_mesh_vertex: Option<usize>,
+    pub weight_brush_radius: f32,
+    pub weight_brush_strength: f32,
+    pub weight_brush_erase: bool,
+    pub pending_image_import: Option<crate::app::image_import::ImageImport>,
+    // 调色板：色带生成器的两端、级数与色相偏移，以及重命名输入框
+    pub ramp_from: crate::core::color::Color,
+    pub ramp_to: crate::core::color::Color,
+    pub ramp_steps: usize,
+    pub ramp_hue_shift: f32,
+    pub palette_rename_buffer: String,
+    // 取色器的颜色模型与明暗色阶的数量、色相偏移
+    pub picker_model: crate::core::color::ColorModel,
+    pub shade_count: usize,
+    pub shade_hue_shift: f32,
+    // 量化到调色板对话框
+    pub show_quantize_modal: bool,
+    // 打开中的调整滤镜预览
+    pub filter_preview: Option<crate::app::handlers::filter_handler::FilterPreview>,
+    // 正在编辑参数的像素画效果对话框
+    pub effect_dialog: Option<crate::render::effects::PixelEffect>,
+    pub quantize_settings: crate::render::quantize::QuantizeSettings,
+    pub palette_gen_method: crate::render::quantize::PaletteGenMethod,
+    pub palette_gen_count: usize,
 }
 
 impl UiState {
@@ -58,6 +106,9 @@ impl UiState {
             resize_new_width: String::new(),
             resize_new_height: String::new(),
             resize_anchor: ResizeAnchor::Center,
+            show_wrap_offset_modal: false,
+            wrap_offset_x: String::new(),
+            wrap_offset_y: String::new(),
             selected_layer_ids: Vec::new(),
             last_clicked_layer_id: None,
             dragging_layer_id: None,
@@ -75,11 +126,29 @@ impl UiState {
             show_curve_editor: false,
             graph_pan: egui::vec2(20.0, 0.0),
             graph_zoom: egui::vec2(100.0, 1.0),
+            graph_channel_filter: None,
+            graph_normalized: false,
+            graph_box_start: None,
+            graph_time_scale: 1.0,
+            graph_value_scale: 1.0,
+            curve_steps: 4,
+            curve_presets: Vec::new(),
+            curve_presets_path: crate::app::io_service::IoService::user_data_path("curve_presets.txt"),
+            new_curve_preset_name: String::new(),
             timeline_zoom: 1.0,
             show_offset_modal: false,
             offset_fixed_frames: 5,
             offset_step_frames: 1,
             offset_mode: 0,
+            show_bake_modal: false,
+            bake_settings: crate::animation::bake::BakeSettings::default(),
+            retime_on_fps_change: false,
+            transition_target_id: None,
+            transition_frames: 10,
+            ik_target_bone_id: None,
+            ik_chain_length: 2,
+            selected_event: None,
+            new_event_name: "event".to_string(),
             selected_node_idx: None,
             show_world_transform: false,
             auto_keyframe: true,
@@ -90,6 +159,26 @@ impl UiState {
             is_offset_mode_active: false,
             expanded_bones: std::collections::HashSet::new(),
             drag_target_bone_id: None,
+            mesh_cell_size: 8,
+            selected_mesh_vertex: None,
+            weight_brush_radius: 8.0,
+            weight_brush_strength: 0.1,
+            weight_brush_erase: false,
+            pending_image_import: None,
+            ramp_from: crate::core::color::Color::new(29, 43, 83, 255),
+            ramp_to: crate::core::color::Color::new(255, 236, 39, 255),
+            ramp_steps: 5,
+            ramp_hue_shift: 0.0,
+            palette_rename_buffer: String::new(),
+            picker_model: crate::core::color::ColorModel::Hsv,
+            shade_count: 7,
+            shade_hue_shift: 15.0,
+            show_quantize_modal: false,
+            filter_preview: None,
+            effect_dialog: None,
+            quantize_settings: crate::render::quantize::QuantizeSettings::default(),
+            palette_gen_method: crate::render::quantize::PaletteGenMethod::MedianCut,
+            palette_gen_count: 16,
         }
     }
 }
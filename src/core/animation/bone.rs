@@ -1,5 +1,25 @@
 use super::transform::Transform;
 
+// 子骨骼从父骨骼继承变换的方式，位置始终跟随父骨骼
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformMode {
+    Normal,
+    OnlyTranslation,
+    NoRotationOrReflection,
+    NoScale,
+    NoScaleOrReflection,
+}
+
+impl TransformMode {
+    pub const ALL: [TransformMode; 5] = [
+        TransformMode::Normal,
+        TransformMode::OnlyTranslation,
+        TransformMode::NoRotationOrReflection,
+        TransformMode::NoScale,
+        TransformMode::NoScaleOrReflection,
+    ];
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BoneData {
     pub id: String,
@@ -9,6 +29,8 @@ pub struct BoneData {
     pub local_transform: Transform,
     pub inherit_rotation: bool,
     pub inherit_scale: bool,
+    // 仅在不继承缩放时生效：是否保留父级的镜像
+    pub inherit_reflection: bool,
 }
 
 impl BoneData {
@@ -21,8 +43,32 @@ impl BoneData {
             local_transform: Transform::default(),
             inherit_rotation: true,
             inherit_scale: true,
+            inherit_reflection: true,
+        }
+    }
+
+    pub fn transform_mode(&self) -> TransformMode {
+        match (self.inherit_rotation, self.inherit_scale) {
+            (true, true) => TransformMode::Normal,
+            (false, false) => TransformMode::OnlyTranslation,
+            (false, true) => TransformMode::NoRotationOrReflection,
+            (true, false) if self.inherit_reflection => TransformMode::NoScale,
+            (true, false) => TransformMode::NoScaleOrReflection,
         }
     }
+
+    pub fn set_transform_mode(&mut self, mode: TransformMode) {
+        let (rotation, scale, reflection) = match mode {
+            TransformMode::Normal => (true, true, true),
+            TransformMode::OnlyTranslation => (false, false, false),
+            TransformMode::NoRotationOrReflection => (false, true, false),
+            TransformMode::NoScale => (true, false, true),
+            TransformMode::NoScaleOrReflection => (true, false, false),
+        };
+        self.inherit_rotation = rotation;
+        self.inherit_scale = scale;
+        self.inherit_reflection = reflection;
+    }
 }
 
 #[cfg(test)]
@@ -38,4 +84,14 @@ mod tests {
         assert_eq!(bone.local_transform.scale_x, 1.0);
         assert_eq!(bone.inherit_rotation, true);
     }
+
+    #[test]
+    fn test_transform_mode_round_trip() {
+        let mut bone = BoneData::new("bone_1".into(), "Root".into());
+        assert_eq!(bone.transform_mode(), TransformMode::Normal);
+        for mode in TransformMode::ALL {
+            bone.set_transform_mode(mode);
+            assert_eq!(bone.transform_mode(), mode);
+        }
+    }
 }
\ No newline at end of file
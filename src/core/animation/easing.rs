@@ -0,0 +1,146 @@
+use std::f32::consts::PI;
+
+// 常用缓动函数，全部为解析式求值 (不经过贝塞尔二分求解)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingFunction {
+    Sine,
+    Quad,
+    Cubic,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingDirection {
+    In,
+    Out,
+    InOut,
+}
+
+impl EasingFunction {
+    pub const ALL: [EasingFunction; 6] = [
+        EasingFunction::Sine, EasingFunction::Quad, EasingFunction::Cubic,
+        EasingFunction::Back, EasingFunction::Elastic, EasingFunction::Bounce,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EasingFunction::Sine => "正弦 (Sine)",
+            EasingFunction::Quad => "二次 (Quad)",
+            EasingFunction::Cubic => "三次 (Cubic)",
+            EasingFunction::Back => "回拉 (Back)",
+            EasingFunction::Elastic => "弹性 (Elastic)",
+            EasingFunction::Bounce => "弹跳 (Bounce)",
+        }
+    }
+
+    fn ease_in(&self, t: f32) -> f32 {
+        match self {
+            EasingFunction::Sine => 1.0 - (t * PI / 2.0).cos(),
+            EasingFunction::Quad => t * t,
+            EasingFunction::Cubic => t * t * t,
+            EasingFunction::Back => {
+                let c1 = 1.70158;
+                (c1 + 1.0) * t * t * t - c1 * t * t
+            }
+            EasingFunction::Elastic => {
+                if t <= 0.0 || t >= 1.0 { return t.clamp(0.0, 1.0); }
+                -(2.0f32).powf(10.0 * t - 10.0) * ((10.0 * t - 10.75) * (2.0 * PI / 3.0)).sin()
+            }
+            EasingFunction::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+impl EasingDirection {
+    pub const ALL: [EasingDirection; 3] = [EasingDirection::In, EasingDirection::Out, EasingDirection::InOut];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EasingDirection::In => "缓入",
+            EasingDirection::Out => "缓出",
+            EasingDirection::InOut => "缓入缓出",
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    let (n1, d1) = (7.5625, 2.75);
+    if t < 1.0 / d1 {
+        n1 * t * t
+    } else if t < 2.0 / d1 {
+        let t = t - 1.5 / d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5 / d1 {
+        let t = t - 2.25 / d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+// 缓出与缓入缓出由缓入对称得到；回拉、弹性允许结果超出 [0, 1]
+pub fn ease(function: EasingFunction, direction: EasingDirection, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match direction {
+        EasingDirection::In => function.ease_in(t),
+        EasingDirection::Out => 1.0 - function.ease_in(1.0 - t),
+        EasingDirection::InOut => {
+            if t < 0.5 { function.ease_in(2.0 * t) * 0.5 } else { 1.0 - function.ease_in(2.0 - 2.0 * t) * 0.5 }
+        }
+    }
+}
+
+// 阶梯缓动：整段均分为 n 级，每级开始时跳变
+pub fn steps(n: u32, t: f32) -> f32 {
+    let n = n.max(1) as f32;
+    if t >= 1.0 { return 1.0; }
+    (t.max(0.0) * n).floor() / n
+}
+
+// 阶梯缓动：每级结束时跳变，只有 t = 0 处停在起点
+pub fn steps_end(n: u32, t: f32) -> f32 {
+    let n = n.max(1) as f32;
+    if t <= 0.0 { return 0.0; }
+    (t.min(1.0) * n).ceil() / n
+}
+
+// 用户保存的贝塞尔曲线预设
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurvePreset {
+    pub name: String,
+    pub handles: [f32; 4],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing_endpoints_and_symmetry() {
+        for f in EasingFunction::ALL {
+            for d in EasingDirection::ALL {
+                assert!(ease(f, d, 0.0).abs() < 1e-5, "{:?} {:?} 起点应为 0", f, d);
+                assert!((ease(f, d, 1.0) - 1.0).abs() < 1e-5, "{:?} {:?} 终点应为 1", f, d);
+            }
+            assert!((ease(f, EasingDirection::InOut, 0.5) - 0.5).abs() < 1e-5, "{:?} 缓入缓出在中点对称", f);
+        }
+        assert!((ease(EasingFunction::Quad, EasingDirection::In, 0.5) - 0.25).abs() < 1e-6);
+        assert!((ease(EasingFunction::Cubic, EasingDirection::Out, 0.5) - 0.875).abs() < 1e-6);
+        assert!((ease(EasingFunction::Sine, EasingDirection::Out, 0.5) - (PI / 4.0).sin()).abs() < 1e-6);
+        assert!(ease(EasingFunction::Back, EasingDirection::In, 0.2) < 0.0, "回拉缓入先反向");
+        assert!((ease(EasingFunction::Bounce, EasingDirection::Out, 1.0 / 2.75) - 1.0).abs() < 1e-5, "弹跳第一次落地");
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(steps(4, 0.0), 0.0);
+        assert_eq!(steps(4, 0.24), 0.0);
+        assert_eq!(steps(4, 0.25), 0.25);
+        assert_eq!(steps(4, 0.99), 0.75);
+        assert_eq!(steps(4, 1.0), 1.0);
+        assert_eq!(steps(0, 0.5), 0.0, "0 级按 1 级处理");
+    }
+}
@@ -0,0 +1,80 @@
+// 动画事件：在指定时间触发，携带整数/浮点/字符串负载 (如脚步声、攻击判定帧)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationEvent {
+    pub name: String,
+    pub time: f32,
+    pub int_value: i32,
+    pub float_value: f32,
+    pub string_value: String,
+}
+
+impl AnimationEvent {
+    pub fn new(name: String, time: f32) -> Self {
+        Self {
+            name,
+            time,
+            int_value: 0,
+            float_value: 0.0,
+            string_value: String::new(),
+        }
+    }
+}
+
+// 收集一次时间推进中经过的事件 (区间左闭右开)。循环播放时跨越结尾会回绕，
+// 依次收集尾段、完整循环与新一轮开头的事件
+pub fn events_in_range(events: &[AnimationEvent], duration: f32, from: f32, to: f32, looping: bool) -> Vec<AnimationEvent> {
+    let mut fired = Vec::new();
+    if to <= from { return fired; }
+
+    let collect = |fired: &mut Vec<AnimationEvent>, start: f32, end: f32| {
+        fired.extend(events.iter().filter(|e| e.time >= start && e.time < end).cloned());
+    };
+
+    if !looping || duration <= 0.0 {
+        collect(&mut fired, from, to);
+        return fired;
+    }
+
+    let from_loop = (from / duration).floor();
+    let to_loop = (to / duration).floor();
+    let local_from = from - from_loop * duration;
+    let local_to = to - to_loop * duration;
+
+    if from_loop == to_loop {
+        collect(&mut fired, local_from, local_to);
+        return fired;
+    }
+
+    collect(&mut fired, local_from, duration);
+    // 单次推进跨越多轮时最多补发若干完整循环，避免卡顿后事件风暴
+    let full_loops = ((to_loop - from_loop) as i32 - 1).clamp(0, 4);
+    for _ in 0..full_loops {
+        collect(&mut fired, 0.0, duration);
+    }
+    collect(&mut fired, 0.0, local_to);
+    fired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(events: &[AnimationEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_events_fire_across_loop_wrap() {
+        let events = vec![
+            AnimationEvent::new("start".into(), 0.0),
+            AnimationEvent::new("step".into(), 0.5),
+            AnimationEvent::new("end".into(), 0.9),
+        ];
+
+        assert_eq!(names(&events_in_range(&events, 1.0, 0.0, 0.1, true)), vec!["start"], "从 0 开始播放应触发 0 秒事件");
+        assert_eq!(names(&events_in_range(&events, 1.0, 0.4, 0.6, true)), vec!["step"]);
+        assert_eq!(names(&events_in_range(&events, 1.0, 0.85, 1.05, true)), vec!["end", "start"], "跨越循环结尾时应先触发尾段再触发开头");
+        assert!(events_in_range(&events, 1.0, 0.6, 0.6, true).is_empty());
+        assert!(events_in_range(&events, 1.0, 1.2, 1.4, false).is_empty(), "非循环播放超出时长后不再触发");
+    }
+}
@@ -0,0 +1,338 @@
+use crate::core::animation::timeline::{CurveType, KeyframeValue, TangentMode, Timeline, TimelineProperty};
+
+// 关键帧切线以段内归一化贝塞尔手柄保存：关键帧 i 的出切线是第 i 段曲线的 (cx1, cy1)，
+// 入切线是第 i-1 段曲线的 (cx2, cy2)。多通道轨道 (X/Y) 共用同一条缓动曲线，
+// 因此斜率按该关键帧两侧变化量最大的通道换算。
+
+pub const HANDLE_LENGTH: f32 = 1.0 / 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TangentSide {
+    In,
+    Out,
+}
+
+// 曲线编辑器能显示的数值轨道
+pub fn is_graph_property(prop: &TimelineProperty) -> bool {
+    matches!(prop, TimelineProperty::Rotation | TimelineProperty::Translation | TimelineProperty::Scale | TimelineProperty::Shear)
+}
+
+pub fn channel_count(prop: &TimelineProperty) -> usize {
+    match prop {
+        TimelineProperty::Rotation => 1,
+        TimelineProperty::Translation | TimelineProperty::Scale | TimelineProperty::Shear => 2,
+        _ => 0,
+    }
+}
+
+pub fn channel_value(value: &KeyframeValue, channel: usize) -> Option<f32> {
+    match (value, channel) {
+        (KeyframeValue::Rotate(r), 0) => Some(*r),
+        (KeyframeValue::Translate(x, _), 0) | (KeyframeValue::Scale(x, _), 0) | (KeyframeValue::Shear(x, _), 0) => Some(*x),
+        (KeyframeValue::Translate(_, y), 1) | (KeyframeValue::Scale(_, y), 1) | (KeyframeValue::Shear(_, y), 1) => Some(*y),
+        _ => None,
+    }
+}
+
+pub fn set_channel_value(value: &mut KeyframeValue, channel: usize, v: f32) {
+    match (value, channel) {
+        (KeyframeValue::Rotate(r), 0) => *r = v,
+        (KeyframeValue::Translate(x, _), 0) | (KeyframeValue::Scale(x, _), 0) | (KeyframeValue::Shear(x, _), 0) => *x = v,
+        (KeyframeValue::Translate(_, y), 1) | (KeyframeValue::Scale(_, y), 1) | (KeyframeValue::Shear(_, y), 1) => *y = v,
+        _ => {}
+    }
+}
+
+// 第 seg 段 (关键帧 seg -> seg+1) 在某通道上的变化量，旋转与采样一致取最短角度
+fn segment_delta(tl: &Timeline, seg: usize, channel: usize) -> f32 {
+    let (a, b) = match (tl.keyframes.get(seg), tl.keyframes.get(seg + 1)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return 0.0,
+    };
+    let (va, vb) = match (channel_value(&a.value, channel), channel_value(&b.value, channel)) {
+        (Some(va), Some(vb)) => (va, vb),
+        _ => return 0.0,
+    };
+    let diff = vb - va;
+    if tl.property == TimelineProperty::Rotation { super::ik::shortest_angle(diff) } else { diff }
+}
+
+fn segment_duration(tl: &Timeline, seg: usize) -> f32 {
+    match (tl.keyframes.get(seg), tl.keyframes.get(seg + 1)) {
+        (Some(a), Some(b)) => b.time - a.time,
+        _ => 0.0,
+    }
+}
+
+// 切线换算所依据的通道：关键帧两侧变化量之和最大的通道
+pub fn key_channel(tl: &Timeline, index: usize) -> usize {
+    let mut best = (0, -1.0);
+    for c in 0..channel_count(&tl.property) {
+        let mut total = segment_delta(tl, index, c).abs();
+        if index > 0 { total += segment_delta(tl, index - 1, c).abs(); }
+        if total > best.1 { best = (c, total); }
+    }
+    best.0
+}
+
+// 段曲线的归一化手柄；直线等价于 1/3、2/3 处的手柄，阶跃与预设缓动没有手柄
+pub fn segment_handles(curve: &CurveType) -> Option<(f32, f32, f32, f32)> {
+    match *curve {
+        CurveType::Linear => Some((HANDLE_LENGTH, HANDLE_LENGTH, 1.0 - HANDLE_LENGTH, 1.0 - HANDLE_LENGTH)),
+        CurveType::Bezier(cx1, cy1, cx2, cy2) => Some((cx1, cy1, cx2, cy2)),
+        CurveType::Stepped | CurveType::Easing(..) | CurveType::Steps(_) | CurveType::StepsEnd(_) => None,
+    }
+}
+
+// 出切线斜率 (数值/秒)，手柄竖直或没有后一段时为 None
+pub fn out_slope(tl: &Timeline, index: usize) -> Option<f32> {
+    if index + 1 >= tl.keyframes.len() { return None; }
+    let (cx1, cy1, _, _) = segment_handles(&tl.keyframes[index].curve)?;
+    let duration = segment_duration(tl, index);
+    if cx1 < 1e-4 || duration <= 1e-4 { return None; }
+    Some(segment_delta(tl, index, key_channel(tl, index)) * cy1 / (cx1 * duration))
+}
+
+pub fn in_slope(tl: &Timeline, index: usize) -> Option<f32> {
+    if index == 0 || index >= tl.keyframes.len() { return None; }
+    let (_, _, cx2, cy2) = segment_handles(&tl.keyframes[index - 1].curve)?;
+    let duration = segment_duration(tl, index - 1);
+    if 1.0 - cx2 < 1e-4 || duration <= 1e-4 { return None; }
+    Some(segment_delta(tl, index - 1, key_channel(tl, index)) * (1.0 - cy2) / ((1.0 - cx2) * duration))
+}
+
+// 保持手柄的时间长度不变，只改斜率；段内数值不变时无法表达斜率，手柄放平
+pub fn set_out_slope(tl: &mut Timeline, index: usize, slope: f32) {
+    if index + 1 >= tl.keyframes.len() { return; }
+    let (mut cx1, _, cx2, cy2) = match segment_handles(&tl.keyframes[index].curve) {
+        Some(h) => h,
+        None => return,
+    };
+    if cx1 < 1e-4 { cx1 = HANDLE_LENGTH; }
+    let delta = segment_delta(tl, index, key_channel(tl, index));
+    let duration = segment_duration(tl, index);
+    let cy1 = if delta.abs() < 1e-6 { 0.0 } else { slope * cx1 * duration / delta };
+    tl.keyframes[index].curve = CurveType::Bezier(cx1, cy1, cx2, cy2);
+}
+
+pub fn set_in_slope(tl: &mut Timeline, index: usize, slope: f32) {
+    if index == 0 || index >= tl.keyframes.len() { return; }
+    let (cx1, cy1, mut cx2, _) = match segment_handles(&tl.keyframes[index - 1].curve) {
+        Some(h) => h,
+        None => return,
+    };
+    if 1.0 - cx2 < 1e-4 { cx2 = 1.0 - HANDLE_LENGTH; }
+    let delta = segment_delta(tl, index - 1, key_channel(tl, index));
+    let duration = segment_duration(tl, index - 1);
+    let cy2 = if delta.abs() < 1e-6 { 1.0 } else { 1.0 - slope * (1.0 - cx2) * duration / delta };
+    tl.keyframes[index - 1].curve = CurveType::Bezier(cx1, cy1, cx2, cy2);
+}
+
+// 钳制的自动切线：首尾与极值处放平，其余取相邻关键帧连线斜率并限制在不过冲的范围内
+pub fn auto_slope(tl: &Timeline, index: usize) -> f32 {
+    if index == 0 || index + 1 >= tl.keyframes.len() { return 0.0; }
+    let channel = key_channel(tl, index);
+    let (dp, dn) = (segment_delta(tl, index - 1, channel), segment_delta(tl, index, channel));
+    let (tp, tn) = (segment_duration(tl, index - 1), segment_duration(tl, index));
+    if dp * dn <= 0.0 || tp <= 1e-4 || tn <= 1e-4 { return 0.0; }
+    let slope = (dp + dn) / (tp + tn);
+    let limit = 3.0 * (dp.abs() / tp).min(dn.abs() / tn);
+    slope.clamp(-limit, limit)
+}
+
+pub fn refresh_auto_tangents(tl: &mut Timeline) {
+    if !is_graph_property(&tl.property) { return; }
+    for i in 0..tl.keyframes.len() {
+        if tl.keyframes[i].tangent == TangentMode::Auto {
+            let slope = auto_slope(tl, i);
+            set_in_slope(tl, i, slope);
+            set_out_slope(tl, i, slope);
+        }
+    }
+}
+
+pub fn set_tangent_mode(tl: &mut Timeline, index: usize, mode: TangentMode) {
+    if index >= tl.keyframes.len() { return; }
+    tl.keyframes[index].tangent = mode;
+    match mode {
+        TangentMode::Auto => refresh_auto_tangents(tl),
+        TangentMode::Aligned => {
+            if let (Some(a), Some(b)) = (in_slope(tl, index), out_slope(tl, index)) {
+                let slope = (a + b) * 0.5;
+                set_in_slope(tl, index, slope);
+                set_out_slope(tl, index, slope);
+            }
+        }
+        TangentMode::Broken => {}
+    }
+}
+
+// 拖动手柄 (段内归一化坐标)；拖动自动切线会转为对齐切线，对齐切线同步另一侧斜率
+pub fn set_handle(tl: &mut Timeline, index: usize, side: TangentSide, x: f32, y: f32) {
+    let seg = match side {
+        TangentSide::Out if index + 1 < tl.keyframes.len() => index,
+        TangentSide::In if index > 0 && index < tl.keyframes.len() => index - 1,
+        _ => return,
+    };
+    let (cx1, cy1, cx2, cy2) = match segment_handles(&tl.keyframes[seg].curve) {
+        Some(h) => h,
+        None => return,
+    };
+    let x = x.clamp(0.0, 1.0);
+    tl.keyframes[seg].curve = match side {
+        TangentSide::Out => CurveType::Bezier(x, y, cx2, cy2),
+        TangentSide::In => CurveType::Bezier(cx1, cy1, x, y),
+    };
+    if tl.keyframes[index].tangent == TangentMode::Auto {
+        tl.keyframes[index].tangent = TangentMode::Aligned;
+    }
+    if tl.keyframes[index].tangent == TangentMode::Aligned {
+        match side {
+            TangentSide::Out => if let Some(s) = out_slope(tl, index) { set_in_slope(tl, index, s) },
+            TangentSide::In => if let Some(s) = in_slope(tl, index) { set_out_slope(tl, index, s) },
+        }
+    }
+    refresh_auto_tangents(tl);
+}
+
+// 按轴心缩放选中关键帧的时间与数值，数值轴心为选中关键帧在各通道上的范围中心。
+// 缩放后落在未选中关键帧上时整条轨道不变 (不覆盖未选中的关键帧)；选中关键帧之间重合时只保留一个。
+// 返回 (旧时间, 新时间) 以便更新选区
+pub fn scale_keys(tl: &mut Timeline, times: &[f32], time_pivot: f32, time_scale: f32, value_scale: f32, snap_fps: Option<f32>) -> Vec<(f32, f32)> {
+    let selected: Vec<usize> = (0..tl.keyframes.len())
+        .filter(|&i| times.iter().any(|t| (tl.keyframes[i].time - t).abs() < 0.001))
+        .collect();
+    if selected.is_empty() { return Vec::new(); }
+
+    let scaled_time = |old: f32| {
+        let new = (time_pivot + (old - time_pivot) * time_scale).max(0.0);
+        match snap_fps {
+            Some(fps) => crate::core::animation::timeline::snap_to_frame(new, fps).max(0.0),
+            None => new,
+        }
+    };
+    let targets: Vec<f32> = selected.iter().map(|&i| scaled_time(tl.keyframes[i].time)).collect();
+    let collides = tl.keyframes.iter().enumerate()
+        .filter(|(i, _)| !selected.contains(i))
+        .any(|(_, k)| targets.iter().any(|t| (k.time - t).abs() < 0.0001));
+    if collides {
+        return selected.iter().map(|&i| (tl.keyframes[i].time, tl.keyframes[i].time)).collect();
+    }
+
+    if value_scale != 1.0 {
+        for c in 0..channel_count(&tl.property) {
+            let values: Vec<f32> = selected.iter().filter_map(|&i| channel_value(&tl.keyframes[i].value, c)).collect();
+            let min = values.iter().cloned().fold(f32::MAX, f32::min);
+            let max = values.iter().cloned().fold(f32::MIN, f32::max);
+            let center = (min + max) * 0.5;
+            for &i in &selected {
+                if let Some(v) = channel_value(&tl.keyframes[i].value, c) {
+                    set_channel_value(&mut tl.keyframes[i].value, c, center + (v - center) * value_scale);
+                }
+            }
+        }
+    }
+
+    let mut moved = Vec::new();
+    for (&i, &new) in selected.iter().zip(&targets) {
+        moved.push((tl.keyframes[i].time, new));
+        tl.keyframes[i].time = new;
+    }
+    // 未选中的关键帧不会与任何关键帧重合，去重只会合并选中的关键帧
+    tl.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+    tl.keyframes.dedup_by(|b, a| (a.time - b.time).abs() < 0.0001);
+    refresh_auto_tangents(tl);
+    moved
+}
+
+// 归一化显示：把数值映射到 [-1, 1]
+pub fn normalize_value(v: f32, min: f32, max: f32) -> f32 {
+    let half = (max - min) * 0.5;
+    if half.abs() < 1e-6 { 0.0 } else { (v - (min + max) * 0.5) / half }
+}
+
+// 让时间范围 [t0, t1]、数值范围 [v0, v1] 填满视图 (留出边距)。
+// 视图约定：screen_x = left + pan_x + t * zoom_x，screen_y = center_y + pan_y - v * zoom_y
+pub fn frame_view(t_range: (f32, f32), v_range: (f32, f32), view_size: (f32, f32), margin: f32) -> ((f32, f32), (f32, f32)) {
+    let (w, h) = ((view_size.0 - margin * 2.0).max(1.0), (view_size.1 - margin * 2.0).max(1.0));
+    let dt = t_range.1 - t_range.0;
+    let dv = v_range.1 - v_range.0;
+    let zoom_x = if dt > 1e-4 { (w / dt).clamp(10.0, 1000.0) } else { 100.0 };
+    let zoom_y = if dv > 1e-4 { (h / dv).clamp(0.1, 50.0) } else { 1.0 };
+    let pan_x = margin - t_range.0 * zoom_x;
+    let pan_y = (v_range.0 + v_range.1) * 0.5 * zoom_y;
+    ((pan_x, pan_y), (zoom_x, zoom_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotation_timeline(keys: &[(f32, f32)]) -> Timeline {
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Rotation);
+        for &(t, v) in keys { tl.add_keyframe(t, KeyframeValue::Rotate(v), CurveType::Linear); }
+        tl
+    }
+
+    #[test]
+    fn test_auto_tangent_clamps_extremes() {
+        let mut tl = rotation_timeline(&[(0.0, 0.0), (1.0, 10.0), (2.0, 30.0), (3.0, 0.0)]);
+        for i in 0..4 { set_tangent_mode(&mut tl, i, TangentMode::Auto); }
+        assert!(out_slope(&tl, 0).unwrap().abs() < 1e-4, "首帧放平");
+        assert!((in_slope(&tl, 1).unwrap() - 15.0).abs() < 1e-3);
+        assert!((out_slope(&tl, 1).unwrap() - 15.0).abs() < 1e-3);
+        assert!(out_slope(&tl, 2).unwrap().abs() < 1e-4, "极值处放平，不过冲");
+        for step in 0..=20 {
+            let t = 1.0 + step as f32 * 0.05;
+            if let Some(KeyframeValue::Rotate(r)) = tl.sample(t) { assert!((10.0 - 1e-3..=30.0 + 1e-3).contains(&r), "t={} r={}", t, r); }
+        }
+    }
+
+    #[test]
+    fn test_aligned_handle_mirrors_slope() {
+        let mut tl = rotation_timeline(&[(0.0, 0.0), (1.0, 10.0), (3.0, 20.0)]);
+        set_tangent_mode(&mut tl, 1, TangentMode::Aligned);
+        set_handle(&mut tl, 1, TangentSide::Out, 0.25, 1.0);
+        let out = out_slope(&tl, 1).unwrap();
+        assert!((out - 20.0).abs() < 1e-3);
+        assert!((in_slope(&tl, 1).unwrap() - out).abs() < 1e-3, "对齐切线两侧斜率一致");
+
+        tl.keyframes[1].tangent = TangentMode::Broken;
+        set_handle(&mut tl, 1, TangentSide::In, 0.5, 0.5);
+        assert!((in_slope(&tl, 1).unwrap() - 10.0).abs() < 1e-3);
+        assert!((out_slope(&tl, 1).unwrap() - out).abs() < 1e-3, "断开切线不影响另一侧");
+    }
+
+    #[test]
+    fn test_scale_keys_time_and_value() {
+        let mut tl = rotation_timeline(&[(0.0, 0.0), (1.0, 10.0), (2.0, 30.0)]);
+        let moved = scale_keys(&mut tl, &[1.0, 2.0], 0.0, 2.0, 0.5, None);
+        assert_eq!(moved, vec![(1.0, 2.0), (2.0, 4.0)]);
+        let values: Vec<_> = tl.keyframes.iter().map(|k| (k.time, k.value.clone())).collect();
+        assert_eq!(values, vec![(0.0, KeyframeValue::Rotate(0.0)), (2.0, KeyframeValue::Rotate(15.0)), (4.0, KeyframeValue::Rotate(25.0))]);
+    }
+
+    #[test]
+    fn test_scale_keys_keeps_unselected_keys() {
+        // 选中的 2.0 缩放到 1.0，与未选中的关键帧重合：放弃缩放，不删除未选中的关键帧
+        let mut tl = rotation_timeline(&[(0.0, 0.0), (1.0, 10.0), (2.0, 30.0)]);
+        let original = tl.clone();
+        let moved = scale_keys(&mut tl, &[2.0], 0.0, 0.5, 1.0, None);
+        assert_eq!(moved, vec![(2.0, 2.0)]);
+        assert_eq!(tl, original);
+
+        // 选中的关键帧之间重合时合并为一个
+        let moved = scale_keys(&mut tl, &[1.0, 2.0], 4.0, 0.0, 1.0, None);
+        assert_eq!(moved, vec![(1.0, 4.0), (2.0, 4.0)]);
+        assert_eq!(tl.keyframes.iter().map(|k| k.time).collect::<Vec<_>>(), vec![0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_frame_view_fits_range() {
+        let ((pan_x, pan_y), (zoom_x, zoom_y)) = frame_view((1.0, 3.0), (-50.0, 150.0), (420.0, 220.0), 10.0);
+        assert!((pan_x + 1.0 * zoom_x - 10.0).abs() < 1e-3);
+        assert!((pan_x + 3.0 * zoom_x - 410.0).abs() < 1e-3);
+        assert!((110.0 + pan_y - 150.0 * zoom_y - 10.0).abs() < 1e-3);
+        assert!((normalize_value(150.0, -50.0, 150.0) - 1.0).abs() < 1e-6);
+    }
+}
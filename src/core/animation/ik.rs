@@ -0,0 +1,98 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct IkConstraintData {
+    pub id: String,
+    pub name: String,
+    // 从链根到链尾的骨骼，每根骨骼必须是前一根的子骨骼
+    pub bone_ids: Vec<String>,
+    pub target_id: String,
+    pub bend_positive: bool,
+    pub mix: f32,
+}
+
+impl IkConstraintData {
+    pub fn new(id: String, name: String, bone_ids: Vec<String>, target_id: String) -> Self {
+        Self {
+            id,
+            name,
+            bone_ids,
+            target_id,
+            bend_positive: true,
+            mix: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IkConstraint {
+    pub data: IkConstraintData,
+    pub mix: f32,
+    pub bend_positive: bool,
+}
+
+impl IkConstraint {
+    pub fn new(data: IkConstraintData) -> Self {
+        let mix = data.mix;
+        let bend_positive = data.bend_positive;
+        Self { data, mix, bend_positive }
+    }
+}
+
+pub(crate) fn world_angle(dx: f32, dy: f32) -> f32 {
+    dy.atan2(dx).to_degrees()
+}
+
+// 角度差归一化到 [-180, 180)；不用循环，文件中的超大值或 NaN 也不会卡住
+pub(crate) fn shortest_angle(diff: f32) -> f32 {
+    (diff + 180.0).rem_euclid(360.0) - 180.0
+}
+
+// 两骨骼解析解：返回父骨骼 (根→子骨骼原点) 应指向的世界角度
+pub(crate) fn two_bone_parent_angle(root: (f32, f32), len1: f32, len2: f32, target: (f32, f32), bend_positive: bool) -> Option<f32> {
+    if len1 < 1e-4 || len2 < 1e-4 { return None; }
+    let (dx, dy) = (target.0 - root.0, target.1 - root.1);
+    let dist = (dx * dx + dy * dy).sqrt().clamp((len1 - len2).abs() + 1e-4, len1 + len2 - 1e-4);
+    let cos_a = ((len1 * len1 + dist * dist - len2 * len2) / (2.0 * len1 * dist)).clamp(-1.0, 1.0);
+    let a = cos_a.acos().to_degrees();
+    let base = world_angle(dx, dy);
+    Some(if bend_positive { base + a } else { base - a })
+}
+
+// FABRIK：在世界坐标下迭代关节位置，joints[0] 为链根，最后一个为链尾末端
+pub(crate) fn fabrik(joints: &mut [(f32, f32)], target: (f32, f32), iterations: usize) {
+    let n = joints.len();
+    if n < 2 { return; }
+    let lengths: Vec<f32> = (0..n - 1).map(|i| {
+        let (dx, dy) = (joints[i + 1].0 - joints[i].0, joints[i + 1].1 - joints[i].1);
+        (dx * dx + dy * dy).sqrt()
+    }).collect();
+    let root = joints[0];
+    let total: f32 = lengths.iter().sum();
+    let (tdx, tdy) = (target.0 - root.0, target.1 - root.1);
+
+    if (tdx * tdx + tdy * tdy).sqrt() >= total {
+        // 目标不可达时整条链伸直指向目标
+        for i in 0..n - 1 {
+            let (dx, dy) = (target.0 - joints[i].0, target.1 - joints[i].1);
+            let d = (dx * dx + dy * dy).sqrt().max(1e-6);
+            joints[i + 1] = (joints[i].0 + dx / d * lengths[i], joints[i].1 + dy / d * lengths[i]);
+        }
+        return;
+    }
+
+    for _ in 0..iterations {
+        joints[n - 1] = target;
+        for i in (0..n - 1).rev() {
+            let (dx, dy) = (joints[i].0 - joints[i + 1].0, joints[i].1 - joints[i + 1].1);
+            let d = (dx * dx + dy * dy).sqrt().max(1e-6);
+            joints[i] = (joints[i + 1].0 + dx / d * lengths[i], joints[i + 1].1 + dy / d * lengths[i]);
+        }
+        joints[0] = root;
+        for i in 0..n - 1 {
+            let (dx, dy) = (joints[i + 1].0 - joints[i].0, joints[i + 1].1 - joints[i].1);
+            let d = (dx * dx + dy * dy).sqrt().max(1e-6);
+            joints[i + 1] = (joints[i].0 + dx / d * lengths[i], joints[i].1 + dy / d * lengths[i]);
+        }
+        let (ex, ey) = (joints[n - 1].0 - target.0, joints[n - 1].1 - target.1);
+        if ex * ex + ey * ey < 1e-4 { break; }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::core::animation::easing::EasingDirection;
+use crate::core::animation::skeleton::Skeleton;
+use crate::core::animation::timeline::{Animation, CurveType, Keyframe, KeyframeValue, Timeline, TimelineProperty};
+use crate::core::animation::transform::Transform;
+
+// 剪贴板中的一条轨道，关键帧时间相对于复制内容的最早时间
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardTrack {
+    pub target_id: String,
+    pub property: TimelineProperty,
+    pub keyframes: Vec<Keyframe>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeyframeClipboard {
+    pub tracks: Vec<ClipboardTrack>,
+}
+
+// 选区条目与摄影表一致：属性为 None 表示该目标在此时间的所有轨道
+pub type KeySelection = (String, Option<TimelineProperty>, f32);
+
+fn is_selected(selection: &[KeySelection], tl: &Timeline, time: f32) -> bool {
+    selection.iter().any(|k| k.0 == tl.target_id && k.1.as_ref().is_none_or(|p| p == &tl.property) && (k.2 - time).abs() < 0.001)
+}
+
+impl KeyframeClipboard {
+    pub fn from_selection(anim: &Animation, selection: &[KeySelection]) -> Option<Self> {
+        let mut tracks = Vec::new();
+        for tl in &anim.timelines {
+            let keyframes: Vec<Keyframe> = tl.keyframes.iter().filter(|k| is_selected(selection, tl, k.time)).cloned().collect();
+            if !keyframes.is_empty() {
+                tracks.push(ClipboardTrack { target_id: tl.target_id.clone(), property: tl.property.clone(), keyframes });
+            }
+        }
+        if tracks.is_empty() { return None; }
+        let start = tracks.iter().flat_map(|t| t.keyframes.iter().map(|k| k.time)).fold(f32::MAX, f32::min);
+        for track in &mut tracks {
+            for kf in &mut track.keyframes { kf.time -= start; }
+        }
+        Some(Self { tracks })
+    }
+
+    // 当前姿势：每根骨骼的局部变换各记一个关键帧
+    pub fn from_pose(skeleton: &Skeleton) -> Self {
+        let mut tracks = Vec::new();
+        for bone in &skeleton.bones {
+            let t = &bone.local_transform;
+            let values = [
+                (TimelineProperty::Rotation, KeyframeValue::Rotate(t.rotation)),
+                (TimelineProperty::Translation, KeyframeValue::Translate(t.x, t.y)),
+                (TimelineProperty::Scale, KeyframeValue::Scale(t.scale_x, t.scale_y)),
+                (TimelineProperty::Shear, KeyframeValue::Shear(t.shear_x, t.shear_y)),
+            ];
+            for (property, value) in values {
+                tracks.push(ClipboardTrack {
+                    target_id: bone.data.id.clone(),
+                    property,
+                    keyframes: vec![Keyframe { time: 0.0, value, curve: CurveType::Linear, tangent: Default::default() }],
+                });
+            }
+        }
+        Self { tracks }
+    }
+
+    pub fn target_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.tracks.iter().map(|t| t.target_id.as_str()).collect();
+        ids.dedup();
+        ids
+    }
+
+    // 只有一个目标时可以粘贴到另一根骨骼上
+    pub fn retarget(&mut self, target_id: &str) {
+        if self.target_ids().len() == 1 {
+            for track in &mut self.tracks { track.target_id = target_id.to_string(); }
+        }
+    }
+
+    // 左右镜像：按命名约定换到对侧骨骼，变换值相对装配姿势取镜像 (假定骨架左右对称搭建)。
+    // 没有对侧的骨骼 (如躯干) 映射到自身；非骨骼变换轨道保持不变
+    pub fn mirrored(&self, skeleton: &Skeleton) -> Self {
+        let mut tracks = Vec::new();
+        for track in &self.tracks {
+            let source = match skeleton.bones.iter().find(|b| b.data.id == track.target_id) {
+                Some(b) => b,
+                None => { tracks.push(track.clone()); continue; }
+            };
+            let target = mirror_name(&source.data.name)
+                .and_then(|name| skeleton.bones.iter().find(|b| b.data.name == name))
+                .unwrap_or(source);
+            let keyframes = track.keyframes.iter().map(|kf| Keyframe {
+                value: mirror_value(&kf.value, &source.data.local_transform, &target.data.local_transform),
+                ..kf.clone()
+            }).collect();
+            tracks.push(ClipboardTrack { target_id: target.data.id.clone(), property: track.property.clone(), keyframes });
+        }
+        Self { tracks }
+    }
+
+    // 以 time 为起点写入动画，已有同一时间的关键帧被覆盖。返回粘贴出的关键帧，供更新选区
+    pub fn paste(&self, anim: &mut Animation, time: f32) -> Vec<KeySelection> {
+        let mut pasted = Vec::new();
+        for track in &self.tracks {
+            let idx = match anim.timelines.iter().position(|t| t.target_id == track.target_id && t.property == track.property) {
+                Some(idx) => idx,
+                None => {
+                    anim.timelines.push(Timeline::new(track.target_id.clone(), track.property.clone()));
+                    anim.timelines.len() - 1
+                }
+            };
+            let tl = &mut anim.timelines[idx];
+            for kf in &track.keyframes {
+                let kf = Keyframe { time: time + kf.time, ..kf.clone() };
+                pasted.push((tl.target_id.clone(), Some(tl.property.clone()), kf.time));
+                match tl.keyframes.iter_mut().find(|k| (k.time - kf.time).abs() < 0.0001) {
+                    Some(existing) => *existing = kf,
+                    None => tl.keyframes.push(kf),
+                }
+            }
+            tl.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+            crate::core::animation::graph::refresh_auto_tangents(tl);
+        }
+        anim.recalculate_duration();
+        pasted
+    }
+}
+
+// 常见的左右命名：arm_L / arm.R / L_arm / armLeft / 左臂 等
+pub fn mirror_name(name: &str) -> Option<String> {
+    const SUFFIXES: [(&str, &str); 8] = [
+        ("_L", "_R"), ("_l", "_r"), (".L", ".R"), (".l", ".r"),
+        ("-L", "-R"), ("Left", "Right"), ("left", "right"), ("左", "右"),
+    ];
+    const PREFIXES: [(&str, &str); 6] = [
+        ("L_", "R_"), ("l_", "r_"), ("Left", "Right"), ("left", "right"), ("左", "右"), ("L.", "R."),
+    ];
+    for (a, b) in SUFFIXES {
+        if let Some(stem) = name.strip_suffix(a) { return Some(format!("{}{}", stem, b)); }
+        if let Some(stem) = name.strip_suffix(b) { return Some(format!("{}{}", stem, a)); }
+    }
+    for (a, b) in PREFIXES {
+        if let Some(rest) = name.strip_prefix(a) { return Some(format!("{}{}", b, rest)); }
+        if let Some(rest) = name.strip_prefix(b) { return Some(format!("{}{}", a, rest)); }
+    }
+    None
+}
+
+// 以装配姿势为基准取镜像：旋转、水平位移与切变取反，缩放保持相对比例
+pub fn mirror_value(value: &KeyframeValue, source: &Transform, target: &Transform) -> KeyframeValue {
+    let ratio = |v: f32, base: f32| if base.abs() < 1e-6 { v } else { v / base };
+    match *value {
+        KeyframeValue::Rotate(r) => KeyframeValue::Rotate(target.rotation - (r - source.rotation)),
+        KeyframeValue::Translate(x, y) => KeyframeValue::Translate(target.x - (x - source.x), target.y + (y - source.y)),
+        KeyframeValue::Scale(x, y) => KeyframeValue::Scale(target.scale_x * ratio(x, source.scale_x), target.scale_y * ratio(y, source.scale_y)),
+        KeyframeValue::Shear(x, y) => KeyframeValue::Shear(target.shear_x - (x - source.shear_x), target.shear_y - (y - source.shear_y)),
+        ref other => other.clone(),
+    }
+}
+
+// 时间反转后段曲线的形状：贝塞尔手柄绕中心翻转，缓入与缓出互换；
+// 阶跃原本停在起点，反转后应停在终点
+pub fn reverse_curve(curve: CurveType) -> CurveType {
+    match curve {
+        CurveType::Stepped => CurveType::StepsEnd(1),
+        CurveType::Steps(n) => CurveType::StepsEnd(n),
+        CurveType::StepsEnd(1) => CurveType::Stepped,
+        CurveType::StepsEnd(n) => CurveType::Steps(n),
+        CurveType::Bezier(cx1, cy1, cx2, cy2) => CurveType::Bezier(1.0 - cx2, 1.0 - cy2, 1.0 - cx1, 1.0 - cy1),
+        CurveType::Easing(f, EasingDirection::In) => CurveType::Easing(f, EasingDirection::Out),
+        CurveType::Easing(f, EasingDirection::Out) => CurveType::Easing(f, EasingDirection::In),
+        other => other,
+    }
+}
+
+// 在 [start, end] 内把选中的关键帧按时间翻转；与翻转后时间重合的未选中关键帧被覆盖
+pub fn reverse_keys(tl: &mut Timeline, selection: &[KeySelection], start: f32, end: f32) -> Vec<(f32, f32)> {
+    let selected: Vec<usize> = (0..tl.keyframes.len()).filter(|&i| is_selected(selection, tl, tl.keyframes[i].time)).collect();
+    if selected.is_empty() { return Vec::new(); }
+
+    let old_curves: Vec<CurveType> = selected.iter().map(|&i| tl.keyframes[i].curve).collect();
+    for (j, &i) in selected.iter().enumerate() {
+        // 相邻的选中关键帧之间的段反转后由后一个关键帧开始
+        if j > 0 && selected[j - 1] + 1 == i {
+            tl.keyframes[i].curve = reverse_curve(old_curves[j - 1]);
+        } else if j == 0 {
+            tl.keyframes[i].curve = old_curves[selected.len() - 1];
+        }
+    }
+
+    let mut moved = Vec::new();
+    let mut reversed = Vec::new();
+    for &i in selected.iter().rev() {
+        let mut kf = tl.keyframes[i].clone();
+        moved.push((kf.time, start + end - kf.time));
+        kf.time = start + end - kf.time;
+        reversed.push(kf);
+    }
+    let mut index = 0;
+    tl.keyframes.retain(|k| {
+        let keep = !selected.contains(&index) && !reversed.iter().any(|r| (r.time - k.time).abs() < 0.0001);
+        index += 1;
+        keep
+    });
+    tl.keyframes.extend(reversed);
+    tl.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+    crate::core::animation::graph::refresh_auto_tangents(tl);
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_name() {
+        assert_eq!(mirror_name("arm_L").as_deref(), Some("arm_R"));
+        assert_eq!(mirror_name("leg.r").as_deref(), Some("leg.l"));
+        assert_eq!(mirror_name("L_hand").as_deref(), Some("R_hand"));
+        assert_eq!(mirror_name("footRight").as_deref(), Some("footLeft"));
+        assert_eq!(mirror_name("左臂").as_deref(), Some("右臂"));
+        assert_eq!(mirror_name("torso"), None);
+    }
+
+    #[test]
+    fn test_reverse_keys_flips_curves() {
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Rotation);
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(0.0), CurveType::Bezier(0.1, 0.2, 0.3, 0.4));
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(10.0), CurveType::Stepped);
+        tl.add_keyframe(3.0, KeyframeValue::Rotate(30.0), CurveType::Linear);
+        let selection = vec![("b".to_string(), None, 0.0), ("b".to_string(), None, 1.0), ("b".to_string(), None, 3.0)];
+
+        let moved = reverse_keys(&mut tl, &selection, 0.0, 3.0);
+        assert_eq!(moved.len(), 3);
+        let keys: Vec<_> = tl.keyframes.iter().map(|k| (k.time, k.value.clone())).collect();
+        assert_eq!(keys, vec![(0.0, KeyframeValue::Rotate(30.0)), (2.0, KeyframeValue::Rotate(10.0)), (3.0, KeyframeValue::Rotate(0.0))]);
+        assert_eq!(tl.keyframes[0].curve, CurveType::StepsEnd(1), "原 1s→3s 段反转后由 30° 开始，停在终点");
+        assert_eq!(tl.sample(0.0), Some(KeyframeValue::Rotate(30.0)));
+        assert_eq!(tl.sample(1.0), Some(KeyframeValue::Rotate(10.0)), "原段在 [1, 3) 保持 10°，反转后 (0, 2] 保持 10°");
+        assert_eq!(tl.sample(1.99), Some(KeyframeValue::Rotate(10.0)));
+        assert_eq!(tl.keyframes[1].curve, CurveType::Bezier(0.7, 0.6, 0.9, 0.8), "贝塞尔手柄绕中心翻转");
+        let before = Timeline::solve_bezier_y(0.1, 0.2, 0.3, 0.4, 0.5) * 10.0;
+        match tl.sample(2.5) {
+            Some(KeyframeValue::Rotate(r)) => assert!((r - before).abs() < 0.05, "反转后的曲线应与原曲线对称: {} vs {}", r, before),
+            other => panic!("采样结果错误: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reverse_steps_holds_end_value() {
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Translation);
+        tl.add_keyframe(0.0, KeyframeValue::Translate(0.0, 0.0), CurveType::Steps(4));
+        tl.add_keyframe(1.0, KeyframeValue::Translate(100.0, 0.0), CurveType::Linear);
+        let original: Vec<_> = [0.1, 0.3, 0.6, 0.9].iter().map(|&t| tl.sample(t)).collect();
+        let selection = vec![("b".to_string(), None, 0.0), ("b".to_string(), None, 1.0)];
+
+        reverse_keys(&mut tl, &selection, 0.0, 1.0);
+        assert_eq!(tl.keyframes[0].curve, CurveType::StepsEnd(4));
+        let reversed: Vec<_> = [0.9, 0.7, 0.4, 0.1].iter().map(|&t| tl.sample(t)).collect();
+        assert_eq!(reversed, original, "反转后的阶梯与原阶梯时间对称");
+
+        reverse_keys(&mut tl, &selection, 0.0, 1.0);
+        assert_eq!(tl.keyframes[0].curve, CurveType::Steps(4), "再次反转还原");
+    }
+}
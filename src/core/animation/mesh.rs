@@ -0,0 +1,293 @@
+use crate::core::layer::{Layer, CHUNK_SIZE};
+use std::collections::{HashMap, BTreeSet};
+
+// 每个顶点最多受几根骨骼影响
+pub const MAX_BONE_WEIGHTS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshVertex {
+    // 装配姿态下的画布坐标
+    pub x: f32,
+    pub y: f32,
+    // 图层内的像素坐标 (纹理坐标)
+    pub u: f32,
+    pub v: f32,
+    // (骨骼 id, 权重)，权重之和为 1
+    pub weights: Vec<(String, f32)>,
+}
+
+// 网格附件：替代插槽图层的刚性变换，顶点由多根骨骼加权蒙皮
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshAttachment {
+    pub layer_id: String,
+    pub vertices: Vec<MeshVertex>,
+    pub triangles: Vec<[u32; 3]>,
+    // 运行时的自由变形 (FFD) 偏移，装配空间下逐顶点叠加；为空表示无变形
+    pub deform: Vec<(f32, f32)>,
+}
+
+impl MeshAttachment {
+    // 按 cell_size 的网格覆盖图层的不透明区域，每个含不透明像素的格子拆成两个三角形
+    pub fn from_layer(layer: &Layer, cell_size: u32) -> Option<Self> {
+        let cell = cell_size.max(1);
+        let mut cells = BTreeSet::new();
+        for (&(cx, cy), chunk) in &layer.chunks {
+            if chunk.is_empty() { continue; }
+            for ly in 0..CHUNK_SIZE {
+                for lx in 0..CHUNK_SIZE {
+                    let (x, y) = (cx * CHUNK_SIZE + lx, cy * CHUNK_SIZE + ly);
+                    if x >= layer.width || y >= layer.height { continue; }
+                    if chunk.data[((ly * CHUNK_SIZE + lx) * 4 + 3) as usize] > 0 {
+                        cells.insert((y / cell, x / cell));
+                    }
+                }
+            }
+        }
+        if cells.is_empty() { return None; }
+
+        let mut mesh = MeshAttachment { layer_id: layer.id.clone(), vertices: Vec::new(), triangles: Vec::new(), deform: Vec::new() };
+        let mut corner_index: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut corner = |mesh: &mut MeshAttachment, u: u32, v: u32| -> u32 {
+            *corner_index.entry((u, v)).or_insert_with(|| {
+                mesh.vertices.push(MeshVertex {
+                    x: (layer.offset_x + u as i32) as f32,
+                    y: (layer.offset_y + v as i32) as f32,
+                    u: u as f32,
+                    v: v as f32,
+                    weights: Vec::new(),
+                });
+                (mesh.vertices.len() - 1) as u32
+            })
+        };
+        for (row, col) in cells {
+            let (u0, v0) = (col * cell, row * cell);
+            let (u1, v1) = ((u0 + cell).min(layer.width), (v0 + cell).min(layer.height));
+            let tl = corner(&mut mesh, u0, v0);
+            let tr = corner(&mut mesh, u1, v0);
+            let bl = corner(&mut mesh, u0, v1);
+            let br = corner(&mut mesh, u1, v1);
+            mesh.triangles.push([tl, tr, bl]);
+            mesh.triangles.push([bl, tr, br]);
+        }
+        Some(mesh)
+    }
+
+    pub fn deform_offset(&self, index: usize) -> (f32, f32) {
+        self.deform.get(index).copied().unwrap_or((0.0, 0.0))
+    }
+
+    // 按顶点到各骨骼 (装配姿态下的线段) 的距离自动分配权重
+    pub fn auto_weight(&mut self, bones: &[(String, [f32; 6], f32)]) {
+        for vertex in &mut self.vertices {
+            let mut weights: Vec<(String, f32)> = bones.iter().map(|(id, m, length)| {
+                let d = distance_to_segment((vertex.x, vertex.y), (m[4], m[5]), (m[4] + m[0] * length, m[5] + m[1] * length));
+                (id.clone(), 1.0 / (1.0 + d).powi(4))
+            }).collect();
+            normalize_weights(&mut weights);
+            vertex.weights = weights;
+        }
+    }
+
+    // 在 positions (与顶点一一对应的当前显示位置) 上以圆形笔刷为 bone_id 增减权重，返回是否有顶点被修改
+    pub fn paint_weight(&mut self, positions: &[[f32; 2]], bone_id: &str, center: (f32, f32), radius: f32, strength: f32) -> bool {
+        let radius = radius.max(0.5);
+        let mut changed = false;
+        for (vertex, p) in self.vertices.iter_mut().zip(positions.iter()) {
+            let d = ((p[0] - center.0).powi(2) + (p[1] - center.1).powi(2)).sqrt();
+            if d > radius { continue; }
+            let amount = strength * (1.0 - d / radius);
+            let old = vertex.weights.iter().find(|(id, _)| id == bone_id).map(|w| w.1).unwrap_or(0.0);
+            let new = (old + amount).clamp(0.0, 1.0);
+            let others = 1.0 - old;
+            // 仅剩这根骨骼时无法再减少
+            if (new - old).abs() < 1e-6 || (new < old && others < 1e-6) { continue; }
+
+            for (id, w) in vertex.weights.iter_mut() {
+                if id != bone_id {
+                    *w = if others > 1e-6 { *w * (1.0 - new) / others } else { 0.0 };
+                }
+            }
+            match vertex.weights.iter_mut().find(|(id, _)| id == bone_id) {
+                Some(w) => w.1 = new,
+                None => vertex.weights.push((bone_id.to_string(), new)),
+            }
+            normalize_weights(&mut vertex.weights);
+            changed = true;
+        }
+        changed
+    }
+
+    // 删除骨骼时将其权重并入 replacement
+    pub fn replace_bone(&mut self, bone_id: &str, replacement: &str) {
+        for vertex in &mut self.vertices {
+            let moved: f32 = vertex.weights.iter().filter(|(id, _)| id == bone_id).map(|w| w.1).sum();
+            if moved <= 0.0 { continue; }
+            vertex.weights.retain(|(id, _)| id != bone_id);
+            match vertex.weights.iter_mut().find(|(id, _)| id == replacement) {
+                Some(w) => w.1 += moved,
+                None => vertex.weights.push((replacement.to_string(), moved)),
+            }
+            normalize_weights(&mut vertex.weights);
+        }
+    }
+}
+
+// 去掉过小的权重，只保留最大的 MAX_BONE_WEIGHTS 个并归一化
+pub fn normalize_weights(weights: &mut Vec<(String, f32)>) {
+    weights.retain(|(_, w)| *w > 1e-4);
+    weights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    weights.truncate(MAX_BONE_WEIGHTS);
+    let sum: f32 = weights.iter().map(|w| w.1).sum();
+    if sum > 0.0 {
+        for w in weights.iter_mut() { w.1 /= sum; }
+    }
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let len2 = abx * abx + aby * aby;
+    let t = if len2 > 1e-8 { (((p.0 - a.0) * abx + (p.1 - a.1) * aby) / len2).clamp(0.0, 1.0) } else { 0.0 };
+    ((p.0 - a.0 - abx * t).powi(2) + (p.1 - a.1 - aby * t).powi(2)).sqrt()
+}
+
+// 蒙皮后的网格：画布坐标的顶点位置与图层内纹理坐标
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeformedMesh {
+    pub positions: Vec<[f32; 2]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+// 网格光栅化结果：包围盒内每个画布像素对应的图层像素 (最近邻采样)
+#[derive(Debug, Clone)]
+pub struct MeshRaster {
+    x0: i32,
+    y0: i32,
+    width: i32,
+    height: i32,
+    texels: Vec<Option<(i32, i32)>>,
+}
+
+impl MeshRaster {
+    pub fn lookup(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (rx, ry) = (x - self.x0, y - self.y0);
+        if rx < 0 || ry < 0 || rx >= self.width || ry >= self.height { return None; }
+        self.texels[(ry * self.width + rx) as usize]
+    }
+}
+
+impl DeformedMesh {
+    // 以像素中心做重心坐标插值，纹理坐标向下取整得到图层像素，保持像素锐利
+    pub fn rasterize(&self, canvas_w: u32, canvas_h: u32) -> MeshRaster {
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+        for p in &self.positions {
+            min = (min.0.min(p[0]), min.1.min(p[1]));
+            max = (max.0.max(p[0]), max.1.max(p[1]));
+        }
+        let x0 = (min.0.floor() as i32).max(0);
+        let y0 = (min.1.floor() as i32).max(0);
+        let x1 = (max.0.ceil() as i32).min(canvas_w as i32);
+        let y1 = (max.1.ceil() as i32).min(canvas_h as i32);
+        let (width, height) = ((x1 - x0).max(0), (y1 - y0).max(0));
+        let mut raster = MeshRaster { x0, y0, width, height, texels: vec![None; (width * height) as usize] };
+        if width == 0 || height == 0 { return raster; }
+
+        for tri in &self.triangles {
+            let (p, uv) = match (self.corners(tri, &self.positions), self.corners(tri, &self.uvs)) {
+                (Some(p), Some(uv)) => (p, uv),
+                _ => continue,
+            };
+            let area = (p[1][0] - p[0][0]) * (p[2][1] - p[0][1]) - (p[2][0] - p[0][0]) * (p[1][1] - p[0][1]);
+            if area.abs() < 1e-6 { continue; }
+
+            let tx0 = (p[0][0].min(p[1][0]).min(p[2][0]).floor() as i32).max(x0);
+            let ty0 = (p[0][1].min(p[1][1]).min(p[2][1]).floor() as i32).max(y0);
+            let tx1 = (p[0][0].max(p[1][0]).max(p[2][0]).ceil() as i32).min(x1);
+            let ty1 = (p[0][1].max(p[1][1]).max(p[2][1]).ceil() as i32).min(y1);
+            for y in ty0..ty1 {
+                for x in tx0..tx1 {
+                    let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+                    let w0 = ((p[1][0] - cx) * (p[2][1] - cy) - (p[2][0] - cx) * (p[1][1] - cy)) / area;
+                    let w1 = ((p[2][0] - cx) * (p[0][1] - cy) - (p[0][0] - cx) * (p[2][1] - cy)) / area;
+                    let w2 = 1.0 - w0 - w1;
+                    if w0 < -1e-4 || w1 < -1e-4 || w2 < -1e-4 { continue; }
+                    let u = w0 * uv[0][0] + w1 * uv[1][0] + w2 * uv[2][0];
+                    let v = w0 * uv[0][1] + w1 * uv[1][1] + w2 * uv[2][1];
+                    raster.texels[((y - y0) * width + (x - x0)) as usize] = Some((u.floor() as i32, v.floor() as i32));
+                }
+            }
+        }
+        raster
+    }
+
+    fn corners(&self, tri: &[u32; 3], points: &[[f32; 2]]) -> Option<[[f32; 2]; 3]> {
+        Some([*points.get(tri[0] as usize)?, *points.get(tri[1] as usize)?, *points.get(tri[2] as usize)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+
+    fn block_layer() -> Layer {
+        // 16x16 图层，(4..12, 4..8) 不透明
+        let mut layer = Layer::new("l".into(), "L".into(), 16, 16);
+        layer.offset_x = 10;
+        for y in 4..8 {
+            for x in 4..12 {
+                layer.set_pixel_raw(x, y, Color::new(255, 0, 0, 255)).unwrap();
+            }
+        }
+        layer
+    }
+
+    #[test]
+    fn test_triangulate_opaque_region() {
+        let mesh = MeshAttachment::from_layer(&block_layer(), 4).unwrap();
+        // 两个 4x4 格子：6 个顶点、4 个三角形
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.triangles.len(), 4);
+        assert!(mesh.vertices.iter().all(|v| v.x == v.u + 10.0 && v.y == v.v), "装配位置 = 图层偏移 + 纹理坐标");
+        assert!(MeshAttachment::from_layer(&Layer::new("e".into(), "E".into(), 8, 8), 4).is_none(), "空图层不生成网格");
+    }
+
+    #[test]
+    fn test_weights_are_normalized_and_limited() {
+        let mut w: Vec<(String, f32)> = (0..6).map(|i| (format!("b{}", i), i as f32 + 1.0)).collect();
+        normalize_weights(&mut w);
+        assert_eq!(w.len(), MAX_BONE_WEIGHTS);
+        assert_eq!(w[0].0, "b5");
+        assert!((w.iter().map(|x| x.1).sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_paint_weight_shifts_influence() {
+        let mut mesh = MeshAttachment::from_layer(&block_layer(), 4).unwrap();
+        for v in &mut mesh.vertices { v.weights = vec![("a".into(), 1.0)]; }
+        let positions: Vec<[f32; 2]> = mesh.vertices.iter().map(|v| [v.x, v.y]).collect();
+        assert!(mesh.paint_weight(&positions, "b", (14.0, 4.0), 1.0, 0.5));
+
+        let painted = mesh.vertices.iter().position(|v| v.x == 14.0 && v.y == 4.0).unwrap();
+        let w = &mesh.vertices[painted].weights;
+        assert_eq!(w.len(), 2);
+        assert!((w.iter().map(|x| x.1).sum::<f32>() - 1.0).abs() < 1e-5, "权重之和应为 1");
+        assert!(mesh.vertices.iter().enumerate().all(|(i, v)| i == painted || v.weights.len() == 1), "笔刷外的顶点不受影响");
+    }
+
+    #[test]
+    fn test_rasterize_maps_pixels_to_texels() {
+        let mesh = MeshAttachment::from_layer(&block_layer(), 4).unwrap();
+        // 整体右移 3 像素
+        let deformed = DeformedMesh {
+            positions: mesh.vertices.iter().map(|v| [v.x + 3.0, v.y]).collect(),
+            uvs: mesh.vertices.iter().map(|v| [v.u, v.v]).collect(),
+            triangles: mesh.triangles.clone(),
+        };
+        let raster = deformed.rasterize(64, 64);
+        assert_eq!(raster.lookup(17, 4), Some((4, 4)), "平移后像素应一一对应");
+        assert_eq!(raster.lookup(24, 7), Some((11, 7)));
+        assert_eq!(raster.lookup(16, 4), None, "网格外无像素");
+    }
+}
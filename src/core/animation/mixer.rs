@@ -0,0 +1,389 @@
+use super::skeleton::Skeleton;
+use super::timeline::{Animation, KeyframeValue, TimelineProperty};
+use super::transform::Transform;
+use crate::core::color::Color;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixBlend {
+    // 按权重向动画姿态插值
+    Replace,
+    // 将动画相对装配姿态的差值按权重叠加 (如呼吸、抖动)
+    Additive,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixTrack {
+    pub animation_id: String,
+    pub time: f32,
+    pub weight: f32,
+    pub blend: MixBlend,
+    pub looping: bool,
+    // 交叉淡入：从 mix_from 过渡到本轨道，mix_time / mix_duration 为过渡进度
+    pub mix_from: Option<Box<MixTrack>>,
+    pub mix_duration: f32,
+    pub mix_time: f32,
+}
+
+impl MixTrack {
+    pub fn new(animation_id: String, blend: MixBlend, weight: f32, looping: bool) -> Self {
+        Self {
+            animation_id,
+            time: 0.0,
+            weight,
+            blend,
+            looping,
+            mix_from: None,
+            mix_duration: 0.0,
+            mix_time: 0.0,
+        }
+    }
+
+    pub fn mix_alpha(&self) -> f32 {
+        if self.mix_from.is_none() || self.mix_duration <= 0.0 { return 1.0; }
+        (self.mix_time / self.mix_duration).clamp(0.0, 1.0)
+    }
+
+    fn advance(&mut self, dt: f32) {
+        self.time += dt;
+        if let Some(from) = &mut self.mix_from {
+            from.advance(dt);
+            self.mix_time += dt;
+            if self.mix_time >= self.mix_duration {
+                self.mix_from = None;
+            }
+        }
+    }
+}
+
+// 骨骼局部变换与插槽颜色的快照，按 Skeleton 中的索引排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pose {
+    pub bones: Vec<Transform>,
+    pub colors: Vec<Color>,
+    // IK 约束的 (混合度, 弯曲方向)
+    pub iks: Vec<(f32, bool)>,
+    pub attachments: Vec<Option<String>>,
+    pub draw_order: Option<Vec<String>>,
+    // 网格附件的顶点变形偏移，按 Skeleton::meshes 的索引排列，空表示无变形
+    pub deforms: Vec<Vec<(f32, f32)>>,
+    // 图层效果数值，只含有动画轨道的效果
+    pub effects: HashMap<String, f32>,
+}
+
+impl Pose {
+    pub fn setup(skeleton: &Skeleton) -> Self {
+        Self {
+            bones: skeleton.bones.iter().map(|b| b.data.local_transform).collect(),
+            colors: skeleton.slots.iter().map(|s| s.data.color).collect(),
+            iks: skeleton.ik_constraints.iter().map(|c| (c.data.mix, c.data.bend_positive)).collect(),
+            attachments: skeleton.slots.iter().map(|s| s.data.attachment.clone()).collect(),
+            draw_order: None,
+            deforms: vec![Vec::new(); skeleton.meshes.len()],
+            effects: HashMap::new(),
+        }
+    }
+
+    pub fn apply_to(&self, skeleton: &mut Skeleton) {
+        for (bone, t) in skeleton.bones.iter_mut().zip(self.bones.iter()) { bone.local_transform = *t; }
+        for (slot, c) in skeleton.slots.iter_mut().zip(self.colors.iter()) { slot.current_color = *c; }
+        for (ik, (mix, bend)) in skeleton.ik_constraints.iter_mut().zip(self.iks.iter()) {
+            ik.mix = *mix;
+            ik.bend_positive = *bend;
+        }
+        for (slot, a) in skeleton.slots.iter_mut().zip(self.attachments.iter()) { slot.current_attachment = a.clone(); }
+        skeleton.draw_order = self.draw_order.clone();
+        for (mesh, d) in skeleton.meshes.iter_mut().zip(self.deforms.iter()) { mesh.deform = d.clone(); }
+        skeleton.effect_amounts = self.effects.clone();
+    }
+
+    pub fn lerp(&self, other: &Pose, alpha: f32) -> Pose {
+        Pose {
+            bones: self.bones.iter().zip(other.bones.iter()).map(|(a, b)| lerp_transform(a, b, alpha)).collect(),
+            colors: self.colors.iter().zip(other.colors.iter()).map(|(a, b)| lerp_color(*a, *b, alpha)).collect(),
+            iks: self.iks.iter().zip(other.iks.iter()).map(|(a, b)| (a.0 + (b.0 - a.0) * alpha, if alpha < 0.5 { a.1 } else { b.1 })).collect(),
+            // 显示图层与绘制顺序无法插值，过半时切换
+            attachments: if alpha < 0.5 { self.attachments.clone() } else { other.attachments.clone() },
+            draw_order: if alpha < 0.5 { self.draw_order.clone() } else { other.draw_order.clone() },
+            deforms: self.deforms.iter().zip(other.deforms.iter()).map(|(a, b)| lerp_deform(a, b, alpha)).collect(),
+            effects: lerp_effects(&self.effects, &other.effects, alpha),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnimationMixer {
+    // 轨道按索引从低到高依次叠加
+    pub tracks: Vec<Option<MixTrack>>,
+}
+
+impl AnimationMixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_track(&mut self, index: usize, track: MixTrack) -> &mut MixTrack {
+        if self.tracks.len() <= index { self.tracks.resize(index + 1, None); }
+        self.tracks[index].insert(track)
+    }
+
+    pub fn set_animation(&mut self, index: usize, animation_id: &str, looping: bool) -> &mut MixTrack {
+        self.set_track(index, MixTrack::new(animation_id.to_string(), MixBlend::Replace, 1.0, looping))
+    }
+
+    pub fn add_additive(&mut self, index: usize, animation_id: &str, weight: f32) -> &mut MixTrack {
+        self.set_track(index, MixTrack::new(animation_id.to_string(), MixBlend::Additive, weight, true))
+    }
+
+    // 在同一轨道上从当前动画交叉淡入到新动画
+    pub fn crossfade(&mut self, index: usize, animation_id: &str, duration: f32, looping: bool) -> &mut MixTrack {
+        let previous = self.tracks.get_mut(index).and_then(|t| t.take());
+        let mut track = MixTrack::new(animation_id.to_string(), MixBlend::Replace, 1.0, looping);
+        if let Some(mut prev) = previous {
+            if duration > 0.0 {
+                track.weight = prev.weight;
+                track.blend = prev.blend;
+                // 嵌套的过渡只保留最近一层
+                prev.mix_from = None;
+                track.mix_from = Some(Box::new(prev));
+                track.mix_duration = duration;
+            }
+        }
+        self.set_track(index, track)
+    }
+
+    pub fn clear_track(&mut self, index: usize) {
+        if let Some(t) = self.tracks.get_mut(index) { *t = None; }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for track in self.tracks.iter_mut().flatten() { track.advance(dt); }
+    }
+
+    pub fn apply(&self, animations: &HashMap<String, Animation>, skeleton: &mut Skeleton) {
+        let mut pose = Pose::setup(skeleton);
+        let setup = pose.clone();
+
+        for track in self.tracks.iter().flatten() {
+            let weight = track.weight.clamp(0.0, 1.0);
+            if weight <= 0.0 { continue; }
+
+            match track.blend {
+                MixBlend::Replace => {
+                    let mut target = sample_track(track, animations, skeleton, &pose);
+                    if let Some(from) = &track.mix_from {
+                        let from_pose = sample_track(from, animations, skeleton, &pose);
+                        target = from_pose.lerp(&target, track.mix_alpha());
+                    }
+                    pose = pose.lerp(&target, weight);
+                }
+                MixBlend::Additive => {
+                    let mut target = sample_track(track, animations, skeleton, &setup);
+                    if let Some(from) = &track.mix_from {
+                        let from_pose = sample_track(from, animations, skeleton, &setup);
+                        target = from_pose.lerp(&target, track.mix_alpha());
+                    }
+                    add_delta(&mut pose, &setup, &target, weight);
+                }
+            }
+        }
+
+        pose.apply_to(skeleton);
+    }
+}
+
+fn local_time(track: &MixTrack, anim: &Animation) -> f32 {
+    if track.looping && anim.duration > 0.0 { track.time.rem_euclid(anim.duration) } else { track.time.clamp(0.0, anim.duration.max(0.0)) }
+}
+
+// 以 base 为底，将轨道动画在其时间点的采样值写入
+fn sample_track(track: &MixTrack, animations: &HashMap<String, Animation>, skeleton: &Skeleton, base: &Pose) -> Pose {
+    let mut pose = base.clone();
+    let anim = match animations.get(&track.animation_id) {
+        Some(a) => a,
+        None => return pose,
+    };
+    let t = local_time(track, anim);
+
+    for timeline in &anim.timelines {
+        let val = match timeline.sample(t) {
+            Some(v) => v,
+            None => continue,
+        };
+        if timeline.property == TimelineProperty::Color {
+            if let (Some(idx), KeyframeValue::Color(r, g, b, a)) = (skeleton.slots.iter().position(|s| s.data.id == timeline.target_id), val) {
+                pose.colors[idx] = Color::new(r, g, b, a);
+            }
+            continue;
+        }
+        if timeline.property == TimelineProperty::Attachment {
+            if let (Some(idx), KeyframeValue::Attachment(a)) = (skeleton.slots.iter().position(|s| s.data.id == timeline.target_id), val) {
+                pose.attachments[idx] = a;
+            }
+            continue;
+        }
+        if let KeyframeValue::DrawOrder(order) = val {
+            pose.draw_order = Some(order);
+            continue;
+        }
+        if timeline.property == TimelineProperty::Ik {
+            if let (Some(idx), KeyframeValue::Ik(mix, bend)) = (skeleton.ik_constraints.iter().position(|c| c.data.id == timeline.target_id), val) {
+                pose.iks[idx] = (mix, bend);
+            }
+            continue;
+        }
+        if let KeyframeValue::EffectAmount(value) = val {
+            pose.effects.insert(timeline.target_id.clone(), value);
+            continue;
+        }
+        if timeline.property == TimelineProperty::Deform {
+            if let (Some(idx), KeyframeValue::Deform(offsets)) = (skeleton.meshes.iter().position(|m| m.layer_id == timeline.target_id), val) {
+                pose.deforms[idx] = offsets;
+            }
+            continue;
+        }
+        if let Some(idx) = skeleton.bones.iter().position(|b| b.data.id == timeline.target_id) {
+            let bone = &mut pose.bones[idx];
+            match val {
+                KeyframeValue::Rotate(r) => bone.rotation = r,
+                KeyframeValue::Translate(x, y) => { bone.x = x; bone.y = y; }
+                KeyframeValue::Scale(x, y) => { bone.scale_x = x; bone.scale_y = y; }
+                KeyframeValue::Shear(x, y) => { bone.shear_x = x; bone.shear_y = y; }
+                _ => {}
+            }
+        }
+    }
+    pose
+}
+
+fn add_delta(pose: &mut Pose, setup: &Pose, target: &Pose, weight: f32) {
+    for ((p, s), t) in pose.bones.iter_mut().zip(setup.bones.iter()).zip(target.bones.iter()) {
+        p.x += (t.x - s.x) * weight;
+        p.y += (t.y - s.y) * weight;
+        p.rotation += shortest_angle(s.rotation, t.rotation) * weight;
+        p.scale_x += (t.scale_x - s.scale_x) * weight;
+        p.scale_y += (t.scale_y - s.scale_y) * weight;
+        p.shear_x += (t.shear_x - s.shear_x) * weight;
+        p.shear_y += (t.shear_y - s.shear_y) * weight;
+    }
+    for ((p, s), t) in pose.deforms.iter_mut().zip(setup.deforms.iter()).zip(target.deforms.iter()) {
+        let delta: Vec<(f32, f32)> = lerp_deform(s, t, 1.0).iter().enumerate()
+            .map(|(i, d)| { let o = s.get(i).copied().unwrap_or((0.0, 0.0)); (d.0 - o.0, d.1 - o.1) })
+            .collect();
+        *p = lerp_deform(p, &delta, 0.0).iter().zip(delta.iter().chain(std::iter::repeat(&(0.0, 0.0))))
+            .map(|(a, d)| (a.0 + d.0 * weight, a.1 + d.1 * weight))
+            .collect();
+    }
+}
+
+// 只有一侧有动画值的效果不插值 (另一侧的参数保存在图层上)
+fn lerp_effects(a: &HashMap<String, f32>, b: &HashMap<String, f32>, alpha: f32) -> HashMap<String, f32> {
+    let mut out = a.clone();
+    for (id, &to) in b {
+        out.insert(id.clone(), a.get(id).map_or(to, |&from| from + (to - from) * alpha));
+    }
+    out
+}
+
+// 空的变形视为全零偏移
+fn lerp_deform(a: &[(f32, f32)], b: &[(f32, f32)], alpha: f32) -> Vec<(f32, f32)> {
+    if a.is_empty() && b.is_empty() { return Vec::new(); }
+    (0..a.len().max(b.len())).map(|i| {
+        let p = a.get(i).copied().unwrap_or((0.0, 0.0));
+        let q = b.get(i).copied().unwrap_or((0.0, 0.0));
+        (p.0 + (q.0 - p.0) * alpha, p.1 + (q.1 - p.1) * alpha)
+    }).collect()
+}
+
+fn shortest_angle(from: f32, to: f32) -> f32 {
+    super::ik::shortest_angle(to - from)
+}
+
+fn lerp_transform(a: &Transform, b: &Transform, t: f32) -> Transform {
+    Transform {
+        x: a.x + (b.x - a.x) * t,
+        y: a.y + (b.y - a.y) * t,
+        rotation: a.rotation + shortest_angle(a.rotation, b.rotation) * t,
+        scale_x: a.scale_x + (b.scale_x - a.scale_x) * t,
+        scale_y: a.scale_y + (b.scale_y - a.scale_y) * t,
+        shear_x: a.shear_x + (b.shear_x - a.shear_x) * t,
+        shear_y: a.shear_y + (b.shear_y - a.shear_y) * t,
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let l = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color::new(l(a.r, b.r), l(a.g, b.g), l(a.b, b.b), l(a.a, b.a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::animation::bone::BoneData;
+    use crate::core::animation::timeline::{Timeline, CurveType};
+
+    fn rotation_anim(name: &str, from: f32, to: f32) -> Animation {
+        let mut anim = Animation::new(name.into(), 1.0);
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Rotation);
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(from), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(to), CurveType::Linear);
+        anim.timelines.push(tl);
+        anim
+    }
+
+    fn setup() -> (Skeleton, HashMap<String, Animation>) {
+        let mut skel = Skeleton::new();
+        skel.add_bone(BoneData::new("b".into(), "b".into()));
+        let mut anims = HashMap::new();
+        anims.insert("idle".to_string(), rotation_anim("idle", 10.0, 10.0));
+        anims.insert("run".to_string(), rotation_anim("run", 50.0, 50.0));
+        anims.insert("breath".to_string(), rotation_anim("breath", 0.0, 20.0));
+        (skel, anims)
+    }
+
+    fn rot(skel: &Skeleton) -> f32 { skel.bones[0].local_transform.rotation }
+
+    #[test]
+    fn test_crossfade_progress() {
+        let (mut skel, anims) = setup();
+        let mut mixer = AnimationMixer::new();
+        mixer.set_animation(0, "idle", true);
+        mixer.crossfade(0, "run", 0.4, true);
+
+        mixer.apply(&anims, &mut skel);
+        assert!((rot(&skel) - 10.0).abs() < 1e-4, "过渡开始时应为旧动画姿态");
+
+        mixer.update(0.2);
+        mixer.apply(&anims, &mut skel);
+        assert!((rot(&skel) - 30.0).abs() < 1e-4, "过渡一半时应为两者平均");
+
+        mixer.update(0.3);
+        assert!(mixer.tracks[0].as_ref().unwrap().mix_from.is_none(), "过渡结束后应丢弃旧轨道");
+        mixer.apply(&anims, &mut skel);
+        assert!((rot(&skel) - 50.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_track_weight_and_additive_layer() {
+        let (mut skel, anims) = setup();
+        let mut mixer = AnimationMixer::new();
+        mixer.set_animation(0, "idle", true);
+        mixer.set_animation(1, "run", true).weight = 0.25;
+        mixer.apply(&anims, &mut skel);
+        assert!((rot(&skel) - 20.0).abs() < 1e-4, "上层轨道按权重混合");
+
+        mixer.clear_track(1);
+        mixer.add_additive(2, "breath", 0.5).time = 0.5;
+        mixer.apply(&anims, &mut skel);
+        assert!((rot(&skel) - 15.0).abs() < 1e-4, "叠加层以相对装配姿态的差值叠加");
+    }
+
+    #[test]
+    fn test_shortest_angle_handles_extreme_values() {
+        assert_eq!(shortest_angle(350.0, 10.0), 20.0);
+        assert_eq!(shortest_angle(10.0, 350.0), -20.0);
+        assert!(shortest_angle(0.0, 1e30).abs() <= 180.0, "超大角度应立即归一化");
+        assert!(shortest_angle(0.0, f32::NAN).is_nan());
+        assert!(shortest_angle(0.0, f32::INFINITY).is_nan());
+    }
+}
@@ -3,4 +3,12 @@ pub mod bone;
 pub mod skeleton;
 pub mod timeline;
 pub mod slot;
+pub mod mixer;
+pub mod ik;
+pub mod event;
+pub mod mesh;
+pub mod graph;
+pub mod easing;
+pub mod keyframe_edit;
+pub mod retarget;
 pub mod tests_z_order;
\ No newline at end of file
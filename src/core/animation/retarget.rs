@@ -0,0 +1,157 @@
+use crate::core::animation::bone::BoneData;
+use crate::core::animation::skeleton::Skeleton;
+use crate::core::animation::timeline::{Animation, Keyframe, KeyframeValue, Timeline, TimelineProperty, DRAW_ORDER_TARGET};
+
+// 源工程的一根骨骼对应到当前骨架的哪根骨骼，None 表示丢弃该骨骼的轨道
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoneMapping {
+    pub source_id: String,
+    pub source_name: String,
+    pub target_id: Option<String>,
+}
+
+// 从其它工程导入动画时等待用户确认的内容
+#[derive(Debug, Clone)]
+pub struct AnimationImport {
+    pub source_path: String,
+    pub skeleton: Skeleton,
+    // (动画 id, 动画, 是否导入)，按名称排序
+    pub animations: Vec<(String, Animation, bool)>,
+    pub mapping: Vec<BoneMapping>,
+    // 位移按骨骼长度比例缩放
+    pub rescale_translation: bool,
+}
+
+// 忽略大小写与分隔符：Arm_L、arm.l、ARM-L 视为同名
+fn normalize_name(name: &str) -> String {
+    name.chars().filter(|c| !matches!(c, '_' | '.' | '-' | ' ')).flat_map(|c| c.to_lowercase()).collect()
+}
+
+// 先按名称精确匹配，再按规范化后的名称匹配；每根目标骨骼最多被匹配一次
+pub fn auto_map(source: &Skeleton, target: &Skeleton) -> Vec<BoneMapping> {
+    let mut used: Vec<&str> = Vec::new();
+    let mut mapping: Vec<BoneMapping> = source.bones.iter().map(|b| BoneMapping {
+        source_id: b.data.id.clone(),
+        source_name: b.data.name.clone(),
+        target_id: None,
+    }).collect();
+
+    let passes: [fn(&str, &str) -> bool; 2] = [
+        |a, b| a == b,
+        |a, b| normalize_name(a) == normalize_name(b),
+    ];
+    for matches in passes {
+        for m in mapping.iter_mut().filter(|m| m.target_id.is_none()) {
+            if let Some(bone) = target.bones.iter().find(|b| !used.contains(&b.data.id.as_str()) && matches(&m.source_name, &b.data.name)) {
+                used.push(&bone.data.id);
+                m.target_id = Some(bone.data.id.clone());
+            }
+        }
+    }
+    mapping
+}
+
+// 以装配姿势为基准换算到目标骨骼：旋转、切变取差值，缩放取比例，位移取差值并可按骨骼长度比例缩放
+pub fn retarget_value(value: &KeyframeValue, source: &BoneData, target: &BoneData, rescale_translation: bool) -> KeyframeValue {
+    let (s, t) = (&source.local_transform, &target.local_transform);
+    let ratio = |v: f32, base: f32| if base.abs() < 1e-6 { v } else { v / base };
+    let length_ratio = if rescale_translation && source.length > 1e-6 && target.length > 1e-6 { target.length / source.length } else { 1.0 };
+    match *value {
+        KeyframeValue::Rotate(r) => KeyframeValue::Rotate(t.rotation + (r - s.rotation)),
+        KeyframeValue::Translate(x, y) => KeyframeValue::Translate(t.x + (x - s.x) * length_ratio, t.y + (y - s.y) * length_ratio),
+        KeyframeValue::Scale(x, y) => KeyframeValue::Scale(t.scale_x * ratio(x, s.scale_x), t.scale_y * ratio(y, s.scale_y)),
+        KeyframeValue::Shear(x, y) => KeyframeValue::Shear(t.shear_x + (x - s.shear_x), t.shear_y + (y - s.shear_y)),
+        ref other => other.clone(),
+    }
+}
+
+// 非骨骼轨道 (插槽、IK、网格、绘制顺序) 只在当前骨架有同 id 的目标时保留
+fn keeps_target(tl: &Timeline, target: &Skeleton) -> bool {
+    match tl.property {
+        TimelineProperty::Color | TimelineProperty::Attachment => target.slots.iter().any(|s| s.data.id == tl.target_id),
+        TimelineProperty::Ik => target.ik_constraints.iter().any(|c| c.data.id == tl.target_id),
+        TimelineProperty::Deform => target.mesh(&tl.target_id).is_some_and(|mesh| tl.keyframes.iter().all(|k| match &k.value {
+            KeyframeValue::Deform(offsets) => offsets.len() == mesh.vertices.len(),
+            _ => false,
+        })),
+        TimelineProperty::DrawOrder => tl.target_id == DRAW_ORDER_TARGET && tl.keyframes.iter().all(|k| match &k.value {
+            KeyframeValue::DrawOrder(order) => order.iter().all(|id| target.slots.iter().any(|s| &s.data.id == id)),
+            _ => false,
+        }),
+        // 图层效果不属于骨架，随动画保留
+        TimelineProperty::EffectAmount => true,
+        _ => false,
+    }
+}
+
+// 按映射表把动画换到当前骨架上；未映射骨骼的轨道被丢弃，多根骨骼映射到同一目标时只取第一条有关键帧的轨道
+pub fn retarget_animation(anim: &Animation, source: &Skeleton, target: &Skeleton, mapping: &[BoneMapping], rescale_translation: bool) -> Animation {
+    let mut result = Animation::new(anim.name.clone(), anim.duration);
+    result.events = anim.events.clone();
+    for tl in anim.timelines.iter().filter(|t| !t.keyframes.is_empty()) {
+        let is_bone_property = matches!(tl.property, TimelineProperty::Rotation | TimelineProperty::Translation | TimelineProperty::Scale | TimelineProperty::Shear);
+        if !is_bone_property {
+            if keeps_target(tl, target) { result.timelines.push(tl.clone()); }
+            continue;
+        }
+        let source_bone = match source.bones.iter().find(|b| b.data.id == tl.target_id) {
+            Some(b) => b,
+            None => continue,
+        };
+        let target_id = match mapping.iter().find(|m| m.source_id == tl.target_id).and_then(|m| m.target_id.as_ref()) {
+            Some(id) => id,
+            None => continue,
+        };
+        let target_bone = match target.bones.iter().find(|b| &b.data.id == target_id) {
+            Some(b) => b,
+            None => continue,
+        };
+        if result.timelines.iter().any(|t| &t.target_id == target_id && t.property == tl.property) { continue; }
+        let keyframes = tl.keyframes.iter().map(|kf| Keyframe {
+            value: retarget_value(&kf.value, &source_bone.data, &target_bone.data, rescale_translation),
+            ..kf.clone()
+        }).collect();
+        result.timelines.push(Timeline { target_id: target_id.clone(), property: tl.property.clone(), keyframes });
+    }
+    result.initialize_tracks(target);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skeleton(bones: &[(&str, &str, f32)]) -> Skeleton {
+        let mut skel = Skeleton::new();
+        for &(id, name, length) in bones {
+            let mut bone = BoneData::new(id.into(), name.into());
+            bone.length = length;
+            skel.add_bone(bone);
+        }
+        skel
+    }
+
+    #[test]
+    fn test_auto_map_prefers_exact_names() {
+        let source = skeleton(&[("a", "Arm_L", 10.0), ("b", "arm_l", 10.0), ("c", "Tail", 10.0)]);
+        let target = skeleton(&[("x", "arm.L", 10.0), ("y", "arm_l", 10.0)]);
+        let mapping = auto_map(&source, &target);
+        assert_eq!(mapping[0].target_id.as_deref(), Some("x"), "规范化后同名");
+        assert_eq!(mapping[1].target_id.as_deref(), Some("y"), "精确同名优先");
+        assert_eq!(mapping[2].target_id, None);
+    }
+
+    #[test]
+    fn test_retarget_value_relative_to_setup() {
+        let mut source = BoneData::new("s".into(), "s".into());
+        source.length = 10.0;
+        source.local_transform.x = 5.0;
+        source.local_transform.rotation = 90.0;
+        let mut target = BoneData::new("t".into(), "t".into());
+        target.length = 20.0;
+        target.local_transform.x = 8.0;
+        assert_eq!(retarget_value(&KeyframeValue::Rotate(100.0), &source, &target, true), KeyframeValue::Rotate(10.0));
+        assert_eq!(retarget_value(&KeyframeValue::Translate(7.0, 1.0), &source, &target, false), KeyframeValue::Translate(10.0, 1.0));
+        assert_eq!(retarget_value(&KeyframeValue::Translate(7.0, 1.0), &source, &target, true), KeyframeValue::Translate(12.0, 2.0));
+    }
+}
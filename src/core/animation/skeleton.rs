@@ -1,6 +1,9 @@
-use super::bone::BoneData;
+use super::bone::{BoneData, TransformMode};
 use super::slot::RuntimeSlot;
-use super::transform::Transform;
+use super::transform::{Transform, multiply_matrix, invert_matrix};
+use super::mesh::{MeshAttachment, DeformedMesh};
+use super::ik::{IkConstraint, world_angle, shortest_angle, two_bone_parent_angle, fabrik};
+use crate::core::layer::LosslessTransform;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -27,6 +30,13 @@ impl RuntimeBone {
 pub struct Skeleton {
     pub bones: Vec<RuntimeBone>,
     pub slots: Vec<RuntimeSlot>,
+    pub ik_constraints: Vec<IkConstraint>,
+    // 动画设置的绘制顺序 (插槽 id 从下到上)，None 时按 slots 的装配顺序
+    pub draw_order: Option<Vec<String>>,
+    // 网格附件 (按图层 id 对应)，存在时该图层由顶点蒙皮变形代替刚性变换
+    pub meshes: Vec<MeshAttachment>,
+    // 动画驱动的图层效果数值 (效果 id -> 数值)，同步到 PixelStore::effect_amounts
+    pub effect_amounts: HashMap<String, f32>,
     name_to_index: HashMap<String, usize>,
 }
 
@@ -49,42 +59,120 @@ impl Skeleton {
     }
 
     pub fn update(&mut self) {
+        // IK 的结果作为额外的局部旋转叠加，不改写 local_transform，以免污染关键帧数据
+        let mut ik_rotation = vec![0.0_f32; self.bones.len()];
+        self.update_world(&ik_rotation);
+        for c in 0..self.ik_constraints.len() {
+            self.solve_ik(c, &mut ik_rotation);
+        }
+    }
+
+    fn update_world(&mut self, ik_rotation: &[f32]) {
         for i in 0..self.bones.len() {
-            let (parent_matrix, local_matrix) = {
+            let (parent_matrix, local_matrix, mode) = {
                 let bone = &self.bones[i];
                 let pm = if let Some(p_idx) = bone.parent_index {
                     Some(self.bones[p_idx].world_matrix)
                 } else {
                     None
                 };
-                (pm, bone.local_transform.to_matrix())
+                let mut local = bone.local_transform;
+                local.rotation += ik_rotation.get(i).copied().unwrap_or(0.0);
+                (pm, local.to_matrix(), bone.data.transform_mode())
             };
 
-            let final_matrix = match parent_matrix {
-                None => local_matrix,
-                Some(pm) => {
-                    let pa = pm[0]; let pb = pm[1];
-                    let pc = pm[2]; let pd = pm[3];
-                    let px = pm[4]; let py = pm[5];
-
-                    let la = local_matrix[0]; let lb = local_matrix[1];
-                    let lc = local_matrix[2]; let ld = local_matrix[3];
-                    let lx = local_matrix[4]; let ly = local_matrix[5];
-                    let wa = pa * la + pc * lb;
-                    let wb = pb * la + pd * lb;
-                    let wc = pa * lc + pc * ld;
-                    let wd = pb * lc + pd * ld;
-                    let wx = pa * lx + pc * ly + px;
-                    let wy = pb * lx + pd * ly + py;
-
-                    [wa, wb, wc, wd, wx, wy]
+            self.bones[i].world_matrix = compose_world(parent_matrix, local_matrix, mode);
+        }
+    }
+    
+    fn solve_ik(&mut self, c: usize, ik_rotation: &mut [f32]) {
+        let constraint = &self.ik_constraints[c];
+        let mix = constraint.mix.clamp(0.0, 1.0);
+        if mix <= 0.0 { return; }
+        let bend_positive = constraint.bend_positive;
+        let chain: Vec<usize> = constraint.data.bone_ids.iter()
+            .filter_map(|id| self.bones.iter().position(|b| &b.data.id == id))
+            .collect();
+        let target_idx = match self.bones.iter().position(|b| b.data.id == constraint.data.target_id) {
+            Some(idx) => idx,
+            None => return,
+        };
+        if chain.is_empty() || chain.len() != constraint.data.bone_ids.len() || chain.contains(&target_idx) { return; }
+        let target = (self.bones[target_idx].world_matrix[4], self.bones[target_idx].world_matrix[5]);
+
+        match chain.len() {
+            1 => self.aim_bone(chain[0], target, mix, ik_rotation),
+            2 => {
+                let (parent, child) = (chain[0], chain[1]);
+                let pm = self.bones[parent].world_matrix;
+                let cm = self.bones[child].world_matrix;
+                let root = (pm[4], pm[5]);
+                let len1 = ((cm[4] - pm[4]).powi(2) + (cm[5] - pm[5]).powi(2)).sqrt();
+                let len2 = self.bones[child].data.length * (cm[0] * cm[0] + cm[1] * cm[1]).sqrt();
+                if let Some(angle) = two_bone_parent_angle(root, len1, len2, target, bend_positive) {
+                    let current = world_angle(cm[4] - pm[4], cm[5] - pm[5]);
+                    self.rotate_bone(parent, shortest_angle(angle - current) * mix, ik_rotation);
                 }
-            };
+                self.aim_bone(child, target, mix, ik_rotation);
+            }
+            _ => {
+                let mut joints: Vec<(f32, f32)> = chain.iter().map(|&b| (self.bones[b].world_matrix[4], self.bones[b].world_matrix[5])).collect();
+                joints.push(self.bone_tip(*chain.last().unwrap_or(&0)));
+                fabrik(&mut joints, target, 10);
+                for (k, &b) in chain.iter().enumerate() {
+                    let m = self.bones[b].world_matrix;
+                    let current_next = if k + 1 < chain.len() {
+                        (self.bones[chain[k + 1]].world_matrix[4], self.bones[chain[k + 1]].world_matrix[5])
+                    } else {
+                        self.bone_tip(b)
+                    };
+                    let current = world_angle(current_next.0 - m[4], current_next.1 - m[5]);
+                    let desired = world_angle(joints[k + 1].0 - m[4], joints[k + 1].1 - m[5]);
+                    self.rotate_bone(b, shortest_angle(desired - current) * mix, ik_rotation);
+                }
+            }
+        }
+    }
+
+    // 旋转骨骼使其 x 轴指向目标点
+    fn aim_bone(&mut self, idx: usize, target: (f32, f32), mix: f32, ik_rotation: &mut [f32]) {
+        let m = self.bones[idx].world_matrix;
+        let (dx, dy) = (target.0 - m[4], target.1 - m[5]);
+        if dx * dx + dy * dy < 1e-8 { return; }
+        let delta = shortest_angle(world_angle(dx, dy) - world_angle(m[0], m[1]));
+        self.rotate_bone(idx, delta * mix, ik_rotation);
+    }
+
+    // 将世界空间的旋转量换算为局部旋转 (父级带镜像时方向相反) 并刷新世界矩阵
+    fn rotate_bone(&mut self, idx: usize, world_delta: f32, ik_rotation: &mut [f32]) {
+        if world_delta.abs() < 1e-6 { return; }
+        let pm = self.get_inherited_parent_matrix(idx);
+        let sign = if pm[0] * pm[3] - pm[1] * pm[2] < 0.0 { -1.0 } else { 1.0 };
+        ik_rotation[idx] += world_delta * sign;
+        self.update_world(ik_rotation);
+    }
 
-            self.bones[i].world_matrix = final_matrix;
+    fn bone_tip(&self, idx: usize) -> (f32, f32) {
+        let m = self.bones[idx].world_matrix;
+        let len = self.bones[idx].data.length;
+        (m[4] + m[0] * len, m[5] + m[1] * len)
+    }
+
+    // 按当前绘制顺序返回插槽，绘制顺序中缺失的插槽保持装配顺序追加在最上层
+    pub fn draw_order_slots(&self) -> Vec<&RuntimeSlot> {
+        let order = match &self.draw_order {
+            Some(order) => order,
+            None => return self.slots.iter().collect(),
+        };
+        let mut result: Vec<&RuntimeSlot> = order.iter()
+            .filter_map(|id| self.slots.iter().find(|s| &s.data.id == id))
+            .collect();
+        for slot in &self.slots {
+            if !order.contains(&slot.data.id) { result.push(slot); }
         }
+        result
     }
-    
+
     pub fn get_bone_world_position(&self, id: &str) -> Option<(f32, f32)> {
         let idx = self.name_to_index.get(id)?;
         let m = self.bones[*idx].world_matrix;
@@ -96,11 +184,192 @@ impl Skeleton {
             None => [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
         }
     }
+
+    // 骨骼实际继承的父矩阵 (已按继承方式去掉旋转/缩放/镜像)
+    pub fn get_inherited_parent_matrix(&self, bone_idx: usize) -> [f32; 6] {
+        inherited_parent_matrix(self.get_parent_world_matrix(bone_idx), self.bones[bone_idx].data.transform_mode())
+    }
+
+    // 装配姿态 (BoneData 中的局部变换) 下各骨骼的世界矩阵
+    pub fn setup_world_matrices(&self) -> Vec<[f32; 6]> {
+        let mut setup_matrices = vec![[1.0, 0.0, 0.0, 1.0, 0.0, 0.0]; self.bones.len()];
+        for i in 0..self.bones.len() {
+            let bone = &self.bones[i];
+            let parent_matrix = bone.parent_index.map(|p_idx| setup_matrices[p_idx]);
+            setup_matrices[i] = compose_world(parent_matrix, bone.data.local_transform.to_matrix(), bone.data.transform_mode());
+        }
+        setup_matrices
+    }
+
+    // 蒙皮矩阵 M_curr * M_bind⁻¹：把装配姿态下的画布坐标变换到当前姿态
+    pub fn skin_matrices(&self) -> HashMap<String, [f32; 6]> {
+        let setup = self.setup_world_matrices();
+        self.bones.iter().zip(setup.iter()).map(|(bone, bind)| {
+            let skin = invert_matrix(bind)
+                .map(|inv| multiply_matrix(&bone.world_matrix, &inv))
+                .unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+            (bone.data.id.clone(), skin)
+        }).collect()
+    }
+
+    pub fn mesh(&self, layer_id: &str) -> Option<&MeshAttachment> {
+        self.meshes.iter().find(|m| m.layer_id == layer_id)
+    }
+
+    pub fn mesh_mut(&mut self, layer_id: &str) -> Option<&mut MeshAttachment> {
+        self.meshes.iter_mut().find(|m| m.layer_id == layer_id)
+    }
+
+    // 每个顶点的加权蒙皮矩阵；没有权重的顶点跟随显示该图层的插槽骨骼
+    pub fn vertex_skin_matrices(&self, mesh: &MeshAttachment) -> Vec<[f32; 6]> {
+        let skins = self.skin_matrices();
+        let identity = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let fallback = self.slots.iter()
+            .find(|s| s.current_attachment.as_deref() == Some(mesh.layer_id.as_str()) || s.data.attachment.as_deref() == Some(mesh.layer_id.as_str()))
+            .and_then(|s| skins.get(&s.data.bone_id))
+            .copied()
+            .unwrap_or(identity);
+        mesh.vertices.iter().map(|v| {
+            let mut blended = [0.0; 6];
+            let mut total = 0.0;
+            for (bone_id, w) in &v.weights {
+                if let Some(m) = skins.get(bone_id) {
+                    for (b, x) in blended.iter_mut().zip(m.iter()) { *b += x * w; }
+                    total += w;
+                }
+            }
+            if total <= 1e-6 { return fallback; }
+            blended.map(|b| b / total)
+        }).collect()
+    }
+
+    // 当前姿态下网格的顶点位置 (装配位置 + FFD 偏移，再经蒙皮)
+    pub fn deformed_mesh(&self, layer_id: &str) -> Option<DeformedMesh> {
+        let mesh = self.mesh(layer_id)?;
+        let skins = self.vertex_skin_matrices(mesh);
+        let positions = mesh.vertices.iter().zip(skins.iter()).enumerate().map(|(i, (v, m))| {
+            let (dx, dy) = mesh.deform_offset(i);
+            let (x, y) = (v.x + dx, v.y + dy);
+            [m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]]
+        }).collect();
+        Some(DeformedMesh {
+            positions,
+            uvs: mesh.vertices.iter().map(|v| [v.u, v.v]).collect(),
+            triangles: mesh.triangles.clone(),
+        })
+    }
+
+    // 画布整体旋转或翻转：根骨骼随画布变换，子骨骼经继承跟随；
+    // 网格顶点按画布坐标、纹理坐标按所在图层变换前的宽高变换
+    pub fn apply_lossless(&mut self, op: LosslessTransform, width: u32, height: u32, layer_sizes: &HashMap<String, (u32, u32)>) {
+        let (w, h) = (width as f32, height as f32);
+        for bone in self.bones.iter_mut().filter(|b| b.data.parent_id.is_none()) {
+            map_root_transform(op, &mut bone.data.local_transform, w, h);
+            map_root_transform(op, &mut bone.local_transform, w, h);
+        }
+        for mesh in &mut self.meshes {
+            let (lw, lh) = layer_sizes.get(&mesh.layer_id).map_or((w, h), |&(lw, lh)| (lw as f32, lh as f32));
+            for v in &mut mesh.vertices {
+                (v.x, v.y) = op.map_point(v.x, v.y, w, h);
+                (v.u, v.v) = op.map_point(v.u, v.v, lw, lh);
+            }
+            for d in &mut mesh.deform {
+                *d = op.map_vector(d.0, d.1);
+            }
+        }
+        self.update();
+    }
+
+    // 画布整体放大 factor 倍：骨骼位置与长度、网格顶点与纹理坐标同比放大
+    pub fn scale_positions(&mut self, factor: f32) {
+        for bone in &mut self.bones {
+            for t in [&mut bone.data.local_transform, &mut bone.local_transform] {
+                t.x *= factor;
+                t.y *= factor;
+            }
+            bone.data.length *= factor;
+        }
+        for mesh in &mut self.meshes {
+            for v in &mut mesh.vertices {
+                v.x *= factor;
+                v.y *= factor;
+                v.u *= factor;
+                v.v *= factor;
+            }
+            for d in &mut mesh.deform {
+                *d = (d.0 * factor, d.1 * factor);
+            }
+        }
+        self.update();
+    }
+}
+
+// 根骨骼的局部变换即世界变换：位置与朝向随画布变换，镜像时翻转 y 缩放
+pub fn map_root_transform(op: LosslessTransform, t: &mut Transform, w: f32, h: f32) {
+    (t.x, t.y) = op.map_point(t.x, t.y, w, h);
+    t.rotation = op.map_rotation(t.rotation);
+    if op.is_reflection() {
+        t.scale_y = -t.scale_y;
+    }
+}
+
+// 按继承方式处理父矩阵的线性部分，平移部分不变
+pub fn inherited_parent_matrix(pm: [f32; 6], mode: TransformMode) -> [f32; 6] {
+    let [pa, pb, pc, pd, px, py] = pm;
+    match mode {
+        TransformMode::Normal => pm,
+        TransformMode::OnlyTranslation => [1.0, 0.0, 0.0, 1.0, px, py],
+        TransformMode::NoRotationOrReflection => {
+            // 将父级 x 轴转回水平方向，保留缩放与切变；镜像时反转 y 轴 (与 Spine 一致，切变方向随之取反)
+            let len = (pa * pa + pb * pb).sqrt();
+            if len < 1e-6 {
+                return [0.0, 0.0, 0.0, (pc * pc + pd * pd).sqrt(), px, py];
+            }
+            let (cos, sin) = (pa / len, pb / len);
+            let c = cos * pc + sin * pd;
+            let d = cos * pd - sin * pc;
+            if d < 0.0 { [len, 0.0, -c, -d, px, py] } else { [len, 0.0, c, d, px, py] }
+        }
+        TransformMode::NoScale | TransformMode::NoScaleOrReflection => {
+            // 只保留父级旋转 (x 轴退化时由 y 轴推出)，NoScale 额外保留镜像
+            let len_x = (pa * pa + pb * pb).sqrt();
+            let len_y = (pc * pc + pd * pd).sqrt();
+            let (cos, sin) = if len_x > 1e-6 {
+                (pa / len_x, pb / len_x)
+            } else if len_y > 1e-6 {
+                (pd / len_y, -pc / len_y)
+            } else {
+                (1.0, 0.0)
+            };
+            let reflected = mode == TransformMode::NoScale && pa * pd - pb * pc < 0.0;
+            if reflected {
+                [cos, sin, sin, -cos, px, py]
+            } else {
+                [cos, sin, -sin, cos, px, py]
+            }
+        }
+    }
+}
+
+// 由父骨骼世界矩阵与局部矩阵求世界矩阵：线性部分使用继承后的父矩阵，位置始终经过完整父矩阵
+pub fn compose_world(parent: Option<[f32; 6]>, local: [f32; 6], mode: TransformMode) -> [f32; 6] {
+    let pm = match parent {
+        Some(pm) => pm,
+        None => return local,
+    };
+    let [ba, bb, bc, bd, _, _] = inherited_parent_matrix(pm, mode);
+    let [la, lb, lc, ld, lx, ly] = local;
+    [
+        ba * la + bc * lb, bb * la + bd * lb,
+        ba * lc + bc * ld, bb * lc + bd * ld,
+        pm[0] * lx + pm[2] * ly + pm[4], pm[1] * lx + pm[3] * ly + pm[5],
+    ]
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::animation::ik::IkConstraintData;
 
     #[test]
     fn test_skeleton_hierarchy_math() {
@@ -122,4 +391,133 @@ mod tests {
         assert!((cx - 150.0).abs() < 0.001, "Child X 应该是 150, 实际: {}", cx);
         assert!((cy - 100.0).abs() < 0.001, "Child Y 应该是 100, 实际: {}", cy);
     }
+
+    // 父骨骼位于 (100, 100)，子骨骼局部位置 (10, 0)，返回子骨骼世界矩阵
+    fn child_matrix(parent: Transform, mode: TransformMode) -> [f32; 6] {
+        let mut skel = Skeleton::new();
+        let mut root_data = BoneData::new("root".into(), "Root".into());
+        root_data.local_transform = parent;
+        root_data.local_transform.x = 100.0;
+        root_data.local_transform.y = 100.0;
+        skel.add_bone(root_data);
+
+        let mut child_data = BoneData::new("child".into(), "Child".into());
+        child_data.parent_id = Some("root".into());
+        child_data.local_transform.x = 10.0;
+        child_data.set_transform_mode(mode);
+        skel.add_bone(child_data);
+
+        skel.update();
+        skel.bones[1].world_matrix
+    }
+
+    fn assert_matrix(m: [f32; 6], expected: [f32; 6], what: &str) {
+        for (a, b) in m.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-4, "{}: 期望 {:?}, 实际 {:?}", what, expected, m);
+        }
+    }
+
+    #[test]
+    fn test_transform_mode_hierarchy_math() {
+        // 父骨骼旋转 90° 并放大 2 倍：子骨骼位置始终跟随父矩阵
+        let parent = Transform { rotation: 90.0, scale_x: 2.0, scale_y: 2.0, ..Default::default() };
+
+        assert_matrix(child_matrix(parent, TransformMode::Normal), [0.0, 2.0, -2.0, 0.0, 100.0, 120.0], "完整继承");
+        assert_matrix(child_matrix(parent, TransformMode::OnlyTranslation), [1.0, 0.0, 0.0, 1.0, 100.0, 120.0], "仅平移");
+        assert_matrix(child_matrix(parent, TransformMode::NoRotationOrReflection), [2.0, 0.0, 0.0, 2.0, 100.0, 120.0], "不继承旋转");
+        assert_matrix(child_matrix(parent, TransformMode::NoScale), [0.0, 1.0, -1.0, 0.0, 100.0, 120.0], "不继承缩放");
+        assert_matrix(child_matrix(parent, TransformMode::NoScaleOrReflection), [0.0, 1.0, -1.0, 0.0, 100.0, 120.0], "不继承缩放/镜像");
+    }
+
+    #[test]
+    fn test_transform_mode_reflection() {
+        // 父骨骼 y 轴镜像
+        let parent = Transform { scale_x: 3.0, scale_y: -1.0, ..Default::default() };
+
+        assert_matrix(child_matrix(parent, TransformMode::Normal), [3.0, 0.0, 0.0, -1.0, 130.0, 100.0], "完整继承保留镜像");
+        assert_matrix(child_matrix(parent, TransformMode::NoScale), [1.0, 0.0, 0.0, -1.0, 130.0, 100.0], "NoScale 保留镜像");
+        assert_matrix(child_matrix(parent, TransformMode::NoScaleOrReflection), [1.0, 0.0, 0.0, 1.0, 130.0, 100.0], "去除镜像");
+        assert_matrix(child_matrix(parent, TransformMode::NoRotationOrReflection), [3.0, 0.0, 0.0, 1.0, 130.0, 100.0], "保留缩放去除镜像");
+    }
+
+    #[test]
+    fn test_no_rotation_or_reflection_mirrored_sheared_parent() {
+        // 镜像且带切变的父骨骼：父矩阵 y 轴为 (1, -1)，去除镜像后整条 y 轴反向为 (-1, 1)
+        let parent = Transform { scale_y: -1.0, shear_x: 45.0, ..Default::default() };
+        assert_matrix(child_matrix(parent, TransformMode::Normal), [1.0, 0.0, 1.0, -1.0, 110.0, 100.0], "完整继承");
+        assert_matrix(child_matrix(parent, TransformMode::NoRotationOrReflection), [1.0, 0.0, -1.0, 1.0, 110.0, 100.0], "切变方向随镜像一起取反");
+    }
+
+    fn arm_skeleton() -> Skeleton {
+        let mut skel = Skeleton::new();
+        let mut upper = BoneData::new("upper".into(), "Upper".into());
+        upper.length = 10.0;
+        skel.add_bone(upper);
+        let mut lower = BoneData::new("lower".into(), "Lower".into());
+        lower.parent_id = Some("upper".into());
+        lower.local_transform.x = 10.0;
+        lower.length = 10.0;
+        skel.add_bone(lower);
+        let mut target = BoneData::new("target".into(), "Target".into());
+        target.local_transform.x = 10.0;
+        target.local_transform.y = 10.0;
+        skel.add_bone(target);
+        skel
+    }
+
+    fn tip(skel: &Skeleton, id: &str) -> (f32, f32) {
+        let idx = skel.bones.iter().position(|b| b.data.id == id).unwrap();
+        skel.bone_tip(idx)
+    }
+
+    #[test]
+    fn test_two_bone_ik_reaches_target() {
+        let mut skel = arm_skeleton();
+        skel.ik_constraints.push(IkConstraint::new(IkConstraintData::new("ik".into(), "ik".into(), vec!["upper".into(), "lower".into()], "target".into())));
+        skel.update();
+
+        let (tx, ty) = tip(&skel, "lower");
+        assert!((tx - 10.0).abs() < 0.01 && (ty - 10.0).abs() < 0.01, "链尾应到达目标, 实际: ({}, {})", tx, ty);
+        assert_eq!(skel.bones[0].local_transform.rotation, 0.0, "IK 不应改写局部变换");
+        let elbow_y = skel.bones[1].world_matrix[5];
+
+        skel.ik_constraints[0].bend_positive = false;
+        skel.update();
+        let (tx2, ty2) = tip(&skel, "lower");
+        assert!((tx2 - 10.0).abs() < 0.01 && (ty2 - 10.0).abs() < 0.01);
+        assert!((skel.bones[1].world_matrix[5] - elbow_y).abs() > 1.0, "弯曲方向应翻转肘部位置");
+
+        skel.ik_constraints[0].mix = 0.0;
+        skel.update();
+        let (tx3, ty3) = tip(&skel, "lower");
+        assert!((tx3 - 20.0).abs() < 0.01 && ty3.abs() < 0.01, "mix 为 0 时保持原姿态");
+    }
+
+    #[test]
+    fn test_single_and_chain_ik() {
+        let mut skel = arm_skeleton();
+        skel.ik_constraints.push(IkConstraint::new(IkConstraintData::new("aim".into(), "aim".into(), vec!["lower".into()], "target".into())));
+        skel.update();
+        let m = skel.bones[1].world_matrix;
+        assert!(m[0].abs() < 0.01 && (m[1] - 1.0).abs() < 0.01, "单骨骼 IK 应指向目标");
+
+        let mut chain = Skeleton::new();
+        let mut parent: Option<String> = None;
+        for i in 0..4 {
+            let mut b = BoneData::new(format!("b{}", i), format!("b{}", i));
+            b.parent_id = parent.clone();
+            b.length = 5.0;
+            if parent.is_some() { b.local_transform.x = 5.0; }
+            chain.add_bone(b);
+            parent = Some(format!("b{}", i));
+        }
+        let mut target = BoneData::new("t".into(), "t".into());
+        target.local_transform.x = 8.0;
+        target.local_transform.y = 12.0;
+        chain.add_bone(target);
+        chain.ik_constraints.push(IkConstraint::new(IkConstraintData::new("fabrik".into(), "fabrik".into(), (0..4).map(|i| format!("b{}", i)).collect(), "t".into())));
+        chain.update();
+        let (tx, ty) = tip(&chain, "b3");
+        assert!((tx - 8.0).abs() < 0.1 && (ty - 12.0).abs() < 0.1, "FABRIK 链尾应到达目标, 实际: ({}, {})", tx, ty);
+    }
 }
\ No newline at end of file
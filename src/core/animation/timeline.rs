@@ -5,6 +5,26 @@ pub enum CurveType {
     Linear,
     Stepped,
     Bezier(f32, f32, f32, f32),
+    // 预设缓动函数
+    Easing(crate::core::animation::easing::EasingFunction, crate::core::animation::easing::EasingDirection),
+    // 均分为 n 级的阶梯插值，适合像素风格的顿挫运动
+    Steps(u32),
+    // 每级结束时跳变的阶梯 (段一开始即离开起点)，为 Steps 时间反转后的形状
+    StepsEnd(u32),
+}
+
+impl CurveType {
+    // 段内归一化时间 t 对应的插值进度；阶跃始终停在起点
+    pub fn ease(&self, t: f32) -> f32 {
+        match *self {
+            CurveType::Linear => t,
+            CurveType::Stepped => 0.0,
+            CurveType::Bezier(cx1, cy1, cx2, cy2) => Timeline::solve_bezier_y(cx1, cy1, cx2, cy2, t),
+            CurveType::Easing(function, direction) => crate::core::animation::easing::ease(function, direction, t),
+            CurveType::Steps(n) => crate::core::animation::easing::steps(n, t),
+            CurveType::StepsEnd(n) => crate::core::animation::easing::steps_end(n, t),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -12,7 +32,19 @@ pub enum KeyframeValue {
     Rotate(f32),
     Translate(f32, f32),
     Scale(f32, f32),
+    // 切变角度 (度)
+    Shear(f32, f32),
     Color(u8, u8, u8, u8), 
+    // IK 约束的混合度与弯曲方向 (true 为正向)
+    Ik(f32, bool),
+    // 插槽当前显示的图层，None 为隐藏
+    Attachment(Option<String>),
+    // 插槽 id 按从下到上的绘制顺序排列
+    DrawOrder(Vec<String>),
+    // 网格顶点的自由变形偏移 (装配空间，与顶点一一对应)
+    Deform(Vec<(f32, f32)>),
+    // 图层效果的数值参数 (叠加强度、色相偏移角度)
+    EffectAmount(f32),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -20,14 +52,35 @@ pub enum TimelineProperty {
     Rotation,
     Translation,
     Scale,
+    Shear,
     Color,
+    Ik,
+    Attachment,
+    DrawOrder,
+    Deform,
+    // 以图层效果 id 为目标
+    EffectAmount,
+}
+
+// 曲线编辑器中关键帧切线的约束方式：
+// Broken 两侧手柄互不影响，Aligned 两侧斜率保持一致，Auto 按相邻关键帧自动平滑 (钳制，不会过冲)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TangentMode {
+    #[default]
+    Broken,
+    Aligned,
+    Auto,
 }
 
+// 绘制顺序轨道不属于任何骨骼或插槽，使用固定的目标 id
+pub const DRAW_ORDER_TARGET: &str = "__draw_order";
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Keyframe {
     pub time: f32, 
     pub value: KeyframeValue,
     pub curve: CurveType,
+    pub tangent: TangentMode,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -51,7 +104,13 @@ impl Timeline {
             (TimelineProperty::Rotation, KeyframeValue::Rotate(_)) => true,
             (TimelineProperty::Translation, KeyframeValue::Translate(_, _)) => true,
             (TimelineProperty::Scale, KeyframeValue::Scale(_, _)) => true,
+            (TimelineProperty::Shear, KeyframeValue::Shear(_, _)) => true,
             (TimelineProperty::Color, KeyframeValue::Color(_, _, _, _)) => true,
+            (TimelineProperty::Ik, KeyframeValue::Ik(_, _)) => true,
+            (TimelineProperty::Attachment, KeyframeValue::Attachment(_)) => true,
+            (TimelineProperty::DrawOrder, KeyframeValue::DrawOrder(_)) => true,
+            (TimelineProperty::Deform, KeyframeValue::Deform(_)) => true,
+            (TimelineProperty::EffectAmount, KeyframeValue::EffectAmount(_)) => true,
             _ => false,
         };
 
@@ -60,9 +119,10 @@ impl Timeline {
                 existing.value = value;
                 existing.curve = curve;
             } else {
-                self.keyframes.push(Keyframe { time, value, curve });
+                self.keyframes.push(Keyframe { time, value, curve, tangent: TangentMode::Broken });
                 self.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
             }
+            crate::core::animation::graph::refresh_auto_tangents(self);
         }
     }
 
@@ -92,22 +152,13 @@ impl Timeline {
 
         match start.curve {
             CurveType::Stepped => Some(start.value.clone()),
-            CurveType::Linear => {
+            curve => {
                 let duration = end.time - start.time;
                 if duration <= 0.0001 {
                     return Some(start.value.clone());
                 }
                 let t = (time - start.time) / duration;
-                Self::lerp_value(&start.value, &end.value, t)
-            }
-            CurveType::Bezier(cx1, cy1, cx2, cy2) => {
-                let duration = end.time - start.time;
-                if duration <= 0.0001 {
-                    return Some(start.value.clone());
-                }
-                let t = (time - start.time) / duration;
-                let eased_t = Self::solve_bezier_y(cx1, cy1, cx2, cy2, t);
-                Self::lerp_value(&start.value, &end.value, eased_t)
+                Self::lerp_value(&start.value, &end.value, curve.ease(t))
             }
         }
     }
@@ -151,6 +202,12 @@ impl Timeline {
                     y1 + (y2 - y1) * t,
                 ))
             },
+            (KeyframeValue::Shear(x1, y1), KeyframeValue::Shear(x2, y2)) => {
+                Some(KeyframeValue::Shear(
+                    x1 + (x2 - x1) * t,
+                    y1 + (y2 - y1) * t,
+                ))
+            },
             (KeyframeValue::Color(r1, g1, b1, a1), KeyframeValue::Color(r2, g2, b2, a2)) => {
                 Some(KeyframeValue::Color(
                     (*r1 as f32 + (*r2 as f32 - *r1 as f32) * t) as u8,
@@ -159,6 +216,21 @@ impl Timeline {
                     (*a1 as f32 + (*a2 as f32 - *a1 as f32) * t) as u8,
                 ))
             },
+            // 弯曲方向不可插值，保持前一关键帧的设置；回拉/弹性缓动可能过冲，混合度需钳制
+            (KeyframeValue::Ik(m1, bend), KeyframeValue::Ik(m2, _)) => {
+                Some(KeyframeValue::Ik((m1 + (m2 - m1) * t).clamp(0.0, 1.0), *bend))
+            },
+            // 顶点数不一致 (网格重新生成过) 时无法插值
+            (KeyframeValue::Deform(d1), KeyframeValue::Deform(d2)) => {
+                if d1.len() != d2.len() { return Some(v1.clone()); }
+                Some(KeyframeValue::Deform(d1.iter().zip(d2.iter())
+                    .map(|(a, b)| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t))
+                    .collect()))
+            },
+            (KeyframeValue::EffectAmount(a1), KeyframeValue::EffectAmount(a2)) => Some(KeyframeValue::EffectAmount(a1 + (a2 - a1) * t)),
+            // 显示图层与绘制顺序只能阶跃切换，进度到达终点 (如 StepsEnd) 时才取后一关键帧
+            (KeyframeValue::Attachment(_), KeyframeValue::Attachment(_)) |
+            (KeyframeValue::DrawOrder(_), KeyframeValue::DrawOrder(_)) => Some(if t >= 1.0 { v2.clone() } else { v1.clone() }),
             _ => None,
         }
     }
@@ -169,6 +241,13 @@ pub struct Animation {
     pub name: String,
     pub duration: f32,
     pub timelines: Vec<Timeline>,
+    // 按时间排序
+    pub events: Vec<super::event::AnimationEvent>,
+}
+
+// 时间 (秒) 对齐到最近的帧边界
+pub fn snap_to_frame(time: f32, fps: f32) -> f32 {
+    (time * fps).round() / fps
 }
 
 impl Animation {
@@ -177,6 +256,7 @@ impl Animation {
             name,
             duration,
             timelines: Vec::new(),
+            events: Vec::new(),
         }
     }
     pub fn initialize_tracks(&mut self, skeleton: &crate::core::animation::skeleton::Skeleton) {
@@ -203,11 +283,95 @@ impl Animation {
                 }
             }
         }
+        for event in &self.events {
+            max_time = max_time.max(event.time);
+        }
         self.duration = max_time;
     }
+
+    // 帧率变化时按比例缩放所有关键帧时间 (保持帧号不变)
+    pub fn scale_time(&mut self, factor: f32) {
+        for tl in &mut self.timelines {
+            for kf in &mut tl.keyframes { kf.time *= factor; }
+        }
+        for event in &mut self.events { event.time *= factor; }
+        self.duration *= factor;
+    }
+
+    // 将关键帧对齐到帧边界，落在同一帧上的关键帧只保留靠后的一个
+    pub fn snap_keyframes(&mut self, fps: f32) {
+        for tl in &mut self.timelines {
+            let mut snapped: Vec<Keyframe> = Vec::with_capacity(tl.keyframes.len());
+            for mut kf in tl.keyframes.drain(..) {
+                kf.time = snap_to_frame(kf.time, fps);
+                match snapped.last_mut() {
+                    Some(last) if (last.time - kf.time).abs() < 0.0001 => *last = kf,
+                    _ => snapped.push(kf),
+                }
+            }
+            tl.keyframes = snapped;
+        }
+        for event in &mut self.events { event.time = snap_to_frame(event.time, fps); }
+        self.duration = snap_to_frame(self.duration, fps);
+    }
+
+    // 画布整体旋转或翻转时同步关键帧：根骨骼的位移、旋转与缩放与装配姿势一样变换，网格变形偏移只取线性部分
+    pub fn apply_lossless(&mut self, op: crate::core::layer::LosslessTransform, width: u32, height: u32, skeleton: &super::skeleton::Skeleton) {
+        let (w, h) = (width as f32, height as f32);
+        for tl in &mut self.timelines {
+            let is_root = skeleton.bones.iter().any(|b| b.data.id == tl.target_id && b.data.parent_id.is_none());
+            for kf in &mut tl.keyframes {
+                match &mut kf.value {
+                    KeyframeValue::Translate(x, y) if is_root => (*x, *y) = op.map_point(*x, *y, w, h),
+                    KeyframeValue::Rotate(r) if is_root => *r = op.map_rotation(*r),
+                    KeyframeValue::Scale(_, y) if is_root && op.is_reflection() => *y = -*y,
+                    KeyframeValue::Deform(offsets) => {
+                        for d in offsets.iter_mut() { *d = op.map_vector(d.0, d.1); }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // 画布整体放大时同步关键帧：位移与网格变形偏移按倍率放大
+    pub fn scale_positions(&mut self, factor: f32) {
+        for tl in &mut self.timelines {
+            for kf in &mut tl.keyframes {
+                match &mut kf.value {
+                    KeyframeValue::Translate(x, y) => { *x *= factor; *y *= factor; }
+                    KeyframeValue::Deform(offsets) => {
+                        for d in offsets.iter_mut() { *d = (d.0 * factor, d.1 * factor); }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
     
+    pub fn add_event(&mut self, event: super::event::AnimationEvent) -> usize {
+        let idx = self.events.iter().position(|e| e.time > event.time).unwrap_or(self.events.len());
+        self.events.insert(idx, event);
+        idx
+    }
+
+    pub fn sort_events(&mut self) {
+        self.events.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
     pub fn apply(&self, skeleton: &mut super::skeleton::Skeleton, time: f32) {
         let t = if self.duration > 0.0 { time % self.duration } else { 0.0 };
+        self.apply_at(skeleton, t);
+    }
+
+    // 不对时长取模，time 等于时长时得到末尾姿势
+    pub fn apply_at(&self, skeleton: &mut super::skeleton::Skeleton, t: f32) {
+        // 没有轨道的属性回到初始姿态，避免沿用上一个动画的值 (与混合器的 Pose::setup 一致)
+        skeleton.draw_order = None;
+        for slot in &mut skeleton.slots { slot.current_attachment = slot.data.attachment.clone(); }
+        for mesh in &mut skeleton.meshes { mesh.deform.clear(); }
+        // 没有轨道的效果使用图层上保存的参数
+        skeleton.effect_amounts.clear();
 
         for timeline in &self.timelines {
             if let Some(val) = timeline.sample(t) {
@@ -219,6 +383,38 @@ impl Animation {
                             }
                         }
                     },
+                    TimelineProperty::Attachment => {
+                        if let Some(slot) = skeleton.slots.iter_mut().find(|s| s.data.id == timeline.target_id) {
+                            if let KeyframeValue::Attachment(attachment) = val {
+                                slot.current_attachment = attachment;
+                            }
+                        }
+                    },
+                    TimelineProperty::DrawOrder => {
+                        if let KeyframeValue::DrawOrder(order) = val {
+                            skeleton.draw_order = Some(order);
+                        }
+                    },
+                    TimelineProperty::Deform => {
+                        if let Some(mesh) = skeleton.mesh_mut(&timeline.target_id) {
+                            if let KeyframeValue::Deform(offsets) = val {
+                                mesh.deform = offsets;
+                            }
+                        }
+                    },
+                    TimelineProperty::EffectAmount => {
+                        if let KeyframeValue::EffectAmount(value) = val {
+                            skeleton.effect_amounts.insert(timeline.target_id.clone(), value);
+                        }
+                    },
+                    TimelineProperty::Ik => {
+                        if let Some(ik) = skeleton.ik_constraints.iter_mut().find(|c| c.data.id == timeline.target_id) {
+                            if let KeyframeValue::Ik(mix, bend) = val {
+                                ik.mix = mix;
+                                ik.bend_positive = bend;
+                            }
+                        }
+                    },
                     _ => {
                         if let Some(bone) = skeleton.bones.iter_mut().find(|b| b.data.id == timeline.target_id) {
                             match val {
@@ -231,6 +427,10 @@ impl Animation {
                                     bone.local_transform.scale_x = x;
                                     bone.local_transform.scale_y = y;
                                 },
+                                KeyframeValue::Shear(x, y) => {
+                                    bone.local_transform.shear_x = x;
+                                    bone.local_transform.shear_y = y;
+                                },
                                 _ => {}
                             }
                         }
@@ -255,4 +455,105 @@ mod tests {
             panic!("Lerp failed");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shear_sampling() {
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Shear);
+        tl.add_keyframe(0.0, KeyframeValue::Shear(0.0, 10.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Shear(20.0, -10.0), CurveType::Linear);
+        tl.add_keyframe(2.0, KeyframeValue::Scale(1.0, 1.0), CurveType::Linear);
+        assert_eq!(tl.keyframes.len(), 2, "类型不符的关键帧应被忽略");
+        assert_eq!(Timeline::lerp_value(&tl.keyframes[0].value, &tl.keyframes[1].value, 0.25), Some(KeyframeValue::Shear(5.0, 5.0)));
+    }
+
+    #[test]
+    fn test_easing_and_steps_sampling() {
+        use crate::core::animation::easing::{EasingFunction, EasingDirection};
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Translation);
+        tl.add_keyframe(0.0, KeyframeValue::Translate(0.0, 0.0), CurveType::Easing(EasingFunction::Quad, EasingDirection::In));
+        tl.add_keyframe(1.0, KeyframeValue::Translate(100.0, 0.0), CurveType::Steps(4));
+        tl.add_keyframe(2.0, KeyframeValue::Translate(200.0, 0.0), CurveType::Linear);
+
+        assert_eq!(tl.sample(0.5), Some(KeyframeValue::Translate(25.0, 0.0)), "二次缓入应精确求值");
+        assert_eq!(tl.sample(1.2), Some(KeyframeValue::Translate(100.0, 0.0)), "阶梯第一级保持起点");
+        assert_eq!(tl.sample(1.3), Some(KeyframeValue::Translate(125.0, 0.0)));
+        assert_eq!(tl.sample(1.99), Some(KeyframeValue::Translate(175.0, 0.0)));
+    }
+
+    #[test]
+    fn test_retime_and_snap_keyframes() {
+        let mut anim = Animation::new("a".into(), 1.0);
+        let mut tl = Timeline::new("b".into(), TimelineProperty::Rotation);
+        tl.add_keyframe(0.5, KeyframeValue::Rotate(10.0), CurveType::Linear);
+        tl.add_keyframe(0.52, KeyframeValue::Rotate(20.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(30.0), CurveType::Linear);
+        anim.timelines.push(tl);
+
+        let mut scaled = anim.clone();
+        scaled.scale_time(30.0 / 12.0);
+        assert!((scaled.timelines[0].keyframes[2].time - 2.5).abs() < 1e-5);
+        assert!((scaled.duration - 2.5).abs() < 1e-5);
+
+        anim.snap_keyframes(12.0);
+        let kfs = &anim.timelines[0].keyframes;
+        assert_eq!(kfs.len(), 2, "对齐到同一帧的关键帧应合并");
+        assert!((kfs[0].time - 0.5).abs() < 1e-5);
+        assert_eq!(kfs[0].value, KeyframeValue::Rotate(20.0));
+    }
+
+    #[test]
+    fn test_attachment_and_draw_order_are_stepped() {
+        use crate::core::animation::skeleton::Skeleton;
+        use crate::core::animation::slot::{SlotData, RuntimeSlot};
+
+        let mut skel = Skeleton::new();
+        for id in ["A", "B"] {
+            let mut slot = SlotData::new(id.into(), id.into(), "root".into());
+            slot.attachment = Some(id.into());
+            skel.slots.push(RuntimeSlot::new(slot));
+        }
+
+        let mut anim = Animation::new("blink".into(), 1.0);
+        let mut att = Timeline::new("A".into(), TimelineProperty::Attachment);
+        att.add_keyframe(0.0, KeyframeValue::Attachment(Some("A".into())), CurveType::Linear);
+        att.add_keyframe(0.5, KeyframeValue::Attachment(None), CurveType::Linear);
+        let mut order = Timeline::new(DRAW_ORDER_TARGET.into(), TimelineProperty::DrawOrder);
+        order.add_keyframe(0.0, KeyframeValue::DrawOrder(vec!["A".into(), "B".into()]), CurveType::Linear);
+        order.add_keyframe(0.5, KeyframeValue::DrawOrder(vec!["B".into(), "A".into()]), CurveType::Linear);
+        anim.timelines.push(att);
+        anim.timelines.push(order);
+
+        anim.apply(&mut skel, 0.49);
+        assert_eq!(skel.slots[0].current_attachment.as_deref(), Some("A"), "线性曲线也不应在关键帧之间插值");
+        assert_eq!(skel.draw_order_slots()[0].data.id, "A");
+
+        anim.apply(&mut skel, 0.5);
+        assert_eq!(skel.slots[0].current_attachment, None);
+        let ids: Vec<&str> = skel.draw_order_slots().iter().map(|s| s.data.id.as_str()).collect();
+        assert_eq!(ids, vec!["B", "A"], "绘制顺序应切换");
+
+        // 不含这两类轨道的动画应回到初始的显示图层与绘制顺序
+        Animation::new("idle".into(), 1.0).apply(&mut skel, 0.0);
+        assert_eq!(skel.slots[0].current_attachment.as_deref(), Some("A"));
+        assert_eq!(skel.draw_order, None);
+    }
+
+    #[test]
+    fn test_deform_does_not_leak_between_animations() {
+        use crate::core::animation::skeleton::Skeleton;
+        use crate::core::animation::mesh::MeshAttachment;
+
+        let mut skel = Skeleton::new();
+        skel.meshes.push(MeshAttachment { layer_id: "L".into(), vertices: Vec::new(), triangles: Vec::new(), deform: Vec::new() });
+
+        let mut wobble = Animation::new("wobble".into(), 1.0);
+        let mut tl = Timeline::new("L".into(), TimelineProperty::Deform);
+        tl.add_keyframe(0.0, KeyframeValue::Deform(vec![(2.0, 0.0)]), CurveType::Linear);
+        wobble.timelines.push(tl);
+
+        wobble.apply(&mut skel, 0.0);
+        assert_eq!(skel.meshes[0].deform, vec![(2.0, 0.0)]);
+        Animation::new("idle".into(), 1.0).apply(&mut skel, 0.0);
+        assert!(skel.meshes[0].deform.is_empty(), "没有变形轨道的动画应回到无变形");
+    }
+}
@@ -66,6 +66,32 @@ impl Transform {
     }
 }
 
+// 仿射矩阵 [a, b, c, d, tx, ty] 相乘：先应用 m 再应用 parent
+pub fn multiply_matrix(parent: &[f32; 6], m: &[f32; 6]) -> [f32; 6] {
+    [
+        parent[0] * m[0] + parent[2] * m[1],
+        parent[1] * m[0] + parent[3] * m[1],
+        parent[0] * m[2] + parent[2] * m[3],
+        parent[1] * m[2] + parent[3] * m[3],
+        parent[0] * m[4] + parent[2] * m[5] + parent[4],
+        parent[1] * m[4] + parent[3] * m[5] + parent[5],
+    ]
+}
+
+pub fn invert_matrix(m: &[f32; 6]) -> Option<[f32; 6]> {
+    let det = m[0] * m[3] - m[1] * m[2];
+    if det.abs() < 1e-8 { return None; }
+    let inv_det = 1.0 / det;
+    Some([
+        m[3] * inv_det,
+        -m[1] * inv_det,
+        -m[2] * inv_det,
+        m[0] * inv_det,
+        (m[2] * m[5] - m[3] * m[4]) * inv_det,
+        (m[1] * m[4] - m[0] * m[5]) * inv_det,
+    ])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +107,16 @@ mod tests {
         assert!((m[4] - 0.0).abs() < 1e-5);
         assert!((m[5] - 0.0).abs() < 1e-5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_matrix_inverse_round_trip() {
+        let t = Transform { x: 5.0, y: -3.0, rotation: 30.0, scale_x: 2.0, scale_y: 0.5, ..Default::default() };
+        let m = t.to_matrix();
+        let inv = invert_matrix(&m).unwrap();
+        let id = multiply_matrix(&m, &inv);
+        for (a, b) in id.iter().zip([1.0, 0.0, 0.0, 1.0, 0.0, 0.0].iter()) {
+            assert!((a - b).abs() < 1e-5, "{:?}", id);
+        }
+        assert!(invert_matrix(&[0.0, 0.0, 0.0, 0.0, 1.0, 1.0]).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -14,6 +14,163 @@ impl Color {
     pub fn transparent() -> Self {
         Self { r: 0, g: 0, b: 0, a: 0 }
     }
+
+    // (色相 0..360, 饱和度 0..1, 明度 0..1)，灰色的色相为 0
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.r as f32 / 255.0, self.g as f32 / 255.0, self.b as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+        let hue = if delta <= 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max <= 0.0 { 0.0 } else { delta / max };
+        (hue, saturation, max)
+    }
+
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, a: u8) -> Self {
+        let (s, v) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let c = v * s;
+        Self::from_hue_chroma(hue, c, v - c, a)
+    }
+
+    // (色相 0..360, 饱和度 0..1, 亮度 0..1)，色相与 HSV 相同
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, _, max) = self.to_hsv();
+        let min = self.r.min(self.g).min(self.b) as f32 / 255.0;
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        let saturation = if delta <= 0.0 { 0.0 } else { delta / (1.0 - (2.0 * lightness - 1.0).abs()) };
+        (hue, saturation.min(1.0), lightness)
+    }
+
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, a: u8) -> Self {
+        let (s, l) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue_chroma(hue, c, l - c / 2.0, a)
+    }
+
+    // HSV / HSL 共用：色相、色度与各通道的公共偏移 m
+    fn from_hue_chroma(hue: f32, c: f32, m: f32, a: u8) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let to_u8 = |f: f32| ((f + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Self { r: to_u8(r), g: to_u8(g), b: to_u8(b), a }
+    }
+
+    // (L 0..1, a, b)，感知均匀的 OKLab
+    pub fn to_oklab(&self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(srgb_to_linear);
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    // 超出 sRGB 色域的部分按通道截断
+    pub fn from_oklab(lightness: f32, a: f32, b: f32, alpha: u8) -> Self {
+        let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m = (lightness - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+        Self {
+            r: linear_to_srgb(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            g: linear_to_srgb(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            b: linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+            a: alpha,
+        }
+    }
+
+    // (L 0..1, 色度 C 约 0..0.37, 色相 0..360)
+    pub fn to_oklch(&self) -> (f32, f32, f32) {
+        let (l, a, b) = self.to_oklab();
+        let chroma = (a * a + b * b).sqrt();
+        let hue = if chroma < 1e-4 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+        (l, chroma, hue)
+    }
+
+    pub fn from_oklch(lightness: f32, chroma: f32, hue: f32, alpha: u8) -> Self {
+        let (sin, cos) = hue.to_radians().sin_cos();
+        Self::from_oklab(lightness, chroma.max(0.0) * cos, chroma.max(0.0) * sin, alpha)
+    }
+
+    // Rec. 601 亮度，0..255
+    pub fn luminance(&self) -> f32 {
+        0.299 * self.r as f32 + 0.587 * self.g as f32 + 0.114 * self.b as f32
+    }
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let s = if c <= 0.003_130_8 { 12.92 * c } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (s * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+// 取色器的颜色模型，三个分量的含义与范围见 ranges()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorModel {
+    Hsv,
+    Hsl,
+    Oklch,
+}
+
+impl ColorModel {
+    pub const ALL: [ColorModel; 3] = [ColorModel::Hsv, ColorModel::Hsl, ColorModel::Oklch];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorModel::Hsv => "HSV",
+            ColorModel::Hsl => "HSL",
+            ColorModel::Oklch => "OKLCH",
+        }
+    }
+
+    // 各分量的 (名称, 最小值, 最大值)
+    pub fn ranges(&self) -> [(&'static str, f32, f32); 3] {
+        match self {
+            ColorModel::Hsv => [("H", 0.0, 360.0), ("S", 0.0, 1.0), ("V", 0.0, 1.0)],
+            ColorModel::Hsl => [("H", 0.0, 360.0), ("S", 0.0, 1.0), ("L", 0.0, 1.0)],
+            ColorModel::Oklch => [("L", 0.0, 1.0), ("C", 0.0, 0.37), ("H", 0.0, 360.0)],
+        }
+    }
+
+    pub fn components(&self, color: Color) -> [f32; 3] {
+        let (x, y, z) = match self {
+            ColorModel::Hsv => color.to_hsv(),
+            ColorModel::Hsl => color.to_hsl(),
+            ColorModel::Oklch => color.to_oklch(),
+        };
+        [x, y, z]
+    }
+
+    pub fn to_color(&self, [x, y, z]: [f32; 3], alpha: u8) -> Color {
+        match self {
+            ColorModel::Hsv => Color::from_hsv(x, y, z, alpha),
+            ColorModel::Hsl => Color::from_hsl(x, y, z, alpha),
+            ColorModel::Oklch => Color::from_oklch(x, y, z, alpha),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -8,4 +8,54 @@ fn test_color_new() {
 fn test_color_trans() {
     let c = Color::transparent();
     assert_eq!(c.a, 0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_color_hsv_round_trip() {
+    assert_eq!(Color::new(255, 0, 0, 255).to_hsv(), (0.0, 1.0, 1.0));
+    let (h, s, v) = Color::new(0, 128, 255, 255).to_hsv();
+    assert!((h - 210.0).abs() < 0.5 && s == 1.0 && v == 1.0);
+    for c in [Color::new(12, 200, 99, 255), Color::new(250, 250, 250, 7), Color::new(90, 30, 160, 255)] {
+        let (h, s, v) = c.to_hsv();
+        assert_eq!(Color::from_hsv(h, s, v, c.a), c);
+    }
+}
+#[test]
+fn test_color_hsl_round_trip() {
+    assert_eq!(Color::new(255, 0, 0, 255).to_hsl(), (0.0, 1.0, 0.5));
+    assert_eq!(Color::new(128, 128, 128, 255).to_hsl().1, 0.0);
+    for c in [Color::new(12, 200, 99, 255), Color::new(250, 250, 250, 7), Color::new(90, 30, 160, 255), Color::new(0, 0, 0, 255)] {
+        let (h, s, l) = c.to_hsl();
+        assert_eq!(Color::from_hsl(h, s, l, c.a), c);
+    }
+}
+#[test]
+fn test_color_oklab_oklch() {
+    // 参考值：sRGB 白色 L = 1，纯红 (0.628, 0.2249, 0.1258)
+    let (l, a, b) = Color::new(255, 255, 255, 255).to_oklab();
+    assert!((l - 1.0).abs() < 1e-3 && a.abs() < 1e-3 && b.abs() < 1e-3);
+    let (l, a, b) = Color::new(255, 0, 0, 255).to_oklab();
+    assert!((l - 0.628).abs() < 1e-3 && (a - 0.2249).abs() < 1e-3 && (b - 0.1258).abs() < 1e-3);
+    let (_, c, h) = Color::new(255, 0, 0, 255).to_oklch();
+    assert!((c - 0.2577).abs() < 1e-3 && (h - 29.23).abs() < 0.1);
+
+    for c in [Color::new(12, 200, 99, 255), Color::new(250, 250, 250, 7), Color::new(90, 30, 160, 255), Color::new(0, 0, 0, 255)] {
+        let (l, a, b) = c.to_oklab();
+        assert_eq!(Color::from_oklab(l, a, b, c.a), c);
+        let (l, ch, h) = c.to_oklch();
+        assert_eq!(Color::from_oklch(l, ch, h, c.a), c);
+    }
+    // 超出色域时截断而不是溢出
+    assert_eq!(Color::from_oklch(0.9, 0.37, 140.0, 255).a, 255);
+}
+#[test]
+fn test_color_model_components() {
+    let c = Color::new(40, 120, 200, 255);
+    for model in ColorModel::ALL {
+        let parts = model.components(c);
+        for (v, (_, lo, hi)) in parts.iter().zip(model.ranges()) {
+            assert!(*v >= lo && *v <= hi, "{:?} {:?}", model, parts);
+        }
+        assert_eq!(model.to_color(parts, 255), c, "{:?}", model);
+    }
+}
@@ -25,6 +25,64 @@ impl Chunk {
     }
 }
 
+// 90° 倍数的旋转与镜像：只搬移像素，不经过取样
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LosslessTransform {
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl LosslessTransform {
+    pub fn swaps_axes(&self) -> bool {
+        matches!(self, LosslessTransform::RotateCw | LosslessTransform::RotateCcw)
+    }
+
+    // 宽 w、高 h 的图像中 (x, y) 变换后的坐标
+    pub fn map(&self, x: u32, y: u32, w: u32, h: u32) -> (u32, u32) {
+        match self {
+            LosslessTransform::RotateCw => (h - 1 - y, x),
+            LosslessTransform::RotateCcw => (y, w - 1 - x),
+            LosslessTransform::Rotate180 => (w - 1 - x, h - 1 - y),
+            LosslessTransform::FlipHorizontal => (w - 1 - x, y),
+            LosslessTransform::FlipVertical => (x, h - 1 - y),
+        }
+    }
+
+    // 连续坐标 (骨骼位置、网格顶点) 的变换，w、h 为变换前的宽高
+    pub fn map_point(&self, x: f32, y: f32, w: f32, h: f32) -> (f32, f32) {
+        match self {
+            LosslessTransform::RotateCw => (h - y, x),
+            LosslessTransform::RotateCcw => (y, w - x),
+            LosslessTransform::Rotate180 => (w - x, h - y),
+            LosslessTransform::FlipHorizontal => (w - x, y),
+            LosslessTransform::FlipVertical => (x, h - y),
+        }
+    }
+
+    // 方向与偏移量只受线性部分影响
+    pub fn map_vector(&self, x: f32, y: f32) -> (f32, f32) {
+        self.map_point(x, y, 0.0, 0.0)
+    }
+
+    pub fn is_reflection(&self) -> bool {
+        matches!(self, LosslessTransform::FlipHorizontal | LosslessTransform::FlipVertical)
+    }
+
+    // 朝向角 (度，y 轴向下) 的变换；镜像时还需翻转 y 缩放才能保持完整的矩阵
+    pub fn map_rotation(&self, rotation: f32) -> f32 {
+        match self {
+            LosslessTransform::RotateCw => rotation + 90.0,
+            LosslessTransform::RotateCcw => rotation - 90.0,
+            LosslessTransform::Rotate180 => rotation + 180.0,
+            LosslessTransform::FlipHorizontal => 180.0 - rotation,
+            LosslessTransform::FlipVertical => -rotation,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Layer {
     pub id: String,
@@ -41,6 +99,8 @@ pub struct Layer {
     pub blend_mode: BlendMode,
     pub chunks: HashMap<(u32, u32), Chunk>,
     pub version: u64,
+    // 渲染时按顺序应用的非破坏性效果
+    pub effects: Vec<super::layer_effect::LayerEffect>,
 }
 
 impl Layer {
@@ -60,6 +120,7 @@ impl Layer {
             blend_mode: BlendMode::Normal,
             chunks: HashMap::new(),
             version: 0,
+            effects: Vec::new(),
         }
     }
 
@@ -197,6 +258,90 @@ impl Layer {
         self.width = new_width;
         self.height = new_height;
     }
+
+    // 变换后的图像左上角放在 (dx, dy)，超出 new_width x new_height 的部分裁掉
+    pub fn apply_lossless(&mut self, op: LosslessTransform, dx: i32, dy: i32, new_width: u32, new_height: u32) {
+        let (w, h) = (self.width, self.height);
+        let mut new_chunks: HashMap<(u32, u32), Chunk> = HashMap::with_capacity(self.chunks.len());
+        for ((cx, cy), chunk) in self.chunks.drain() {
+            let base_x = cx * CHUNK_SIZE;
+            let base_y = cy * CHUNK_SIZE;
+            for (i, pixel) in chunk.data.chunks_exact(4).enumerate() {
+                if pixel[3] == 0 { continue; }
+                let lx = base_x + (i as u32) % CHUNK_SIZE;
+                let ly = base_y + (i as u32) / CHUNK_SIZE;
+                if lx >= w || ly >= h { continue; }
+
+                let (mx, my) = op.map(lx, ly, w, h);
+                let (nx, ny) = (mx as i32 + dx, my as i32 + dy);
+                if nx < 0 || ny < 0 || nx >= new_width as i32 || ny >= new_height as i32 { continue; }
+                let (nx, ny) = (nx as u32, ny as u32);
+                let target_chunk = new_chunks.entry((nx / CHUNK_SIZE, ny / CHUNK_SIZE)).or_insert_with(Chunk::new);
+                let n_idx = (((ny % CHUNK_SIZE) * CHUNK_SIZE + nx % CHUNK_SIZE) * 4) as usize;
+                target_chunk.data_mut()[n_idx..n_idx+4].copy_from_slice(pixel);
+            }
+        }
+        self.chunks = new_chunks;
+        self.width = new_width;
+        self.height = new_height;
+        self.version += 1;
+    }
+
+    // 用整幅 RGBA 图像替换图层内容与尺寸，不检查锁定 (用于整个画布的放大等操作)
+    pub fn replace_rgba(&mut self, width: u32, height: u32, data: &[u8]) {
+        self.chunks.clear();
+        self.width = width;
+        self.height = height;
+        for (i, p) in data.chunks_exact(4).enumerate().filter(|(_, p)| p[3] > 0) {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            let _ = self.set_pixel_raw(x, y, Color::new(p[0], p[1], p[2], p[3]));
+        }
+        self.version += 1;
+    }
+
+    // 在画布坐标系内循环平移：画布内的像素绕画布边缘回卷，画布外的像素保持不动。
+    // 图层没有盖满画布时先扩展到能容纳回卷结果的范围
+    pub fn shift_wrapped(&mut self, dx: i32, dy: i32, canvas_width: u32, canvas_height: u32) {
+        if self.width == 0 || self.height == 0 || canvas_width == 0 || canvas_height == 0 { return; }
+        let w = canvas_width as i32;
+        let h = canvas_height as i32;
+        if dx.rem_euclid(w) == 0 && dy.rem_euclid(h) == 0 { return; }
+
+        let (left, top) = (self.offset_x, self.offset_y);
+        let (right, bottom) = (left + self.width as i32, top + self.height as i32);
+        if right <= 0 || bottom <= 0 || left >= w || top >= h { return; }
+
+        let (new_left, new_top) = (left.min(0), top.min(0));
+        let (new_right, new_bottom) = (right.max(w), bottom.max(h));
+        self.shift_and_resize(left - new_left, top - new_top, (new_right - new_left) as u32, (new_bottom - new_top) as u32);
+        self.offset_x = new_left;
+        self.offset_y = new_top;
+
+        let mut new_chunks: HashMap<(u32, u32), Chunk> = HashMap::with_capacity(self.chunks.len());
+        for ((cx, cy), chunk) in self.chunks.drain() {
+            let base_x = cx * CHUNK_SIZE;
+            let base_y = cy * CHUNK_SIZE;
+            for (i, pixel) in chunk.data.chunks_exact(4).enumerate() {
+                if pixel[3] == 0 { continue; }
+                let lx = base_x + (i as u32) % CHUNK_SIZE;
+                let ly = base_y + (i as u32) / CHUNK_SIZE;
+                if lx >= self.width || ly >= self.height { continue; }
+
+                let (px, py) = (lx as i32 + self.offset_x, ly as i32 + self.offset_y);
+                let (px, py) = if px >= 0 && px < w && py >= 0 && py < h {
+                    ((px + dx).rem_euclid(w), (py + dy).rem_euclid(h))
+                } else {
+                    (px, py)
+                };
+                let nx = (px - self.offset_x) as u32;
+                let ny = (py - self.offset_y) as u32;
+                let target_chunk = new_chunks.entry((nx / CHUNK_SIZE, ny / CHUNK_SIZE)).or_insert_with(Chunk::new);
+                let n_idx = (((ny % CHUNK_SIZE) * CHUNK_SIZE + nx % CHUNK_SIZE) * 4) as usize;
+                target_chunk.data_mut()[n_idx..n_idx+4].copy_from_slice(pixel);
+            }
+        }
+        self.chunks = new_chunks;
+    }
 }
 
 #[cfg(test)]
@@ -35,4 +35,47 @@ fn test_layer_shift_and_resize() {
     
     l.shift_and_resize(-20, -20, 5, 5);
     assert_eq!(l.chunks_count(), 0);
-}
\ No newline at end of file
+}
+#[test]
+fn test_layer_shift_wrapped() {
+    let mut l = Layer::new("1".into(), "1".into(), 10, 10);
+    l.set_pixel(8, 1, Color::new(255, 0, 0, 255)).unwrap();
+
+    l.shift_wrapped(3, -2, 10, 10);
+    assert_eq!(l.width, 10);
+    assert_eq!(l.get_pixel(1, 9).unwrap().r, 255);
+    assert_eq!(l.get_pixel(8, 1).unwrap().a, 0);
+
+    l.shift_wrapped(-3, 2, 10, 10);
+    assert_eq!(l.get_pixel(8, 1).unwrap().r, 255);
+}
+#[test]
+fn test_layer_shift_wrapped_offset_layer() {
+    // 图层右移 4 像素只盖住画布右半边；回卷以画布为准，画布外的像素不动
+    let mut l = Layer::new("1".into(), "1".into(), 10, 10);
+    l.offset_x = 4;
+    l.set_pixel(5, 2, Color::new(255, 0, 0, 255)).unwrap();
+    l.set_pixel(8, 3, Color::new(0, 255, 0, 255)).unwrap();
+
+    l.shift_wrapped(3, 0, 10, 10);
+    assert_eq!((l.offset_x, l.offset_y, l.width, l.height), (0, 0, 14, 10));
+    assert_eq!(l.get_pixel(2, 2).unwrap().r, 255);
+    assert_eq!(l.get_pixel(12, 3).unwrap().g, 255);
+    assert_eq!(l.get_pixel(9, 2).unwrap().a, 0);
+}
+#[test]
+fn test_layer_apply_lossless() {
+    let mut l = Layer::new("1".into(), "1".into(), 4, 2);
+    l.set_pixel(3, 0, Color::new(255, 0, 0, 255)).unwrap();
+
+    l.apply_lossless(LosslessTransform::RotateCw, 0, 0, 2, 4);
+    assert_eq!((l.width, l.height), (2, 4));
+    assert_eq!(l.get_pixel(1, 3).unwrap().r, 255);
+
+    l.apply_lossless(LosslessTransform::FlipHorizontal, 0, 0, 2, 4);
+    assert_eq!(l.get_pixel(0, 3).unwrap().r, 255);
+
+    l.apply_lossless(LosslessTransform::Rotate180, 0, 0, 2, 4);
+    assert_eq!(l.get_pixel(1, 0).unwrap().r, 255);
+    assert_eq!(l.get_pixel(0, 3).unwrap().a, 0);
+}
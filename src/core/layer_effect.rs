@@ -0,0 +1,137 @@
+use super::color::Color;
+use super::layer::Layer;
+use rust_i18n::t;
+use std::collections::HashMap;
+
+// 判断像素相邻时使用的邻域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Connectivity::Four => t!("effect.connectivity_four").to_string(),
+            Connectivity::Eight => t!("effect.connectivity_eight").to_string(),
+        }
+    }
+}
+
+// 非破坏性的图层效果：渲染时按顺序作用于图层像素，原像素不变
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerEffectKind {
+    Outline { color: Color, connectivity: Connectivity },
+    DropShadow { color: Color, dx: i32, dy: i32 },
+    // strength 0..1 为向 color 混合的程度
+    ColorOverlay { color: Color, strength: f32 },
+    // RGB 完全相同的像素替换为对应颜色，保留原透明度
+    PaletteSwap { pairs: Vec<(Color, Color)> },
+    HueShift { degrees: f32 },
+}
+
+impl LayerEffectKind {
+    pub fn defaults(color: Color) -> [LayerEffectKind; 5] {
+        [
+            LayerEffectKind::Outline { color, connectivity: Connectivity::Four },
+            LayerEffectKind::DropShadow { color, dx: 1, dy: 1 },
+            LayerEffectKind::ColorOverlay { color, strength: 0.5 },
+            LayerEffectKind::PaletteSwap { pairs: Vec::new() },
+            LayerEffectKind::HueShift { degrees: 0.0 },
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            LayerEffectKind::Outline { .. } => t!("effect.outline").to_string(),
+            LayerEffectKind::DropShadow { .. } => t!("effect.drop_shadow").to_string(),
+            LayerEffectKind::ColorOverlay { .. } => t!("effect.color_overlay").to_string(),
+            LayerEffectKind::PaletteSwap { .. } => t!("effect.palette_swap").to_string(),
+            LayerEffectKind::HueShift { .. } => t!("effect.hue_shift").to_string(),
+        }
+    }
+
+    // 效果结果超出源像素的最大距离，局部重绘时按此扩大脏矩形
+    pub fn reach(&self) -> u32 {
+        match self {
+            LayerEffectKind::Outline { .. } => 1,
+            LayerEffectKind::DropShadow { dx, dy, .. } => dx.unsigned_abs().max(dy.unsigned_abs()),
+            _ => 0,
+        }
+    }
+
+    // 可由动画驱动的数值参数 (叠加强度、色相偏移角度)
+    pub fn amount(&self) -> Option<f32> {
+        match self {
+            LayerEffectKind::ColorOverlay { strength, .. } => Some(*strength),
+            LayerEffectKind::HueShift { degrees } => Some(*degrees),
+            _ => None,
+        }
+    }
+
+    pub fn set_amount(&mut self, value: f32) {
+        match self {
+            LayerEffectKind::ColorOverlay { strength, .. } => *strength = value.clamp(0.0, 1.0),
+            LayerEffectKind::HueShift { degrees } => *degrees = value,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerEffect {
+    // 动画轨道以此 id 为目标，调整顺序后仍然有效
+    pub id: String,
+    pub enabled: bool,
+    pub kind: LayerEffectKind,
+}
+
+impl LayerEffect {
+    pub fn new(id: String, kind: LayerEffectKind) -> Self {
+        Self { id, enabled: true, kind }
+    }
+}
+
+// 启用的效果，amounts (效果 id -> 数值) 中有动画值时覆盖参数
+pub fn resolve_effects(effects: &[LayerEffect], amounts: &HashMap<String, f32>) -> Vec<LayerEffectKind> {
+    effects.iter().filter(|e| e.enabled).map(|e| {
+        let mut kind = e.kind.clone();
+        if let Some(&value) = amounts.get(&e.id) { kind.set_amount(value); }
+        kind
+    }).collect()
+}
+
+// 图层应用效果后的结果，按源图层 version 与解析后的效果参数判断是否过期
+#[derive(Debug, Clone)]
+pub struct EffectCacheEntry {
+    pub source_version: u64,
+    pub effects: Vec<LayerEffectKind>,
+    pub layer: Layer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_effects_skips_disabled_and_applies_amounts() {
+        let mut hue = LayerEffect::new("e1".into(), LayerEffectKind::HueShift { degrees: 10.0 });
+        let overlay = LayerEffect::new("e2".into(), LayerEffectKind::ColorOverlay { color: Color::new(255, 255, 255, 255), strength: 0.2 });
+        let amounts = HashMap::from([("e2".to_string(), 3.0)]);
+
+        let resolved = resolve_effects(&[hue.clone(), overlay.clone()], &amounts);
+        assert_eq!(resolved[0], LayerEffectKind::HueShift { degrees: 10.0 });
+        assert_eq!(resolved[1].amount(), Some(1.0), "叠加强度限制在 0..1");
+
+        hue.enabled = false;
+        assert_eq!(resolve_effects(&[hue, overlay], &HashMap::new()).len(), 1);
+    }
+}
@@ -2,6 +2,7 @@ pub mod error;
 pub mod blend_mode;
 pub mod color;
 pub mod layer;
+pub mod layer_effect;
 pub mod store;
 pub mod id_gen;
 pub mod symmetry;
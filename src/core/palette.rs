@@ -1,21 +1,57 @@
 use crate::core::color::Color;
+use crate::core::layer::{Layer, CHUNK_SIZE};
 use rust_i18n::t;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Palette {
     pub name: String,
     pub colors: Vec<Color>,
+    // 与 colors 按下标对应的颜色名称 (来自 GPL / ASE 等格式)，可以比 colors 短，空字符串为未命名
+    pub names: Vec<String>,
+    pub ramps: Vec<PaletteRamp>,
+    // 与 colors 按下标对应的所属色带 id，可以比 colors 短
+    pub ramp_ids: Vec<Option<u32>>,
+}
+
+// 色带：一组通常由暗到亮排列的颜色，颜色按 ramp_ids 归属，不要求在调色板中相邻
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteRamp {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteSortKey {
+    Hue,
+    Luminance,
+    Saturation,
+}
+
+impl PaletteSortKey {
+    pub const ALL: [PaletteSortKey; 3] = [PaletteSortKey::Hue, PaletteSortKey::Luminance, PaletteSortKey::Saturation];
+
+    pub fn label(&self) -> String {
+        match self {
+            PaletteSortKey::Hue => t!("palette.sort_hue").to_string(),
+            PaletteSortKey::Luminance => t!("palette.sort_luminance").to_string(),
+            PaletteSortKey::Saturation => t!("palette.sort_saturation").to_string(),
+        }
+    }
 }
 
 impl Palette {
     pub fn new() -> Self {
-        Self { name: t!("palette.default_custom").to_string(), colors: Vec::new() }
+        Self::from_colors(t!("palette.default_custom").to_string(), Vec::new())
+    }
+
+    pub fn from_colors(name: String, colors: Vec<Color>) -> Self {
+        Self { name, colors, names: Vec::new(), ramps: Vec::new(), ramp_ids: Vec::new() }
     }
 
     pub fn default_pico8() -> Self {
-        Self {
-            name: t!("palette.default_pico8").to_string(),
-            colors: vec![
+        Self::from_colors(
+            t!("palette.default_pico8").to_string(),
+            vec![
                 Color::new(0, 0, 0, 255),       
                 Color::new(29, 43, 83, 255),    
                 Color::new(126, 37, 83, 255),   
@@ -32,8 +68,8 @@ impl Palette {
                 Color::new(131, 118, 156, 255), 
                 Color::new(255, 119, 168, 255), 
                 Color::new(255, 204, 170, 255), 
-            ]
-        }
+            ],
+        )
     }
 
     pub fn add_color(&mut self, color: Color) {
@@ -46,5 +82,197 @@ impl Palette {
         if index < self.colors.len() {
             self.colors.remove(index);
         }
+        if index < self.names.len() {
+            self.names.remove(index);
+        }
+        if index < self.ramp_ids.len() {
+            self.ramp_ids.remove(index);
+        }
+    }
+
+    pub fn color_name(&self, index: usize) -> &str {
+        self.names.get(index).map(|s| s.as_str()).unwrap_or("")
+    }
+
+    pub fn ramp_of(&self, index: usize) -> Option<u32> {
+        self.ramp_ids.get(index).copied().flatten()
+    }
+
+    pub fn ramp(&self, id: u32) -> Option<&PaletteRamp> {
+        self.ramps.iter().find(|r| r.id == id)
+    }
+
+    // 色带内的颜色下标，按调色板顺序
+    pub fn ramp_members(&self, id: u32) -> Vec<usize> {
+        (0..self.colors.len()).filter(|&i| self.ramp_of(i) == Some(id)).collect()
+    }
+
+    // 名称与色带归属补齐到与 colors 等长
+    fn fill_metadata(&mut self) {
+        self.names.resize(self.colors.len(), String::new());
+        self.ramp_ids.resize(self.colors.len(), None);
+    }
+
+    pub fn set_color_name(&mut self, index: usize, name: String) {
+        if index >= self.colors.len() { return; }
+        self.fill_metadata();
+        self.names[index] = name;
+    }
+
+    // 追加一组颜色作为新色带 (不去重：同一颜色可以出现在多个色带中)，返回色带 id
+    pub fn add_ramp(&mut self, name: String, colors: &[Color]) -> u32 {
+        self.fill_metadata();
+        let id = self.ramps.iter().map(|r| r.id + 1).max().unwrap_or(0);
+        self.ramps.push(PaletteRamp { id, name });
+        for &color in colors {
+            self.colors.push(color);
+            self.names.push(String::new());
+            self.ramp_ids.push(Some(id));
+        }
+        id
+    }
+
+    // 把已有颜色归入色带 (None 为移出色带)；不存在的色带 id 视为 None
+    pub fn assign_ramp(&mut self, indices: &[usize], ramp: Option<u32>) {
+        let ramp = ramp.filter(|&id| self.ramp(id).is_some());
+        self.fill_metadata();
+        for &i in indices.iter().filter(|&&i| i < self.colors.len()) {
+            self.ramp_ids[i] = ramp;
+        }
+    }
+
+    pub fn rename_ramp(&mut self, id: u32, name: String) {
+        if let Some(ramp) = self.ramps.iter_mut().find(|r| r.id == id) {
+            ramp.name = name;
+        }
+    }
+
+    // 只删除分组，颜色保留在调色板中
+    pub fn remove_ramp(&mut self, id: u32) {
+        self.ramps.retain(|r| r.id != id);
+        for ramp_id in self.ramp_ids.iter_mut().filter(|r| **r == Some(id)) {
+            *ramp_id = None;
+        }
+    }
+
+    // 同一色带 (以及未分组颜色) 只在自己占据的位置之间重新排列，色带在调色板中的布局不变
+    pub fn sort(&mut self, key: PaletteSortKey) {
+        self.fill_metadata();
+        let sort_key = |c: &Color| -> (f32, f32) {
+            let (h, s, _) = c.to_hsv();
+            match key {
+                // 灰色排在最前，同色相按亮度
+                PaletteSortKey::Hue => (if s < 0.05 { -1.0 } else { h }, c.luminance()),
+                PaletteSortKey::Luminance => (c.luminance(), h),
+                PaletteSortKey::Saturation => (s, c.luminance()),
+            }
+        };
+        let mut groups: Vec<Option<u32>> = self.ramp_ids.clone();
+        groups.sort();
+        groups.dedup();
+        for group in groups {
+            let slots: Vec<usize> = (0..self.colors.len()).filter(|&i| self.ramp_ids[i] == group).collect();
+            let mut order = slots.clone();
+            order.sort_by(|&a, &b| sort_key(&self.colors[a]).partial_cmp(&sort_key(&self.colors[b])).unwrap_or(std::cmp::Ordering::Equal));
+            let entries: Vec<(Color, String)> = order.iter().map(|&i| (self.colors[i], self.names[i].clone())).collect();
+            for (&slot, (color, name)) in slots.iter().zip(entries) {
+                self.colors[slot] = color;
+                self.names[slot] = name;
+            }
+        }
+    }
+
+    // 删除完全相同的颜色 (保留第一次出现的位置、名称与色带)，返回删除的数量
+    pub fn dedupe(&mut self) -> usize {
+        self.fill_metadata();
+        let mut seen = std::collections::HashSet::new();
+        let keep: Vec<bool> = self.colors.iter().map(|c| seen.insert(*c)).collect();
+        let before = self.colors.len();
+        let mut iter = keep.iter();
+        self.colors.retain(|_| *iter.next().unwrap());
+        let mut iter = keep.iter();
+        self.names.retain(|_| *iter.next().unwrap());
+        let mut iter = keep.iter();
+        self.ramp_ids.retain(|_| *iter.next().unwrap());
+        before - self.colors.len()
+    }
+
+    // 追加另一个调色板中尚未出现的颜色，连同名称与色带 (色带重新编号)
+    pub fn merge(&mut self, other: &Palette) {
+        self.fill_metadata();
+        let mut next_id = self.ramps.iter().map(|r| r.id + 1).max().unwrap_or(0);
+        let mut id_map = std::collections::HashMap::new();
+        for (i, &color) in other.colors.iter().enumerate() {
+            if self.colors.contains(&color) { continue; }
+            let ramp = other.ramp_of(i).and_then(|id| {
+                let ramp = other.ramp(id)?;
+                Some(*id_map.entry(id).or_insert_with(|| {
+                    self.ramps.push(PaletteRamp { id: next_id, name: ramp.name.clone() });
+                    next_id += 1;
+                    next_id - 1
+                }))
+            });
+            self.colors.push(color);
+            self.names.push(other.color_name(i).to_string());
+            self.ramp_ids.push(ramp);
+        }
     }
-}
\ No newline at end of file
+}
+
+// 在 HSV 空间中从 from 过渡到 to，共 steps 个颜色 (含两端)。
+// hue_shift 让中间的颜色额外偏转色相 (度)，两端保持不变，用于做出暗部偏冷、亮部偏暖的色带
+pub fn generate_ramp(from: Color, to: Color, steps: usize, hue_shift: f32) -> Vec<Color> {
+    if steps < 2 { return vec![from]; }
+    let (h0, s0, v0) = from.to_hsv();
+    let (h1, s1, v1) = to.to_hsv();
+    // 一端为灰色时沿用另一端的色相，避免经过无关的颜色
+    let (h0, h1) = match (s0 < 0.01, s1 < 0.01) {
+        (true, false) => (h1, h1),
+        (false, true) => (h0, h0),
+        _ => (h0, h1),
+    };
+    // 沿较短的方向过渡色相
+    let dh = (h1 - h0 + 540.0).rem_euclid(360.0) - 180.0;
+    (0..steps).map(|i| {
+        if i == 0 { return from; }
+        if i == steps - 1 { return to; }
+        let t = i as f32 / (steps - 1) as f32;
+        let hue = h0 + dh * t + hue_shift * (std::f32::consts::PI * t).sin();
+        let alpha = (from.a as f32 + (to.a as f32 - from.a as f32) * t).round() as u8;
+        Color::from_hsv(hue, s0 + (s1 - s0) * t, v0 + (v1 - v0) * t, alpha)
+    }).collect()
+}
+
+// 以 base 为中心生成 count 个由暗到亮的明暗色阶 (在 OKLCH 中改变亮度)。
+// 暗部色相偏转 -hue_shift、亮部偏转 +hue_shift，两端的色度略为降低；count 为奇数时正中间就是 base
+pub fn generate_shades(base: Color, count: usize, hue_shift: f32) -> Vec<Color> {
+    if count < 2 { return vec![base]; }
+    let (l, c, h) = base.to_oklch();
+    let (darkest, lightest) = (0.12f32.min(l), 0.97f32.max(l));
+    (0..count).map(|i| {
+        let t = i as f32 / (count - 1) as f32 * 2.0 - 1.0;
+        if t.abs() < 1e-6 { return base; }
+        let lightness = if t < 0.0 { l + (l - darkest) * t } else { l + (lightest - l) * t };
+        Color::from_oklch(lightness, c * (1.0 - 0.4 * t.abs()), h + hue_shift * t, base.a)
+    }).collect()
+}
+
+// 各图层中不透明像素的颜色及其出现次数，按次数从多到少排列
+pub fn used_colors(layers: &[Layer]) -> Vec<(Color, u64)> {
+    let mut counts: std::collections::HashMap<Color, u64> = std::collections::HashMap::new();
+    for layer in layers {
+        for (&(cx, cy), chunk) in &layer.chunks {
+            for (i, p) in chunk.data.chunks_exact(4).enumerate() {
+                let (x, y) = (cx * CHUNK_SIZE + i as u32 % CHUNK_SIZE, cy * CHUNK_SIZE + i as u32 / CHUNK_SIZE);
+                if p[3] == 0 || x >= layer.width || y >= layer.height { continue; }
+                *counts.entry(Color::new(p[0], p[1], p[2], p[3])).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut used: Vec<(Color, u64)> = counts.into_iter().collect();
+    used.sort_by(|a, b| b.1.cmp(&a.1).then((a.0.r, a.0.g, a.0.b, a.0.a).cmp(&(b.0.r, b.0.g, b.0.b, b.0.a))));
+    used
+}
+
+#[cfg(test)]
+mod tests;
\ No newline at end of file
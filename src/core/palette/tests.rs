@@ -0,0 +1,82 @@
+use super::*;
+
+fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::new(r, g, b, 255)
+}
+
+#[test]
+fn test_sort_keeps_ramp_slots() {
+    let mut p = Palette::from_colors("t".into(), vec![rgb(255, 255, 255), rgb(0, 0, 0)]);
+    let ramp = p.add_ramp("Red".into(), &[rgb(255, 80, 80), rgb(120, 0, 0)]);
+    p.add_color(rgb(128, 128, 128));
+    p.set_color_name(0, "White".into());
+
+    p.sort(PaletteSortKey::Luminance);
+    // 未分组颜色占据 0、1、4，只在这些位置之间排序
+    assert_eq!([p.colors[0], p.colors[1], p.colors[4]], [rgb(0, 0, 0), rgb(128, 128, 128), rgb(255, 255, 255)]);
+    assert_eq!(p.color_name(4), "White", "名称随颜色移动");
+    assert_eq!(p.colors[2..4], [rgb(120, 0, 0), rgb(255, 80, 80)], "色带内由暗到亮");
+    assert_eq!(p.ramp_members(ramp), vec![2, 3]);
+}
+
+#[test]
+fn test_dedupe_and_merge() {
+    let mut p = Palette::from_colors("t".into(), vec![rgb(1, 1, 1), rgb(2, 2, 2), rgb(1, 1, 1)]);
+    p.set_color_name(2, "dup".into());
+    assert_eq!(p.dedupe(), 1);
+    assert_eq!(p.colors, vec![rgb(1, 1, 1), rgb(2, 2, 2)]);
+    assert_eq!(p.names, vec!["", ""], "保留第一次出现的条目");
+
+    let mut other = Palette::from_colors("o".into(), vec![rgb(2, 2, 2)]);
+    let ramp = other.add_ramp("Skin".into(), &[rgb(200, 150, 120), rgb(240, 200, 170)]);
+    other.set_color_name(1, "Tan".into());
+    other.ramps[0].id = ramp + 7;
+    other.ramp_ids = vec![None, Some(ramp + 7), Some(ramp + 7)];
+    p.merge(&other);
+    assert_eq!(p.colors.len(), 4, "已存在的颜色不重复添加");
+    assert_eq!(p.color_name(2), "Tan");
+    assert_eq!(p.ramps.len(), 1);
+    assert_eq!(p.ramps[0].name, "Skin");
+    assert_eq!(p.ramp_members(p.ramps[0].id), vec![2, 3], "色带重新编号后保持归属");
+
+    p.remove_ramp(p.ramps[0].id);
+    assert!(p.ramps.is_empty() && p.ramp_of(2).is_none());
+    assert_eq!(p.colors.len(), 4, "删除色带不删除颜色");
+}
+
+#[test]
+fn test_generate_ramp_hue_shift() {
+    let (dark, light) = (Color::from_hsv(0.0, 0.8, 0.2, 255), Color::from_hsv(0.0, 0.4, 1.0, 255));
+    let ramp = generate_ramp(dark, light, 5, 0.0);
+    assert_eq!(ramp.len(), 5);
+    assert_eq!((ramp[0], ramp[4]), (dark, light), "两端保持不变");
+    assert!(ramp.windows(2).all(|w| w[0].luminance() < w[1].luminance()), "亮度单调递增");
+
+    let shifted = generate_ramp(dark, light, 5, 30.0);
+    assert_eq!((shifted[0], shifted[4]), (dark, light));
+    let (h, _, _) = shifted[2].to_hsv();
+    assert!((h - 30.0).abs() < 2.0, "中间颜色偏转 hue_shift 度: {}", h);
+}
+
+#[test]
+fn test_generate_shades() {
+    let base = Color::new(200, 60, 40, 255);
+    let shades = generate_shades(base, 7, 0.0);
+    assert_eq!(shades.len(), 7);
+    assert_eq!(shades[3], base, "奇数个时中间为原色");
+    assert!(shades.windows(2).all(|w| w[0].to_oklch().0 < w[1].to_oklch().0), "由暗到亮");
+
+    // 暗部与亮部的色相向相反方向偏转
+    let shifted = generate_shades(base, 7, 20.0);
+    let hue = |c: Color| c.to_oklch().2;
+    assert!(hue(shifted[1]) < hue(base) && hue(shifted[5]) > hue(base));
+    assert_eq!(generate_shades(base, 1, 20.0), vec![base]);
+}
+
+#[test]
+fn test_used_colors_counts() {
+    let mut layer = Layer::new("l".into(), "l".into(), 4, 4);
+    for x in 0..3 { layer.set_pixel(x, 0, rgb(9, 9, 9)).unwrap(); }
+    layer.set_pixel(0, 1, rgb(5, 5, 5)).unwrap();
+    assert_eq!(used_colors(&[layer]), vec![(rgb(9, 9, 9), 3), (rgb(5, 5, 5), 1)]);
+}
@@ -127,6 +127,47 @@ impl SelectionData {
         self.mask[(y * self.width + x) as usize]
     }
 
+    // 选区在图层坐标下的逐像素标记 (长度 width * height)，未激活时返回 None 表示整个图层
+    pub fn layer_mask(&self, layer: &crate::core::layer::Layer) -> Option<Vec<bool>> {
+        if !self.is_active { return None; }
+        Some((0..layer.height).flat_map(|y| (0..layer.width).map(move |x| (x, y))).map(|(x, y)| {
+            let (cx, cy) = (x as i32 + layer.offset_x, y as i32 + layer.offset_y);
+            cx >= 0 && cy >= 0 && self.contains(cx as u32, cy as u32)
+        }).collect())
+    }
+
+    // 与 Layer::apply_lossless 相同的坐标换算
+    pub fn apply_lossless(&mut self, op: crate::core::layer::LosslessTransform, dx: i32, dy: i32, new_width: u32, new_height: u32) {
+        let mut new_mask = vec![false; (new_width * new_height) as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.mask[(y * self.width + x) as usize] { continue; }
+                let (mx, my) = op.map(x, y, self.width, self.height);
+                let (nx, ny) = (mx as i32 + dx, my as i32 + dy);
+                if nx >= 0 && nx < new_width as i32 && ny >= 0 && ny < new_height as i32 {
+                    new_mask[(ny as u32 * new_width + nx as u32) as usize] = true;
+                }
+            }
+        }
+        self.mask = new_mask;
+        self.width = new_width;
+        self.height = new_height;
+    }
+
+    // 每个选区像素放大为 factor x factor
+    pub fn scale_nearest(&mut self, factor: u32) {
+        let (new_width, new_height) = (self.width * factor, self.height * factor);
+        let mut new_mask = vec![false; (new_width * new_height) as usize];
+        for y in 0..new_height {
+            for x in 0..new_width {
+                new_mask[(y * new_width + x) as usize] = self.mask[((y / factor) * self.width + x / factor) as usize];
+            }
+        }
+        self.mask = new_mask;
+        self.width = new_width;
+        self.height = new_height;
+    }
+
     pub fn shift_and_resize(&mut self, dx: i32, dy: i32, new_width: u32, new_height: u32) {
         let mut new_mask = vec![false; (new_width * new_height) as usize];
         if self.is_active {
@@ -11,20 +11,47 @@ pub enum BrushShape {
     Circle,
 }
 
+pub const RECENT_COLORS_MAX: usize = 16;
+
+// 滤镜预览的结果：source_version 与图层一致时代替图层像素参与合成，图层本身不变
+#[derive(Debug, Clone)]
+pub struct PixelPreview {
+    pub source_version: u64,
+    pub layer: Layer,
+}
+
 pub struct PixelStore {
     pub canvas_width: u32,
     pub canvas_height: u32,
     pub layers: Vec<Layer>,
     pub active_layer_id: Option<String>,
     pub primary_color: Color,
+    pub secondary_color: Color,
+    // 最近使用的颜色，最新的在前
+    pub recent_colors: Vec<Color>,
+    // 当前笔画使用副色 (右键绘制)
+    pub paint_with_secondary: bool,
     pub brush_size: u32,
     pub brush_shape: BrushShape,
     pub brush_jitter: u32,
+    pub tiling_mode: bool,
     pub palette: Palette,
     pub selection: SelectionData,
     pub composite_cache: Vec<u8>,
+    pub onion_cache: Vec<u8>,
     pub active_path: BezierPath,
     pub layer_anim_transforms: std::collections::HashMap<String, [f32; 6]>,
+    // 动画模式下由插槽决定的图层合成顺序 (从下到上)，None 时按图层列表顺序
+    pub layer_draw_order: Option<Vec<String>>,
+    // 动画模式下由网格附件蒙皮变形的图层，优先于 layer_anim_transforms 与动画偏移
+    pub layer_meshes: std::collections::HashMap<String, crate::core::animation::mesh::DeformedMesh>,
+    // 动画驱动的图层效果数值 (效果 id -> 数值)
+    pub effect_amounts: std::collections::HashMap<String, f32>,
+    // 带效果图层的渲染结果，由 render::effects::refresh_effect_cache 维护
+    pub effect_cache: std::collections::HashMap<String, crate::core::layer_effect::EffectCacheEntry>,
+    // 效果缓存与滤镜预览生成的 version 序号
+    pub effect_cache_serial: u64,
+    pub pixel_preview: Option<PixelPreview>,
 }
 
 impl PixelStore {
@@ -35,17 +62,48 @@ impl PixelStore {
             layers: Vec::new(),
             active_layer_id: None,
             primary_color: Color::new(0, 0, 0, 255),
+            secondary_color: Color::new(255, 255, 255, 255),
+            recent_colors: Vec::new(),
+            paint_with_secondary: false,
             brush_size: 1,
             brush_shape: BrushShape::Square,
             brush_jitter: 0,
+            tiling_mode: false,
             palette: Palette::default_pico8(),
             selection: SelectionData::new(width, height),
             composite_cache: vec![0u8; (width * height * 4) as usize],
+            onion_cache: Vec::new(),
             active_path: BezierPath::new(),
             layer_anim_transforms: std::collections::HashMap::new(),
+            layer_draw_order: None,
+            layer_meshes: std::collections::HashMap::new(),
+            effect_amounts: std::collections::HashMap::new(),
+            effect_cache: std::collections::HashMap::new(),
+            effect_cache_serial: 0,
+            pixel_preview: None,
         }
     }
 
+    // 绘制工具当前使用的颜色
+    pub fn paint_color(&self) -> Color {
+        if self.paint_with_secondary { self.secondary_color } else { self.primary_color }
+    }
+
+    pub fn set_paint_color(&mut self, color: Color) {
+        if self.paint_with_secondary { self.secondary_color = color; } else { self.primary_color = color; }
+    }
+
+    pub fn swap_colors(&mut self) {
+        std::mem::swap(&mut self.primary_color, &mut self.secondary_color);
+    }
+
+    // 移到最前，已存在时不重复
+    pub fn push_recent_color(&mut self, color: Color) {
+        self.recent_colors.retain(|c| *c != color);
+        self.recent_colors.insert(0, color);
+        self.recent_colors.truncate(RECENT_COLORS_MAX);
+    }
+
     pub fn add_layer(&mut self, layer: Layer) {
         if self.layers.is_empty() {
             self.active_layer_id = Some(layer.id.clone());
@@ -83,6 +141,36 @@ impl PixelStore {
         self.layers.iter().find(|l| l.id == id)
     }
 
+    // 效果的输入像素：有未过期的滤镜预览时为预览结果
+    pub fn source_layer<'a>(&'a self, layer: &'a Layer) -> &'a Layer {
+        Self::preview_or(&self.pixel_preview, layer)
+    }
+
+    // 合成时使用的图层：在 source_layer 的基础上，有未过期的效果缓存时返回应用效果后的结果
+    pub fn rendered_layer<'a>(&'a self, layer: &'a Layer) -> &'a Layer {
+        Self::rendered_from(&self.effect_cache, &self.pixel_preview, layer)
+    }
+
+    // 只借用缓存字段，供需要同时写入 composite_cache 的合成使用
+    pub fn rendered_from<'a>(
+        effect_cache: &'a std::collections::HashMap<String, crate::core::layer_effect::EffectCacheEntry>,
+        preview: &'a Option<PixelPreview>,
+        layer: &'a Layer,
+    ) -> &'a Layer {
+        let source = Self::preview_or(preview, layer);
+        match effect_cache.get(&layer.id) {
+            Some(entry) if entry.source_version == source.version => &entry.layer,
+            _ => source,
+        }
+    }
+
+    fn preview_or<'a>(preview: &'a Option<PixelPreview>, layer: &'a Layer) -> &'a Layer {
+        match preview {
+            Some(p) if p.layer.id == layer.id && p.source_version == layer.version => &p.layer,
+            _ => layer,
+        }
+    }
+
     pub fn get_layer_mut(&mut self, id: &str) -> Option<&mut Layer> {
         self.layers.iter_mut().find(|l| l.id == id)
     }
@@ -112,6 +200,13 @@ impl PixelStore {
         layer.set_pixel(local_x as u32, local_y as u32, color)
     }
 
+    pub fn wrap_coords(&self, x: i32, y: i32) -> (i32, i32) {
+        if !self.tiling_mode || self.canvas_width == 0 || self.canvas_height == 0 {
+            return (x, y);
+        }
+        (x.rem_euclid(self.canvas_width as i32), y.rem_euclid(self.canvas_height as i32))
+    }
+
     pub fn get_composite_pixel(&self, x: u32, y: u32) -> Color {
         if x >= self.canvas_width || y >= self.canvas_height {
             return Color::transparent();
@@ -67,4 +67,28 @@ fn test_store_bounds_check() {
     let mut s = PixelStore::new(10, 10);
     s.add_layer(Layer::new("L".into(), "L".into(), 10, 10));
     assert!(s.get_pixel("L", 10, 10).is_none());
-}
\ No newline at end of file
+}
+#[test]
+fn test_store_wrap_coords() {
+    let mut s = PixelStore::new(10, 8);
+    assert_eq!(s.wrap_coords(-1, 9), (-1, 9));
+    s.tiling_mode = true;
+    assert_eq!(s.wrap_coords(-1, 9), (9, 1));
+    assert_eq!(s.wrap_coords(20, -8), (0, 0));
+}
+
+#[test]
+fn test_store_secondary_and_recent_colors() {
+    let mut s = PixelStore::new(10, 10);
+    s.primary_color = Color::new(1, 2, 3, 255);
+    s.swap_colors();
+    assert_eq!((s.primary_color, s.secondary_color), (Color::new(255, 255, 255, 255), Color::new(1, 2, 3, 255)));
+    s.paint_with_secondary = true;
+    assert_eq!(s.paint_color(), Color::new(1, 2, 3, 255));
+
+    for i in 0..20 { s.push_recent_color(Color::new(i, 0, 0, 255)); }
+    s.push_recent_color(Color::new(10, 0, 0, 255));
+    assert_eq!(s.recent_colors.len(), RECENT_COLORS_MAX);
+    assert_eq!(s.recent_colors[0], Color::new(10, 0, 0, 255), "重复使用的颜色移到最前");
+    assert_eq!(s.recent_colors.iter().filter(|c| c.r == 10).count(), 1);
+}
@@ -0,0 +1,69 @@
+use crate::core::animation::easing::CurvePreset;
+use crate::format::error::{FormatError, Result};
+use std::io::{BufRead, Write};
+use std::fs::File;
+use std::path::Path;
+
+// 每行一个预设：四个贝塞尔控制点坐标后跟名称，# 开头为注释
+pub fn save_presets(path: &Path, presets: &[CurvePreset]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = File::create(path)?;
+    writeln!(file, "# PXA curve presets: cx1 cy1 cx2 cy2 name")?;
+    for preset in presets {
+        let [cx1, cy1, cx2, cy2] = preset.handles;
+        writeln!(file, "{} {} {} {} {}", cx1, cy1, cx2, cy2, preset.name)?;
+    }
+    Ok(())
+}
+
+pub fn load_presets(path: &Path) -> Result<Vec<CurvePreset>> {
+    let file = File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    let mut presets = Vec::new();
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let mut parts = line.splitn(5, char::is_whitespace);
+        let mut handles = [0.0f32; 4];
+        for h in &mut handles {
+            *h = parts.next().and_then(|v| v.parse().ok())
+                .ok_or_else(|| FormatError::InvalidData(format!("Invalid curve preset at line {}", line_no + 1)))?;
+        }
+        let name = parts.next().unwrap_or("").trim().to_string();
+        if name.is_empty() {
+            return Err(FormatError::InvalidData(format!("Missing curve preset name at line {}", line_no + 1)));
+        }
+        presets.push(CurvePreset { name, handles });
+    }
+    Ok(presets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_curve_presets_io() {
+        let presets = vec![
+            CurvePreset { name: "快速起步".into(), handles: [0.1, 0.8, 0.3, 1.0] },
+            CurvePreset { name: "slow end".into(), handles: [0.5, 0.0, 0.9, 0.2] },
+        ];
+        let mut path = env::temp_dir();
+        path.push(format!("pxa_presets_{}", std::process::id()));
+        path.push("curve_presets.txt");
+
+        save_presets(&path, &presets).expect("保存失败");
+        let loaded = load_presets(&path).expect("加载失败");
+        assert_eq!(loaded, presets, "名称中的空格应被保留");
+
+        std::fs::write(&path, "0.1 0.2 zz name\n").unwrap();
+        assert!(load_presets(&path).is_err(), "非法数值应报错");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
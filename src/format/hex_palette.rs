@@ -7,10 +7,19 @@ use std::path::Path;
 
 pub fn save_to_hex(path: &Path, palette: &Palette) -> Result<()> {
     let mut file = File::create(path)?;
+    file.write_all(write_hex(palette).as_bytes())?;
+    Ok(())
+}
+
+// 不透明颜色写 RRGGBB，半透明 / 透明颜色写 RRGGBBAA
+pub fn write_hex(palette: &Palette) -> String {
+    let mut text = String::new();
     for color in &palette.colors {
-        writeln!(file, "{:02X}{:02X}{:02X}", color.r, color.g, color.b)?;
+        text.push_str(&format!("{:02X}{:02X}{:02X}", color.r, color.g, color.b));
+        if color.a < 255 { text.push_str(&format!("{:02X}", color.a)); }
+        text.push('\n');
     }
-    Ok(())
+    text
 }
 
 pub fn load_from_hex(path: &Path) -> Result<Palette> {
@@ -19,19 +28,8 @@ pub fn load_from_hex(path: &Path) -> Result<Palette> {
     let mut colors = Vec::new();
 
     for line in reader.lines() {
-        let line = line?.trim().to_string();
-        if line.is_empty() { continue; }
-        
-        let hex_str = if line.starts_with('#') { &line[1..] } else { &line };
-        
-        if hex_str.len() == 6 {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&hex_str[0..2], 16),
-                u8::from_str_radix(&hex_str[2..4], 16),
-                u8::from_str_radix(&hex_str[4..6], 16),
-            ) {
-                colors.push(Color::new(r, g, b, 255));
-            }
+        if let Some(color) = parse_hex_line(&line?) {
+            colors.push(color);
         }
     }
     
@@ -40,7 +38,17 @@ pub fn load_from_hex(path: &Path) -> Result<Palette> {
         .to_string_lossy()
         .into_owned();
         
-    Ok(Palette { name, colors })
+    Ok(Palette::from_colors(name, colors))
+}
+
+// 每行一个 RRGGBB 或 RRGGBBAA，可带 # 前缀；其它内容视为注释忽略
+pub fn parse_hex_line(line: &str) -> Option<Color> {
+    let line = line.trim();
+    let hex_str = line.strip_prefix('#').unwrap_or(line);
+    if (hex_str.len() != 6 && hex_str.len() != 8) || !hex_str.is_ascii() { return None; }
+    let channel = |i: usize| u8::from_str_radix(&hex_str[i..i + 2], 16).ok();
+    let alpha = if hex_str.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::new(channel(0)?, channel(2)?, channel(4)?, alpha))
 }
 
 #[cfg(test)]
@@ -70,4 +78,14 @@ mod tests {
 
         let _ = std::fs::remove_file(path);
     }
+
+    #[test]
+    fn test_hex_palette_alpha() {
+        let mut palette = Palette::new();
+        palette.colors = vec![Color::new(255, 0, 0, 255), Color::new(0, 255, 0, 128)];
+        assert_eq!(write_hex(&palette), "FF0000\n00FF0080\n", "只有半透明颜色写出 alpha");
+        assert_eq!(parse_hex_line("#00FF0080"), Some(Color::new(0, 255, 0, 128)));
+        assert_eq!(parse_hex_line("00ff00"), Some(Color::new(0, 255, 0, 255)));
+        assert_eq!(parse_hex_line("00FF0"), None);
+    }
 }
\ No newline at end of file
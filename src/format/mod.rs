@@ -3,4 +3,6 @@ pub mod header;
 pub mod stream;
 pub mod block;
 pub mod payload;
-pub mod hex_palette;
\ No newline at end of file
+pub mod hex_palette;
+pub mod palette_io;
+pub mod curve_presets;
\ No newline at end of file
@@ -0,0 +1,398 @@
+use crate::core::palette::Palette;
+use crate::core::color::Color;
+use crate::format::error::{FormatError, Result};
+use crate::format::hex_palette;
+use std::path::Path;
+
+// 调色板文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    Hex,
+    // GIMP Palette (.gpl)，保留颜色名称
+    Gpl,
+    // Photoshop Color Table (.act)：256 个 RGB，可选颜色数与透明色下标
+    Act,
+    // Paint Shop Pro 文本格式 (.pal)
+    JascPal,
+    // Microsoft RIFF 二进制格式 (.pal)
+    RiffPal,
+    // Adobe Swatch Exchange (.ase)
+    Ase,
+    // 图片中出现的颜色 (按扫描顺序去重)，也用于 Lospec 式的 1 像素色条
+    Image,
+}
+
+impl PaletteFormat {
+    // 导出时按扩展名选择格式，.pal 写为更通用的 JASC 文本格式
+    pub fn from_extension(path: &Path) -> Option<PaletteFormat> {
+        let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match ext.as_str() {
+            "hex" | "txt" => Some(PaletteFormat::Hex),
+            "gpl" => Some(PaletteFormat::Gpl),
+            "act" => Some(PaletteFormat::Act),
+            "pal" => Some(PaletteFormat::JascPal),
+            "ase" => Some(PaletteFormat::Ase),
+            "png" | "gif" | "bmp" => Some(PaletteFormat::Image),
+            _ => None,
+        }
+    }
+
+    // 明确的扩展名优先 (.act 没有文件头，颜色数据可能恰好像图片文件头)，其余先看文件头、最后才按图片猜测；
+    // 都无法判断时按 HEX 文本读取
+    pub fn detect(path: &Path, data: &[u8]) -> PaletteFormat {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        match ext.as_str() {
+            // 无文件头的 .act 只能靠长度判断
+            "act" if data.len() == 768 || data.len() == 772 => return PaletteFormat::Act,
+            // .txt 常被其他格式借用，仍交给文件头判断
+            "gpl" => return PaletteFormat::Gpl,
+            "hex" => return PaletteFormat::Hex,
+            _ => {}
+        }
+        if data.starts_with(b"GIMP Palette") { return PaletteFormat::Gpl; }
+        if data.starts_with(b"JASC-PAL") { return PaletteFormat::JascPal; }
+        if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"PAL ") { return PaletteFormat::RiffPal; }
+        if data.starts_with(b"ASEF") { return PaletteFormat::Ase; }
+        if image::guess_format(data).is_ok() { return PaletteFormat::Image; }
+        match PaletteFormat::from_extension(path) {
+            Some(PaletteFormat::Act) | Some(PaletteFormat::JascPal) | None => PaletteFormat::Hex,
+            Some(format) => format,
+        }
+    }
+}
+
+pub fn load_palette(path: &Path) -> Result<Palette> {
+    let data = std::fs::read(path)?;
+    let mut palette = parse_palette(PaletteFormat::detect(path, &data), &data)?;
+    if palette.name.is_empty() {
+        palette.name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    }
+    Ok(palette)
+}
+
+pub fn save_palette(path: &Path, palette: &Palette) -> Result<()> {
+    let format = PaletteFormat::from_extension(path).unwrap_or(PaletteFormat::Hex);
+    std::fs::write(path, write_palette(format, palette)?)?;
+    Ok(())
+}
+
+// 解析得到的调色板名称为空时由调用方补上文件名
+pub fn parse_palette(format: PaletteFormat, data: &[u8]) -> Result<Palette> {
+    let mut palette = match format {
+        PaletteFormat::Hex => {
+            let text = String::from_utf8_lossy(data);
+            Palette::from_colors(String::new(), text.lines().filter_map(hex_palette::parse_hex_line).collect())
+        }
+        PaletteFormat::Gpl => parse_gpl(&String::from_utf8_lossy(data))?,
+        PaletteFormat::Act => parse_act(data)?,
+        PaletteFormat::JascPal => parse_jasc_pal(&String::from_utf8_lossy(data))?,
+        PaletteFormat::RiffPal => parse_riff_pal(data)?,
+        PaletteFormat::Ase => parse_ase(data)?,
+        PaletteFormat::Image => parse_image(data)?,
+    };
+    if palette.colors.len() > 4096 {
+        return Err(FormatError::InvalidData(rust_i18n::t!("error.too_many_colors").to_string()));
+    }
+    palette.names.truncate(palette.colors.len());
+    Ok(palette)
+}
+
+pub fn write_palette(format: PaletteFormat, palette: &Palette) -> Result<Vec<u8>> {
+    Ok(match format {
+        PaletteFormat::Hex => hex_palette::write_hex(palette).into_bytes(),
+        PaletteFormat::Gpl => write_gpl(palette).into_bytes(),
+        PaletteFormat::Act => write_act(palette),
+        PaletteFormat::JascPal => write_jasc_pal(palette).into_bytes(),
+        PaletteFormat::RiffPal => write_riff_pal(palette),
+        PaletteFormat::Ase => write_ase(palette),
+        PaletteFormat::Image => write_image(palette)?,
+    })
+}
+
+fn invalid(msg: &str) -> FormatError {
+    FormatError::InvalidData(msg.to_string())
+}
+
+// ---- GIMP Palette ----
+
+fn parse_gpl(text: &str) -> Result<Palette> {
+    let mut lines = text.lines();
+    if lines.next().map(|l| l.trim()) != Some("GIMP Palette") { return Err(invalid("GPL: missing header")); }
+    let mut palette = Palette::from_colors(String::new(), Vec::new());
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") { continue; }
+        if let Some(name) = line.strip_prefix("Name:") {
+            palette.name = name.trim().to_string();
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let mut channel = || parts.next().and_then(|v| v.parse::<u8>().ok());
+        let (r, g, b) = match (channel(), channel(), channel()) {
+            (Some(r), Some(g), Some(b)) => (r, g, b),
+            _ => continue,
+        };
+        palette.colors.push(Color::new(r, g, b, 255));
+        palette.names.push(parts.collect::<Vec<_>>().join(" "));
+    }
+    Ok(palette)
+}
+
+fn write_gpl(palette: &Palette) -> String {
+    let mut text = format!("GIMP Palette\nName: {}\nColumns: 8\n#\n", palette.name);
+    for (i, c) in palette.colors.iter().enumerate() {
+        let name = palette.color_name(i);
+        let name = if name.is_empty() { format!("#{:02X}{:02X}{:02X}", c.r, c.g, c.b) } else { name.to_string() };
+        text.push_str(&format!("{:3} {:3} {:3}\t{}\n", c.r, c.g, c.b, name));
+    }
+    text
+}
+
+// ---- Photoshop .act ----
+
+fn parse_act(data: &[u8]) -> Result<Palette> {
+    if data.len() < 768 { return Err(invalid("ACT: file shorter than 768 bytes")); }
+    let (count, transparent) = if data.len() >= 772 {
+        (u16::from_be_bytes([data[768], data[769]]).min(256) as usize, u16::from_be_bytes([data[770], data[771]]) as usize)
+    } else {
+        (256, usize::MAX)
+    };
+    let colors = (0..count).map(|i| {
+        let alpha = if i == transparent { 0 } else { 255 };
+        Color::new(data[i * 3], data[i * 3 + 1], data[i * 3 + 2], alpha)
+    }).collect();
+    Ok(Palette::from_colors(String::new(), colors))
+}
+
+// 只写前 256 色；第一个完全透明的颜色记为透明色
+fn write_act(palette: &Palette) -> Vec<u8> {
+    let mut buf = vec![0u8; 772];
+    let colors = &palette.colors[..palette.colors.len().min(256)];
+    for (i, c) in colors.iter().enumerate() {
+        buf[i * 3..i * 3 + 3].copy_from_slice(&[c.r, c.g, c.b]);
+    }
+    let transparent = colors.iter().position(|c| c.a == 0).map(|i| i as u16).unwrap_or(0xFFFF);
+    buf[768..770].copy_from_slice(&(colors.len() as u16).to_be_bytes());
+    buf[770..772].copy_from_slice(&transparent.to_be_bytes());
+    buf
+}
+
+// ---- JASC-PAL / RIFF PAL ----
+
+fn parse_jasc_pal(text: &str) -> Result<Palette> {
+    let mut lines = text.lines().map(|l| l.trim());
+    if lines.next() != Some("JASC-PAL") { return Err(invalid("PAL: missing JASC-PAL header")); }
+    let _version = lines.next();
+    let count = lines.next().and_then(|l| l.parse::<usize>().ok()).ok_or_else(|| invalid("PAL: missing color count"))?;
+    let mut colors = Vec::with_capacity(count.min(4096));
+    for line in lines.take(count) {
+        let channels: Vec<u8> = line.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        if channels.len() >= 3 {
+            colors.push(Color::new(channels[0], channels[1], channels[2], 255));
+        }
+    }
+    Ok(Palette::from_colors(String::new(), colors))
+}
+
+fn write_jasc_pal(palette: &Palette) -> String {
+    let mut text = format!("JASC-PAL\r\n0100\r\n{}\r\n", palette.colors.len());
+    for c in &palette.colors {
+        text.push_str(&format!("{} {} {}\r\n", c.r, c.g, c.b));
+    }
+    text
+}
+
+// RIFF "PAL " 文件：data 块内为 LOGPALETTE (版本 0x0300、颜色数、每色 r g b flags)
+fn parse_riff_pal(data: &[u8]) -> Result<Palette> {
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().map_err(|_| FormatError::InvalidSliceLength)?) as usize;
+        let body = data.get(pos + 8..pos + 8 + size).ok_or_else(|| FormatError::UnexpectedEof("RIFF PAL".to_string()))?;
+        if &data[pos..pos + 4] == b"data" {
+            if body.len() < 4 { return Err(FormatError::UnexpectedEof("RIFF PAL".to_string())); }
+            let count = u16::from_le_bytes([body[2], body[3]]) as usize;
+            let entries = body.get(4..4 + count * 4).ok_or_else(|| FormatError::UnexpectedEof("RIFF PAL".to_string()))?;
+            let colors = entries.chunks_exact(4).map(|e| Color::new(e[0], e[1], e[2], 255)).collect();
+            return Ok(Palette::from_colors(String::new(), colors));
+        }
+        // 块按偶数字节对齐
+        pos += 8 + size + size % 2;
+    }
+    Err(invalid("RIFF PAL: missing data chunk"))
+}
+
+fn write_riff_pal(palette: &Palette) -> Vec<u8> {
+    let count = palette.colors.len().min(u16::MAX as usize);
+    let data_size = 4 + count * 4;
+    let mut buf = Vec::with_capacity(20 + data_size);
+    buf.extend_from_slice(b"RIFF");
+    buf.extend_from_slice(&((12 + data_size) as u32).to_le_bytes());
+    buf.extend_from_slice(b"PAL data");
+    buf.extend_from_slice(&(data_size as u32).to_le_bytes());
+    buf.extend_from_slice(&0x0300u16.to_le_bytes());
+    buf.extend_from_slice(&(count as u16).to_le_bytes());
+    for c in &palette.colors[..count] {
+        buf.extend_from_slice(&[c.r, c.g, c.b, 0]);
+    }
+    buf
+}
+
+// ---- Adobe Swatch Exchange ----
+
+const ASE_GROUP_START: u16 = 0xC001;
+const ASE_COLOR: u16 = 0x0001;
+
+// 读取所有颜色条目 (分组展开)；支持 RGB、CMYK 与灰度，LAB 条目被忽略
+fn parse_ase(data: &[u8]) -> Result<Palette> {
+    let eof = || FormatError::UnexpectedEof("ASE".to_string());
+    let u16_at = |p: usize| data.get(p..p + 2).map(|b| u16::from_be_bytes([b[0], b[1]])).ok_or_else(eof);
+    let u32_at = |p: usize| data.get(p..p + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).ok_or_else(eof);
+    let f32_at = |p: usize| u32_at(p).map(f32::from_bits);
+    let name_at = |p: usize| -> Result<(String, usize)> {
+        let len = u16_at(p)? as usize;
+        let units: Vec<u16> = (0..len).map(|i| u16_at(p + 2 + i * 2)).collect::<Result<_>>()?;
+        Ok((String::from_utf16_lossy(&units).trim_end_matches('\0').to_string(), p + 2 + len * 2))
+    };
+
+    if !data.starts_with(b"ASEF") { return Err(invalid("ASE: missing ASEF header")); }
+    let block_count = u32_at(8)?;
+    let mut palette = Palette::from_colors(String::new(), Vec::new());
+    let mut pos = 12;
+    for _ in 0..block_count {
+        let (kind, len) = (u16_at(pos)?, u32_at(pos + 2)? as usize);
+        let body = pos + 6;
+        if kind == ASE_GROUP_START && palette.name.is_empty() {
+            palette.name = name_at(body)?.0;
+        } else if kind == ASE_COLOR {
+            let (name, p) = name_at(body)?;
+            let model = data.get(p..p + 4).ok_or_else(eof)?;
+            let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            let color = match model {
+                b"RGB " => Some(Color::new(to_u8(f32_at(p + 4)?), to_u8(f32_at(p + 8)?), to_u8(f32_at(p + 12)?), 255)),
+                b"Gray" => { let v = to_u8(f32_at(p + 4)?); Some(Color::new(v, v, v, 255)) }
+                b"CMYK" => {
+                    let k = 1.0 - f32_at(p + 16)?;
+                    Some(Color::new(to_u8((1.0 - f32_at(p + 4)?) * k), to_u8((1.0 - f32_at(p + 8)?) * k), to_u8((1.0 - f32_at(p + 12)?) * k), 255))
+                }
+                _ => None,
+            };
+            if let Some(color) = color {
+                palette.colors.push(color);
+                palette.names.push(name);
+            }
+        }
+        pos = body + len;
+    }
+    Ok(palette)
+}
+
+fn write_ase(palette: &Palette) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(b"ASEF");
+    buf.extend_from_slice(&1u16.to_be_bytes());
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf.extend_from_slice(&(palette.colors.len() as u32).to_be_bytes());
+    for (i, c) in palette.colors.iter().enumerate() {
+        let name = palette.color_name(i);
+        let name = if name.is_empty() { format!("#{:02X}{:02X}{:02X}", c.r, c.g, c.b) } else { name.to_string() };
+        // 名称为带结尾 0 的 UTF-16BE
+        let units: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut body = Vec::new();
+        body.extend_from_slice(&(units.len() as u16).to_be_bytes());
+        for u in units { body.extend_from_slice(&u.to_be_bytes()); }
+        body.extend_from_slice(b"RGB ");
+        for v in [c.r, c.g, c.b] { body.extend_from_slice(&(v as f32 / 255.0).to_be_bytes()); }
+        // 颜色类型：2 为普通色
+        body.extend_from_slice(&2u16.to_be_bytes());
+
+        buf.extend_from_slice(&ASE_COLOR.to_be_bytes());
+        buf.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&body);
+    }
+    buf
+}
+
+// ---- 图片 ----
+
+// 按行扫描，完全透明的像素跳过；1 像素高的色条即按从左到右的顺序
+fn parse_image(data: &[u8]) -> Result<Palette> {
+    let img = image::load_from_memory(data).map_err(|e| FormatError::InvalidData(e.to_string()))?.to_rgba8();
+    let mut palette = Palette::from_colors(String::new(), Vec::new());
+    for p in img.pixels() {
+        if p[3] == 0 { continue; }
+        palette.add_color(Color::new(p[0], p[1], p[2], p[3]));
+        if palette.colors.len() > 4096 { break; }
+    }
+    Ok(palette)
+}
+
+// 导出为 Lospec 式的 N x 1 色条
+fn write_image(palette: &Palette) -> Result<Vec<u8>> {
+    if palette.colors.is_empty() { return Err(invalid("palette is empty")); }
+    let pixels: Vec<u8> = palette.colors.iter().flat_map(|c| [c.r, c.g, c.b, c.a]).collect();
+    let mut out = std::io::Cursor::new(Vec::new());
+    image::write_buffer_with_format(&mut out, &pixels, palette.colors.len() as u32, 1, image::ColorType::Rgba8, image::ImageOutputFormat::Png)
+        .map_err(|e| FormatError::InvalidData(e.to_string()))?;
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_palette() -> Palette {
+        let mut palette = Palette::from_colors("Sample".into(), vec![Color::new(255, 0, 0, 255), Color::new(0, 128, 64, 255), Color::new(10, 20, 30, 255)]);
+        palette.names = vec!["Red".into(), "Deep Green".into(), String::new()];
+        palette
+    }
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let palette = sample_palette();
+        for format in [PaletteFormat::Hex, PaletteFormat::Gpl, PaletteFormat::Act, PaletteFormat::JascPal, PaletteFormat::RiffPal, PaletteFormat::Ase, PaletteFormat::Image] {
+            let data = write_palette(format, &palette).unwrap();
+            assert_eq!(PaletteFormat::detect(Path::new("palette.bin"), &data), if format == PaletteFormat::Act { PaletteFormat::Hex } else { format }, "{:?} 按文件头识别", format);
+            let loaded = parse_palette(format, &data).unwrap();
+            assert_eq!(loaded.colors, palette.colors, "{:?} 颜色一致", format);
+        }
+    }
+
+    #[test]
+    fn test_extension_wins_over_image_sniffing() {
+        // 首个颜色为 (0x42, 0x4D, ..) 的 .act 以 "BM" 开头，看起来像 BMP
+        let mut palette = sample_palette();
+        palette.colors[0] = Color::new(0x42, 0x4D, 0x36, 255);
+        let act = write_palette(PaletteFormat::Act, &palette).unwrap();
+        assert!(image::guess_format(&act).is_ok());
+        assert_eq!(PaletteFormat::detect(Path::new("palette.act"), &act), PaletteFormat::Act);
+        assert_eq!(PaletteFormat::detect(Path::new("palette.hex"), b"424d36\n"), PaletteFormat::Hex);
+
+        let png = write_palette(PaletteFormat::Image, &palette).unwrap();
+        assert_eq!(PaletteFormat::detect(Path::new("palette.pal"), &png), PaletteFormat::Image, "其余扩展名仍按内容识别");
+    }
+
+    #[test]
+    fn test_gpl_and_ase_keep_names() {
+        let palette = sample_palette();
+        let gpl = parse_palette(PaletteFormat::Gpl, &write_palette(PaletteFormat::Gpl, &palette).unwrap()).unwrap();
+        assert_eq!(gpl.name, "Sample");
+        assert_eq!(gpl.names[..2], ["Red", "Deep Green"]);
+        assert_eq!(gpl.names[2], "#0A141E", "未命名的颜色以十六进制值命名");
+
+        let ase = parse_palette(PaletteFormat::Ase, &write_palette(PaletteFormat::Ase, &palette).unwrap()).unwrap();
+        assert_eq!(ase.names[1], "Deep Green");
+    }
+
+    #[test]
+    fn test_act_transparent_index() {
+        let mut palette = sample_palette();
+        palette.colors[1].a = 0;
+        let data = write_act(&palette);
+        assert_eq!(data.len(), 772);
+        let loaded = parse_act(&data).unwrap();
+        assert_eq!(loaded.colors.len(), 3, "按记录的颜色数读取");
+        assert_eq!(loaded.colors[1].a, 0, "透明色下标还原为透明");
+        // 旧版 768 字节文件：固定 256 色
+        assert_eq!(parse_act(&data[..768]).unwrap().colors.len(), 256);
+    }
+}
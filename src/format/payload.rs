@@ -5,6 +5,7 @@ use crate::core::store::PixelStore;
 use crate::core::color::Color;
 use crate::core::symmetry::{SymmetryConfig, SymmetryMode};
 use crate::core::layer::{Layer, Chunk};
+use crate::core::layer_effect::{Connectivity, LayerEffect, LayerEffectKind};
 use crate::core::blend_mode::BlendMode;
 use crate::core::layer::CHUNK_SIZE;
 use crate::core::palette::Palette;
@@ -116,10 +117,85 @@ pub fn serialize_layer(layer: &Layer) -> Vec<u8> {
         buf.extend_from_slice(&cy.to_le_bytes());
         buf.extend_from_slice(&*chunk.data);
     }
+
+    // 图层效果作为带版本号的扩展数据追加在末尾，旧版读取时会忽略
+    if !layer.effects.is_empty() {
+        buf.extend_from_slice(&LAYER_EFFECTS_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(layer.effects.len() as u32).to_le_bytes());
+        for effect in &layer.effects {
+            write_str(&mut buf, &effect.id);
+            buf.push(if effect.enabled { 1 } else { 0 });
+            write_layer_effect(&mut buf, &effect.kind);
+        }
+    }
     
     buf
 }
 
+const LAYER_EFFECTS_VERSION: u16 = 1;
+
+fn write_color(buf: &mut Vec<u8>, c: Color) {
+    buf.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+}
+
+fn read_color(c: &mut Cursor<&[u8]>) -> Result<Color> {
+    let mut rgba = [0u8; 4];
+    c.read_exact(&mut rgba)?;
+    Ok(Color::new(rgba[0], rgba[1], rgba[2], rgba[3]))
+}
+
+fn write_layer_effect(buf: &mut Vec<u8>, kind: &LayerEffectKind) {
+    match kind {
+        LayerEffectKind::Outline { color, connectivity } => {
+            buf.push(0);
+            write_color(buf, *color);
+            buf.push(if *connectivity == Connectivity::Eight { 8 } else { 4 });
+        }
+        LayerEffectKind::DropShadow { color, dx, dy } => {
+            buf.push(1);
+            write_color(buf, *color);
+            buf.extend_from_slice(&dx.to_le_bytes());
+            buf.extend_from_slice(&dy.to_le_bytes());
+        }
+        LayerEffectKind::ColorOverlay { color, strength } => {
+            buf.push(2);
+            write_color(buf, *color);
+            buf.extend_from_slice(&strength.to_le_bytes());
+        }
+        LayerEffectKind::PaletteSwap { pairs } => {
+            buf.push(3);
+            buf.extend_from_slice(&(pairs.len() as u32).to_le_bytes());
+            for (from, to) in pairs {
+                write_color(buf, *from);
+                write_color(buf, *to);
+            }
+        }
+        LayerEffectKind::HueShift { degrees } => {
+            buf.push(4);
+            buf.extend_from_slice(&degrees.to_le_bytes());
+        }
+    }
+}
+
+fn read_layer_effect(c: &mut Cursor<&[u8]>) -> Result<LayerEffectKind> {
+    Ok(match read_u8(c)? {
+        0 => LayerEffectKind::Outline {
+            color: read_color(c)?,
+            connectivity: if read_u8(c)? == 8 { Connectivity::Eight } else { Connectivity::Four },
+        },
+        1 => LayerEffectKind::DropShadow { color: read_color(c)?, dx: read_u32(c)? as i32, dy: read_u32(c)? as i32 },
+        2 => LayerEffectKind::ColorOverlay { color: read_color(c)?, strength: read_f32(c)? },
+        3 => {
+            let n = read_count(c, 4096)?;
+            let mut pairs = Vec::with_capacity(n as usize);
+            for _ in 0..n { pairs.push((read_color(c)?, read_color(c)?)); }
+            LayerEffectKind::PaletteSwap { pairs }
+        }
+        4 => LayerEffectKind::HueShift { degrees: read_f32(c)? },
+        tag => return Err(FormatError::InvalidData(format!("Unknown layer effect: {}", tag))),
+    })
+}
+
 pub fn deserialize_layer(data: &[u8], minor_version: u16) -> Result<Layer> {
     let mut cursor = Cursor::new(data);
     
@@ -188,6 +264,21 @@ pub fn deserialize_layer(data: &[u8], minor_version: u16) -> Result<Layer> {
         cursor.read_exact(chunk_data.as_mut_slice())?;
         layer.chunks.insert((cx, cy), Chunk { data: Arc::from(chunk_data) });
     }
+
+    if cursor.position() < data.len() as u64 {
+        let mut ver = [0u8; 2];
+        cursor.read_exact(&mut ver)?;
+        let version = u16::from_le_bytes(ver);
+        if version > LAYER_EFFECTS_VERSION {
+            return Err(FormatError::InvalidData(format!("Unsupported layer effects version: {}", version)));
+        }
+        for _ in 0..read_count(&mut cursor, 256)? {
+            let id = read_str(&mut cursor, "layer effect id")?;
+            let enabled = read_u8(&mut cursor)? != 0;
+            let kind = read_layer_effect(&mut cursor)?;
+            layer.effects.push(LayerEffect { id, enabled, kind });
+        }
+    }
     
     Ok(layer)
 }
@@ -200,9 +291,30 @@ pub fn serialize_palette(palette: &Palette) -> Vec<u8> {
         buf.push(color.b);
         buf.push(color.a);
     }
+    // 颜色名称与色带作为带版本号的扩展数据追加在末尾，旧版读取时会忽略
+    if palette.names.iter().any(|n| !n.is_empty()) || !palette.ramps.is_empty() {
+        buf.extend_from_slice(&PALETTE_META_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(palette.names.len() as u32).to_le_bytes());
+        for name in &palette.names {
+            write_str(&mut buf, name);
+        }
+        buf.extend_from_slice(&(palette.ramps.len() as u32).to_le_bytes());
+        for ramp in &palette.ramps {
+            buf.extend_from_slice(&ramp.id.to_le_bytes());
+            write_str(&mut buf, &ramp.name);
+        }
+        buf.extend_from_slice(&(palette.ramp_ids.len() as u32).to_le_bytes());
+        for id in &palette.ramp_ids {
+            buf.extend_from_slice(&id.unwrap_or(u32::MAX).to_le_bytes());
+        }
+    }
     buf
 }
 
+// 1: 颜色名称
+// 2: 色带
+const PALETTE_META_VERSION: u16 = 2;
+
 pub fn deserialize_palette(data: &[u8]) -> Result<Palette> {
     if data.len() < 4 { return Err(FormatError::UnexpectedEof(t!("error.payload_too_short", block = "PALT").to_string())); }
     let count = u32::from_le_bytes(data[0..4].try_into().map_err(|_| FormatError::InvalidSliceLength)?) as usize;
@@ -214,13 +326,322 @@ pub fn deserialize_palette(data: &[u8]) -> Result<Palette> {
         let offset = 4 + i * 4;
         colors.push(Color::new(data[offset], data[offset+1], data[offset+2], data[offset+3]));
     }
-    Ok(Palette { name: t!("palette.project_palette").to_string(), colors })
+
+    let mut palette = Palette::from_colors(t!("palette.project_palette").to_string(), colors);
+    if data.len() > 4 + count * 4 {
+        let mut c = Cursor::new(&data[4 + count * 4..]);
+        let mut ver = [0u8; 2];
+        c.read_exact(&mut ver)?;
+        let version = u16::from_le_bytes(ver);
+        if version == 0 || version > PALETTE_META_VERSION {
+            return Err(FormatError::InvalidData(t!("error.unsupported_palette_version", version = version).to_string()));
+        }
+        for _ in 0..read_count(&mut c, count as u32)? {
+            palette.names.push(read_str(&mut c, "palette color name")?);
+        }
+        if version >= 2 {
+            for _ in 0..read_count(&mut c, 4096)? {
+                let id = read_u32(&mut c)?;
+                palette.ramps.push(crate::core::palette::PaletteRamp { id, name: read_str(&mut c, "palette ramp name")? });
+            }
+            for _ in 0..read_count(&mut c, count as u32)? {
+                let id = read_u32(&mut c)?;
+                palette.ramp_ids.push(if id == u32::MAX { None } else { Some(id) });
+            }
+        }
+    }
+    Ok(palette)
+}
+
+pub fn serialize_frame_rate(project: &crate::animation::project::AnimProject) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(5);
+    buf.extend_from_slice(&project.fps.to_le_bytes());
+    buf.push(if project.snap_to_frames { 1 } else { 0 });
+    buf
+}
+
+pub fn deserialize_frame_rate(data: &[u8], project: &mut crate::animation::project::AnimProject) -> Result<()> {
+    if data.len() < 5 {
+        return Err(FormatError::UnexpectedEof(t!("error.payload_too_short", block = "FRMR").to_string()));
+    }
+    // 超出范围的帧率读取时钳制，不让整个工程因此无法打开
+    let fps = u32::from_le_bytes(data[0..4].try_into().map_err(|_| FormatError::InvalidSliceLength)?);
+    project.fps = fps.clamp(1, crate::animation::project::MAX_FPS);
+    project.snap_to_frames = data[4] != 0;
+    Ok(())
+}
+
+const ANIMATION_VERSION: u16 = 3;
+
+fn property_tag(prop: &crate::core::animation::timeline::TimelineProperty) -> u8 {
+    use crate::core::animation::timeline::TimelineProperty;
+    match prop {
+        TimelineProperty::Rotation => 0,
+        TimelineProperty::Translation => 1,
+        TimelineProperty::Scale => 2,
+        TimelineProperty::Color => 3,
+        TimelineProperty::Ik => 4,
+        TimelineProperty::Attachment => 5,
+        TimelineProperty::DrawOrder => 6,
+        TimelineProperty::Shear => 7,
+        TimelineProperty::Deform => 8,
+        TimelineProperty::EffectAmount => 9,
+    }
+}
+
+fn property_from_tag(tag: u8) -> Result<crate::core::animation::timeline::TimelineProperty> {
+    use crate::core::animation::timeline::TimelineProperty;
+    Ok(match tag {
+        0 => TimelineProperty::Rotation,
+        1 => TimelineProperty::Translation,
+        2 => TimelineProperty::Scale,
+        3 => TimelineProperty::Color,
+        4 => TimelineProperty::Ik,
+        5 => TimelineProperty::Attachment,
+        6 => TimelineProperty::DrawOrder,
+        7 => TimelineProperty::Shear,
+        8 => TimelineProperty::Deform,
+        9 => TimelineProperty::EffectAmount,
+        _ => return Err(FormatError::InvalidData(format!("Unknown timeline property: {}", tag))),
+    })
+}
+
+fn write_keyframe_value(buf: &mut Vec<u8>, value: &crate::core::animation::timeline::KeyframeValue) {
+    use crate::core::animation::timeline::KeyframeValue;
+    match value {
+        KeyframeValue::Rotate(r) | KeyframeValue::EffectAmount(r) => buf.extend_from_slice(&r.to_le_bytes()),
+        KeyframeValue::Translate(x, y) | KeyframeValue::Scale(x, y) | KeyframeValue::Shear(x, y) => {
+            buf.extend_from_slice(&x.to_le_bytes());
+            buf.extend_from_slice(&y.to_le_bytes());
+        }
+        KeyframeValue::Color(r, g, b, a) => buf.extend_from_slice(&[*r, *g, *b, *a]),
+        KeyframeValue::Ik(mix, bend) => {
+            buf.extend_from_slice(&mix.to_le_bytes());
+            buf.push(if *bend { 1 } else { 0 });
+        }
+        KeyframeValue::Attachment(a) => match a {
+            Some(a) => { buf.push(1); write_str(buf, a); }
+            None => buf.push(0),
+        },
+        KeyframeValue::DrawOrder(order) => {
+            buf.extend_from_slice(&(order.len() as u32).to_le_bytes());
+            for id in order { write_str(buf, id); }
+        }
+        KeyframeValue::Deform(offsets) => {
+            buf.extend_from_slice(&(offsets.len() as u32).to_le_bytes());
+            for (x, y) in offsets {
+                buf.extend_from_slice(&x.to_le_bytes());
+                buf.extend_from_slice(&y.to_le_bytes());
+            }
+        }
+    }
+}
+
+// 关键帧值的类型由所属轨道的属性决定
+fn read_keyframe_value(c: &mut Cursor<&[u8]>, prop: &crate::core::animation::timeline::TimelineProperty) -> Result<crate::core::animation::timeline::KeyframeValue> {
+    use crate::core::animation::timeline::{KeyframeValue, TimelineProperty};
+    Ok(match prop {
+        TimelineProperty::Rotation => KeyframeValue::Rotate(read_f32(c)?),
+        TimelineProperty::EffectAmount => KeyframeValue::EffectAmount(read_f32(c)?),
+        TimelineProperty::Translation => KeyframeValue::Translate(read_f32(c)?, read_f32(c)?),
+        TimelineProperty::Scale => KeyframeValue::Scale(read_f32(c)?, read_f32(c)?),
+        TimelineProperty::Shear => KeyframeValue::Shear(read_f32(c)?, read_f32(c)?),
+        TimelineProperty::Color => {
+            let mut rgba = [0u8; 4];
+            c.read_exact(&mut rgba)?;
+            KeyframeValue::Color(rgba[0], rgba[1], rgba[2], rgba[3])
+        }
+        TimelineProperty::Ik => KeyframeValue::Ik(read_f32(c)?, read_u8(c)? != 0),
+        TimelineProperty::Attachment => {
+            if read_u8(c)? != 0 { KeyframeValue::Attachment(Some(read_str(c, "Attachment")?)) } else { KeyframeValue::Attachment(None) }
+        }
+        TimelineProperty::DrawOrder => {
+            let n = read_count(c, 4096)?;
+            let mut order = Vec::with_capacity(n as usize);
+            for _ in 0..n { order.push(read_str(c, "Draw Order")?); }
+            KeyframeValue::DrawOrder(order)
+        }
+        TimelineProperty::Deform => {
+            let n = read_count(c, 1 << 20)?;
+            let mut offsets = Vec::with_capacity(n as usize);
+            for _ in 0..n { offsets.push((read_f32(c)?, read_f32(c)?)); }
+            KeyframeValue::Deform(offsets)
+        }
+    })
+}
+
+fn write_curve(buf: &mut Vec<u8>, curve: &crate::core::animation::timeline::CurveType) {
+    use crate::core::animation::timeline::CurveType;
+    use crate::core::animation::easing::{EasingFunction, EasingDirection};
+    match curve {
+        CurveType::Linear => buf.push(0),
+        CurveType::Stepped => buf.push(1),
+        CurveType::Bezier(cx1, cy1, cx2, cy2) => {
+            buf.push(2);
+            for v in [cx1, cy1, cx2, cy2] { buf.extend_from_slice(&v.to_le_bytes()); }
+        }
+        CurveType::Easing(function, direction) => {
+            buf.push(3);
+            buf.push(EasingFunction::ALL.iter().position(|f| f == function).unwrap_or(0) as u8);
+            buf.push(EasingDirection::ALL.iter().position(|d| d == direction).unwrap_or(0) as u8);
+        }
+        CurveType::Steps(n) => {
+            buf.push(4);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+        CurveType::StepsEnd(n) => {
+            buf.push(5);
+            buf.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+}
+
+fn read_curve(c: &mut Cursor<&[u8]>) -> Result<crate::core::animation::timeline::CurveType> {
+    use crate::core::animation::timeline::CurveType;
+    use crate::core::animation::easing::{EasingFunction, EasingDirection};
+    Ok(match read_u8(c)? {
+        0 => CurveType::Linear,
+        1 => CurveType::Stepped,
+        2 => CurveType::Bezier(read_f32(c)?, read_f32(c)?, read_f32(c)?, read_f32(c)?),
+        3 => {
+            let function = *EasingFunction::ALL.get(read_u8(c)? as usize).ok_or_else(|| FormatError::InvalidData("Unknown easing function".into()))?;
+            let direction = *EasingDirection::ALL.get(read_u8(c)? as usize).ok_or_else(|| FormatError::InvalidData("Unknown easing direction".into()))?;
+            CurveType::Easing(function, direction)
+        }
+        4 => CurveType::Steps(read_u32(c)?.max(1)),
+        5 => CurveType::StepsEnd(read_u32(c)?.max(1)),
+        tag => return Err(FormatError::InvalidData(format!("Unknown curve type: {}", tag))),
+    })
+}
+
+fn tangent_tag(mode: crate::core::animation::timeline::TangentMode) -> u8 {
+    use crate::core::animation::timeline::TangentMode;
+    match mode {
+        TangentMode::Broken => 0,
+        TangentMode::Aligned => 1,
+        TangentMode::Auto => 2,
+    }
+}
+
+fn tangent_from_tag(tag: u8) -> Result<crate::core::animation::timeline::TangentMode> {
+    use crate::core::animation::timeline::TangentMode;
+    Ok(match tag {
+        0 => TangentMode::Broken,
+        1 => TangentMode::Aligned,
+        2 => TangentMode::Auto,
+        _ => return Err(FormatError::InvalidData(format!("Unknown tangent mode: {}", tag))),
+    })
+}
+
+// 每个 ANIM 块保存一个动画：id、是否为当前动画、轨道与事件
+pub fn serialize_animation(id: &str, anim: &crate::core::animation::timeline::Animation, is_active: bool) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&ANIMATION_VERSION.to_le_bytes());
+    write_str(&mut buf, id);
+    write_str(&mut buf, &anim.name);
+    buf.push(if is_active { 1 } else { 0 });
+    buf.extend_from_slice(&anim.duration.to_le_bytes());
+
+    buf.extend_from_slice(&(anim.timelines.len() as u32).to_le_bytes());
+    for tl in &anim.timelines {
+        write_str(&mut buf, &tl.target_id);
+        buf.push(property_tag(&tl.property));
+        buf.extend_from_slice(&(tl.keyframes.len() as u32).to_le_bytes());
+        for kf in &tl.keyframes {
+            buf.extend_from_slice(&kf.time.to_le_bytes());
+            write_curve(&mut buf, &kf.curve);
+            buf.push(tangent_tag(kf.tangent));
+            write_keyframe_value(&mut buf, &kf.value);
+        }
+    }
+
+    buf.extend_from_slice(&(anim.events.len() as u32).to_le_bytes());
+    for event in &anim.events {
+        write_str(&mut buf, &event.name);
+        buf.extend_from_slice(&event.time.to_le_bytes());
+        buf.extend_from_slice(&event.int_value.to_le_bytes());
+        buf.extend_from_slice(&event.float_value.to_le_bytes());
+        write_str(&mut buf, &event.string_value);
+    }
+    buf
+}
+
+pub fn deserialize_animation(data: &[u8]) -> Result<(String, crate::core::animation::timeline::Animation, bool)> {
+    use crate::core::animation::timeline::{Animation, Timeline, Keyframe, TangentMode};
+    use crate::core::animation::event::AnimationEvent;
+
+    if data.len() < 2 {
+        return Err(FormatError::UnexpectedEof(t!("error.payload_too_short", block = "ANIM").to_string()));
+    }
+    let mut cursor = Cursor::new(data);
+    let mut ver = [0u8; 2];
+    cursor.read_exact(&mut ver)?;
+    let version = u16::from_le_bytes(ver);
+    if version > ANIMATION_VERSION {
+        return Err(FormatError::InvalidData(format!("Unsupported animation version: {}", version)));
+    }
+
+    let id = read_str(&mut cursor, "Animation ID")?;
+    let name = read_str(&mut cursor, "Animation Name")?;
+    let is_active = read_u8(&mut cursor)? != 0;
+    let mut anim = Animation::new(name, read_f32(&mut cursor)?);
+
+    let tl_count = read_count(&mut cursor, 65536)?;
+    for _ in 0..tl_count {
+        let target_id = read_str(&mut cursor, "Timeline Target")?;
+        let property = property_from_tag(read_u8(&mut cursor)?)?;
+        let mut tl = Timeline::new(target_id, property);
+        let kf_count = read_count(&mut cursor, 1 << 20)?;
+        for _ in 0..kf_count {
+            let time = read_f32(&mut cursor)?;
+            let curve = read_curve(&mut cursor)?;
+            // v1 没有切线模式
+            let tangent = if version >= 2 { tangent_from_tag(read_u8(&mut cursor)?)? } else { TangentMode::Broken };
+            let value = read_keyframe_value(&mut cursor, &tl.property)?;
+            tl.keyframes.push(Keyframe { time, value, curve, tangent });
+        }
+        anim.timelines.push(tl);
+    }
+
+    let event_count = read_count(&mut cursor, 1 << 16)?;
+    for _ in 0..event_count {
+        let mut event = AnimationEvent::new(read_str(&mut cursor, "Event Name")?, read_f32(&mut cursor)?);
+        let mut int_buf = [0u8; 4];
+        cursor.read_exact(&mut int_buf)?;
+        event.int_value = i32::from_le_bytes(int_buf);
+        event.float_value = read_f32(&mut cursor)?;
+        event.string_value = read_str(&mut cursor, "Event String")?;
+        anim.events.push(event);
+    }
+    anim.sort_events();
+    Ok((id, anim, is_active))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_palette_meta_versions() {
+        // 版本 1 的扩展数据只有颜色名称
+        let tail = |version: u16| {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(&2u32.to_le_bytes());
+            buf.extend_from_slice(&[0, 0, 0, 255, 255, 255, 255, 255]);
+            buf.extend_from_slice(&version.to_le_bytes());
+            buf.extend_from_slice(&2u32.to_le_bytes());
+            write_str(&mut buf, "Ink");
+            write_str(&mut buf, "Paper");
+            buf
+        };
+        let palette = deserialize_palette(&tail(1)).unwrap();
+        assert_eq!(palette.names, vec!["Ink".to_string(), "Paper".to_string()]);
+        assert!(palette.ramps.is_empty());
+
+        assert!(deserialize_palette(&tail(0)).is_err());
+        assert!(deserialize_palette(&tail(PALETTE_META_VERSION + 1)).is_err(), "拒绝更新版本的扩展数据");
+    }
+
     #[test]
     fn test_canvas_payload() {
         let mut original = PixelStore::new(1920, 1080);
@@ -292,6 +713,155 @@ mod tests {
         assert_eq!(restored.offset_x, -50);
         
         assert_eq!(restored.get_pixel(10, 10).unwrap().r, 255);
+        assert!(restored.effects.is_empty());
+    }
+
+    #[test]
+    fn test_layer_effects_payload() {
+        let mut original = Layer::new("fx".into(), "fx".into(), 4, 4);
+        let red = Color::new(255, 0, 0, 255);
+        original.effects = vec![
+            LayerEffect::new("e1".into(), LayerEffectKind::Outline { color: red, connectivity: Connectivity::Eight }),
+            LayerEffect::new("e2".into(), LayerEffectKind::DropShadow { color: red, dx: -2, dy: 3 }),
+            LayerEffect::new("e3".into(), LayerEffectKind::ColorOverlay { color: red, strength: 0.25 }),
+            LayerEffect::new("e4".into(), LayerEffectKind::PaletteSwap { pairs: vec![(red, Color::new(0, 0, 255, 255))] }),
+            LayerEffect::new("e5".into(), LayerEffectKind::HueShift { degrees: -30.0 }),
+        ];
+        original.effects[1].enabled = false;
+
+        let restored = deserialize_layer(&serialize_layer(&original), 2).unwrap();
+        assert_eq!(restored.effects, original.effects);
+    }
+
+    #[test]
+    fn test_onion_skin_roundtrip() {
+        let settings = crate::animation::onion_skin::OnionSkinSettings {
+            enabled: true,
+            frames_before: 3,
+            frames_after: 1,
+            step: 2,
+            tint_before: [10, 20, 30],
+            tint_after: [40, 50, 60],
+            opacity: 200,
+        };
+        let data = serialize_onion_skin(&settings);
+        assert_eq!(data.len(), 20);
+        assert_eq!(deserialize_onion_skin(&data).unwrap(), settings);
+        assert!(deserialize_onion_skin(&data[..10]).is_err());
+
+        let mut corrupt = data.clone();
+        corrupt[1..5].copy_from_slice(&u32::MAX.to_le_bytes());
+        corrupt[9..13].copy_from_slice(&0u32.to_le_bytes());
+        let clamped = deserialize_onion_skin(&corrupt).unwrap();
+        assert_eq!((clamped.frames_before, clamped.step), (10, 1), "读取时限制在界面允许的范围");
+    }
+
+    #[test]
+    fn test_frame_rate_roundtrip() {
+        let mut project = crate::animation::project::AnimProject::new();
+        project.fps = 12;
+        project.snap_to_frames = false;
+        let data = serialize_frame_rate(&project);
+
+        let mut restored = crate::animation::project::AnimProject::new();
+        deserialize_frame_rate(&data, &mut restored).unwrap();
+        assert_eq!(restored.fps, 12);
+        assert!(!restored.snap_to_frames);
+        deserialize_frame_rate(&[0, 0, 0, 0, 1], &mut restored).unwrap();
+        assert_eq!(restored.fps, 1, "帧率为 0 时钳制到 1");
+        deserialize_frame_rate(&[255, 255, 255, 255, 1], &mut restored).unwrap();
+        assert_eq!(restored.fps, crate::animation::project::MAX_FPS);
+    }
+
+    #[test]
+    fn test_skeleton_payload() {
+        use crate::core::animation::bone::BoneData;
+        use crate::core::animation::slot::{SlotData, RuntimeSlot};
+        use crate::core::animation::ik::{IkConstraintData, IkConstraint};
+
+        let mut skel = crate::core::animation::skeleton::Skeleton::new();
+        skel.add_bone(BoneData::new("root".into(), "root".into()));
+        let mut arm = BoneData::new("arm".into(), "手臂".into());
+        arm.parent_id = Some("root".into());
+        arm.length = 12.5;
+        arm.local_transform.rotation = 30.0;
+        arm.inherit_scale = false;
+        arm.inherit_reflection = false;
+        skel.add_bone(arm);
+        let mut slot = SlotData::new("layer_1".into(), "Layer".into(), "arm".into());
+        slot.attachment = Some("layer_1".into());
+        slot.color = Color::new(10, 20, 30, 40);
+        skel.slots.push(RuntimeSlot::new(slot));
+        let mut ik = IkConstraintData::new("ik_1".into(), "IK 1".into(), vec!["arm".into()], "root".into());
+        ik.bend_positive = false;
+        ik.mix = 0.5;
+        skel.ik_constraints.push(IkConstraint::new(ik));
+        skel.meshes.push(crate::core::animation::mesh::MeshAttachment {
+            layer_id: "layer_1".into(),
+            vertices: (0..3).map(|i| crate::core::animation::mesh::MeshVertex {
+                x: i as f32, y: 2.0, u: i as f32 * 0.5, v: 1.0,
+                weights: vec![("root".into(), 0.25), ("arm".into(), 0.75)],
+            }).collect(),
+            triangles: vec![[0, 1, 2]],
+            deform: Vec::new(),
+        });
+
+        let bytes = serialize_skeleton(&skel);
+        let restored = deserialize_skeleton(&bytes).unwrap();
+
+        assert_eq!(restored.bones.len(), 2);
+        assert_eq!(restored.bones[1].data, skel.bones[1].data);
+        assert_eq!(restored.bones[1].parent_index, Some(0));
+        assert_eq!(restored.slots[0].data.attachment.as_deref(), Some("layer_1"));
+        assert_eq!(restored.slots[0].data.color, Color::new(10, 20, 30, 40));
+        assert_eq!(restored.ik_constraints[0].data, skel.ik_constraints[0].data);
+        assert_eq!(restored.ik_constraints[0].mix, 0.5);
+        assert_eq!(restored.meshes, skel.meshes);
+
+        assert!(deserialize_skeleton(&bytes[..bytes.len() - 3]).is_err(), "截断的数据应报错");
+    }
+
+    #[test]
+    fn test_animation_payload() {
+        use crate::core::animation::timeline::{Animation, Timeline, TimelineProperty, KeyframeValue, CurveType};
+        use crate::core::animation::event::AnimationEvent;
+
+        let mut anim = Animation::new("walk".into(), 1.0);
+        let mut rot = Timeline::new("arm".into(), TimelineProperty::Rotation);
+        rot.add_keyframe(0.0, KeyframeValue::Rotate(10.0), CurveType::Bezier(0.25, 0.1, 0.25, 1.0));
+        rot.add_keyframe(1.0, KeyframeValue::Rotate(90.0), CurveType::Linear);
+        rot.keyframes[1].tangent = crate::core::animation::timeline::TangentMode::Aligned;
+        anim.timelines.push(rot);
+        let mut shear = Timeline::new("arm".into(), TimelineProperty::Shear);
+        shear.add_keyframe(0.5, KeyframeValue::Shear(15.0, -5.0), CurveType::Easing(crate::core::animation::easing::EasingFunction::Elastic, crate::core::animation::easing::EasingDirection::InOut));
+        shear.add_keyframe(0.75, KeyframeValue::Shear(0.0, 0.0), CurveType::Steps(4));
+        anim.timelines.push(shear);
+        let mut att = Timeline::new("slot_1".into(), TimelineProperty::Attachment);
+        att.add_keyframe(0.5, KeyframeValue::Attachment(None), CurveType::Stepped);
+        anim.timelines.push(att);
+        let mut order = Timeline::new("__draw_order".into(), TimelineProperty::DrawOrder);
+        order.add_keyframe(0.5, KeyframeValue::DrawOrder(vec!["b".into(), "a".into()]), CurveType::Stepped);
+        anim.timelines.push(order);
+        let mut deform = Timeline::new("layer_1".into(), TimelineProperty::Deform);
+        deform.add_keyframe(0.5, KeyframeValue::Deform(vec![(1.0, -2.0), (0.0, 0.5)]), CurveType::Linear);
+        anim.timelines.push(deform);
+        let mut event = AnimationEvent::new("footstep".into(), 0.25);
+        event.int_value = -3;
+        event.float_value = 0.5;
+        event.string_value = "left".into();
+        anim.add_event(event);
+
+        let bytes = serialize_animation("anim_1", &anim, true);
+        let (id, restored, is_active) = deserialize_animation(&bytes).unwrap();
+
+        assert_eq!(id, "anim_1");
+        assert!(is_active);
+        assert_eq!(restored.name, "walk");
+        assert_eq!(restored.duration, 1.0);
+        assert_eq!(restored.timelines, anim.timelines);
+        assert_eq!(restored.events, anim.events);
+
+        assert!(deserialize_animation(&bytes[..bytes.len() - 2]).is_err(), "截断的数据应报错");
     }
 }
 
@@ -320,3 +890,240 @@ pub fn deserialize_selection(data: &[u8]) -> Result<crate::core::selection::Sele
     }
     Ok(sel)
 }
+
+pub fn serialize_onion_skin(settings: &crate::animation::onion_skin::OnionSkinSettings) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(20);
+    buf.push(if settings.enabled { 1 } else { 0 });
+    buf.extend_from_slice(&settings.frames_before.to_le_bytes());
+    buf.extend_from_slice(&settings.frames_after.to_le_bytes());
+    buf.extend_from_slice(&settings.step.to_le_bytes());
+    buf.extend_from_slice(&settings.tint_before);
+    buf.extend_from_slice(&settings.tint_after);
+    buf.push(settings.opacity);
+    buf
+}
+
+pub fn deserialize_onion_skin(data: &[u8]) -> Result<crate::animation::onion_skin::OnionSkinSettings> {
+    if data.len() < 20 {
+        return Err(FormatError::UnexpectedEof(t!("error.payload_too_short", block = "ONIO").to_string()));
+    }
+
+    use crate::animation::onion_skin::{MAX_ONION_FRAMES, MAX_ONION_STEP};
+    // 损坏或构造的文件不能请求过多残影或 0 帧间隔
+    Ok(crate::animation::onion_skin::OnionSkinSettings {
+        enabled: data[0] != 0,
+        frames_before: u32::from_le_bytes(data[1..5].try_into().map_err(|_| FormatError::InvalidSliceLength)?).min(MAX_ONION_FRAMES),
+        frames_after: u32::from_le_bytes(data[5..9].try_into().map_err(|_| FormatError::InvalidSliceLength)?).min(MAX_ONION_FRAMES),
+        step: u32::from_le_bytes(data[9..13].try_into().map_err(|_| FormatError::InvalidSliceLength)?).clamp(1, MAX_ONION_STEP),
+        tint_before: [data[13], data[14], data[15]],
+        tint_after: [data[16], data[17], data[18]],
+        opacity: data[19],
+    })
+}
+
+// 2: 追加网格附件
+const SKELETON_VERSION: u16 = 2;
+
+fn write_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn read_u8(c: &mut Cursor<&[u8]>) -> Result<u8> {
+    let mut b = [0u8; 1];
+    c.read_exact(&mut b)?;
+    Ok(b[0])
+}
+
+fn read_u32(c: &mut Cursor<&[u8]>) -> Result<u32> {
+    let mut b = [0u8; 4];
+    c.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_f32(c: &mut Cursor<&[u8]>) -> Result<f32> {
+    let mut b = [0u8; 4];
+    c.read_exact(&mut b)?;
+    Ok(f32::from_le_bytes(b))
+}
+
+fn read_str(c: &mut Cursor<&[u8]>, what: &str) -> Result<String> {
+    let len = read_u32(c)?;
+    if len > 2048 { return Err(FormatError::InvalidData(t!("error.name_too_long").to_string())); }
+    let mut buf = vec![0u8; len as usize];
+    c.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|_| FormatError::InvalidUtf8(t!("error.invalid_utf8", msg = what).to_string()))
+}
+
+fn read_count(c: &mut Cursor<&[u8]>, limit: u32) -> Result<u32> {
+    let n = read_u32(c)?;
+    if n > limit { return Err(FormatError::InvalidData(format!("Element count {} exceeds limit {}", n, limit))); }
+    Ok(n)
+}
+
+pub fn serialize_skeleton(skeleton: &crate::core::animation::skeleton::Skeleton) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&SKELETON_VERSION.to_le_bytes());
+
+    // 骨骼按父级在前的顺序保存，读取时可直接依次 add_bone
+    buf.extend_from_slice(&(skeleton.bones.len() as u32).to_le_bytes());
+    for bone in &skeleton.bones {
+        let d = &bone.data;
+        write_str(&mut buf, &d.id);
+        write_str(&mut buf, &d.name);
+        match &d.parent_id {
+            Some(p) => { buf.push(1); write_str(&mut buf, p); }
+            None => buf.push(0),
+        }
+        buf.extend_from_slice(&d.length.to_le_bytes());
+        let t = &d.local_transform;
+        for v in [t.x, t.y, t.rotation, t.scale_x, t.scale_y, t.shear_x, t.shear_y] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        // 第 2 位记录“不继承镜像”，旧文件该位为 0 时保持默认的继承
+        buf.push((d.inherit_rotation as u8) | ((d.inherit_scale as u8) << 1) | ((!d.inherit_reflection as u8) << 2));
+    }
+
+    buf.extend_from_slice(&(skeleton.slots.len() as u32).to_le_bytes());
+    for slot in &skeleton.slots {
+        let d = &slot.data;
+        write_str(&mut buf, &d.id);
+        write_str(&mut buf, &d.name);
+        write_str(&mut buf, &d.bone_id);
+        buf.extend_from_slice(&[d.color.r, d.color.g, d.color.b, d.color.a]);
+        match &d.attachment {
+            Some(a) => { buf.push(1); write_str(&mut buf, a); }
+            None => buf.push(0),
+        }
+    }
+
+    buf.extend_from_slice(&(skeleton.ik_constraints.len() as u32).to_le_bytes());
+    for ik in &skeleton.ik_constraints {
+        let d = &ik.data;
+        write_str(&mut buf, &d.id);
+        write_str(&mut buf, &d.name);
+        buf.extend_from_slice(&(d.bone_ids.len() as u32).to_le_bytes());
+        for id in &d.bone_ids { write_str(&mut buf, id); }
+        write_str(&mut buf, &d.target_id);
+        buf.push(if d.bend_positive { 1 } else { 0 });
+        buf.extend_from_slice(&d.mix.to_le_bytes());
+    }
+
+    buf.extend_from_slice(&(skeleton.meshes.len() as u32).to_le_bytes());
+    for mesh in &skeleton.meshes {
+        write_str(&mut buf, &mesh.layer_id);
+        buf.extend_from_slice(&(mesh.vertices.len() as u32).to_le_bytes());
+        for v in &mesh.vertices {
+            for f in [v.x, v.y, v.u, v.v] { buf.extend_from_slice(&f.to_le_bytes()); }
+            buf.push(v.weights.len() as u8);
+            for (bone_id, w) in &v.weights {
+                write_str(&mut buf, bone_id);
+                buf.extend_from_slice(&w.to_le_bytes());
+            }
+        }
+        buf.extend_from_slice(&(mesh.triangles.len() as u32).to_le_bytes());
+        for tri in &mesh.triangles {
+            for i in tri { buf.extend_from_slice(&i.to_le_bytes()); }
+        }
+    }
+    buf
+}
+
+pub fn deserialize_skeleton(data: &[u8]) -> Result<crate::core::animation::skeleton::Skeleton> {
+    use crate::core::animation::bone::BoneData;
+    use crate::core::animation::slot::{SlotData, RuntimeSlot};
+    use crate::core::animation::ik::{IkConstraintData, IkConstraint};
+
+    if data.len() < 2 {
+        return Err(FormatError::UnexpectedEof(t!("error.payload_too_short", block = "SKEL").to_string()));
+    }
+    let mut cursor = Cursor::new(data);
+    let mut ver = [0u8; 2];
+    cursor.read_exact(&mut ver)?;
+    let version = u16::from_le_bytes(ver);
+    if version > SKELETON_VERSION {
+        return Err(FormatError::InvalidData(format!("Unsupported skeleton version: {}", version)));
+    }
+
+    let mut skeleton = crate::core::animation::skeleton::Skeleton::new();
+    let bone_count = read_count(&mut cursor, 4096)?;
+    for _ in 0..bone_count {
+        let mut bone = BoneData::new(read_str(&mut cursor, "Bone ID")?, read_str(&mut cursor, "Bone Name")?);
+        if read_u8(&mut cursor)? != 0 {
+            bone.parent_id = Some(read_str(&mut cursor, "Bone Parent")?);
+        }
+        bone.length = read_f32(&mut cursor)?;
+        let t = &mut bone.local_transform;
+        t.x = read_f32(&mut cursor)?;
+        t.y = read_f32(&mut cursor)?;
+        t.rotation = read_f32(&mut cursor)?;
+        t.scale_x = read_f32(&mut cursor)?;
+        t.scale_y = read_f32(&mut cursor)?;
+        t.shear_x = read_f32(&mut cursor)?;
+        t.shear_y = read_f32(&mut cursor)?;
+        let flags = read_u8(&mut cursor)?;
+        bone.inherit_rotation = flags & 1 != 0;
+        bone.inherit_scale = flags & 2 != 0;
+        bone.inherit_reflection = flags & 4 == 0;
+        skeleton.add_bone(bone);
+    }
+
+    let slot_count = read_count(&mut cursor, 4096)?;
+    for _ in 0..slot_count {
+        let mut slot = SlotData::new(read_str(&mut cursor, "Slot ID")?, read_str(&mut cursor, "Slot Name")?, read_str(&mut cursor, "Slot Bone")?);
+        let mut rgba = [0u8; 4];
+        cursor.read_exact(&mut rgba)?;
+        slot.color = Color::new(rgba[0], rgba[1], rgba[2], rgba[3]);
+        if read_u8(&mut cursor)? != 0 {
+            slot.attachment = Some(read_str(&mut cursor, "Slot Attachment")?);
+        }
+        skeleton.slots.push(RuntimeSlot::new(slot));
+    }
+
+    let ik_count = read_count(&mut cursor, 1024)?;
+    for _ in 0..ik_count {
+        let id = read_str(&mut cursor, "IK ID")?;
+        let name = read_str(&mut cursor, "IK Name")?;
+        let chain_len = read_count(&mut cursor, 256)?;
+        let mut bone_ids = Vec::with_capacity(chain_len as usize);
+        for _ in 0..chain_len { bone_ids.push(read_str(&mut cursor, "IK Bone")?); }
+        let mut data = IkConstraintData::new(id, name, bone_ids, read_str(&mut cursor, "IK Target")?);
+        data.bend_positive = read_u8(&mut cursor)? != 0;
+        data.mix = read_f32(&mut cursor)?;
+        skeleton.ik_constraints.push(IkConstraint::new(data));
+    }
+
+    if version >= 2 {
+        use crate::core::animation::mesh::{MeshAttachment, MeshVertex, MAX_BONE_WEIGHTS};
+        let mesh_count = read_count(&mut cursor, 4096)?;
+        for _ in 0..mesh_count {
+            let layer_id = read_str(&mut cursor, "Mesh Layer")?;
+            let vertex_count = read_count(&mut cursor, 1 << 20)?;
+            let mut vertices = Vec::with_capacity(vertex_count as usize);
+            for _ in 0..vertex_count {
+                let (x, y, u, v) = (read_f32(&mut cursor)?, read_f32(&mut cursor)?, read_f32(&mut cursor)?, read_f32(&mut cursor)?);
+                let weight_count = read_u8(&mut cursor)? as usize;
+                if weight_count > MAX_BONE_WEIGHTS {
+                    return Err(FormatError::InvalidData(format!("Too many bone weights: {}", weight_count)));
+                }
+                let mut weights = Vec::with_capacity(weight_count);
+                for _ in 0..weight_count { weights.push((read_str(&mut cursor, "Mesh Weight Bone")?, read_f32(&mut cursor)?)); }
+                vertices.push(MeshVertex { x, y, u, v, weights });
+            }
+            let triangle_count = read_count(&mut cursor, 1 << 21)?;
+            let mut triangles = Vec::with_capacity(triangle_count as usize);
+            for _ in 0..triangle_count {
+                let tri = [read_u32(&mut cursor)?, read_u32(&mut cursor)?, read_u32(&mut cursor)?];
+                if tri.iter().any(|&i| i >= vertex_count) {
+                    return Err(FormatError::InvalidData(format!("Mesh triangle index out of range: {:?}", tri)));
+                }
+                triangles.push(tri);
+            }
+            skeleton.meshes.push(MeshAttachment { layer_id, vertices, triangles, deform: Vec::new() });
+        }
+    }
+
+    skeleton.update();
+    Ok(skeleton)
+}
+
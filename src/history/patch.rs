@@ -1,9 +1,11 @@
 use crate::core::color::Color;
 use crate::core::layer::Layer; 
 use crate::core::blend_mode::BlendMode;
+use crate::core::layer_effect::LayerEffect;
 use crate::core::selection::SelectionData;
 use crate::core::store::PixelStore;
 use crate::core::path::BezierPath;
+use crate::core::palette::Palette;
 use crate::core::error::{CoreError, Result};
 use std::fmt::Debug;
 use std::any::Any;
@@ -262,6 +264,20 @@ impl Patch for LayerBlendModePatch {
     fn as_any_mut(&mut self) -> &mut dyn Any { self }
 }
 
+#[derive(Debug, Clone)]
+pub struct LayerEffectsPatch { pub old_effects: Vec<LayerEffect>, pub new_effects: Vec<LayerEffect> }
+impl Patch for LayerEffectsPatch {
+    fn apply(&self, layer_id: &str, store: &mut PixelStore, forward: bool) -> Result<()> {
+        if let Some(layer) = store.get_layer_mut(layer_id) {
+            layer.effects = if forward { self.new_effects.clone() } else { self.old_effects.clone() };
+        }
+        Ok(())
+    }
+    fn clone_box(&self) -> Box<dyn Patch> { Box::new(self.clone()) }
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+}
+
 #[derive(Debug, Clone)]
 pub struct CanvasResizePatch {
     pub old_width: u32, pub old_height: u32,
@@ -298,6 +314,18 @@ impl Patch for PathChangePatch {
     fn as_any_mut(&mut self) -> &mut dyn Any { self }
 }
 
+#[derive(Debug, Clone)]
+pub struct PaletteChangePatch { pub old_palette: Palette, pub new_palette: Palette }
+impl Patch for PaletteChangePatch {
+    fn apply(&self, _layer_id: &str, store: &mut PixelStore, forward: bool) -> Result<()> {
+        store.palette = if forward { self.new_palette.clone() } else { self.old_palette.clone() };
+        Ok(())
+    }
+    fn clone_box(&self) -> Box<dyn Patch> { Box::new(self.clone()) }
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+}
+
 #[derive(Debug, Clone)]
 pub struct ActionPatch {
     pub id: String,
@@ -373,10 +401,18 @@ impl ActionPatch {
         Self { id, layer_id, action: Box::new(LayerBlendModePatch { old_mode, new_mode }) }
     }
 
+    pub fn new_layer_effects(id: String, layer_id: String, old_effects: Vec<LayerEffect>, new_effects: Vec<LayerEffect>) -> Self {
+        Self { id, layer_id, action: Box::new(LayerEffectsPatch { old_effects, new_effects }) }
+    }
+
     pub fn new_path_change(id: String, old_path: BezierPath, new_path: BezierPath) -> Self {
         Self { id, layer_id: "global".into(), action: Box::new(PathChangePatch { old_path, new_path }) }
     }
 
+    pub fn new_palette_change(id: String, old_palette: Palette, new_palette: Palette) -> Self {
+        Self { id, layer_id: "global".into(), action: Box::new(PaletteChangePatch { old_palette, new_palette }) }
+    }
+
     pub fn new_canvas_resize(
         id: String, old_width: u32, old_height: u32, new_width: u32, new_height: u32,
         old_layers: Vec<Layer>, new_layers: Vec<Layer>, old_selection: SelectionData, new_selection: SelectionData
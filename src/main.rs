@@ -5,7 +5,7 @@ use pxa_engine_win32::app::commands::AppCommand;
 use pxa_engine_win32::app::command_handler::CommandHandler;
 use pxa_engine_win32::ui::gui::Gui;
 use pxa_engine_win32::ui::framework::GuiFramework;
-use pxa_engine_win32::render::anim_compositor::AnimCompositor; 
+use pxa_engine_win32::render::anim_compositor::{AnimCompositor, SlotBuffers};
 use pxa_engine_win32::animation::controller::AnimationController; 
 use pxa_engine_win32::app::state::AppMode; 
 
@@ -261,8 +261,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     app_state.engine.update_render_cache(Some(rect));
                 }
 
+                let mut onion_ghosts = Vec::new();
                 match app_state.mode {
                     AppMode::PixelEdit => {
+                        app_state.update_onion_skin();
                         Compositor::render(app_state.engine.store(), pixels.frame_mut(), viewport);
                         if app_state.engine.tool_manager().active_type == pxa_engine_win32::app::state::ToolType::CreateBone {
                              AnimCompositor::render_cpu(app_state.engine.store(), &app_state.animation.project.skeleton, pixels.frame_mut(), viewport, app_state.ui.selected_bone_id.as_ref());
@@ -287,8 +289,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     AppMode::Animation => {
                         AnimationController::update(&mut app_state.animation, dt);
                         app_state.sync_animation_to_layers();
+                        onion_ghosts = app_state.update_onion_skin();
                         Compositor::render(app_state.engine.store(), pixels.frame_mut(), viewport);
+                        AnimCompositor::render_onion_cpu(app_state.engine.store(), &onion_ghosts, pixels.frame_mut(), viewport);
                         AnimCompositor::render_cpu(app_state.engine.store(), &app_state.animation.project.skeleton, pixels.frame_mut(), viewport, app_state.ui.selected_bone_id.as_ref());
+                        let active_tool = app_state.engine.tool_manager().active_type;
+                        if matches!(active_tool, pxa_engine_win32::app::state::ToolType::MeshEdit | pxa_engine_win32::app::state::ToolType::WeightPaint) {
+                            if let Some(layer_id) = app_state.engine.store().active_layer_id.clone() {
+                                let weight_bone = if active_tool == pxa_engine_win32::app::state::ToolType::WeightPaint { app_state.ui.selected_bone_id.as_ref() } else { None };
+                                AnimCompositor::render_mesh_overlay(app_state.engine.store(), &app_state.animation.project.skeleton, pixels.frame_mut(), viewport, &layer_id, weight_bone, app_state.ui.selected_mesh_vertex);
+                            }
+                        }
                         anim_renderer.prepare_textures(pixels.device(), pixels.queue(), app_state.engine.store(), &app_state.animation.project.skeleton);
                     }
                 }
@@ -296,36 +307,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let render_result = pixels.render_with(|encoder, render_target, context| {
                     context.scaling_renderer.render(encoder, render_target);
                     if app_state.mode == AppMode::Animation {
-                        use pixels::wgpu::util::DeviceExt;
                         let skeleton = &app_state.animation.project.skeleton;
                         let store = app_state.engine.store();
 
-                        let mut instances = Vec::new();
-                        for slot in &skeleton.slots {
-                            if let (Some(layer_id), Some(bone_idx)) = (&slot.current_attachment, skeleton.bones.iter().position(|b| b.data.id == slot.data.bone_id)) {
-                                if let Some(layer) = store.get_layer(layer_id) {
-                                    let m = skeleton.bones[bone_idx].world_matrix;
-                                    let final_matrix = [
-                                        [m[0] * layer.width as f32, m[1] * layer.width as f32, 0.0, 0.0],
-                                        [m[2] * layer.height as f32, m[3] * layer.height as f32, 0.0, 0.0],
-                                        [0.0, 0.0, 1.0, 0.0],
-                                        [m[4], m[5], 0.0, 1.0],
-                                    ];
-                                    let c = slot.current_color;
-                                    let color_f32 = [c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0, c.a as f32 / 255.0];
-                                    
-                                    let mut instance_data = Vec::with_capacity(80);
-                                    instance_data.extend_from_slice(bytemuck::cast_slice(&final_matrix));
-                                    instance_data.extend_from_slice(bytemuck::cast_slice(&color_f32));
-
-                                    instances.push(context.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                                        label: None,
-                                        contents: &instance_data,
-                                        usage: wgpu::BufferUsages::VERTEX,
-                                    }));
-                                }
-                            }
-                        }
+                        let ghost_buffers: Vec<SlotBuffers> = onion_ghosts.iter()
+                            .map(|g| SlotBuffers::new(&context.device, store, &g.skeleton, Some((g.tint, g.opacity))))
+                            .collect();
+                        let buffers = SlotBuffers::new(&context.device, store, skeleton, None);
 
                         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                             label: Some("anim_gpu_pass"),
@@ -337,7 +325,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             depth_stencil_attachment: None,
                         });
                         
-                        anim_renderer.render_gpu(&context.queue, &mut rpass, store, skeleton, viewport, app_state.ui.selected_bone_id.as_ref(), &instances);
+                        for (ghost, ghost_buffers) in onion_ghosts.iter().zip(ghost_buffers.iter()) {
+                            anim_renderer.render_gpu(&context.queue, &mut rpass, store, &ghost.skeleton, viewport, ghost_buffers);
+                        }
+                        anim_renderer.render_gpu(&context.queue, &mut rpass, store, skeleton, viewport, &buffers);
                     }
                     framework.render(encoder, render_target, context);
                     Ok(())
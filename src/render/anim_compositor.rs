@@ -1,7 +1,8 @@
 use pixels::wgpu;
 use pixels::wgpu::util::DeviceExt;
 use crate::core::store::PixelStore;
-use crate::core::animation::skeleton::Skeleton;
+use crate::core::animation::skeleton::{Skeleton, RuntimeBone};
+use crate::animation::onion_skin::OnionGhost;
 use crate::render::compositor::Viewport;
 use crate::render::texture_manager::TextureManager;
 use bytemuck;
@@ -19,6 +20,33 @@ struct Uniforms {
     view_proj: [[f32; 4]; 4],
 }
 
+// 网格插槽的顶点/索引缓冲 (u32 索引)
+pub struct MeshBuffers {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+}
+
+// 一个骨架姿态在 render_gpu 中用到的全部缓冲，按绘制顺序每个可见插槽一项
+pub struct SlotBuffers {
+    pub instances: Vec<wgpu::Buffer>,
+    pub meshes: Vec<Option<MeshBuffers>>,
+}
+
+impl SlotBuffers {
+    pub fn new(device: &wgpu::Device, store: &PixelStore, skeleton: &Skeleton, tint: Option<([u8; 3], u8)>) -> Self {
+        let instances = AnimCompositor::slot_instance_data(store, skeleton, tint).iter()
+            .map(|data| device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: data,
+                usage: wgpu::BufferUsages::VERTEX,
+            }))
+            .collect();
+        let meshes = AnimCompositor::create_mesh_buffers(device, AnimCompositor::slot_mesh_data(store, skeleton));
+        Self { instances, meshes }
+    }
+}
+
 pub struct AnimCompositor {
     pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
@@ -145,7 +173,7 @@ impl AnimCompositor {
         for slot in &skeleton.slots {
             if let Some(layer_id) = &slot.current_attachment {
                 if let Some(layer) = store.get_layer(layer_id) {
-                    self.texture_manager.sync_layer(device, queue, &self.sampler, layer);
+                    self.texture_manager.sync_layer(device, queue, &self.sampler, store.rendered_layer(layer));
                 }
             }
         }
@@ -158,9 +186,9 @@ impl AnimCompositor {
         store: &PixelStore,
         skeleton: &Skeleton,
         view: Viewport, 
-        _selected_id: Option<&String>,
-        instance_buffers: &'a [wgpu::Buffer],
+        buffers: &'a SlotBuffers,
     ) {
+        let (instance_buffers, mesh_buffers) = (&buffers.instances, &buffers.meshes);
         let world_to_clip = self.calculate_projection(view);
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[Uniforms { view_proj: world_to_clip }]));
 
@@ -170,7 +198,7 @@ impl AnimCompositor {
         render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
 
         let mut instance_idx = 0;
-        for slot in &skeleton.slots {
+        for slot in skeleton.draw_order_slots() {
             if let Some(layer_id) = &slot.current_attachment {
                 if store.get_layer(layer_id).is_some() {
                     if let Some(bind_group) = self.texture_manager.get_bind_group(layer_id) {
@@ -178,7 +206,15 @@ impl AnimCompositor {
                             if instance_idx < instance_buffers.len() {
                                 render_pass.set_vertex_buffer(1, instance_buffers[instance_idx].slice(..));
                                 render_pass.set_bind_group(1, bind_group, &[]);
-                                render_pass.draw_indexed(0..6, 0, 0..1);
+                                if let Some(Some(mesh)) = mesh_buffers.get(instance_idx) {
+                                    render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                                    render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                                    render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+                                    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                                    render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                                } else {
+                                    render_pass.draw_indexed(0..6, 0, 0..1);
+                                }
                                 instance_idx += 1;
                             }
                         }
@@ -188,6 +224,79 @@ impl AnimCompositor {
         }
     }
 
+    // 每个可见插槽一份 80 字节的实例数据 (4x4 矩阵 + 颜色)，顺序与 render_gpu 的绘制顺序一致
+    pub fn slot_instance_data(store: &PixelStore, skeleton: &Skeleton, tint: Option<([u8; 3], u8)>) -> Vec<Vec<u8>> {
+        let mut instances = Vec::new();
+        for slot in skeleton.draw_order_slots() {
+            if let (Some(layer_id), Some(bone_idx)) = (&slot.current_attachment, skeleton.bones.iter().position(|b| b.data.id == slot.data.bone_id)) {
+                if let Some(layer) = store.get_layer(layer_id) {
+                    let m = skeleton.bones[bone_idx].world_matrix;
+                    // 网格的顶点已在画布坐标下，模型矩阵取单位矩阵
+                    let final_matrix = if skeleton.mesh(layer_id).is_some() {
+                        [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]]
+                    } else {
+                        [
+                            [m[0] * layer.width as f32, m[1] * layer.width as f32, 0.0, 0.0],
+                            [m[2] * layer.height as f32, m[3] * layer.height as f32, 0.0, 0.0],
+                            [0.0, 0.0, 1.0, 0.0],
+                            [m[4], m[5], 0.0, 1.0],
+                        ]
+                    };
+                    let c = slot.current_color;
+                    let mut color_f32 = [c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0, c.a as f32 / 255.0];
+                    if let Some((rgb, opacity)) = tint {
+                        color_f32[0] *= rgb[0] as f32 / 255.0;
+                        color_f32[1] *= rgb[1] as f32 / 255.0;
+                        color_f32[2] *= rgb[2] as f32 / 255.0;
+                        color_f32[3] *= opacity as f32 / 255.0;
+                    }
+
+                    let mut instance_data = Vec::with_capacity(80);
+                    instance_data.extend_from_slice(bytemuck::cast_slice(&final_matrix));
+                    instance_data.extend_from_slice(bytemuck::cast_slice(&color_f32));
+                    instances.push(instance_data);
+                }
+            }
+        }
+        instances
+    }
+
+    // 与 slot_instance_data 一一对应：网格插槽为 (顶点字节, u32 索引字节)，其余为 None
+    pub fn slot_mesh_data(store: &PixelStore, skeleton: &Skeleton) -> Vec<Option<(Vec<u8>, Vec<u8>)>> {
+        let mut meshes = Vec::new();
+        for slot in skeleton.draw_order_slots() {
+            if let (Some(layer_id), true) = (&slot.current_attachment, skeleton.bones.iter().any(|b| b.data.id == slot.data.bone_id)) {
+                if let Some(layer) = store.get_layer(layer_id) {
+                    let (w, h) = (layer.width.max(1) as f32, layer.height.max(1) as f32);
+                    meshes.push(skeleton.deformed_mesh(layer_id).map(|mesh| {
+                        let vertices: Vec<Vertex> = mesh.positions.iter().zip(mesh.uvs.iter())
+                            .map(|(p, uv)| Vertex { position: *p, tex_coords: [uv[0] / w, uv[1] / h] })
+                            .collect();
+                        let indices: Vec<u32> = mesh.triangles.iter().flatten().copied().collect();
+                        (bytemuck::cast_slice(&vertices).to_vec(), bytemuck::cast_slice(&indices).to_vec())
+                    }));
+                }
+            }
+        }
+        meshes
+    }
+
+    pub fn create_mesh_buffers(device: &wgpu::Device, data: Vec<Option<(Vec<u8>, Vec<u8>)>>) -> Vec<Option<MeshBuffers>> {
+        data.into_iter().map(|d| d.map(|(vertices, indices)| MeshBuffers {
+            vertex_buffer: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("anim_mesh_vbuf"),
+                contents: &vertices,
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+            index_buffer: device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("anim_mesh_ibuf"),
+                contents: &indices,
+                usage: wgpu::BufferUsages::INDEX,
+            }),
+            index_count: (indices.len() / 4) as u32,
+        })).collect()
+    }
+
     fn calculate_projection(&self, v: Viewport) -> [[f32; 4]; 4] {
         let zoom = v.zoom;
         let sw = v.screen_width as f32;
@@ -210,77 +319,124 @@ impl AnimCompositor {
         view: Viewport,
         selected_id: Option<&String> 
     ) {
-
-        let zoom = view.zoom;
-        let sw = view.screen_width as f32;
-        let sh = view.screen_height as f32;
-        let cw = store.canvas_width as f32;
-        let ch = store.canvas_height as f32;
-
-        let to_screen = |wx: f32, wy: f32| -> (i32, i32) {
-            let canvas_cx = cw / 2.0;
-            let canvas_cy = ch / 2.0;
-            let screen_cx = sw / 2.0;
-            let screen_cy = sh / 2.0;
-            let sx = (wx - canvas_cx + view.pan_x) * zoom + screen_cx;
-            let sy = (wy - canvas_cy + view.pan_y) * zoom + screen_cy;
-            (sx as i32, sy as i32)
-        };
-
-        for bone in &skeleton.bones {
+        draw_bones(store, skeleton, frame, view, |bone| {
             let is_selected = selected_id == Some(&bone.data.id);
-            let color = if bone.data.id.starts_with("preview") {
+            if bone.data.id.starts_with("preview") {
                 [255, 255, 0, 255]
             } else if is_selected {
                 [255, 50, 50, 255]
             } else {
                 [200, 200, 200, 255]
-            };
-            
-            let m = bone.world_matrix;
-            let root_x = m[4].round();
-            let root_y = m[5].round();
-            let length = bone.data.length;
-
-            if length < 1.0 {
-                let p = to_screen(root_x, root_y);
-                draw_cross(frame, view.screen_width, view.screen_height, p.0, p.1, 4, color);
-                continue;
             }
+        });
+    }
 
-            let tip_x = root_x + length * m[0];
-            let tip_y = root_y + length * m[1];
-
-            let width = (length * 0.15).clamp(3.0, 15.0);
+    // 网格线框；给定骨骼时顶点按该骨骼的权重从蓝 (0) 到红 (1) 着色
+    pub fn render_mesh_overlay(
+        store: &PixelStore,
+        skeleton: &Skeleton,
+        frame: &mut [u8],
+        view: Viewport,
+        layer_id: &str,
+        weight_bone: Option<&String>,
+        selected_vertex: Option<usize>,
+    ) {
+        let (mesh, deformed) = match (skeleton.mesh(layer_id), skeleton.deformed_mesh(layer_id)) {
+            (Some(m), Some(d)) => (m, d),
+            _ => return,
+        };
+        let (w, h) = (view.screen_width, view.screen_height);
+        let points: Vec<(i32, i32)> = deformed.positions.iter().map(|p| canvas_to_screen(store, view, p[0], p[1])).collect();
+        let edge_color = [120, 220, 200, 160];
+        for tri in &deformed.triangles {
+            for k in 0..3 {
+                if let (Some(&a), Some(&b)) = (points.get(tri[k] as usize), points.get(tri[(k + 1) % 3] as usize)) {
+                    draw_line(frame, w, h, a, b, edge_color);
+                }
+            }
+        }
+        for (i, (p, vertex)) in points.iter().zip(mesh.vertices.iter()).enumerate() {
+            let color = match weight_bone {
+                Some(bone_id) => {
+                    let weight = vertex.weights.iter().find(|(id, _)| id == bone_id).map(|x| x.1).unwrap_or(0.0);
+                    [(weight * 255.0) as u8, 40, ((1.0 - weight) * 255.0) as u8, 255]
+                }
+                None => [120, 220, 200, 255],
+            };
+            let radius = if selected_vertex == Some(i) { 4 } else { 2 };
+            draw_circle_filled(frame, w, h, p.0, p.1, radius, if selected_vertex == Some(i) { [255, 255, 0, 255] } else { color });
+        }
+    }
 
-            let dx = m[0]; 
-            let dy = m[1];
-            let perp_x = -dy * width;
-            let perp_y = dx * width;
+    pub fn render_onion_cpu(store: &PixelStore, ghosts: &[OnionGhost], frame: &mut [u8], view: Viewport) {
+        for ghost in ghosts {
+            let color = [ghost.tint[0], ghost.tint[1], ghost.tint[2], ghost.opacity];
+            draw_bones(store, &ghost.skeleton, frame, view, |_| color);
+        }
+    }
+}
 
-            
-            let split_ratio = 0.2; 
-            let mid_x = root_x + dx * length * split_ratio;
-            let mid_y = root_y + dy * length * split_ratio;
-
-            let p_root = to_screen(root_x, root_y);
-            let p_tip = to_screen(tip_x, tip_y);
-            let p_left = to_screen(mid_x + perp_x, mid_y + perp_y);
-            let p_right = to_screen(mid_x - perp_x, mid_y - perp_y);
-
-            draw_line(frame, view.screen_width, view.screen_height, p_root, p_left, color);
-            draw_line(frame, view.screen_width, view.screen_height, p_left, p_tip, color);
-            draw_line(frame, view.screen_width, view.screen_height, p_tip, p_right, color);
-            draw_line(frame, view.screen_width, view.screen_height, p_right, p_root, color);
-            
-            let mid_color = [color[0], color[1], color[2], 128];
-            draw_line(frame, view.screen_width, view.screen_height, p_root, p_tip, mid_color);
+fn draw_bones<F>(store: &PixelStore, skeleton: &Skeleton, frame: &mut [u8], view: Viewport, color_of: F)
+where
+    F: Fn(&RuntimeBone) -> [u8; 4],
+{
+    let to_screen = |wx: f32, wy: f32| canvas_to_screen(store, view, wx, wy);
 
-            draw_circle_filled(frame, view.screen_width, view.screen_height, p_root.0, p_root.1, 3, color);
+    for bone in &skeleton.bones {
+        let color = color_of(bone);
+        
+        let m = bone.world_matrix;
+        let root_x = m[4].round();
+        let root_y = m[5].round();
+        let length = bone.data.length;
+
+        if length < 1.0 {
+            let p = to_screen(root_x, root_y);
+            draw_cross(frame, view.screen_width, view.screen_height, p.0, p.1, 4, color);
+            continue;
         }
+
+        let tip_x = root_x + length * m[0];
+        let tip_y = root_y + length * m[1];
+
+        let width = (length * 0.15).clamp(3.0, 15.0);
+
+        let dx = m[0]; 
+        let dy = m[1];
+        let perp_x = -dy * width;
+        let perp_y = dx * width;
+
+        
+        let split_ratio = 0.2; 
+        let mid_x = root_x + dx * length * split_ratio;
+        let mid_y = root_y + dy * length * split_ratio;
+
+        let p_root = to_screen(root_x, root_y);
+        let p_tip = to_screen(tip_x, tip_y);
+        let p_left = to_screen(mid_x + perp_x, mid_y + perp_y);
+        let p_right = to_screen(mid_x - perp_x, mid_y - perp_y);
+
+        draw_line(frame, view.screen_width, view.screen_height, p_root, p_left, color);
+        draw_line(frame, view.screen_width, view.screen_height, p_left, p_tip, color);
+        draw_line(frame, view.screen_width, view.screen_height, p_tip, p_right, color);
+        draw_line(frame, view.screen_width, view.screen_height, p_right, p_root, color);
+        
+        let mid_color = [color[0], color[1], color[2], color[3] / 2];
+        draw_line(frame, view.screen_width, view.screen_height, p_root, p_tip, mid_color);
+
+        draw_circle_filled(frame, view.screen_width, view.screen_height, p_root.0, p_root.1, 3, color);
     }
 }
 
+fn canvas_to_screen(store: &PixelStore, view: Viewport, wx: f32, wy: f32) -> (i32, i32) {
+    let canvas_cx = store.canvas_width as f32 / 2.0;
+    let canvas_cy = store.canvas_height as f32 / 2.0;
+    let screen_cx = view.screen_width as f32 / 2.0;
+    let screen_cy = view.screen_height as f32 / 2.0;
+    let sx = (wx - canvas_cx + view.pan_x) * view.zoom + screen_cx;
+    let sy = (wy - canvas_cy + view.pan_y) * view.zoom + screen_cy;
+    (sx as i32, sy as i32)
+}
 
 fn draw_pixel_safe(frame: &mut [u8], w: u32, h: u32, x: i32, y: i32, color: [u8; 4]) {
     if x >= 0 && y >= 0 && x < w as i32 && y < h as i32 {
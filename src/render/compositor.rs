@@ -1,7 +1,10 @@
 use crate::core::store::PixelStore;
 use crate::core::layer::{Layer, Chunk, CHUNK_SIZE};
 use crate::render::blend::blend_pixels;
+use crate::core::animation::mesh::{DeformedMesh, MeshRaster};
+use crate::render::resample::SampleSource;
 use rayon::prelude::*;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Viewport {
@@ -16,9 +19,12 @@ pub struct Compositor;
 
 struct LayerRenderCache<'a> {
     layer: &'a Layer,
+    // 取像素用的图层：带效果时为效果缓存，位置、混合等属性仍取自 layer
+    pixels: &'a Layer,
     active_chunk: Option<&'a Chunk>,
     active_chunk_coords: (i32, i32),
     anim_transform: Option<[f32; 6]>,
+    mesh: Option<&'a MeshRaster>,
 }
 
 impl Compositor {
@@ -35,28 +41,42 @@ impl Compositor {
         let canvas_w = store.canvas_width;
         let canvas_h = store.canvas_height;
         let stride = (view.screen_width * 4) as usize;
+        let tiling = store.tiling_mode;
+        let has_onion = store.onion_cache.len() == store.composite_cache.len();
 
         frame.par_chunks_exact_mut(stride)
             .enumerate()
             .for_each(|(y_idx, row)| {
                 let sy = y_idx as f32 + 0.5;
                 let ly = (sy - s_cy) * inv_zoom + c_cy - view.pan_y;
-                let ty = ly.floor() as i32;
+                let mut ty = ly.floor() as i32;
+                if tiling && ty >= -(canvas_h as i32) && ty < 2 * canvas_h as i32 {
+                    ty = ty.rem_euclid(canvas_h as i32);
+                }
 
                 let mut current_lx = (0.5f32 - s_cx) * inv_zoom + c_cx - view.pan_x;
                 let step_lx = inv_zoom;
 
                 for x in 0..view.screen_width {
-                    let tx = current_lx.floor() as i32;
+                    let mut tx = current_lx.floor() as i32;
                     current_lx += step_lx;
                     let idx = (x as usize) * 4;
 
+                    // 平铺预览：画布四周各重复一圈 (3x3)
+                    if tiling && tx >= -(canvas_w as i32) && tx < 2 * canvas_w as i32 {
+                        tx = tx.rem_euclid(canvas_w as i32);
+                    }
+
                     if ty >= 0 && ty < canvas_h as i32 && tx >= 0 && tx < canvas_w as i32 {
                         let cache_idx = ((ty as u32 * canvas_w + tx as u32) * 4) as usize;
                         let src = &store.composite_cache[cache_idx..cache_idx+4];
                         
                         let is_even = ((tx >> 3) + (ty >> 3)) % 2 == 0;
-                        let bg = if is_even { color_grid_a } else { color_grid_b };
+                        let mut bg = if is_even { color_grid_a } else { color_grid_b };
+                        if has_onion && store.onion_cache[cache_idx + 3] > 0 {
+                            let o = &store.onion_cache[cache_idx..cache_idx+4];
+                            bg = blend_pixels(bg, [o[0], o[1], o[2], o[3]], crate::core::blend_mode::BlendMode::Normal, 255);
+                        }
                         
                         if src[3] == 255 {
                             row[idx..idx+4].copy_from_slice(src);
@@ -74,19 +94,32 @@ impl Compositor {
         let canvas_w = store.canvas_width;
         let canvas_h = store.canvas_height;
 
+        // 描边、投影会改变脏矩形以外的合成结果，按效果栈的最大扩展距离扩大
+        let margin = store.layers.iter().filter(|l| l.visible)
+            .map(|l| l.effects.iter().filter(|e| e.enabled).map(|e| e.kind.reach()).sum::<u32>())
+            .max().unwrap_or(0);
+        let rect = rect.map(|(x, y, w, h)| (x.saturating_sub(margin), y.saturating_sub(margin), w + 2 * margin, h + 2 * margin));
         let (rx, ry, rw, rh) = rect.unwrap_or((0, 0, canvas_w, canvas_h));
         let x_start = rx.clamp(0, canvas_w);
         let x_end = (rx + rw).clamp(0, canvas_w);
         let y_start = ry.clamp(0, canvas_h);
         let y_end = (ry + rh).clamp(0, canvas_h);
 
-        let layers_refs: Vec<&Layer> = store.layers.iter().filter(|l| l.visible).collect();
         let stride = (canvas_w * 4) as usize;
         let full_range_start = (y_start * canvas_w * 4) as usize;
         let full_range_end = (y_end * canvas_w * 4) as usize;
 
         if full_range_start >= full_range_end || full_range_end > store.composite_cache.len() { return; }
 
+        crate::render::effects::refresh_effect_cache(store);
+        let layers_refs: Vec<&Layer> = match &store.layer_draw_order {
+            Some(order) => order.iter().filter_map(|id| store.layers.iter().find(|l| &l.id == id)).filter(|l| l.visible).collect(),
+            None => store.layers.iter().filter(|l| l.visible).collect(),
+        };
+
+        let rasters = Self::rasterize_meshes(&store.layer_meshes, canvas_w, canvas_h);
+        let effect_cache = &store.effect_cache;
+        let preview = &store.pixel_preview;
         store.composite_cache[full_range_start..full_range_end]
             .par_chunks_exact_mut(stride)
             .enumerate()
@@ -96,9 +129,11 @@ impl Compositor {
                 let mut layer_caches: Vec<LayerRenderCache> = layers_refs.iter()
                     .map(|l| LayerRenderCache {
                         layer: l,
+                        pixels: PixelStore::rendered_from(effect_cache, preview, l),
                         active_chunk: None,
                         active_chunk_coords: (-999, -999),
                         anim_transform: store.layer_anim_transforms.get(&l.id).copied(),
+                        mesh: rasters.get(&l.id),
                     })
                     .collect();
 
@@ -109,7 +144,12 @@ impl Compositor {
                         let mut lx;
                         let mut ly;
                         
-                        if let Some(matrix) = cache.anim_transform {
+                        if let Some(raster) = cache.mesh {
+                            match raster.lookup(tx as i32, ty as i32) {
+                                Some((u, v)) => { lx = u; ly = v; }
+                                None => continue,
+                            }
+                        } else if let Some(matrix) = cache.anim_transform {
                             let orig_tx = matrix[0] * (tx as f32) + matrix[2] * (ty as f32) + matrix[4];
                             let orig_ty = matrix[1] * (tx as f32) + matrix[3] * (ty as f32) + matrix[5];
                             lx = orig_tx.round() as i32 - cache.layer.offset_x;
@@ -124,7 +164,7 @@ impl Compositor {
                             let cy = (ly as u32) / CHUNK_SIZE;
 
                             if (cx as i32, cy as i32) != cache.active_chunk_coords {
-                                cache.active_chunk = cache.layer.chunks.get(&(cx, cy));
+                                cache.active_chunk = cache.pixels.chunks.get(&(cx, cy));
                                 cache.active_chunk_coords = (cx as i32, cy as i32);
                             }
 
@@ -166,6 +206,137 @@ impl Compositor {
             });
     }
 
+    // 网格图层在整张画布上的像素到图层像素映射
+    pub fn rasterize_meshes(meshes: &HashMap<String, DeformedMesh>, canvas_w: u32, canvas_h: u32) -> HashMap<String, MeshRaster> {
+        meshes.par_iter().map(|(id, mesh)| (id.clone(), mesh.rasterize(canvas_w, canvas_h))).collect()
+    }
+
+    // 洋葱皮：按给定姿态合成已绑定骨骼的图层，染色后以 over 方式叠加到 target (画布尺寸 RGBA)。
+    // effect_layers 为该姿态下应用效果后的图层 (见 effects::evaluate_layers)
+    pub fn composite_onion_ghost(
+        store: &PixelStore,
+        transforms: &HashMap<String, [f32; 6]>,
+        offsets: &HashMap<String, (i32, i32)>,
+        meshes: &HashMap<String, DeformedMesh>,
+        effect_layers: &HashMap<String, Layer>,
+        (tint, opacity): ([u8; 3], u8),
+        target: &mut [u8],
+    ) {
+        let canvas_w = store.canvas_width;
+        let stride = (canvas_w * 4) as usize;
+        if opacity == 0 || stride == 0 || target.len() != (canvas_w * store.canvas_height * 4) as usize { return; }
+
+        let layers: Vec<&Layer> = store.layers.iter().filter(|l| l.visible && (offsets.contains_key(&l.id) || meshes.contains_key(&l.id))).collect();
+        if layers.is_empty() { return; }
+        let rasters = Self::rasterize_meshes(meshes, canvas_w, store.canvas_height);
+
+        target.par_chunks_exact_mut(stride)
+            .enumerate()
+            .for_each(|(ty, row)| {
+                let ty = ty as i32;
+                for tx in 0..canvas_w as i32 {
+                    let mut fc = [0, 0, 0, 0];
+                    for layer in &layers {
+                        let (lx, ly) = if let Some(raster) = rasters.get(&layer.id) {
+                            match raster.lookup(tx, ty) {
+                                Some(texel) => texel,
+                                None => continue,
+                            }
+                        } else if let Some(m) = transforms.get(&layer.id) {
+                            let orig_tx = m[0] * (tx as f32) + m[2] * (ty as f32) + m[4];
+                            let orig_ty = m[1] * (tx as f32) + m[3] * (ty as f32) + m[5];
+                            (orig_tx.round() as i32 - layer.offset_x, orig_ty.round() as i32 - layer.offset_y)
+                        } else {
+                            let (dx, dy) = offsets.get(&layer.id).copied().unwrap_or((0, 0));
+                            (tx - layer.offset_x - dx, ty - layer.offset_y - dy)
+                        };
+                        if lx < 0 || ly < 0 { continue; }
+                        if let Some(c) = effect_layers.get(&layer.id).unwrap_or(layer).get_pixel(lx as u32, ly as u32) {
+                            if c.a > 0 {
+                                fc = blend_pixels(fc, [c.r, c.g, c.b, c.a], layer.blend_mode, layer.opacity);
+                            }
+                        }
+                    }
+                    if fc[3] == 0 { continue; }
+
+                    let src_a = opacity as u32;
+                    let src = [
+                        ((fc[0] as u32 + tint[0] as u32) / 2) as u8,
+                        ((fc[1] as u32 + tint[1] as u32) / 2) as u8,
+                        ((fc[2] as u32 + tint[2] as u32) / 2) as u8,
+                    ];
+                    let idx = (tx as usize) * 4;
+                    let dst_a = row[idx + 3] as u32;
+                    let out_a = src_a + dst_a * (255 - src_a) / 255;
+                    for c in 0..3 {
+                        let v = (src[c] as u32 * src_a + row[idx + c] as u32 * dst_a * (255 - src_a) / 255) / out_a.max(1);
+                        row[idx + c] = v.min(255) as u8;
+                    }
+                    row[idx + 3] = out_a as u8;
+                }
+            });
+    }
+
+    // 动画烘焙：按给定姿态与绘制顺序合成画布大小的 RGBA (不含选区与洋葱皮)。
+    // 带旋转或缩放的图层在 sources 中有取样源时按其取样 (如 RotSprite)，否则与画布渲染一致取最近邻
+    pub fn composite_pose(
+        store: &PixelStore,
+        order: &[String],
+        transforms: &HashMap<String, [f32; 6]>,
+        offsets: &HashMap<String, (i32, i32)>,
+        meshes: &HashMap<String, DeformedMesh>,
+        sources: &HashMap<String, &SampleSource>,
+        effect_layers: &HashMap<String, Layer>,
+    ) -> Vec<u8> {
+        let canvas_w = store.canvas_width;
+        let stride = (canvas_w * 4) as usize;
+        let mut target = vec![0u8; (canvas_w * store.canvas_height * 4) as usize];
+        if stride == 0 { return target; }
+
+        let layers: Vec<&Layer> = order.iter().filter_map(|id| store.layers.iter().find(|l| &l.id == id)).filter(|l| l.visible).collect();
+        let rasters = Self::rasterize_meshes(meshes, canvas_w, store.canvas_height);
+
+        target.par_chunks_exact_mut(stride)
+            .enumerate()
+            .for_each(|(ty, row)| {
+                let ty = ty as i32;
+                for tx in 0..canvas_w as i32 {
+                    let mut fc = [0, 0, 0, 0];
+                    for layer in &layers {
+                        let (lx, ly) = if let Some(raster) = rasters.get(&layer.id) {
+                            match raster.lookup(tx, ty) {
+                                Some(texel) => texel,
+                                None => continue,
+                            }
+                        } else if let Some(m) = transforms.get(&layer.id) {
+                            let orig_tx = m[0] * (tx as f32) + m[2] * (ty as f32) + m[4];
+                            let orig_ty = m[1] * (tx as f32) + m[3] * (ty as f32) + m[5];
+                            if let Some(source) = sources.get(&layer.id) {
+                                // 像素中心对齐：与最近邻的 round 取整一致
+                                if let Some(c) = source.sample(orig_tx - layer.offset_x as f32 + 0.5, orig_ty - layer.offset_y as f32 + 0.5) {
+                                    fc = blend_pixels(fc, c, layer.blend_mode, layer.opacity);
+                                }
+                                continue;
+                            }
+                            (orig_tx.round() as i32 - layer.offset_x, orig_ty.round() as i32 - layer.offset_y)
+                        } else {
+                            let (dx, dy) = offsets.get(&layer.id).copied().unwrap_or((0, 0));
+                            (tx - layer.offset_x - dx, ty - layer.offset_y - dy)
+                        };
+                        if lx < 0 || ly < 0 { continue; }
+                        if let Some(c) = effect_layers.get(&layer.id).unwrap_or(layer).get_pixel(lx as u32, ly as u32) {
+                            if c.a > 0 {
+                                fc = blend_pixels(fc, [c.r, c.g, c.b, c.a], layer.blend_mode, layer.opacity);
+                            }
+                        }
+                    }
+                    let idx = (tx as usize) * 4;
+                    row[idx..idx + 4].copy_from_slice(&fc);
+                }
+            });
+        target
+    }
+
     pub fn render(store: &PixelStore, frame: &mut [u8], view: Viewport) {
         Self::render_from_cache(store, frame, view);
     }
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, VecDeque};
+use crate::core::color::Color;
+use crate::core::layer::Layer;
+use crate::core::layer_effect::{resolve_effects, EffectCacheEntry, LayerEffectKind};
+use crate::core::store::PixelStore;
+use rust_i18n::t;
+pub use crate::core::layer_effect::Connectivity;
+
+// 以不透明像素 (alpha > 0) 的形状为依据的像素画效果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelEffect {
+    // inside 为 false 时描在形状外侧的透明像素上，为 true 时替换形状边缘的像素
+    Outline { color: Color, inside: bool, connectivity: Connectivity },
+    // 形状平移 (dx, dy) 后落在透明像素上的部分
+    DropShadow { color: Color, dx: i32, dy: i32 },
+    // 朝向光源 (dx, dy) 一侧的边缘像素
+    InnerHighlight { color: Color, dx: i32, dy: i32 },
+    // 没有任何不透明邻居的孤立像素变为透明
+    RemoveOrphans { connectivity: Connectivity },
+    // 与画面边缘不连通的透明区域
+    FillHoles { color: Color, connectivity: Connectivity },
+}
+
+impl PixelEffect {
+    // 各效果的默认参数，color 为初始颜色
+    pub fn defaults(color: Color) -> [PixelEffect; 5] {
+        [
+            PixelEffect::Outline { color, inside: false, connectivity: Connectivity::Four },
+            PixelEffect::DropShadow { color, dx: 1, dy: 1 },
+            PixelEffect::InnerHighlight { color, dx: -1, dy: -1 },
+            PixelEffect::RemoveOrphans { connectivity: Connectivity::Eight },
+            PixelEffect::FillHoles { color, connectivity: Connectivity::Four },
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            PixelEffect::Outline { .. } => t!("effect.outline").to_string(),
+            PixelEffect::DropShadow { .. } => t!("effect.drop_shadow").to_string(),
+            PixelEffect::InnerHighlight { .. } => t!("effect.inner_highlight").to_string(),
+            PixelEffect::RemoveOrphans { .. } => t!("effect.remove_orphans").to_string(),
+            PixelEffect::FillHoles { .. } => t!("effect.fill_holes").to_string(),
+        }
+    }
+
+    // 返回处理后的新图像，尺寸不变 (超出边界的描边、投影被裁掉)
+    pub fn apply(&self, rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let shape = Shape { rgba, width: width as i32, height: height as i32 };
+        let mut out = rgba.to_vec();
+        let mut paint = |x: i32, y: i32, c: Color| {
+            let idx = ((y * width as i32 + x) * 4) as usize;
+            out[idx..idx + 4].copy_from_slice(&[c.r, c.g, c.b, c.a]);
+        };
+        match *self {
+            PixelEffect::Outline { color, inside, connectivity } => {
+                for (x, y) in shape.coords() {
+                    // 外描边：透明且挨着形状；内描边：不透明且挨着透明 (画面外视为透明)
+                    if shape.filled(x, y) == inside && connectivity.offsets().iter().any(|&(dx, dy)| shape.filled(x + dx, y + dy) != inside) {
+                        paint(x, y, color);
+                    }
+                }
+            }
+            PixelEffect::DropShadow { color, dx, dy } => {
+                for (x, y) in shape.coords() {
+                    if !shape.filled(x, y) && shape.filled(x - dx, y - dy) { paint(x, y, color); }
+                }
+            }
+            PixelEffect::InnerHighlight { color, dx, dy } => {
+                if (dx, dy) == (0, 0) { return out; }
+                for (x, y) in shape.coords() {
+                    if shape.filled(x, y) && !shape.filled(x + dx.signum(), y + dy.signum()) { paint(x, y, color); }
+                }
+            }
+            PixelEffect::RemoveOrphans { connectivity } => {
+                for (x, y) in shape.coords() {
+                    if shape.filled(x, y) && !connectivity.offsets().iter().any(|&(dx, dy)| shape.filled(x + dx, y + dy)) {
+                        paint(x, y, Color::transparent());
+                    }
+                }
+            }
+            PixelEffect::FillHoles { color, connectivity } => {
+                let outside = shape.outside(connectivity);
+                for (x, y) in shape.coords() {
+                    if !shape.filled(x, y) && !outside[(y * shape.width + x) as usize] { paint(x, y, color); }
+                }
+            }
+        }
+        out
+    }
+}
+
+// 按顺序求值图层效果；描边、投影与 PixelEffect 相同，超出图层的部分被裁掉
+pub fn apply_layer_effects(rgba: &[u8], width: u32, height: u32, effects: &[LayerEffectKind]) -> Vec<u8> {
+    effects.iter().fold(rgba.to_vec(), |data, kind| match kind {
+        LayerEffectKind::Outline { color, connectivity } => {
+            PixelEffect::Outline { color: *color, inside: false, connectivity: *connectivity }.apply(&data, width, height)
+        }
+        LayerEffectKind::DropShadow { color, dx, dy } => PixelEffect::DropShadow { color: *color, dx: *dx, dy: *dy }.apply(&data, width, height),
+        LayerEffectKind::ColorOverlay { color, strength } => {
+            let t = strength.clamp(0.0, 1.0) * color.a as f32 / 255.0;
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            map_opaque(data, |c| Color::new(mix(c.r, color.r), mix(c.g, color.g), mix(c.b, color.b), c.a))
+        }
+        LayerEffectKind::PaletteSwap { pairs } => map_opaque(data, |c| {
+            match pairs.iter().find(|(from, _)| (from.r, from.g, from.b) == (c.r, c.g, c.b)) {
+                Some((_, to)) => Color::new(to.r, to.g, to.b, c.a),
+                None => c,
+            }
+        }),
+        LayerEffectKind::HueShift { degrees } => {
+            if *degrees == 0.0 { return data; }
+            map_opaque(data, |c| {
+                let (h, s, l) = c.to_hsl();
+                Color::from_hsl(h + degrees, s, l, c.a)
+            })
+        }
+    })
+}
+
+fn map_opaque(mut data: Vec<u8>, f: impl Fn(Color) -> Color) -> Vec<u8> {
+    for p in data.chunks_exact_mut(4).filter(|p| p[3] > 0) {
+        let c = f(Color::new(p[0], p[1], p[2], p[3]));
+        p.copy_from_slice(&[c.r, c.g, c.b, c.a]);
+    }
+    data
+}
+
+// 渲染结果的 version 取最高位加序号，与源图层的 version 区分开 (GPU 纹理按 version 判断是否重新上传)
+fn evaluate_layer(layer: &Layer, effects: &[LayerEffectKind], serial: u64) -> Layer {
+    let rgba = layer.get_rect_data(0, 0, layer.width, layer.height);
+    let mut rendered = layer.clone();
+    rendered.replace_rgba(layer.width, layer.height, &apply_layer_effects(&rgba, layer.width, layer.height, effects));
+    rendered.version = (1 << 63) | serial;
+    rendered
+}
+
+// 重新计算过期的效果缓存：源图层 version 或解析后的效果参数变化时才求值
+pub fn refresh_effect_cache(store: &mut PixelStore) {
+    let mut cache = std::mem::take(&mut store.effect_cache);
+    let mut serial = store.effect_cache_serial;
+    cache.retain(|id, _| store.layers.iter().any(|l| &l.id == id && l.effects.iter().any(|e| e.enabled)));
+    for layer in store.layers.iter().filter(|l| l.visible) {
+        let effects = resolve_effects(&layer.effects, &store.effect_amounts);
+        if effects.is_empty() { continue; }
+        let source = store.source_layer(layer);
+        if cache.get(&layer.id).is_some_and(|e| e.source_version == source.version && e.effects == effects) { continue; }
+        serial += 1;
+        let rendered = evaluate_layer(source, &effects, serial);
+        cache.insert(layer.id.clone(), EffectCacheEntry { source_version: source.version, effects, layer: rendered });
+    }
+    store.effect_cache = cache;
+    store.effect_cache_serial = serial;
+}
+
+// 不经过缓存的合成 (烘焙、洋葱皮) 按给定的动画数值求值各可见图层，结果与缓存一致时直接复用
+pub fn evaluate_layers(store: &PixelStore, amounts: &HashMap<String, f32>) -> HashMap<String, Layer> {
+    store.layers.iter().filter(|l| l.visible).filter_map(|layer| {
+        let effects = resolve_effects(&layer.effects, amounts);
+        if effects.is_empty() { return None; }
+        let rendered = match store.effect_cache.get(&layer.id) {
+            Some(e) if e.source_version == layer.version && e.effects == effects => e.layer.clone(),
+            _ => evaluate_layer(layer, &effects, 0),
+        };
+        Some((layer.id.clone(), rendered))
+    }).collect()
+}
+
+struct Shape<'a> {
+    rgba: &'a [u8],
+    width: i32,
+    height: i32,
+}
+
+impl Shape<'_> {
+    fn coords(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (w, h) = (self.width, self.height);
+        (0..h).flat_map(move |y| (0..w).map(move |x| (x, y)))
+    }
+
+    fn filled(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height { return false; }
+        self.rgba[((y * self.width + x) * 4 + 3) as usize] > 0
+    }
+
+    // 从画面边缘出发能到达的透明像素
+    fn outside(&self, connectivity: Connectivity) -> Vec<bool> {
+        let mut reached = vec![false; (self.width * self.height) as usize];
+        let mut queue: VecDeque<(i32, i32)> = self.coords()
+            .filter(|&(x, y)| (x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1) && !self.filled(x, y))
+            .collect();
+        for &(x, y) in &queue { reached[(y * self.width + x) as usize] = true; }
+        while let Some((x, y)) = queue.pop_front() {
+            for &(dx, dy) in connectivity.offsets() {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.width || ny >= self.height || self.filled(nx, ny) { continue; }
+                let idx = (ny * self.width + nx) as usize;
+                if !reached[idx] {
+                    reached[idx] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INK: Color = Color { r: 9, g: 9, b: 9, a: 255 };
+
+    // '#' 为不透明，'.' 为透明
+    fn parse(rows: &[&str]) -> (Vec<u8>, u32, u32) {
+        let rgba = rows.iter().flat_map(|r| r.chars()).flat_map(|c| if c == '#' { [200, 50, 50, 255] } else { [0; 4] }).collect();
+        (rgba, rows[0].len() as u32, rows.len() as u32)
+    }
+
+    // 'o' 为效果颜色 INK，'#' 为原像素，'.' 为透明
+    fn render(rgba: &[u8], width: u32) -> Vec<String> {
+        rgba.chunks_exact(4).collect::<Vec<_>>().chunks(width as usize).map(|row| {
+            row.iter().map(|p| match p { [0, 0, 0, 0] => '.', [9, 9, 9, 255] => 'o', _ => '#' }).collect()
+        }).collect()
+    }
+
+    fn run(effect: PixelEffect, rows: &[&str]) -> Vec<String> {
+        let (rgba, w, h) = parse(rows);
+        render(&effect.apply(&rgba, w, h), w)
+    }
+
+    #[test]
+    fn test_outline_outside_and_inside() {
+        let sprite = [".....", ".....", "..#..", ".....", "....."];
+        assert_eq!(run(PixelEffect::Outline { color: INK, inside: false, connectivity: Connectivity::Four }, &sprite), [".....", "..o..", ".o#o.", "..o..", "....."]);
+        assert_eq!(run(PixelEffect::Outline { color: INK, inside: false, connectivity: Connectivity::Eight }, &sprite), [".....", ".ooo.", ".o#o.", ".ooo.", "....."]);
+        let block = ["###", "###", "###"];
+        assert_eq!(run(PixelEffect::Outline { color: INK, inside: true, connectivity: Connectivity::Four }, &block), ["ooo", "o#o", "ooo"], "画面外视为透明");
+    }
+
+    #[test]
+    fn test_shadow_and_highlight() {
+        let sprite = ["##..", "##..", "....", "...."];
+        assert_eq!(run(PixelEffect::DropShadow { color: INK, dx: 1, dy: 1 }, &sprite), ["##..", "##o.", ".oo.", "...."]);
+        let block = ["....", ".##.", ".##.", "...."];
+        assert_eq!(run(PixelEffect::InnerHighlight { color: INK, dx: -1, dy: -1 }, &block), ["....", ".oo.", ".o#.", "...."]);
+    }
+
+    #[test]
+    fn test_layer_effect_stack() {
+        let (rgba, w, h) = parse(&["...", ".#.", "..."]);
+        let stack = [
+            LayerEffectKind::ColorOverlay { color: Color::new(0, 0, 0, 255), strength: 0.5 },
+            LayerEffectKind::Outline { color: INK, connectivity: Connectivity::Four },
+        ];
+        let out = apply_layer_effects(&rgba, w, h, &stack);
+        assert_eq!(&out[16..20], &[100, 25, 25, 255], "叠加一半黑色");
+        assert_eq!(render(&out, w), [".o.", "o#o", ".o."]);
+
+        let swapped = apply_layer_effects(&rgba, w, h, &[LayerEffectKind::PaletteSwap { pairs: vec![(Color::new(200, 50, 50, 255), INK)] }]);
+        assert_eq!(render(&swapped, w)[1], ".o.");
+    }
+
+    #[test]
+    fn test_orphans_and_holes() {
+        assert_eq!(run(PixelEffect::RemoveOrphans { connectivity: Connectivity::Four }, &["#...", "....", "..##", "#..#"]), ["....", "....", "..##", "...#"]);
+        assert_eq!(run(PixelEffect::RemoveOrphans { connectivity: Connectivity::Eight }, &["#...", ".#..", "....", "...#"]), ["#...", ".#..", "....", "...."]);
+
+        let ring = [".....", ".###.", ".#.#.", ".###.", "....."];
+        assert_eq!(run(PixelEffect::FillHoles { color: INK, connectivity: Connectivity::Four }, &ring), [".....", ".###.", ".#o#.", ".###.", "....."]);
+        // 缺了一角的环：8 邻域下内部与外部相通
+        let open = [".....", ".##..", ".#.#.", "..##.", "....."];
+        assert_eq!(run(PixelEffect::FillHoles { color: INK, connectivity: Connectivity::Four }, &open)[2], ".#o#.");
+        assert_eq!(run(PixelEffect::FillHoles { color: INK, connectivity: Connectivity::Eight }, &open)[2], ".#.#.");
+    }
+}
@@ -0,0 +1,241 @@
+use rayon::prelude::*;
+use crate::core::color::Color;
+use crate::core::layer::{Layer, CHUNK_SIZE};
+use crate::render::quantize::{self, QuantizeSettings};
+use rust_i18n::t;
+
+// 图像调整。数值参数的范围见各字段注释，全部为 0 时 (或默认值) 不改变图像
+#[derive(Debug, Clone, PartialEq)]
+pub enum Adjustment {
+    // hue: -180..180 度，saturation / lightness: -1..1
+    HueSaturation { hue: f32, saturation: f32, lightness: f32 },
+    // 均为 -1..1
+    BrightnessContrast { brightness: f32, contrast: f32 },
+    // 输入黑白场之间按 gamma 映射到输出黑白场
+    Levels { in_black: u8, in_white: u8, gamma: f32, out_black: u8, out_white: u8 },
+    // RGB 曲线控制点 (输入, 输出)，点之间线性插值，两端之外保持端点的值
+    Curves(Vec<(u8, u8)>),
+    Invert,
+    Desaturate,
+    // 每个通道的色阶数，至少 2
+    Posterize { levels: u8 },
+    // 暗部、中间调、高光各自的 RGB 偏移，-1..1
+    ColorBalance { shadows: [f32; 3], midtones: [f32; 3], highlights: [f32; 3] },
+}
+
+impl Adjustment {
+    // 各调整的默认参数，用于打开对话框时的初始值
+    pub fn defaults() -> Vec<Adjustment> {
+        vec![
+            Adjustment::HueSaturation { hue: 0.0, saturation: 0.0, lightness: 0.0 },
+            Adjustment::BrightnessContrast { brightness: 0.0, contrast: 0.0 },
+            Adjustment::Levels { in_black: 0, in_white: 255, gamma: 1.0, out_black: 0, out_white: 255 },
+            Adjustment::Curves(vec![(0, 0), (64, 64), (192, 192), (255, 255)]),
+            Adjustment::Invert,
+            Adjustment::Desaturate,
+            Adjustment::Posterize { levels: 4 },
+            Adjustment::ColorBalance { shadows: [0.0; 3], midtones: [0.0; 3], highlights: [0.0; 3] },
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Adjustment::HueSaturation { .. } => t!("filter.hue_saturation").to_string(),
+            Adjustment::BrightnessContrast { .. } => t!("filter.brightness_contrast").to_string(),
+            Adjustment::Levels { .. } => t!("filter.levels").to_string(),
+            Adjustment::Curves(_) => t!("filter.curves").to_string(),
+            Adjustment::Invert => t!("filter.invert").to_string(),
+            Adjustment::Desaturate => t!("filter.desaturate").to_string(),
+            Adjustment::Posterize { .. } => t!("filter.posterize").to_string(),
+            Adjustment::ColorBalance { .. } => t!("filter.color_balance").to_string(),
+        }
+    }
+
+    // 每个通道独立映射的调整预先算成查找表
+    fn lut(&self) -> Option<[u8; 256]> {
+        let map = |f: &dyn Fn(f32) -> f32| {
+            let mut lut = [0u8; 256];
+            for (i, v) in lut.iter_mut().enumerate() { *v = f(i as f32).round().clamp(0.0, 255.0) as u8; }
+            lut
+        };
+        match self {
+            Adjustment::BrightnessContrast { brightness, contrast } => {
+                let c = contrast.clamp(-1.0, 0.99);
+                let factor = (1.0 + c) / (1.0 - c);
+                Some(map(&|v| (v - 127.5) * factor + 127.5 + brightness * 255.0))
+            }
+            Adjustment::Levels { in_black, in_white, gamma, out_black, out_white } => {
+                let (ib, iw) = (*in_black as f32, (*in_white as f32).max(*in_black as f32 + 1.0));
+                let (ob, ow) = (*out_black as f32, *out_white as f32);
+                let gamma = gamma.max(0.01);
+                Some(map(&|v| ob + ((v - ib) / (iw - ib)).clamp(0.0, 1.0).powf(1.0 / gamma) * (ow - ob)))
+            }
+            Adjustment::Curves(points) => {
+                let mut points = points.clone();
+                points.sort_by_key(|p| p.0);
+                points.dedup_by_key(|p| p.0);
+                if points.is_empty() { return Some(map(&|v| v)); }
+                Some(map(&|v| {
+                    let i = points.partition_point(|p| (p.0 as f32) < v);
+                    if i == 0 { return points[0].1 as f32; }
+                    if i == points.len() { return points[i - 1].1 as f32; }
+                    let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
+                    let t = (v - x0 as f32) / (x1 as f32 - x0 as f32);
+                    y0 as f32 + (y1 as f32 - y0 as f32) * t
+                }))
+            }
+            Adjustment::Invert => Some(map(&|v| 255.0 - v)),
+            Adjustment::Posterize { levels } => {
+                let steps = (*levels).max(2) as f32 - 1.0;
+                Some(map(&|v| (v / 255.0 * steps).round() * 255.0 / steps))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterSettings {
+    pub adjustment: Adjustment,
+    // 调整后再映射到调色板 (抖动也在这一步进行)
+    pub snap_to_palette: Option<QuantizeSettings>,
+}
+
+// 预先计算好的逐像素变换
+struct PixelOp<'a> {
+    adjustment: &'a Adjustment,
+    lut: Option<[u8; 256]>,
+}
+
+impl PixelOp<'_> {
+    fn apply(&self, px: &mut [u8]) {
+        if let Some(lut) = &self.lut {
+            for c in &mut px[..3] { *c = lut[*c as usize]; }
+            return;
+        }
+        let color = Color::new(px[0], px[1], px[2], px[3]);
+        let out = match self.adjustment {
+            Adjustment::HueSaturation { hue, saturation, lightness } => {
+                let (h, s, l) = color.to_hsl();
+                let l = if *lightness >= 0.0 { l + (1.0 - l) * lightness } else { l * (1.0 + lightness) };
+                Color::from_hsl(h + hue, s * (1.0 + saturation), l, color.a)
+            }
+            Adjustment::Desaturate => {
+                let y = color.luminance().round() as u8;
+                Color::new(y, y, y, color.a)
+            }
+            Adjustment::ColorBalance { shadows, midtones, highlights } => {
+                // 按亮度用二次 Bernstein 基分配三段的权重，三者之和为 1
+                let l = color.luminance() / 255.0;
+                let (ws, wm, wh) = ((1.0 - l) * (1.0 - l), 2.0 * l * (1.0 - l), l * l);
+                let shift = |v: u8, c: usize| {
+                    let delta = (ws * shadows[c] + wm * midtones[c] + wh * highlights[c]) * 100.0;
+                    (v as f32 + delta).round().clamp(0.0, 255.0) as u8
+                };
+                Color::new(shift(color.r, 0), shift(color.g, 1), shift(color.b, 2), color.a)
+            }
+            _ => color,
+        };
+        px.copy_from_slice(&[out.r, out.g, out.b, out.a]);
+    }
+}
+
+// 对图层的所有分块并行应用调整，透明像素保持不变。
+// mask 为图层坐标下逐像素的选中标记 (长度 width * height)，None 时处理整个图层
+pub fn apply_adjustment(layer: &mut Layer, mask: Option<&[bool]>, adjustment: &Adjustment) {
+    let op = PixelOp { adjustment, lut: adjustment.lut() };
+    let (width, height) = (layer.width, layer.height);
+    layer.chunks.par_iter_mut().for_each(|(&(cx, cy), chunk)| {
+        let data = chunk.data_mut();
+        for ly in 0..CHUNK_SIZE {
+            let y = cy * CHUNK_SIZE + ly;
+            if y >= height { break; }
+            for lx in 0..CHUNK_SIZE {
+                let x = cx * CHUNK_SIZE + lx;
+                if x >= width { break; }
+                let idx = ((ly * CHUNK_SIZE + lx) * 4) as usize;
+                if data[idx + 3] == 0 || mask.is_some_and(|m| !m[(y * width + x) as usize]) { continue; }
+                op.apply(&mut data[idx..idx + 4]);
+            }
+        }
+    });
+    layer.version += 1;
+}
+
+// 调整并 (可选) 映射到调色板。映射只作用于 bounds (x, y, w, h) 之内，抖动需要按顺序扫描，不并行
+pub fn apply_filter(layer: &mut Layer, mask: Option<&[bool]>, bounds: (u32, u32, u32, u32), settings: &FilterSettings, palette: &[Color]) {
+    apply_adjustment(layer, mask, &settings.adjustment);
+    let Some(quantize_settings) = settings.snap_to_palette else { return };
+    let (x, y, w, h) = bounds;
+    if w == 0 || h == 0 || palette.is_empty() { return; }
+    let mut data = layer.get_rect_data(x, y, w, h);
+    let region_mask: Option<Vec<bool>> = mask.map(|m| {
+        (0..h).flat_map(|row| (0..w).map(move |col| (col, row))).map(|(col, row)| m[((y + row) * layer.width + x + col) as usize]).collect()
+    });
+    quantize::remap_to_palette(&mut data, w, h, region_mask.as_deref(), palette, quantize_settings);
+    layer.set_rect_data(x, y, w, h, &data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer_with(pixels: &[Color]) -> Layer {
+        let mut layer = Layer::new("l".into(), "l".into(), pixels.len() as u32, 1);
+        for (x, c) in pixels.iter().enumerate() { layer.set_pixel(x as u32, 0, *c).unwrap(); }
+        layer
+    }
+
+    fn pixels(layer: &Layer) -> Vec<Color> {
+        (0..layer.width).map(|x| layer.get_pixel(x, 0).unwrap_or(Color::transparent())).collect()
+    }
+
+    #[test]
+    fn test_identity_defaults() {
+        let colors = [Color::new(10, 200, 30, 255), Color::new(90, 90, 90, 128), Color::new(250, 5, 128, 255)];
+        for adjustment in Adjustment::defaults() {
+            if matches!(adjustment, Adjustment::Invert | Adjustment::Desaturate | Adjustment::Posterize { .. }) { continue; }
+            let mut layer = layer_with(&colors);
+            apply_adjustment(&mut layer, None, &adjustment);
+            assert_eq!(pixels(&layer), colors, "{:?} 的默认参数不应改变图像", adjustment);
+        }
+    }
+
+    #[test]
+    fn test_basic_adjustments() {
+        let base = [Color::new(200, 100, 0, 255), Color::new(0, 0, 0, 0)];
+        let run = |adjustment: Adjustment| {
+            let mut layer = layer_with(&base);
+            apply_adjustment(&mut layer, None, &adjustment);
+            pixels(&layer)
+        };
+        assert_eq!(run(Adjustment::Invert)[0], Color::new(55, 155, 255, 255));
+        assert_eq!(run(Adjustment::Invert)[1].a, 0, "透明像素不变");
+        assert_eq!(run(Adjustment::Desaturate)[0], Color::new(119, 119, 119, 255));
+        assert_eq!(run(Adjustment::Posterize { levels: 2 })[0], Color::new(255, 0, 0, 255));
+        assert_eq!(run(Adjustment::HueSaturation { hue: 120.0, saturation: 0.0, lightness: 0.0 })[0], Color::new(0, 200, 100, 255));
+        assert_eq!(run(Adjustment::HueSaturation { hue: 0.0, saturation: -1.0, lightness: 0.0 })[0], Color::new(100, 100, 100, 255));
+        assert_eq!(run(Adjustment::BrightnessContrast { brightness: 0.2, contrast: 0.0 })[0], Color::new(251, 151, 51, 255));
+        assert_eq!(run(Adjustment::Levels { in_black: 0, in_white: 200, gamma: 1.0, out_black: 0, out_white: 255 })[0], Color::new(255, 128, 0, 255));
+        assert_eq!(run(Adjustment::Curves(vec![(0, 255), (255, 0)]))[0], Color::new(55, 155, 255, 255), "反向曲线等同反相");
+        let warm = run(Adjustment::ColorBalance { shadows: [0.0; 3], midtones: [0.5, 0.0, -0.5], highlights: [0.0; 3] })[0];
+        assert!(warm.r > 200 && warm.b == 0 && warm.g == 100);
+    }
+
+    #[test]
+    fn test_mask_and_snap_to_palette() {
+        // 跨越多个分块的图层，只处理左半部分
+        let width = CHUNK_SIZE * 2;
+        let mut layer = Layer::new("l".into(), "l".into(), width, 2);
+        for x in 0..width { for y in 0..2 { layer.set_pixel(x, y, Color::new(100, 100, 100, 255)).unwrap(); } }
+        let mask: Vec<bool> = (0..2).flat_map(|_| (0..width).map(|x| x < CHUNK_SIZE + 1)).collect();
+        let settings = FilterSettings {
+            adjustment: Adjustment::BrightnessContrast { brightness: 0.3, contrast: 0.0 },
+            snap_to_palette: Some(QuantizeSettings::default()),
+        };
+        let palette = [Color::new(0, 0, 0, 255), Color::new(255, 255, 255, 255)];
+        apply_filter(&mut layer, Some(&mask), (0, 0, width, 2), &settings, &palette);
+        assert_eq!(layer.get_pixel(CHUNK_SIZE, 1), Some(Color::new(255, 255, 255, 255)), "选中部分调亮后映射为白色");
+        assert_eq!(layer.get_pixel(CHUNK_SIZE + 1, 0), Some(Color::new(100, 100, 100, 255)), "未选中部分不变");
+    }
+}
@@ -1,4 +1,10 @@
 pub mod blend;
 pub mod compositor;
+pub mod resample;
+pub mod upscale;
+pub mod pixel_grid;
+pub mod quantize;
+pub mod filters;
+pub mod effects;
 pub mod anim_compositor;
 pub mod texture_manager;
\ No newline at end of file
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+// 量化后的颜色 (透明为 None) -> (票数, 各通道之和)
+type Votes = HashMap<Option<(u8, u8, u8)>, (u32, [u32; 4])>;
+
+// 放大过的"伪像素画"的原生网格：每个大像素占 cell x cell，网格线位于 offset + k * cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelGrid {
+    pub cell: u32,
+    pub offset_x: u32,
+    pub offset_y: u32,
+}
+
+impl PixelGrid {
+    // 边缘不完整的格子也算一个像素
+    pub fn output_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let count = |len: u32, offset: u32| {
+            let lead = if offset.is_multiple_of(self.cell) { 0 } else { 1 };
+            lead + len.saturating_sub(offset % self.cell).div_ceil(self.cell)
+        };
+        (count(width, self.offset_x), count(height, self.offset_y))
+    }
+}
+
+fn pixel(rgba: &[u8], width: u32, x: u32, y: u32) -> &[u8] {
+    let idx = ((y * width + x) * 4) as usize;
+    &rgba[idx..idx + 4]
+}
+
+// profile[i] 为第 i - 1 与第 i 列 (或行) 之间所有像素差之和，profile[0] 恒为 0
+fn edge_profile(rgba: &[u8], width: u32, height: u32, columns: bool) -> Vec<f32> {
+    let (len, span) = if columns { (width, height) } else { (height, width) };
+    let mut profile = vec![0.0; len as usize];
+    for (i, value) in profile.iter_mut().enumerate().skip(1) {
+        let i = i as u32;
+        for j in 0..span {
+            let (a, b) = if columns { (pixel(rgba, width, i - 1, j), pixel(rgba, width, i, j)) } else { (pixel(rgba, width, j, i - 1), pixel(rgba, width, j, i)) };
+            *value += a.iter().zip(b).map(|(&p, &q)| (p as i32 - q as i32).unsigned_abs()).sum::<u32>() as f32;
+        }
+    }
+    profile
+}
+
+// 对给定格子大小找最佳网格线位置，返回 (offset, 网格线上平均差值, 其余位置平均差值)
+fn best_offset(profile: &[f32], cell: u32) -> (u32, f32, f32) {
+    let mut best = (0, 0.0, 0.0);
+    let mut best_score = f32::MIN;
+    for offset in 0..cell {
+        let (mut on, mut on_count, mut off, mut off_count) = (0.0, 0, 0.0, 0);
+        for (i, &v) in profile.iter().enumerate().skip(1) {
+            if i as u32 % cell == offset { on += v; on_count += 1; } else { off += v; off_count += 1; }
+        }
+        if on_count == 0 || off_count == 0 { continue; }
+        let (on, off) = (on / on_count as f32, off / off_count as f32);
+        if on - off > best_score {
+            best_score = on - off;
+            best = (offset, on, off);
+        }
+    }
+    best
+}
+
+// 在相邻像素差的分布中寻找周期：真实网格线处差异明显，格子内部几乎没有差异。
+// 格子的整数倍与约数都会被较低的得分排除；找不到明显周期时返回 None
+pub fn detect_grid(rgba: &[u8], width: u32, height: u32) -> Option<PixelGrid> {
+    // 至少要有两个完整的格子才能看出周期
+    let max_cell = (width.min(height) / 2).min(64);
+    if max_cell < 2 { return None; }
+    let columns = edge_profile(rgba, width, height, true);
+    let rows = edge_profile(rgba, width, height, false);
+
+    let mut best: Option<(PixelGrid, f32)> = None;
+    for cell in 2..=max_cell {
+        let (offset_x, on_x, off_x) = best_offset(&columns, cell);
+        let (offset_y, on_y, off_y) = best_offset(&rows, cell);
+        // 两个方向上网格线处的差异都要显著高于格子内部
+        if on_x < off_x * 3.0 + 1.0 || on_y < off_y * 3.0 + 1.0 { continue; }
+        let score = (on_x - off_x) + (on_y - off_y);
+        if best.is_none_or(|(_, s)| score > s) {
+            best = Some((PixelGrid { cell, offset_x, offset_y }, score));
+        }
+    }
+    best.map(|(grid, _)| grid)
+}
+
+// 每个格子内按多数颜色取样 (颜色先量化到 5 位再计票，票数最多的一组取平均)，半数以上透明则为透明
+pub fn snap_to_grid(rgba: &[u8], width: u32, height: u32, grid: PixelGrid) -> (Vec<u8>, u32, u32) {
+    let cell = grid.cell.max(1);
+    let (out_w, out_h) = PixelGrid { cell, ..grid }.output_size(width, height);
+    let start = |offset: u32| if offset.is_multiple_of(cell) { 0 } else { offset as i32 % cell as i32 - cell as i32 };
+    let (start_x, start_y) = (start(grid.offset_x), start(grid.offset_y));
+    let mut out = vec![0u8; (out_w * out_h * 4) as usize];
+
+    for gy in 0..out_h {
+        for gx in 0..out_w {
+            let x0 = (start_x + (gx * cell) as i32).max(0) as u32;
+            let y0 = (start_y + (gy * cell) as i32).max(0) as u32;
+            let x1 = ((start_x + ((gx + 1) * cell) as i32).max(0) as u32).min(width);
+            let y1 = ((start_y + ((gy + 1) * cell) as i32).max(0) as u32).min(height);
+
+            let mut votes = Votes::new();
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = pixel(rgba, width, x, y);
+                    let key = if p[3] < 128 { None } else { Some((p[0] >> 3, p[1] >> 3, p[2] >> 3)) };
+                    let entry = votes.entry(key).or_insert((0, [0; 4]));
+                    entry.0 += 1;
+                    for (sum, &v) in entry.1.iter_mut().zip(p) { *sum += v as u32; }
+                }
+            }
+            // 票数相同时不透明优先 (恰好一半透明的格子保留颜色)，再按颜色排序，保证结果稳定
+            let winner = votes.into_iter().max_by(|a, b| a.1.0.cmp(&b.1.0).then(a.0.is_some().cmp(&b.0.is_some())).then(b.0.cmp(&a.0)));
+            if let Some((Some(_), (count, sum))) = winner {
+                let idx = ((gy * out_w + gx) * 4) as usize;
+                for c in 0..4 { out[idx + c] = ((sum[c] + count / 2) / count) as u8; }
+            }
+        }
+    }
+    (out, out_w, out_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 4x3 的原图按 cell 放大，并在左上方裁掉 (crop, crop) 模拟网格偏移
+    fn fake_pixel_art(cell: u32, crop: u32) -> (Vec<u8>, u32, u32, Vec<[u8; 4]>) {
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255], [0, 0, 0, 0]];
+        let src: Vec<[u8; 4]> = (0..12).map(|i| colors[(i * 7 + i / 4) % 4]).collect();
+        let (w, h) = (4 * cell - crop, 3 * cell - crop);
+        let mut rgba = Vec::new();
+        for y in 0..h {
+            for x in 0..w {
+                let (sx, sy) = ((x + crop) / cell, (y + crop) / cell);
+                rgba.extend_from_slice(&src[(sy * 4 + sx) as usize]);
+            }
+        }
+        (rgba, w, h, src)
+    }
+
+    #[test]
+    fn test_detect_and_snap_grid() {
+        let (rgba, w, h, src) = fake_pixel_art(5, 0);
+        let grid = detect_grid(&rgba, w, h).unwrap();
+        assert_eq!(grid, PixelGrid { cell: 5, offset_x: 0, offset_y: 0 });
+        let (out, ow, oh) = snap_to_grid(&rgba, w, h, grid);
+        assert_eq!((ow, oh), (4, 3));
+        assert_eq!(out, src.concat(), "还原为原始像素");
+
+        // 左上角裁掉 2 像素：网格线位于 3 + 5k
+        let (rgba, w, h, _) = fake_pixel_art(5, 2);
+        let grid = detect_grid(&rgba, w, h).unwrap();
+        assert_eq!(grid, PixelGrid { cell: 5, offset_x: 3, offset_y: 3 });
+        assert_eq!(grid.output_size(w, h), (4, 3));
+    }
+
+    #[test]
+    fn test_snap_majority_ignores_noise() {
+        let (mut rgba, w, h, src) = fake_pixel_art(4, 0);
+        // 每个格子里污染一个像素
+        for y in (1..h).step_by(4) {
+            for x in (2..w).step_by(4) {
+                let idx = ((y * w + x) * 4) as usize;
+                rgba[idx..idx + 4].copy_from_slice(&[255, 255, 0, 255]);
+            }
+        }
+        let (out, _, _) = snap_to_grid(&rgba, w, h, PixelGrid { cell: 4, offset_x: 0, offset_y: 0 });
+        assert_eq!(out, src.concat());
+    }
+
+    #[test]
+    fn test_snap_half_transparent_cell_stays_opaque() {
+        // 2x2 的格子上半透明、下半红色：透明不过半，保留红色
+        let rgba = [[0, 0, 0, 0], [0, 0, 0, 0], [255, 0, 0, 255], [255, 0, 0, 255]].concat();
+        let (out, _, _) = snap_to_grid(&rgba, 2, 2, PixelGrid { cell: 2, offset_x: 0, offset_y: 0 });
+        assert_eq!(out, vec![255, 0, 0, 255]);
+
+        let rgba = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [255, 0, 0, 255]].concat();
+        let (out, _, _) = snap_to_grid(&rgba, 2, 2, PixelGrid { cell: 2, offset_x: 0, offset_y: 0 });
+        assert_eq!(out, vec![0; 4], "透明过半则为透明");
+    }
+
+    #[test]
+    fn test_detect_grid_rejects_native_art() {
+        // 棋盘格：每个像素都与邻居不同，没有放大痕迹
+        let rgba: Vec<u8> = (0..16 * 16).flat_map(|i| if (i % 16 + i / 16) % 2 == 0 { [255, 255, 255, 255] } else { [0, 0, 0, 255] }).collect();
+        assert_eq!(detect_grid(&rgba, 16, 16), None);
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use crate::core::color::Color;
+use rust_i18n::t;
+
+// 找最接近的调色板颜色时使用的距离
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMetric {
+    Rgb,
+    // "redmean" 加权 RGB，计算量小且比直接的 RGB 距离更接近人眼感受
+    Weighted,
+    OkLab,
+    Ciede2000,
+}
+
+impl ColorMetric {
+    pub const ALL: [ColorMetric; 4] = [ColorMetric::Rgb, ColorMetric::Weighted, ColorMetric::OkLab, ColorMetric::Ciede2000];
+
+    pub fn label(&self) -> String {
+        match self {
+            ColorMetric::Rgb => t!("quantize.metric_rgb").to_string(),
+            ColorMetric::Weighted => t!("quantize.metric_weighted").to_string(),
+            ColorMetric::OkLab => t!("quantize.metric_oklab").to_string(),
+            ColorMetric::Ciede2000 => t!("quantize.metric_ciede2000").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    None,
+    FloydSteinberg,
+    // 4x4 Bayer 矩阵
+    Ordered,
+}
+
+impl Dither {
+    pub const ALL: [Dither; 3] = [Dither::None, Dither::FloydSteinberg, Dither::Ordered];
+
+    pub fn label(&self) -> String {
+        match self {
+            Dither::None => t!("quantize.dither_none").to_string(),
+            Dither::FloydSteinberg => t!("quantize.dither_floyd_steinberg").to_string(),
+            Dither::Ordered => t!("quantize.dither_ordered").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantizeSettings {
+    pub metric: ColorMetric,
+    pub dither: Dither,
+    // alpha 低于此值的像素变为全透明，其余变为完全不透明
+    pub alpha_threshold: u8,
+}
+
+impl Default for QuantizeSettings {
+    fn default() -> Self {
+        Self { metric: ColorMetric::Weighted, dither: Dither::None, alpha_threshold: 128 }
+    }
+}
+
+// 从图像生成 N 色调色板的方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteGenMethod {
+    MedianCut,
+    // 以中位切分的结果为初始中心迭代
+    KMeans,
+}
+
+impl PaletteGenMethod {
+    pub const ALL: [PaletteGenMethod; 2] = [PaletteGenMethod::MedianCut, PaletteGenMethod::KMeans];
+
+    pub fn label(&self) -> String {
+        match self {
+            PaletteGenMethod::MedianCut => t!("quantize.method_median_cut").to_string(),
+            PaletteGenMethod::KMeans => t!("quantize.method_kmeans").to_string(),
+        }
+    }
+}
+
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+// 有序抖动在 0..255 空间中的扰动幅度
+const ORDERED_SPREAD: f32 = 32.0;
+const KMEANS_ITERATIONS: usize = 16;
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+// CIE L*a*b*，D65 白点
+fn to_cielab(rgb: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+    let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b;
+    let z = (0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b) / 1.088_83;
+    let f = |t: f32| if t > 0.008_856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+// 两个 L*a*b* 颜色之间的 ΔE00 (kL = kC = kH = 1)
+pub fn ciede2000(lab1: [f32; 3], lab2: [f32; 3]) -> f32 {
+    let [l1, a1, b1] = lab1.map(|v| v as f64);
+    let [l2, a2, b2] = lab2.map(|v| v as f64);
+    let c_bar = ((a1 * a1 + b1 * b1).sqrt() + (a2 * a2 + b2 * b2).sqrt()) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+    let (a1p, a2p) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1p, c2p) = ((a1p * a1p + b1 * b1).sqrt(), (a2p * a2p + b2 * b2).sqrt());
+    let hue = |b: f64, a: f64| if b == 0.0 && a == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    let (h1p, h2p) = (hue(b1, a1p), hue(b2, a2p));
+
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let dh_big = 2.0 * (c1p * c2p).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos() + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos() - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_p7 = c_bar_p.powi(7);
+    let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + 25f64.powi(7))).sqrt();
+    let l50 = (l_bar - 50.0).powi(2);
+    let s_l = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_p;
+    let s_h = 1.0 + 0.015 * c_bar_p * t;
+    let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+    let (tl, tc, th) = (dl / s_l, dc / s_c, dh_big / s_h);
+    (tl * tl + tc * tc + th * th + r_t * tc * th).sqrt() as f32
+}
+
+// 调色板预先转换到度量空间，查询结果按 RGB 缓存
+struct Matcher {
+    metric: ColorMetric,
+    points: Vec<[f32; 3]>,
+    cache: HashMap<[u8; 3], usize>,
+}
+
+impl Matcher {
+    fn new(palette: &[Color], metric: ColorMetric) -> Self {
+        let points = palette.iter().map(|c| Self::project(metric, [c.r, c.g, c.b])).collect();
+        Self { metric, points, cache: HashMap::new() }
+    }
+
+    fn project(metric: ColorMetric, rgb: [u8; 3]) -> [f32; 3] {
+        match metric {
+            ColorMetric::Rgb | ColorMetric::Weighted => rgb.map(|c| c as f32),
+            ColorMetric::OkLab => {
+                let (l, a, b) = Color::new(rgb[0], rgb[1], rgb[2], 255).to_oklab();
+                [l, a, b]
+            }
+            ColorMetric::Ciede2000 => to_cielab(rgb),
+        }
+    }
+
+    fn distance(&self, p: [f32; 3], q: [f32; 3]) -> f32 {
+        let [d0, d1, d2] = [p[0] - q[0], p[1] - q[1], p[2] - q[2]];
+        match self.metric {
+            ColorMetric::Rgb | ColorMetric::OkLab => d0 * d0 + d1 * d1 + d2 * d2,
+            ColorMetric::Weighted => {
+                let r_mean = (p[0] + q[0]) / 2.0;
+                (2.0 + r_mean / 256.0) * d0 * d0 + 4.0 * d1 * d1 + (2.0 + (255.0 - r_mean) / 256.0) * d2 * d2
+            }
+            ColorMetric::Ciede2000 => ciede2000(p, q),
+        }
+    }
+
+    fn nearest(&mut self, rgb: [u8; 3]) -> usize {
+        if let Some(&idx) = self.cache.get(&rgb) { return idx; }
+        let p = Self::project(self.metric, rgb);
+        let mut best = (0, f32::MAX);
+        for (i, &q) in self.points.iter().enumerate() {
+            let d = self.distance(p, q);
+            if d < best.1 { best = (i, d); }
+        }
+        self.cache.insert(rgb, best.0);
+        best.0
+    }
+}
+
+// 把每个像素替换为调色板中最接近的颜色 (结果完全不透明)，alpha 低于阈值的像素变为全透明。
+// mask 为 Some 时只处理为 true 的像素，Floyd–Steinberg 的误差也不会扩散到未选中的像素
+pub fn remap_to_palette(rgba: &mut [u8], width: u32, height: u32, mask: Option<&[bool]>, palette: &[Color], settings: QuantizeSettings) {
+    if palette.is_empty() { return; }
+    let (w, h) = (width as usize, height as usize);
+    let selected = |i: usize| mask.is_none_or(|m| m[i]);
+    let mut matcher = Matcher::new(palette, settings.metric);
+    let mut error = vec![[0.0f32; 3]; if settings.dither == Dither::FloydSteinberg { w * h } else { 0 }];
+
+    for y in 0..h {
+        for x in 0..w {
+            let i = y * w + x;
+            if !selected(i) { continue; }
+            let px = &mut rgba[i * 4..i * 4 + 4];
+            if px[3] < settings.alpha_threshold {
+                px.copy_from_slice(&[0; 4]);
+                continue;
+            }
+
+            let mut target = [px[0] as f32, px[1] as f32, px[2] as f32];
+            match settings.dither {
+                Dither::None => {}
+                Dither::Ordered => {
+                    let d = ((BAYER_4X4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5) * ORDERED_SPREAD;
+                    for c in &mut target { *c += d; }
+                }
+                Dither::FloydSteinberg => {
+                    for (c, e) in target.iter_mut().zip(error[i]) { *c += e; }
+                }
+            }
+            let chosen = palette[matcher.nearest(target.map(|c| c.round().clamp(0.0, 255.0) as u8))];
+            px.copy_from_slice(&[chosen.r, chosen.g, chosen.b, 255]);
+
+            if settings.dither == Dither::FloydSteinberg {
+                let err = [target[0] - chosen.r as f32, target[1] - chosen.g as f32, target[2] - chosen.b as f32];
+                for (dx, dy, weight) in [(1i32, 0usize, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+                    let (nx, ny) = (x as i32 + dx, y + dy);
+                    if nx < 0 || nx >= w as i32 || ny >= h { continue; }
+                    let j = ny * w + nx as usize;
+                    if !selected(j) { continue; }
+                    for (e, d) in error[j].iter_mut().zip(err) { *e += d * weight / 16.0; }
+                }
+            }
+        }
+    }
+}
+
+// 参与生成调色板的像素 (alpha >= 128)，按颜色计数并排序以保证结果稳定
+fn opaque_colors(rgba: &[u8], mask: Option<&[bool]>) -> Vec<([u8; 3], u32)> {
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    for (i, px) in rgba.chunks_exact(4).enumerate() {
+        if px[3] < 128 || mask.is_some_and(|m| !m[i]) { continue; }
+        *counts.entry([px[0], px[1], px[2]]).or_insert(0) += 1;
+    }
+    let mut colors: Vec<_> = counts.into_iter().collect();
+    colors.sort();
+    colors
+}
+
+fn weighted_mean(colors: &[([u8; 3], u32)]) -> [f32; 3] {
+    let mut sum = [0.0f64; 3];
+    let mut total = 0.0f64;
+    for &(c, n) in colors {
+        for (s, v) in sum.iter_mut().zip(c) { *s += v as f64 * n as f64; }
+        total += n as f64;
+    }
+    sum.map(|s| (s / total.max(1.0)) as f32)
+}
+
+fn to_color(c: [f32; 3]) -> Color {
+    let [r, g, b] = c.map(|v| v.round().clamp(0.0, 255.0) as u8);
+    Color::new(r, g, b, 255)
+}
+
+fn median_cut_boxes(colors: Vec<([u8; 3], u32)>, count: usize) -> Vec<Vec<([u8; 3], u32)>> {
+    let mut boxes = vec![colors];
+    while boxes.len() < count {
+        // 取颜色跨度最大的盒子沿最长的通道切分
+        let widest = boxes.iter().enumerate().filter(|(_, b)| b.len() > 1).map(|(i, b)| {
+            let (channel, range) = (0..3).map(|c| {
+                let (lo, hi) = b.iter().fold((255u8, 0u8), |(lo, hi), (px, _)| (lo.min(px[c]), hi.max(px[c])));
+                (c, hi - lo)
+            }).max_by_key(|&(_, range)| range).unwrap();
+            (i, channel, range)
+        }).max_by_key(|&(_, _, range)| range);
+        let Some((i, channel, _)) = widest else { break };
+
+        let mut b = boxes.swap_remove(i);
+        b.sort_by_key(|(px, _)| px[channel]);
+        // 按像素数取中位，两半都至少保留一种颜色
+        let total: u64 = b.iter().map(|&(_, n)| n as u64).sum();
+        let mut acc = 0u64;
+        let mut split = b.len() - 1;
+        for (k, &(_, n)) in b.iter().enumerate() {
+            acc += n as u64;
+            if acc * 2 >= total { split = k + 1; break; }
+        }
+        let rest = b.split_off(split.clamp(1, b.len() - 1));
+        boxes.push(b);
+        boxes.push(rest);
+    }
+    boxes
+}
+
+pub fn median_cut(rgba: &[u8], mask: Option<&[bool]>, count: usize) -> Vec<Color> {
+    let colors = opaque_colors(rgba, mask);
+    if colors.is_empty() || count == 0 { return Vec::new(); }
+    median_cut_boxes(colors, count).iter().map(|b| to_color(weighted_mean(b))).collect()
+}
+
+pub fn kmeans(rgba: &[u8], mask: Option<&[bool]>, count: usize) -> Vec<Color> {
+    let colors = opaque_colors(rgba, mask);
+    if colors.is_empty() || count == 0 { return Vec::new(); }
+    let mut centers: Vec<[f32; 3]> = median_cut_boxes(colors.clone(), count).iter().map(|b| weighted_mean(b)).collect();
+    let mut assignment = vec![usize::MAX; colors.len()];
+
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (slot, (px, _)) in assignment.iter_mut().zip(&colors) {
+            let p = px.map(|c| c as f32);
+            let nearest = centers.iter().enumerate()
+                .map(|(k, q)| (k, (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)))
+                .min_by(|a, b| a.1.total_cmp(&b.1)).map(|(k, _)| k).unwrap_or(0);
+            if *slot != nearest { *slot = nearest; changed = true; }
+        }
+        if !changed { break; }
+        let mut clusters: Vec<Vec<([u8; 3], u32)>> = vec![Vec::new(); centers.len()];
+        for (&k, &c) in assignment.iter().zip(&colors) { clusters[k].push(c); }
+        // 空的簇保留原中心
+        for (center, cluster) in centers.iter_mut().zip(&clusters) {
+            if !cluster.is_empty() { *center = weighted_mean(cluster); }
+        }
+    }
+    centers.into_iter().map(to_color).collect()
+}
+
+// 生成的颜色去重后按亮度由暗到亮排列
+pub fn generate_palette(rgba: &[u8], mask: Option<&[bool]>, count: usize, method: PaletteGenMethod) -> Vec<Color> {
+    let mut colors = match method {
+        PaletteGenMethod::MedianCut => median_cut(rgba, mask, count),
+        PaletteGenMethod::KMeans => kmeans(rgba, mask, count),
+    };
+    colors.sort_by(|a, b| a.luminance().total_cmp(&b.luminance()).then((a.r, a.g, a.b).cmp(&(b.r, b.g, b.b))));
+    colors.dedup();
+    colors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[[u8; 4]]) -> Vec<u8> {
+        pixels.concat()
+    }
+
+    #[test]
+    fn test_remap_nearest_and_alpha_threshold() {
+        let palette = [Color::new(0, 0, 0, 255), Color::new(255, 255, 255, 255), Color::new(255, 0, 0, 255)];
+        let mut rgba = image(&[[20, 10, 10, 255], [240, 230, 250, 200], [200, 40, 30, 255], [255, 255, 255, 100]]);
+        remap_to_palette(&mut rgba, 4, 1, None, &palette, QuantizeSettings::default());
+        assert_eq!(rgba, image(&[[0, 0, 0, 255], [255, 255, 255, 255], [255, 0, 0, 255], [0, 0, 0, 0]]));
+
+        // 未选中的像素保持不变
+        let mut rgba = image(&[[20, 10, 10, 255], [200, 40, 30, 255]]);
+        remap_to_palette(&mut rgba, 2, 1, Some(&[false, true]), &palette, QuantizeSettings::default());
+        assert_eq!(rgba, image(&[[20, 10, 10, 255], [255, 0, 0, 255]]));
+    }
+
+    #[test]
+    fn test_metrics_agree_on_obvious_matches() {
+        let palette = [Color::new(0, 0, 255, 255), Color::new(0, 200, 0, 255), Color::new(250, 250, 250, 255)];
+        for metric in ColorMetric::ALL {
+            let mut rgba = image(&[[10, 20, 230, 255], [30, 180, 40, 255], [230, 240, 235, 255]]);
+            remap_to_palette(&mut rgba, 3, 1, None, &palette, QuantizeSettings { metric, ..Default::default() });
+            assert_eq!(rgba, image(&[[0, 0, 255, 255], [0, 200, 0, 255], [250, 250, 250, 255]]), "{:?}", metric);
+        }
+    }
+
+    #[test]
+    fn test_ciede2000_reference_pair() {
+        // Sharma 等人论文中的测试数据第 1 组
+        let d = ciede2000([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485]);
+        assert!((d - 2.0425).abs() < 1e-3, "{}", d);
+        assert_eq!(ciede2000([50.0, 10.0, 10.0], [50.0, 10.0, 10.0]), 0.0);
+    }
+
+    #[test]
+    fn test_dithering_preserves_average() {
+        // 50% 灰映射到黑白：不抖动时全部变成同一颜色，抖动后约一半为白
+        let palette = [Color::new(0, 0, 0, 255), Color::new(255, 255, 255, 255)];
+        let gray = [128u8, 128, 128, 255].repeat(16 * 16);
+        for dither in [Dither::FloydSteinberg, Dither::Ordered] {
+            let mut rgba = gray.clone();
+            remap_to_palette(&mut rgba, 16, 16, None, &palette, QuantizeSettings { dither, metric: ColorMetric::Rgb, ..Default::default() });
+            let white = rgba.chunks_exact(4).filter(|p| p[0] == 255).count();
+            assert!((96..=160).contains(&white), "{:?}: {}", dither, white);
+        }
+        let mut rgba = gray.clone();
+        remap_to_palette(&mut rgba, 16, 16, None, &palette, QuantizeSettings { metric: ColorMetric::Rgb, ..Default::default() });
+        assert!(rgba.chunks_exact(4).all(|p| p[0] == rgba[0]));
+    }
+
+    #[test]
+    fn test_generate_palette() {
+        // 两簇颜色加少量噪声
+        let mut pixels = Vec::new();
+        for i in 0..20u8 {
+            pixels.push([200 + i % 3, 10, 10, 255]);
+            pixels.push([10, 10, 180 + i % 5, 255]);
+        }
+        pixels.push([0, 0, 0, 0]);
+        let rgba = image(&pixels);
+        for method in PaletteGenMethod::ALL {
+            let colors = generate_palette(&rgba, None, 2, method);
+            assert_eq!(colors.len(), 2, "{:?}", method);
+            assert!(colors.iter().any(|c| c.r > 190 && c.b < 20) && colors.iter().any(|c| c.b > 170 && c.r < 20), "{:?}: {:?}", method, colors);
+        }
+        assert_eq!(median_cut(&rgba, None, 100).len(), 8, "颜色种类不足时每种颜色一个盒子");
+        assert!(median_cut(&image(&[[0, 0, 0, 0]]), None, 4).is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::core::layer::{Layer, CHUNK_SIZE};
+use rust_i18n::t;
+
+// 旋转、缩放后的像素取样方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleMode {
+    // 最近邻：最快，奇数角度下线条会断裂
+    #[default]
+    Nearest,
+    // 先用 Scale2x 放大 8 倍再最近邻取样 (RotSprite 的做法)，斜线与轮廓保持连贯
+    RotSprite,
+    // 同上，改用 Scale3x 放大 9 倍，小角度下台阶更均匀
+    RotSprite3x,
+    // cleanEdge 风格：不放大图像，按子像素位置在 45° 斜边处切角
+    CleanEdge,
+}
+
+impl ResampleMode {
+    pub const ALL: [ResampleMode; 4] = [ResampleMode::Nearest, ResampleMode::RotSprite, ResampleMode::RotSprite3x, ResampleMode::CleanEdge];
+
+    pub fn label(&self) -> String {
+        match self {
+            ResampleMode::Nearest => t!("transform.resample_nearest").to_string(),
+            ResampleMode::RotSprite => t!("transform.resample_rotsprite").to_string(),
+            ResampleMode::RotSprite3x => t!("transform.resample_rotsprite3x").to_string(),
+            ResampleMode::CleanEdge => t!("transform.resample_clean_edge").to_string(),
+        }
+    }
+
+    // (放大算法的倍率, 重复次数)
+    fn upscale_passes(&self) -> (u32, u32) {
+        match self {
+            ResampleMode::RotSprite => (2, 3),
+            ResampleMode::RotSprite3x => (3, 2),
+            ResampleMode::Nearest | ResampleMode::CleanEdge => (1, 0),
+        }
+    }
+}
+
+// Scale2x (EPX)：每个像素拆成 2x2，只在相邻像素构成斜边时取邻居颜色，不产生新颜色
+pub fn scale2x(src: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let (w, h) = (width as i32, height as i32);
+    let px = |x: i32, y: i32| -> [u8; 4] {
+        let idx = ((y.clamp(0, h - 1) * w + x.clamp(0, w - 1)) * 4) as usize;
+        [src[idx], src[idx + 1], src[idx + 2], src[idx + 3]]
+    };
+    let out_w = (width * 2) as usize;
+    let mut out = vec![0u8; out_w * (height * 2) as usize * 4];
+    for y in 0..h {
+        for x in 0..w {
+            let p = px(x, y);
+            let (a, b, c, d) = (px(x, y - 1), px(x + 1, y), px(x - 1, y), px(x, y + 1));
+            let e0 = if c == a && c != d && a != b { a } else { p };
+            let e1 = if a == b && a != c && b != d { b } else { p };
+            let e2 = if d == c && d != b && c != a { c } else { p };
+            let e3 = if b == d && b != a && d != c { d } else { p };
+            for (i, e) in [e0, e1, e2, e3].iter().enumerate() {
+                let ox = (x * 2) as usize + i % 2;
+                let oy = (y * 2) as usize + i / 2;
+                let idx = (oy * out_w + ox) * 4;
+                out[idx..idx + 4].copy_from_slice(e);
+            }
+        }
+    }
+    out
+}
+
+// Scale3x (AdvMAME3x)：每个像素拆成 3x3，规则与 Scale2x 相同，边中点只在两侧斜边一致时取邻居颜色
+pub fn scale3x(src: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let (w, h) = (width as i32, height as i32);
+    let px = |x: i32, y: i32| -> [u8; 4] {
+        let idx = ((y.clamp(0, h - 1) * w + x.clamp(0, w - 1)) * 4) as usize;
+        [src[idx], src[idx + 1], src[idx + 2], src[idx + 3]]
+    };
+    let out_w = (width * 3) as usize;
+    let mut out = vec![0u8; out_w * (height * 3) as usize * 4];
+    for y in 0..h {
+        for x in 0..w {
+            let (a, b, c) = (px(x - 1, y - 1), px(x, y - 1), px(x + 1, y - 1));
+            let (d, e, f) = (px(x - 1, y), px(x, y), px(x + 1, y));
+            let (g, hh, i) = (px(x - 1, y + 1), px(x, y + 1), px(x + 1, y + 1));
+            let db = d == b && d != hh && b != f;
+            let bf = b == f && b != d && f != hh;
+            let dh = d == hh && d != b && hh != f;
+            let hf = hh == f && hh != d && f != b;
+            let cells = [
+                if db { d } else { e },
+                if (db && e != c) || (bf && e != a) { b } else { e },
+                if bf { f } else { e },
+                if (db && e != g) || (dh && e != a) { d } else { e },
+                e,
+                if (bf && e != i) || (hf && e != c) { f } else { e },
+                if dh { d } else { e },
+                if (dh && e != i) || (hf && e != g) { hh } else { e },
+                if hf { f } else { e },
+            ];
+            for (k, cell) in cells.iter().enumerate() {
+                let ox = (x * 3) as usize + k % 3;
+                let oy = (y * 3) as usize + k / 3;
+                let idx = (oy * out_w + ox) * 4;
+                out[idx..idx + 4].copy_from_slice(cell);
+            }
+        }
+    }
+    out
+}
+
+// 放大后的取样源最多占用的像素数 (RGBA 共 64 MB)，超出时退回最近邻，避免大图层放大 8~9 倍耗尽内存
+const MAX_UPSCALED_PIXELS: u64 = 16 * 1024 * 1024;
+
+// 图层不透明区域的取样源 (RotSprite 时预先放大)，同一图层在多帧之间复用
+pub struct SampleSource {
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    factor: u32,
+    origin_x: i32,
+    origin_y: i32,
+    clean_edge: bool,
+}
+
+impl SampleSource {
+    pub fn from_layer(layer: &Layer, mode: ResampleMode) -> Self {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+        for &(cx, cy) in layer.chunks.iter().filter(|(_, c)| !c.is_empty()).map(|(k, _)| k) {
+            min_x = min_x.min(cx * CHUNK_SIZE);
+            min_y = min_y.min(cy * CHUNK_SIZE);
+            max_x = max_x.max(((cx + 1) * CHUNK_SIZE).min(layer.width));
+            max_y = max_y.max(((cy + 1) * CHUNK_SIZE).min(layer.height));
+        }
+        if min_x >= max_x || min_y >= max_y {
+            return Self::from_rgba(&[], 0, 0, mode);
+        }
+        let (w, h) = (max_x - min_x, max_y - min_y);
+        let mut source = Self::from_rgba(&layer.get_rect_data(min_x, min_y, w, h), w, h, mode);
+        source.origin_x += min_x as i32;
+        source.origin_y += min_y as i32;
+        source
+    }
+
+    // 以 RGBA 图像 (左上角为坐标原点) 建立取样源
+    pub fn from_rgba(rgba: &[u8], width: u32, height: u32, mode: ResampleMode) -> Self {
+        // 四周留 1 像素透明边，放大时轮廓也参与斜边判断
+        let (mut w, mut h) = (width + 2, height + 2);
+        let mut pixels = vec![0u8; (w * h * 4) as usize];
+        let row_bytes = (width * 4) as usize;
+        for row in 0..height as usize {
+            let dst = ((row + 1) * w as usize + 1) * 4;
+            pixels[dst..dst + row_bytes].copy_from_slice(&rgba[row * row_bytes..(row + 1) * row_bytes]);
+        }
+
+        let (step, mut passes) = mode.upscale_passes();
+        let scale = (step as u64).pow(passes);
+        if w as u64 * h as u64 * scale * scale > MAX_UPSCALED_PIXELS { passes = 0; }
+        let mut factor = 1;
+        for _ in 0..passes {
+            pixels = if step == 3 { scale3x(&pixels, w, h) } else { scale2x(&pixels, w, h) };
+            w *= step;
+            h *= step;
+            factor *= step;
+        }
+        Self { pixels, width: w, height: h, factor, origin_x: -1, origin_y: -1, clean_edge: mode == ResampleMode::CleanEdge }
+    }
+
+    fn texel(&self, x: i32, y: i32) -> [u8; 4] {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 { return [0; 4]; }
+        let idx = ((y as u32 * self.width + x as u32) * 4) as usize;
+        [self.pixels[idx], self.pixels[idx + 1], self.pixels[idx + 2], self.pixels[idx + 3]]
+    }
+
+    // (x, y) 为图层坐标系下的连续坐标，像素 (i, j) 覆盖 [i, i + 1) x [j, j + 1)
+    pub fn sample(&self, x: f32, y: f32) -> Option<[u8; 4]> {
+        let sx = (x - self.origin_x as f32) * self.factor as f32;
+        let sy = (y - self.origin_y as f32) * self.factor as f32;
+        if sx < 0.0 || sy < 0.0 || sx >= self.width as f32 || sy >= self.height as f32 { return None; }
+        let (px, py) = (sx.floor() as i32, sy.floor() as i32);
+        let mut p = self.texel(px, py);
+        if self.clean_edge {
+            // 取样点所在的四分之一像素朝向的两个邻居同色、且不是细线的一侧时，
+            // 按到该角的距离切掉一个三角形 (斜边经过两条边的中点)
+            let (fx, fy) = (sx - px as f32, sy - py as f32);
+            let (qx, qy) = (if fx < 0.5 { -1 } else { 1 }, if fy < 0.5 { -1 } else { 1 });
+            let horizontal = self.texel(px + qx, py);
+            let vertical = self.texel(px, py + qy);
+            let corner_dist = (if qx > 0 { 1.0 - fx } else { fx }) + (if qy > 0 { 1.0 - fy } else { fy });
+            if horizontal == vertical && horizontal != p
+                && horizontal != self.texel(px, py - qy) && vertical != self.texel(px - qx, py)
+                && corner_dist < 0.5 {
+                p = horizontal;
+            }
+        }
+        if p[3] == 0 { None } else { Some(p) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale2x_smooths_diagonal() {
+        let (o, x) = ([0u8, 0, 0, 0], [255u8, 0, 0, 255]);
+        // 2x2 的对角线：左上与右下为红色
+        let src: Vec<u8> = [x, o, o, x].concat();
+        let out = scale2x(&src, 2, 2);
+        let at = |px: usize, py: usize| { let i = (py * 4 + px) * 4; [out[i], out[i + 1], out[i + 2], out[i + 3]] };
+        assert_eq!(at(0, 0), x);
+        assert_eq!(at(1, 1), o, "朝向斜边的外角被削去");
+        assert_eq!(at(2, 1), x, "斜边另一侧补上邻居颜色，放大后仍是一条单像素斜线");
+        assert_eq!(at(3, 0), o);
+        assert_eq!(at(3, 3), x);
+    }
+
+    #[test]
+    fn test_scale3x_keeps_solid_and_single_pixels() {
+        let x = [0u8, 255, 0, 255];
+        let out = scale3x(&[x, x, x, x].concat(), 2, 2);
+        assert!(out.chunks_exact(4).all(|p| p == x), "纯色区域放大后不变");
+
+        let o = [0u8; 4];
+        let out = scale3x(&[o, o, o, o, x, o, o, o, o].concat(), 3, 3);
+        let opaque = out.chunks_exact(4).filter(|p| p[3] > 0).count();
+        assert_eq!(opaque, 9, "孤立像素放大为完整的 3x3");
+    }
+
+    #[test]
+    fn test_clean_edge_cuts_diagonal_corner() {
+        let (o, x) = ([0u8; 4], [255u8, 0, 0, 255]);
+        let src: Vec<u8> = [x, o, o, x].concat();
+        let source = SampleSource::from_rgba(&src, 2, 2, ResampleMode::CleanEdge);
+        assert_eq!(source.sample(1.1, 0.9), Some(x), "空白像素靠近斜边的角被补上");
+        assert_eq!(source.sample(1.4, 0.6), None, "离斜边较远的部分保持透明");
+        assert_eq!(source.sample(0.5, 0.5), Some(x));
+        assert_eq!(source.sample(1.5, 1.5), Some(x));
+    }
+
+    #[test]
+    fn test_large_source_falls_back_to_nearest() {
+        let small = SampleSource::from_rgba(&vec![0u8; 100 * 100 * 4], 100, 100, ResampleMode::RotSprite);
+        assert_eq!(small.factor, 8);
+        let large = SampleSource::from_rgba(&vec![0u8; 1024 * 1024 * 4], 1024, 1024, ResampleMode::RotSprite3x);
+        assert_eq!(large.factor, 1, "放大后超过上限时不放大");
+        assert_eq!(large.pixels.len(), 1026 * 1026 * 4);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::render::resample::{scale2x, scale3x};
+
+// 像素画放大算法：Scale2x/3x 不产生新颜色，hq2x 与 xBR 会在斜边处混合出过渡色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upscaler {
+    Scale2x,
+    Scale3x,
+    Hq2x,
+    Xbr2x,
+}
+
+impl Upscaler {
+    pub const ALL: [Upscaler; 4] = [Upscaler::Scale2x, Upscaler::Scale3x, Upscaler::Hq2x, Upscaler::Xbr2x];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Upscaler::Scale2x => "Scale2x (EPX)",
+            Upscaler::Scale3x => "Scale3x",
+            Upscaler::Hq2x => "hq2x",
+            Upscaler::Xbr2x => "2xBR",
+        }
+    }
+
+    pub fn factor(&self) -> u32 {
+        match self {
+            Upscaler::Scale3x => 3,
+            Upscaler::Scale2x | Upscaler::Hq2x | Upscaler::Xbr2x => 2,
+        }
+    }
+}
+
+// 返回 (像素, 宽, 高)
+pub fn upscale(rgba: &[u8], width: u32, height: u32, mode: Upscaler) -> (Vec<u8>, u32, u32) {
+    if width == 0 || height == 0 { return (Vec::new(), 0, 0); }
+    let pixels = match mode {
+        Upscaler::Scale2x => scale2x(rgba, width, height),
+        Upscaler::Scale3x => scale3x(rgba, width, height),
+        Upscaler::Hq2x => hq2x(rgba, width, height),
+        Upscaler::Xbr2x => xbr2x(rgba, width, height),
+    };
+    (pixels, width * mode.factor(), height * mode.factor())
+}
+
+fn yuv(p: [u8; 4]) -> (f32, f32, f32) {
+    let (r, g, b) = (p[0] as f32, p[1] as f32, p[2] as f32);
+    (
+        0.299 * r + 0.587 * g + 0.114 * b,
+        -0.169 * r - 0.331 * g + 0.5 * b,
+        0.5 * r - 0.419 * g - 0.081 * b,
+    )
+}
+
+// hq 系列的相似判定：YUV 各分量差都在阈值内，透明像素只与透明像素相似
+fn similar(a: [u8; 4], b: [u8; 4]) -> bool {
+    if a[3] == 0 || b[3] == 0 { return a[3] == b[3]; }
+    let ((y1, u1, v1), (y2, u2, v2)) = (yuv(a), yuv(b));
+    (y1 - y2).abs() <= 48.0 && (u1 - u2).abs() <= 7.0 && (v1 - v2).abs() <= 6.0 && (a[3] as i32 - b[3] as i32).abs() <= 32
+}
+
+// xBR 的加权颜色距离
+fn distance(a: [u8; 4], b: [u8; 4]) -> f32 {
+    let ((y1, u1, v1), (y2, u2, v2)) = (yuv(a), yuv(b));
+    48.0 * (y1 - y2).abs() + 7.0 * (u1 - u2).abs() + 6.0 * (v1 - v2).abs() + 48.0 * (a[3] as f32 - b[3] as f32).abs()
+}
+
+// 按权重混合，颜色按不透明度加权，避免与透明像素混合后发黑
+fn mix(parts: &[([u8; 4], u32)]) -> [u8; 4] {
+    let total: u32 = parts.iter().map(|(_, w)| w).sum();
+    let alpha_weight: u32 = parts.iter().map(|(p, w)| p[3] as u32 * w).sum();
+    if alpha_weight == 0 { return [0; 4]; }
+    let channel = |c: usize| ((parts.iter().map(|(p, w)| p[c] as u32 * p[3] as u32 * w).sum::<u32>() + alpha_weight / 2) / alpha_weight) as u8;
+    [channel(0), channel(1), channel(2), ((alpha_weight + total / 2) / total) as u8]
+}
+
+fn sampler(src: &[u8], width: u32, height: u32) -> impl Fn(i32, i32) -> [u8; 4] + '_ {
+    let (w, h) = (width as i32, height as i32);
+    move |x: i32, y: i32| {
+        let idx = ((y.clamp(0, h - 1) * w + x.clamp(0, w - 1)) * 4) as usize;
+        [src[idx], src[idx + 1], src[idx + 2], src[idx + 3]]
+    }
+}
+
+// 每个像素拆成 2x2，四个子像素分别按所在角的邻域计算；(sx, sy) 为该角的方向
+fn expand2x<F>(src: &[u8], width: u32, height: u32, corner: F) -> Vec<u8>
+where F: Fn(&dyn Fn(i32, i32) -> [u8; 4], i32, i32, i32, i32) -> [u8; 4] {
+    let px = sampler(src, width, height);
+    let out_w = (width * 2) as usize;
+    let mut out = vec![0u8; out_w * (height * 2) as usize * 4];
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            for (i, (sx, sy)) in [(-1, -1), (1, -1), (-1, 1), (1, 1)].into_iter().enumerate() {
+                let value = corner(&px, x, y, sx, sy);
+                let idx = (((y * 2) as usize + i / 2) * out_w + (x * 2) as usize + i % 2) * 4;
+                out[idx..idx + 4].copy_from_slice(&value);
+            }
+        }
+    }
+    out
+}
+
+// hq2x 的精简实现：不展开完整的 256 种邻域查表，只按与该角相邻的三个像素是否相似决定混合方式
+pub fn hq2x(src: &[u8], width: u32, height: u32) -> Vec<u8> {
+    expand2x(src, width, height, |px, x, y, sx, sy| {
+        let e = px(x, y);
+        let (horizontal, vertical, diagonal) = (px(x + sx, y), px(x, y + sy), px(x + sx, y + sy));
+        if similar(horizontal, vertical) && !similar(e, horizontal) {
+            // 斜边穿过该角；对角像素与自身相似时是连续的斜线，只轻微混合以免断开
+            if similar(e, diagonal) {
+                mix(&[(e, 6), (horizontal, 1), (vertical, 1)])
+            } else {
+                mix(&[(e, 2), (horizontal, 1), (vertical, 1)])
+            }
+        } else if !similar(e, horizontal) && similar(e, vertical) {
+            mix(&[(e, 3), (horizontal, 1)])
+        } else if !similar(e, vertical) && similar(e, horizontal) {
+            mix(&[(e, 3), (vertical, 1)])
+        } else if !similar(e, diagonal) && similar(e, horizontal) && similar(e, vertical) {
+            mix(&[(e, 3), (diagonal, 1)])
+        } else {
+            e
+        }
+    })
+}
+
+// 2xBR (Hyllian)：比较该角两条对角方向上的边缘强度，沿较弱的方向判定存在斜边时与近邻色各取一半
+pub fn xbr2x(src: &[u8], width: u32, height: u32) -> Vec<u8> {
+    expand2x(src, width, height, |px, x, y, sx, sy| {
+        // 按右下角写出的邻域，其余三个角通过 (sx, sy) 镜像
+        let p = |dx: i32, dy: i32| px(x + dx * sx, y + dy * sy);
+        let e = p(0, 0);
+        let (b, c, d, f) = (p(0, -1), p(1, -1), p(-1, 0), p(1, 0));
+        let (g, h, i) = (p(-1, 1), p(0, 1), p(1, 1));
+        let (f4, i4, h5, i5) = (p(2, 0), p(2, 1), p(0, 2), p(1, 2));
+        if e == f || e == h { return e; }
+
+        let anti_diagonal = distance(e, c) + distance(e, g) + distance(i, f4) + distance(i, h5) + 4.0 * distance(h, f);
+        let diagonal = distance(h, d) + distance(h, i5) + distance(f, i4) + distance(f, b) + 4.0 * distance(e, i);
+        if anti_diagonal < diagonal {
+            let nearest = if distance(e, f) <= distance(e, h) { f } else { h };
+            mix(&[(e, 1), (nearest, 1)])
+        } else {
+            e
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upscale_sizes_and_solid_color() {
+        let red = [255u8, 0, 0, 255];
+        let src: Vec<u8> = [red; 6].concat();
+        for mode in Upscaler::ALL {
+            let (out, w, h) = upscale(&src, 3, 2, mode);
+            assert_eq!((w, h), (3 * mode.factor(), 2 * mode.factor()));
+            assert!(out.chunks_exact(4).all(|p| p == red), "{:?} 纯色放大后不变", mode);
+        }
+    }
+
+    #[test]
+    fn test_xbr_and_hq2x_smooth_diagonal() {
+        let (o, x) = ([0u8, 0, 0, 255], [255u8, 255, 255, 255]);
+        // 3x3 中的对角线 (左下到右上)
+        let src: Vec<u8> = [o, o, x, o, x, o, x, o, o].concat();
+        for out in [xbr2x(&src, 3, 3), hq2x(&src, 3, 3)] {
+            let at = |px: usize, py: usize| out[(py * 6 + px) * 4];
+            // 中心像素的左上角朝向斜线外侧，右上角沿着斜线方向
+            assert!(at(2, 2) < 255 && at(2, 2) > 0, "朝外的角与背景混合");
+            assert!(at(3, 2) > at(2, 2), "沿斜线方向的角保留更多原色");
+        }
+    }
+}
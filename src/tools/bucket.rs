@@ -34,10 +34,17 @@ impl Tool for BucketTool {
             Some(id) => id.clone(), None => return Ok(()),
         };
         self.active_layer_id = Some(layer_id.clone());
+        let tiling = store.tiling_mode;
+        let (x, y) = if tiling {
+            let (wx, wy) = store.wrap_coords(x as i32, y as i32);
+            (wx as u32, wy as u32)
+        } else {
+            (x, y)
+        };
         if !store.selection.contains(x, y) { return Ok(()); }
 
         let target_color = store.get_pixel(&layer_id, x, y).unwrap_or(Color::transparent());
-        let fill_color = store.primary_color;
+        let fill_color = store.paint_color();
         if target_color == fill_color { return Ok(()); }
 
         let layer = match store.layers.iter_mut().find(|l| l.id == layer_id) {
@@ -63,6 +70,14 @@ impl Tool for BucketTool {
         let mut ctx = SafeFillContext::new(layer, &mut self.backup_chunks, &store.selection);
         let has_selection = store.selection.is_active;
 
+        if tiling {
+            fill_wrapped(&mut ctx, &mut stack, target_u32, fill_u32, has_selection);
+            if ctx.min_x <= ctx.max_x {
+                self.dirty_rect = Some((0, 0, u32::MAX, u32::MAX));
+            }
+            return Ok(());
+        }
+
         while let Some((px, py)) = stack.pop() {
             if !ctx.is_fillable(px, py, target_u32, has_selection) {
                 continue;
@@ -206,6 +221,33 @@ fn scan_line(ctx: &mut SafeFillContext, x1: i32, x2: i32, y: i32, target_u32: u3
     }
 }
 
+// 平铺模式：以画布尺寸取模的四邻域填充，越过边缘的像素从对侧继续
+fn fill_wrapped(ctx: &mut SafeFillContext, stack: &mut Vec<(i32, i32)>, target_u32: u32, fill_u32: u32, has_selection: bool) {
+    let canvas_w = ctx.selection.width as i32;
+    let canvas_h = ctx.selection.height as i32;
+    let (offset_x, offset_y) = (ctx.layer.offset_x, ctx.layer.offset_y);
+    let (width, height) = (ctx.layer.width as i32, ctx.layer.height as i32);
+    if canvas_w == 0 || canvas_h == 0 { return; }
+    if (fill_u32 >> 24) == 0 && (target_u32 >> 24) == 0 { return; }
+
+    while let Some((px, py)) = stack.pop() {
+        if px < 0 || py < 0 || px >= width || py >= height { continue; }
+        if !ctx.is_fillable(px, py, target_u32, has_selection) { continue; }
+
+        ctx.set_pixel_u32(px, py, fill_u32);
+        if px < ctx.min_x { ctx.min_x = px; }
+        if px > ctx.max_x { ctx.max_x = px; }
+        if py < ctx.min_y { ctx.min_y = py; }
+        if py > ctx.max_y { ctx.max_y = py; }
+
+        let canvas_x = px + offset_x;
+        let canvas_y = py + offset_y;
+        for (nx, ny) in [(canvas_x - 1, canvas_y), (canvas_x + 1, canvas_y), (canvas_x, canvas_y - 1), (canvas_x, canvas_y + 1)] {
+            stack.push((nx.rem_euclid(canvas_w) - offset_x, ny.rem_euclid(canvas_h) - offset_y));
+        }
+    }
+}
+
 struct SafeFillContext<'a> {
     layer: &'a mut Layer,
     backups: &'a mut HashMap<(u32, u32), Chunk>,
@@ -23,13 +23,13 @@ impl Tool for EyedropperTool {
                 crate::core::color::Color::new(30, 30, 30, 255) 
             };
         }
-        store.primary_color = picked_color;
+        store.set_paint_color(picked_color);
         Ok(())
     }
 
     fn on_pointer_move(&mut self, x: u32, y: u32, store: &mut PixelStore, _symmetry: &SymmetryConfig) -> Result<(), CoreError> {
         let picked_color = store.get_composite_pixel(x, y);
-        store.primary_color = picked_color;
+        store.set_paint_color(picked_color);
         Ok(())
     }
 
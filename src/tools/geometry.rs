@@ -21,5 +21,50 @@ impl Geometry {
     }
 }
 
+// 3x3 射影变换 (行主序)，用于自由变形与透视
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography(pub [f64; 9]);
+
+impl Homography {
+    // 把 w x h 的矩形 (左上角为原点) 映射到四边形，四角顺序为左上、右上、右下、左下
+    pub fn rect_to_quad(w: f32, h: f32, quad: [(f32, f32); 4]) -> Option<Self> {
+        if w <= 0.0 || h <= 0.0 { return None; }
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = quad.map(|(x, y)| (x as f64, y as f64));
+        // 单位正方形到四边形 (Heckbert)
+        let (sx, sy) = (x0 - x1 + x2 - x3, y0 - y1 + y2 - y3);
+        let (dx1, dx2, dy1, dy2) = (x1 - x2, x3 - x2, y1 - y2, y3 - y2);
+        let den = dx1 * dy2 - dx2 * dy1;
+        if den.abs() < 1e-12 { return None; }
+        let g = (sx * dy2 - dx2 * sy) / den;
+        let h_ = (dx1 * sy - sx * dy1) / den;
+        let (w, h) = (w as f64, h as f64);
+        Some(Self([
+            (x1 - x0 + g * x1) / w, (x3 - x0 + h_ * x3) / h, x0,
+            (y1 - y0 + g * y1) / w, (y3 - y0 + h_ * y3) / h, y0,
+            g / w, h_ / h, 1.0,
+        ]))
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d, e, f, g, h, i] = self.0;
+        let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+        if det.abs() < 1e-12 { return None; }
+        Some(Self([
+            (e * i - f * h) / det, (c * h - b * i) / det, (b * f - c * e) / det,
+            (f * g - d * i) / det, (a * i - c * g) / det, (c * d - a * f) / det,
+            (d * h - e * g) / det, (b * g - a * h) / det, (a * e - b * d) / det,
+        ]))
+    }
+
+    // 点落在地平线上 (齐次坐标 w 接近 0) 时返回 None
+    pub fn apply(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        let m = &self.0;
+        let (x, y) = (x as f64, y as f64);
+        let w = m[6] * x + m[7] * y + m[8];
+        if w.abs() < 1e-9 { return None; }
+        Some((((m[0] * x + m[1] * y + m[2]) / w) as f32, ((m[3] * x + m[4] * y + m[5]) / w) as f32))
+    }
+}
+
 #[cfg(test)]
 mod tests;
\ No newline at end of file
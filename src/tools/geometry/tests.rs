@@ -10,4 +10,15 @@ fn test_geom_point() {
     let mut p = Vec::new();
     Geometry::bresenham_line(1, 1, 1, 1, |x, y| p.push((x, y)));
     assert_eq!(p.len(), 1);
-}
\ No newline at end of file
+}
+#[test]
+fn test_homography_maps_corners() {
+    let quad = [(10.0, 10.0), (30.0, 12.0), (28.0, 40.0), (8.0, 30.0)];
+    let h = Homography::rect_to_quad(4.0, 2.0, quad).unwrap();
+    for ((u, v), (qx, qy)) in [(0.0, 0.0), (4.0, 0.0), (4.0, 2.0), (0.0, 2.0)].into_iter().zip(quad) {
+        let (x, y) = h.apply(u, v).unwrap();
+        assert!((x - qx).abs() < 1e-3 && (y - qy).abs() < 1e-3);
+    }
+    let (u, v) = h.inverse().unwrap().apply(28.0, 40.0).unwrap();
+    assert!((u - 4.0).abs() < 1e-3 && (v - 2.0).abs() < 1e-3);
+}
@@ -53,7 +53,8 @@ impl PencilTool {
         };
         
         let brush_size = store.brush_size;
-        let color = if self.is_eraser { Color::transparent() } else { store.primary_color };
+        // 橡皮擦用右键时涂副色
+        let color = if self.is_eraser && !store.paint_with_secondary { Color::transparent() } else { store.paint_color() };
         let (offset_x, offset_y, l_width, l_height) = match store.get_layer(&layer_id) {
             Some(l) => (l.offset_x, l.offset_y, l.width, l.height),
             None => return Ok(()),
@@ -94,10 +95,15 @@ impl PencilTool {
         
         symmetry.apply_symmetry(cx, cy, |tx, ty| {
             if res.is_err() { return; }
-            self.update_dirty_rect_internal(tx, ty, brush_size + store.brush_jitter * 2);
+            let tiling = store.tiling_mode;
+            if !tiling {
+                self.update_dirty_rect_internal(tx, ty, brush_size + store.brush_jitter * 2);
+            }
             for (rx, ry) in &rel_points {
-                let px = tx + rx;
-                let py = ty + ry;
+                let (px, py) = store.wrap_coords(tx + rx, ty + ry);
+                if tiling {
+                    self.update_dirty_rect_internal(px, py, 1);
+                }
                     
                     if px >= 0 && py >= 0 {
                         let px_u = px as u32;
@@ -7,6 +7,8 @@ use crate::core::color::Color;
 use crate::core::selection::SelectionData;
 use crate::core::error::CoreError;
 use crate::core::id_gen;
+use crate::render::resample::{ResampleMode, SampleSource};
+use super::geometry::Homography;
 use std::collections::{HashMap, HashSet};
 use rust_i18n::t;
 
@@ -16,6 +18,42 @@ pub enum DragMode {
     Move,
     Scale(f32, f32),
     Rotate,
+    Skew(f32, f32),
+    Distort(usize),
+}
+
+// 切变角度上限 (度)，避免 tan 发散
+const MAX_SKEW_DEG: f32 = 80.0;
+
+// 控制柄的作用：默认边中点与四角都缩放；切变模式下拖动边中点切变，自由变形模式下四角可单独移动
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HandleMode {
+    #[default]
+    Scale,
+    Skew,
+    Distort,
+}
+
+impl HandleMode {
+    pub const ALL: [HandleMode; 3] = [HandleMode::Scale, HandleMode::Skew, HandleMode::Distort];
+
+    pub fn label(&self) -> String {
+        match self {
+            HandleMode::Scale => t!("transform.handle_scale").to_string(),
+            HandleMode::Skew => t!("transform.handle_skew").to_string(),
+            HandleMode::Distort => t!("transform.handle_distort").to_string(),
+        }
+    }
+}
+
+// 数值面板编辑的参数，角度均为弧度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformParams {
+    pub offset_x: f32, pub offset_y: f32,
+    pub rotation: f32,
+    pub scale_x: f32, pub scale_y: f32,
+    pub pivot_x: f32, pub pivot_y: f32,
+    pub skew_x: f32, pub skew_y: f32,
 }
 
 #[derive(Clone)]
@@ -37,12 +75,24 @@ pub struct TransformTool {
     pub offset_x: f32, pub offset_y: f32,
     pub scale_x: f32, pub scale_y: f32,
     pub rotation: f32,
+    // 缩放之后、旋转之前的切变角度 (弧度)
+    pub skew_x: f32, pub skew_y: f32,
+    // 四角 (左上、右上、右下、左下) 相对仿射结果的位移 (画布坐标)，非零时按透视映射
+    pub distort: [(f32, f32); 4],
+    pub handle_mode: HandleMode,
     pub needs_redraw: bool,
+    // 旋转、缩放时的取样算法
+    pub resample: ResampleMode,
+    // 按当前算法放大后的选区图像，切换算法或重新提取时重建
+    sampler: Option<(ResampleMode, SampleSource)>,
 
     drag_mode: DragMode,
     base_scale_x: f32,
     base_scale_y: f32,
     base_rotation: f32,
+    base_skew_x: f32,
+    base_skew_y: f32,
+    base_distort: (f32, f32),
     start_angle: f32,
 }
 
@@ -54,10 +104,108 @@ impl TransformTool {
             erased_chunks: HashMap::new(),
             preview_chunks: HashSet::new(),
             pivot_x: 0.0, pivot_y: 0.0, offset_x: 0.0, offset_y: 0.0,
-            scale_x: 1.0, scale_y: 1.0, rotation: 0.0, needs_redraw: false,
+            scale_x: 1.0, scale_y: 1.0, rotation: 0.0,
+            skew_x: 0.0, skew_y: 0.0, distort: [(0.0, 0.0); 4], handle_mode: HandleMode::Scale,
+            needs_redraw: false,
+            resample: ResampleMode::Nearest, sampler: None,
             drag_mode: DragMode::None,
-            base_scale_x: 1.0, base_scale_y: 1.0, base_rotation: 0.0, start_angle: 0.0,
+            base_scale_x: 1.0, base_scale_y: 1.0, base_rotation: 0.0,
+            base_skew_x: 0.0, base_skew_y: 0.0, base_distort: (0.0, 0.0), start_angle: 0.0,
+        }
+    }
+
+    // 画布坐标下的仿射部分：缩放 → 切变 → 旋转，绕 pivot 进行后再平移 offset
+    fn affine_forward(&self, x: f32, y: f32) -> (f32, f32) {
+        let sx = (x - self.pivot_x) * self.scale_x;
+        let sy = (y - self.pivot_y) * self.scale_y;
+        let kx = sx + self.skew_x.tan() * sy;
+        let ky = sy + self.skew_y.tan() * sx;
+        let (cos_t, sin_t) = (self.rotation.cos(), self.rotation.sin());
+        (kx * cos_t - ky * sin_t + self.pivot_x + self.offset_x, kx * sin_t + ky * cos_t + self.pivot_y + self.offset_y)
+    }
+
+    fn quad(&self, img: &ExtractedImage) -> [(f32, f32); 4] {
+        let (x0, y0) = (img.min_x as f32, img.min_y as f32);
+        let (x1, y1) = (x0 + img.width as f32, y0 + img.height as f32);
+        let mut quad = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|(x, y)| self.affine_forward(x, y));
+        for (corner, (dx, dy)) in quad.iter_mut().zip(self.distort) {
+            corner.0 += dx;
+            corner.1 += dy;
+        }
+        quad
+    }
+
+    // 变换后的四角 (左上、右上、右下、左下)
+    pub fn corners(&self) -> Option<[(f32, f32); 4]> {
+        if !self.is_active { return None; }
+        self.extracted.as_ref().map(|img| self.quad(img))
+    }
+
+    fn is_distorted(&self) -> bool {
+        self.distort.iter().any(|&(x, y)| x != 0.0 || y != 0.0)
+    }
+
+    pub fn params(&self) -> TransformParams {
+        TransformParams {
+            offset_x: self.offset_x, offset_y: self.offset_y,
+            rotation: self.rotation,
+            scale_x: self.scale_x, scale_y: self.scale_y,
+            pivot_x: self.pivot_x, pivot_y: self.pivot_y,
+            skew_x: self.skew_x, skew_y: self.skew_y,
+        }
+    }
+
+    // 尚未提取像素时先按选区 (或整个图层) 开始一次变换
+    fn ensure_active(&mut self, store: &mut PixelStore) -> Result<(), CoreError> {
+        if self.is_active { return Ok(()); }
+        let layer_id = match &store.active_layer_id { Some(id) => id.clone(), None => return Ok(()) };
+        self.extract_pixels(store, &layer_id)
+    }
+
+    // 数值输入：移动轴心时图像保持不动 (自动补偿偏移)
+    pub fn set_params(&mut self, params: TransformParams, store: &mut PixelStore) -> Result<(), CoreError> {
+        self.ensure_active(store)?;
+        if !self.is_active { return Ok(()); }
+        if (params.pivot_x, params.pivot_y) != (self.pivot_x, self.pivot_y) {
+            let (qx, qy) = self.affine_forward(params.pivot_x, params.pivot_y);
+            self.pivot_x = params.pivot_x;
+            self.pivot_y = params.pivot_y;
+            self.offset_x = qx - params.pivot_x;
+            self.offset_y = qy - params.pivot_y;
+        } else {
+            self.offset_x = params.offset_x;
+            self.offset_y = params.offset_y;
+        }
+        let max_skew = MAX_SKEW_DEG.to_radians();
+        self.rotation = params.rotation;
+        self.scale_x = params.scale_x;
+        self.scale_y = params.scale_y;
+        self.skew_x = params.skew_x.clamp(-max_skew, max_skew);
+        self.skew_y = params.skew_y.clamp(-max_skew, max_skew);
+        self.apply_preview(store)
+    }
+
+    // 以轴心为中心沿变换后的局部轴镜像：缩放取反，切变随之取反，四角位移一并镜像
+    pub fn flip(&mut self, horizontal: bool, store: &mut PixelStore) -> Result<(), CoreError> {
+        self.ensure_active(store)?;
+        if !self.is_active { return Ok(()); }
+        if horizontal { self.scale_x = -self.scale_x; } else { self.scale_y = -self.scale_y; }
+        self.skew_x = -self.skew_x;
+        self.skew_y = -self.skew_y;
+        let (cos_t, sin_t) = (self.rotation.cos(), self.rotation.sin());
+        let (ax, ay) = if horizontal { (cos_t, sin_t) } else { (-sin_t, cos_t) };
+        for (dx, dy) in self.distort.iter_mut() {
+            let dot = *dx * ax + *dy * ay;
+            *dx -= 2.0 * dot * ax;
+            *dy -= 2.0 * dot * ay;
         }
+        self.apply_preview(store)
+    }
+
+    pub fn reset_distort(&mut self, store: &mut PixelStore) -> Result<(), CoreError> {
+        if !self.is_active { return Ok(()); }
+        self.distort = [(0.0, 0.0); 4];
+        self.apply_preview(store)
     }
 
     pub fn get_transform_params(&self) -> Option<(f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32)> {
@@ -127,11 +275,14 @@ impl TransformTool {
         }
         
         self.extracted = Some(ExtractedImage { min_x, min_y, width: w, height: h, pixels, mask });
+        self.sampler = None;
         self.pivot_x = min_x as f32 + w as f32 / 2.0;
         self.pivot_y = min_y as f32 + h as f32 / 2.0;
         self.offset_x = 0.0; self.offset_y = 0.0;
         self.scale_x = 1.0; self.scale_y = 1.0;
         self.rotation = 0.0;
+        self.skew_x = 0.0; self.skew_y = 0.0;
+        self.distort = [(0.0, 0.0); 4];
         self.is_active = true;
 
         self.erased_chunks.clear();
@@ -169,9 +320,26 @@ impl TransformTool {
         Ok(())
     }
 
+    // 切换取样算法，变换进行中时立即刷新预览
+    pub fn set_resample(&mut self, mode: ResampleMode, store: &mut PixelStore) -> Result<(), CoreError> {
+        self.resample = mode;
+        if self.is_active { self.apply_preview(store)?; }
+        Ok(())
+    }
+
     fn apply_preview(&mut self, store: &mut PixelStore) -> Result<(), CoreError> {
         let layer_id = match &store.active_layer_id { Some(id) => id.clone(), None => return Ok(()) };
         let img = match &self.extracted { Some(i) => i, None => return Ok(()) };
+        if self.resample != ResampleMode::Nearest && self.sampler.as_ref().is_none_or(|(mode, _)| *mode != self.resample) {
+            let rgba: Vec<u8> = img.pixels.iter().zip(&img.mask)
+                .flat_map(|(c, &m)| if m { [c.r, c.g, c.b, c.a] } else { [0; 4] })
+                .collect();
+            self.sampler = Some((self.resample, SampleSource::from_rgba(&rgba, img.width, img.height, self.resample)));
+        }
+        let sampler = match &self.sampler {
+            Some((mode, source)) if *mode == self.resample => Some(source),
+            _ => None,
+        };
         let backup = match &self.layer_backup { Some(l) => l, None => return Ok(()) };
         let sel_backup = match &self.sel_backup { Some(s) => s, None => return Ok(()) };
 
@@ -180,23 +348,23 @@ impl TransformTool {
 
         let cos_t = self.rotation.cos();
         let sin_t = self.rotation.sin();
-
-        let corners = [
-            (0.0, 0.0), (img.width as f32, 0.0),
-            (0.0, img.height as f32), (img.width as f32, img.height as f32),
-        ];
+        let tan_x = self.skew_x.tan();
+        let tan_y = self.skew_y.tan();
+        let skew_det = 1.0 - tan_x * tan_y;
+
+        let corners = self.quad(img);
+        // 四角有位移时整体按透视映射，逆映射直接回到选区图像坐标
+        let perspective = if self.is_distorted() {
+            match Homography::rect_to_quad(img.width as f32, img.height as f32, corners).and_then(|h| h.inverse()) {
+                Some(inv) => Some(inv),
+                None => return Ok(()),
+            }
+        } else { None };
 
         let mut out_min_x = f32::MAX; let mut out_min_y = f32::MAX;
         let mut out_max_x = f32::MIN; let mut out_max_y = f32::MIN;
 
-        for (cx, cy) in corners {
-            let x = img.min_x as f32 + cx - self.pivot_x;
-            let y = img.min_y as f32 + cy - self.pivot_y;
-            let sx = x * self.scale_x; let sy = y * self.scale_y;
-            let rx = sx * cos_t - sy * sin_t; let ry = sx * sin_t + sy * cos_t;
-            let fx = rx + self.pivot_x + self.offset_x;
-            let fy = ry + self.pivot_y + self.offset_y;
-            
+        for (fx, fy) in corners {
             if fx < out_min_x { out_min_x = fx; } if fx > out_max_x { out_max_x = fx; }
             if fy < out_min_y { out_min_y = fy; } if fy > out_max_y { out_max_y = fy; }
         }
@@ -220,54 +388,73 @@ impl TransformTool {
 
             for dy in start_y..=end_y {
                 for dx in start_x..=end_x {
-                    let x1 = dx as f32 - self.pivot_x - self.offset_x;
-                    let y1 = dy as f32 - self.pivot_y - self.offset_y;
-                    
-                    let x2 = x1 * cos_t + y1 * sin_t;
-                    let y2 = -x1 * sin_t + y1 * cos_t;
-                    
-                    let x3 = if self.scale_x != 0.0 { x2 / self.scale_x } else { 0.0 };
-                    let y3 = if self.scale_y != 0.0 { y2 / self.scale_y } else { 0.0 };
-                    
-                    let sx = (x3 + self.pivot_x - img.min_x as f32).round() as i32;
-                    let sy = (y3 + self.pivot_y - img.min_y as f32).round() as i32;
-
-                    if sx >= 0 && sy >= 0 && sx < img.width as i32 && sy < img.height as i32 {
-                        let idx = (sy * img.width as i32 + sx) as usize;
-                        if img.mask[idx] {
-                            if dx >= 0 && dy >= 0 && dx < canvas_w as i32 && dy < canvas_h as i32 {
-                                new_selection.mask[(dy as u32 * canvas_w + dx as u32) as usize] = true;
-                            }
+                    // 以目标像素中心逆映射回选区图像，落在哪个像素内就取哪个 (镜像、90° 旋转时无偏移)
+                    let (u, v) = match &perspective {
+                        Some(inv) => match inv.apply(dx as f32 + 0.5, dy as f32 + 0.5) {
+                            Some(p) => p,
+                            None => continue,
+                        },
+                        None => {
+                            if skew_det.abs() < 1e-6 { continue; }
+                            let x1 = dx as f32 + 0.5 - self.pivot_x - self.offset_x;
+                            let y1 = dy as f32 + 0.5 - self.pivot_y - self.offset_y;
+
+                            let x2 = x1 * cos_t + y1 * sin_t;
+                            let y2 = -x1 * sin_t + y1 * cos_t;
+
+                            let kx = (x2 - tan_x * y2) / skew_det;
+                            let ky = (y2 - tan_y * x2) / skew_det;
+
+                            let x3 = if self.scale_x != 0.0 { kx / self.scale_x } else { 0.0 };
+                            let y3 = if self.scale_y != 0.0 { ky / self.scale_y } else { 0.0 };
+                            (x3 + self.pivot_x - img.min_x as f32, y3 + self.pivot_y - img.min_y as f32)
+                        }
+                    };
+
+                    // 最近邻落在的选区像素 (包括选中的透明像素)
+                    let sx = u.floor() as i32;
+                    let sy = v.floor() as i32;
+                    let nearest = (sx >= 0 && sy >= 0 && sx < img.width as i32 && sy < img.height as i32)
+                        .then(|| (sy * img.width as i32 + sx) as usize)
+                        .filter(|&idx| img.mask[idx]);
+                    // 颜色由所选算法取样；放大取样的斜边可能超出最近邻的阶梯形状，两者都计入新选区
+                    let sampled = match sampler {
+                        Some(source) => source.sample(u, v).map(|c| Color::new(c[0], c[1], c[2], c[3])),
+                        None => nearest.map(|idx| img.pixels[idx]),
+                    };
+                    if sampled.is_none() && nearest.is_none() { continue; }
+                    let color = sampled.unwrap_or(Color::transparent());
+
+                    if dx >= 0 && dy >= 0 && dx < canvas_w as i32 && dy < canvas_h as i32 {
+                        new_selection.mask[(dy as u32 * canvas_w + dx as u32) as usize] = true;
+                    }
+
+                    if color.a > 0 {
+                        let lx = dx - off_x; let ly = dy - off_y;
+                        if lx >= 0 && ly >= 0 && lx < layer.width as i32 && ly < layer.height as i32 {
+                            let chunk_x = lx as u32 / CHUNK_SIZE;
+                            let chunk_y = ly as u32 / CHUNK_SIZE;
+                            let k = (chunk_x, chunk_y);
 
-                            let color = img.pixels[idx];
-                            if color.a > 0 {
-                                let lx = dx - off_x; let ly = dy - off_y;
-                                if lx >= 0 && ly >= 0 && lx < layer.width as i32 && ly < layer.height as i32 {
-                                    let chunk_x = lx as u32 / CHUNK_SIZE;
-                                    let chunk_y = ly as u32 / CHUNK_SIZE;
-                                    let k = (chunk_x, chunk_y);
-
-                                    if self.preview_chunks.insert(k) {
-                                        let base_chunk = self.erased_chunks.get(&k)
-                                            .or_else(|| backup.chunks.get(&k))
-                                            .cloned()
-                                            .unwrap_or_else(Chunk::new);
-                                        layer.chunks.insert(k, base_chunk);
-                                    }
-
-                                    let chunk = layer.chunks.get_mut(&k).ok_or_else(|| {
-                                        CoreError::LayerNotFound(t!("error.transform_preview_failed", chunk = format!("{:?}", k)).to_string())
-                                    })?;
-                                    let slx = lx as u32 % CHUNK_SIZE;
-                                    let sly = ly as u32 % CHUNK_SIZE;
-                                    let c_idx = ((sly * CHUNK_SIZE + slx) * 4) as usize;
-                                    let data = chunk.data_mut();
-                                    data[c_idx] = color.r;
-                                    data[c_idx+1] = color.g;
-                                    data[c_idx+2] = color.b;
-                                    data[c_idx+3] = color.a;
-                                }
+                            if self.preview_chunks.insert(k) {
+                                let base_chunk = self.erased_chunks.get(&k)
+                                    .or_else(|| backup.chunks.get(&k))
+                                    .cloned()
+                                    .unwrap_or_else(Chunk::new);
+                                layer.chunks.insert(k, base_chunk);
                             }
+
+                            let chunk = layer.chunks.get_mut(&k).ok_or_else(|| {
+                                CoreError::LayerNotFound(t!("error.transform_preview_failed", chunk = format!("{:?}", k)).to_string())
+                            })?;
+                            let slx = lx as u32 % CHUNK_SIZE;
+                            let sly = ly as u32 % CHUNK_SIZE;
+                            let c_idx = ((sly * CHUNK_SIZE + slx) * 4) as usize;
+                            let data = chunk.data_mut();
+                            data[c_idx] = color.r;
+                            data[c_idx+1] = color.g;
+                            data[c_idx+2] = color.b;
+                            data[c_idx+3] = color.a;
                         }
                     }
                 }
@@ -285,44 +472,50 @@ impl Tool for TransformTool {
         if !self.is_active { self.extract_pixels(store, &layer_id)?; }
         
         if let Some(img) = &self.extracted {
-            let cos_t = self.rotation.cos();
-            let sin_t = self.rotation.sin();
-            let hw = img.width as f32 / 2.0;
-            let hh = img.height as f32 / 2.0;
-            
+            let corners = self.quad(img);
+            let mid = |a: (f32, f32), b: (f32, f32)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+
             let mut hit_mode = DragMode::None;
             let hit_radius = 10.0;
 
+            // (方向, 位置)，四角的序号与 distort 一致
             let handles = [
-                (-1.0, -1.0), (0.0, -1.0), (1.0, -1.0),
-                (-1.0,  0.0),              (1.0,  0.0),
-                (-1.0,  1.0), (0.0,  1.0), (1.0,  1.0),
+                ((-1.0, -1.0), corners[0]), ((0.0, -1.0), mid(corners[0], corners[1])), ((1.0, -1.0), corners[1]),
+                ((-1.0,  0.0), mid(corners[3], corners[0])),                             ((1.0,  0.0), mid(corners[1], corners[2])),
+                ((-1.0,  1.0), corners[3]), ((0.0,  1.0), mid(corners[3], corners[2])), ((1.0,  1.0), corners[2]),
             ];
 
-            for (dx, dy) in handles {
-                let cx = img.min_x as f32 + hw + dx * hw;
-                let cy = img.min_y as f32 + hh + dy * hh;
-                let tx = (cx - self.pivot_x) * self.scale_x;
-                let ty = (cy - self.pivot_y) * self.scale_y;
-                let rx = tx * cos_t - ty * sin_t + self.pivot_x + self.offset_x;
-                let ry = tx * sin_t + ty * cos_t + self.pivot_y + self.offset_y;
-
+            for ((dx, dy), (rx, ry)) in handles {
                 let dist = ((x as f32 - rx).powi(2) + (y as f32 - ry).powi(2)).sqrt();
                 if dist < hit_radius {
-                    hit_mode = DragMode::Scale(dx, dy);
+                    let is_corner = dx != 0.0 && dy != 0.0;
+                    hit_mode = match self.handle_mode {
+                        HandleMode::Skew if !is_corner => DragMode::Skew(dx, dy),
+                        HandleMode::Distort if is_corner => {
+                            let index = match (dx > 0.0, dy > 0.0) {
+                                (false, false) => 0,
+                                (true, false) => 1,
+                                (true, true) => 2,
+                                (false, true) => 3,
+                            };
+                            self.base_distort = self.distort[index];
+                            DragMode::Distort(index)
+                        }
+                        _ => DragMode::Scale(dx, dy),
+                    };
                     break;
                 }
             }
 
             if hit_mode == DragMode::None {
-                let lx = x as f32 - self.pivot_x - self.offset_x;
-                let ly = y as f32 - self.pivot_y - self.offset_y;
-                let u_x = lx * cos_t + ly * sin_t;
-                let u_y = -lx * sin_t + ly * cos_t;
-                let scaled_hw = hw * self.scale_x.abs();
-                let scaled_hh = hh * self.scale_y.abs();
-                
-                if u_x.abs() <= scaled_hw && u_y.abs() <= scaled_hh {
+                // 点在四边形内 (各边叉积同号) 时移动，否则旋转
+                let (px, py) = (x as f32, y as f32);
+                let crosses: Vec<f32> = (0..4).map(|i| {
+                    let (ax, ay) = corners[i];
+                    let (bx, by) = corners[(i + 1) % 4];
+                    (bx - ax) * (py - ay) - (by - ay) * (px - ax)
+                }).collect();
+                if crosses.iter().all(|&c| c >= 0.0) || crosses.iter().all(|&c| c <= 0.0) {
                     hit_mode = DragMode::Move;
                 } else {
                     hit_mode = DragMode::Rotate;
@@ -335,6 +528,8 @@ impl Tool for TransformTool {
             self.base_scale_x = self.scale_x;
             self.base_scale_y = self.scale_y;
             self.base_rotation = self.rotation;
+            self.base_skew_x = self.skew_x;
+            self.base_skew_y = self.skew_y;
             self.start_angle = (y as f32 - (self.pivot_y + self.offset_y)).atan2(x as f32 - (self.pivot_x + self.offset_x));
         }
         Ok(())
@@ -373,6 +568,37 @@ impl Tool for TransformTool {
                         if dir_y != 0.0 { self.scale_y = self.base_scale_y + (local_dy * dir_y) / hh; }
                     }
                 }
+                DragMode::Skew(dir_x, dir_y) => {
+                    if let Some(img) = &self.extracted {
+                        let hw = img.width as f32 / 2.0;
+                        let hh = img.height as f32 / 2.0;
+                        let dx = cx - st_x;
+                        let dy = cy - st_y;
+
+                        let cos_t = self.base_rotation.cos();
+                        let sin_t = self.base_rotation.sin();
+                        let local_dx = dx * cos_t + dy * sin_t;
+                        let local_dy = -dx * sin_t + dy * cos_t;
+                        let max_skew = MAX_SKEW_DEG.to_radians();
+
+                        // 上下边沿水平方向拖动改变 skew_x，左右边沿竖直方向拖动改变 skew_y；
+                        // 边到轴心的距离 (缩放后) 乘以 tan 的增量即为边的位移
+                        if dir_y != 0.0 {
+                            let edge = (img.min_y as f32 + hh + dir_y * hh - self.pivot_y) * self.scale_y;
+                            if edge.abs() > 1e-3 {
+                                self.skew_x = (self.base_skew_x.tan() + local_dx / edge).atan().clamp(-max_skew, max_skew);
+                            }
+                        } else if dir_x != 0.0 {
+                            let edge = (img.min_x as f32 + hw + dir_x * hw - self.pivot_x) * self.scale_x;
+                            if edge.abs() > 1e-3 {
+                                self.skew_y = (self.base_skew_y.tan() + local_dy / edge).atan().clamp(-max_skew, max_skew);
+                            }
+                        }
+                    }
+                }
+                DragMode::Distort(index) => {
+                    self.distort[index] = (self.base_distort.0 + cx - st_x, self.base_distort.1 + cy - st_y);
+                }
                 _ => {}
             }
             
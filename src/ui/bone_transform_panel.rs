@@ -14,6 +14,8 @@ impl BoneTransformPanel {
         let mut rot_changed = false;
         let mut pos_changed = false;
         let mut scale_changed = false;
+        let mut shear_changed = false;
+        let mut mode_change = None;
 
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing.x = 12.0;
@@ -60,6 +62,32 @@ impl BoneTransformPanel {
                     if ui.add(egui::DragValue::new(&mut bone.local_transform.scale_y).speed(0.01)).changed() {
                         needs_update = true; scale_changed = true;
                     }
+
+                    ui.separator();
+                    ui.label("Shear X:");
+                    if ui.add(egui::DragValue::new(&mut bone.local_transform.shear_x).suffix("°").speed(0.1)).changed() {
+                        needs_update = true; shear_changed = true;
+                    }
+
+                    ui.label("Y:");
+                    if ui.add(egui::DragValue::new(&mut bone.local_transform.shear_y).suffix("°").speed(0.1)).changed() {
+                        needs_update = true; shear_changed = true;
+                    }
+                }
+                if let Some(mode) = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == *bone_id).map(|b| b.data.transform_mode()) {
+                    ui.separator();
+                    ui.label("继承:");
+                    let mut selected = mode;
+                    egui::ComboBox::from_id_source("bone_transform_mode")
+                        .selected_text(Self::transform_mode_label(mode))
+                        .show_ui(ui, |ui| {
+                            for m in crate::core::animation::bone::TransformMode::ALL {
+                                ui.selectable_value(&mut selected, m, Self::transform_mode_label(m));
+                            }
+                        });
+                    if selected != mode {
+                        mode_change = Some(AppCommand::SetBoneTransformMode { bone_id: bone_id.clone(), mode: selected });
+                    }
                 }
                 if ui.button("Key Frame").clicked() {
                     app.enqueue_command(AppCommand::InsertManualKeyframe(bone_id.clone()));
@@ -69,6 +97,12 @@ impl BoneTransformPanel {
             }
         });
 
+        if let Some(cmd) = mode_change { app.enqueue_command(cmd); }
+
+        Self::show_ik(ui, app);
+        Self::show_slots(ui, app);
+        Self::show_mesh(ui, app);
+
         if needs_update {
             app.is_dirty = true;
             app.view.needs_full_redraw = true;
@@ -80,8 +114,185 @@ impl BoneTransformPanel {
                     if rot_changed { app.animation.auto_key_bone(&id, crate::core::animation::timeline::TimelineProperty::Rotation); }
                     if pos_changed { app.animation.auto_key_bone(&id, crate::core::animation::timeline::TimelineProperty::Translation); }
                     if scale_changed { app.animation.auto_key_bone(&id, crate::core::animation::timeline::TimelineProperty::Scale); }
+                    if shear_changed { app.animation.auto_key_bone(&id, crate::core::animation::timeline::TimelineProperty::Shear); }
                 }
             }
         }
     }
-}
\ No newline at end of file
+
+    fn transform_mode_label(mode: crate::core::animation::bone::TransformMode) -> &'static str {
+        use crate::core::animation::bone::TransformMode;
+        match mode {
+            TransformMode::Normal => "完整继承",
+            TransformMode::OnlyTranslation => "仅平移",
+            TransformMode::NoRotationOrReflection => "不继承旋转/镜像",
+            TransformMode::NoScale => "不继承缩放",
+            TransformMode::NoScaleOrReflection => "不继承缩放/镜像",
+        }
+    }
+
+    fn show_ik(ui: &mut Ui, app: &mut AppState) {
+        let bone_id = match &app.ui.selected_bone_id {
+            Some(id) => id.clone(),
+            None => return,
+        };
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 12.0;
+            ui.label(RichText::new("IK").strong().color(Color32::LIGHT_GRAY));
+
+            // 只列出包含当前骨骼的约束
+            let constraints: Vec<(String, String, f32, bool)> = app.animation.project.skeleton.ik_constraints.iter()
+                .filter(|c| c.data.bone_ids.contains(&bone_id))
+                .map(|c| (c.data.id.clone(), c.data.name.clone(), c.mix, c.bend_positive))
+                .collect();
+
+            for (id, name, mut mix, mut bend) in constraints {
+                ui.separator();
+                ui.label(name);
+                let mix_changed = ui.add(egui::Slider::new(&mut mix, 0.0..=1.0).text("Mix")).changed();
+                let bend_changed = ui.checkbox(&mut bend, "正向弯曲").changed();
+                if mix_changed || bend_changed {
+                    app.enqueue_command(AppCommand::SetIkConstraint { id: id.clone(), mix, bend_positive: bend });
+                }
+                if ui.small_button("🔑").on_hover_text("记录 IK 关键帧").clicked() {
+                    app.enqueue_command(AppCommand::InsertManualKeyframe(id.clone()));
+                }
+                if ui.small_button("🗑").on_hover_text("删除 IK 约束").clicked() {
+                    app.enqueue_command(AppCommand::RemoveIkConstraint(id));
+                }
+            }
+
+            ui.separator();
+            ui.menu_button("➕ 添加 IK", |ui| {
+                let bones: Vec<(String, String)> = app.animation.project.skeleton.bones.iter()
+                    .filter(|b| b.data.id != bone_id)
+                    .map(|b| (b.data.id.clone(), b.data.name.clone()))
+                    .collect();
+                let target_name = app.ui.ik_target_bone_id.as_ref()
+                    .and_then(|id| bones.iter().find(|(b, _)| b == id))
+                    .map(|(_, n)| n.clone())
+                    .unwrap_or_else(|| "选择目标骨骼".to_string());
+                egui::ComboBox::from_id_source("ik_target")
+                    .selected_text(target_name)
+                    .show_ui(ui, |ui| {
+                        for (id, name) in &bones {
+                            if ui.selectable_label(app.ui.ik_target_bone_id.as_ref() == Some(id), name).clicked() {
+                                app.ui.ik_target_bone_id = Some(id.clone());
+                            }
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("骨骼链长度:");
+                    ui.add(egui::DragValue::new(&mut app.ui.ik_chain_length).clamp_range(1..=16));
+                });
+                if ui.add_enabled(app.ui.ik_target_bone_id.is_some(), egui::Button::new("以选中骨骼为链尾创建")).clicked() {
+                    if let Some(target) = app.ui.ik_target_bone_id.clone() {
+                        app.enqueue_command(AppCommand::AddIkConstraint { tip_bone_id: bone_id.clone(), target_bone_id: target, chain_length: app.ui.ik_chain_length });
+                    }
+                    ui.close_menu();
+                }
+            });
+        });
+    }
+
+    fn show_slots(ui: &mut Ui, app: &mut AppState) {
+        let bone_id = match &app.ui.selected_bone_id {
+            Some(id) => id.clone(),
+            None => return,
+        };
+        let slots: Vec<(String, String, Option<String>)> = app.animation.project.skeleton.slots.iter()
+            .filter(|s| s.data.bone_id == bone_id)
+            .map(|s| (s.data.id.clone(), s.data.name.clone(), s.current_attachment.clone()))
+            .collect();
+        if slots.is_empty() { return; }
+        let layers: Vec<(String, String)> = app.engine.store().layers.iter().map(|l| (l.id.clone(), l.name.clone())).collect();
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 12.0;
+            ui.label(RichText::new("Slots").strong().color(Color32::LIGHT_GRAY));
+
+            for (slot_id, slot_name, current) in slots {
+                ui.separator();
+                ui.label(slot_name);
+                let current_name = current.as_ref()
+                    .and_then(|id| layers.iter().find(|(l, _)| l == id))
+                    .map(|(_, n)| n.clone())
+                    .unwrap_or_else(|| "(隐藏)".to_string());
+                egui::ComboBox::from_id_source(format!("slot_attachment_{}", slot_id))
+                    .selected_text(current_name)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(current.is_none(), "(隐藏)").clicked() {
+                            app.enqueue_command(AppCommand::SetSlotAttachment { slot_id: slot_id.clone(), attachment: None });
+                        }
+                        for (id, name) in &layers {
+                            if ui.selectable_label(current.as_ref() == Some(id), name).clicked() {
+                                app.enqueue_command(AppCommand::SetSlotAttachment { slot_id: slot_id.clone(), attachment: Some(id.clone()) });
+                            }
+                        }
+                    });
+                if ui.small_button("⬆").on_hover_text("上移绘制顺序").clicked() {
+                    app.enqueue_command(AppCommand::MoveSlotDrawOrder { slot_id: slot_id.clone(), delta: 1 });
+                }
+                if ui.small_button("⬇").on_hover_text("下移绘制顺序").clicked() {
+                    app.enqueue_command(AppCommand::MoveSlotDrawOrder { slot_id: slot_id.clone(), delta: -1 });
+                }
+                if ui.small_button("🔑").on_hover_text("记录显示图层关键帧").clicked() {
+                    app.enqueue_command(AppCommand::InsertManualKeyframe(slot_id));
+                }
+            }
+
+            ui.separator();
+            if ui.button("🔑 绘制顺序").on_hover_text("记录当前绘制顺序关键帧").clicked() {
+                app.enqueue_command(AppCommand::InsertManualKeyframe(crate::core::animation::timeline::DRAW_ORDER_TARGET.to_string()));
+            }
+        });
+    }
+
+    // 活动图层的网格附件
+    fn show_mesh(ui: &mut Ui, app: &mut AppState) {
+        let layer = app.engine.store().active_layer_id.as_ref()
+            .and_then(|id| app.engine.store().get_layer(id))
+            .map(|l| (l.id.clone(), l.name.clone()));
+        let (layer_id, layer_name) = match layer {
+            Some(l) => l,
+            None => return,
+        };
+        let vertex_count = app.animation.project.skeleton.mesh(&layer_id).map(|m| m.vertices.len());
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 12.0;
+            ui.label(RichText::new("Mesh").strong().color(Color32::LIGHT_GRAY));
+            ui.label(layer_name);
+            ui.separator();
+
+            ui.label("网格间距:");
+            ui.add(egui::DragValue::new(&mut app.ui.mesh_cell_size).clamp_range(1..=64).suffix("px"));
+            let create_text = if vertex_count.is_some() { "重新生成网格" } else { "生成网格" };
+            if ui.button(create_text).clicked() {
+                app.enqueue_command(AppCommand::CreateMesh { layer_id: layer_id.clone(), cell_size: app.ui.mesh_cell_size });
+            }
+
+            if let Some(count) = vertex_count {
+                ui.label(format!("{} 个顶点", count));
+                if ui.button("自动权重").on_hover_text("按顶点到骨骼的距离重新分配权重").clicked() {
+                    app.enqueue_command(AppCommand::AutoWeightMesh(layer_id.clone()));
+                }
+                if ui.small_button("🔑").on_hover_text("记录网格变形关键帧").clicked() {
+                    app.enqueue_command(AppCommand::InsertManualKeyframe(layer_id.clone()));
+                }
+                if ui.small_button("🗑").on_hover_text("删除网格").clicked() {
+                    app.enqueue_command(AppCommand::DeleteMesh(layer_id.clone()));
+                }
+            }
+
+            if app.engine.tool_manager().active_type == crate::app::state::ToolType::WeightPaint {
+                ui.separator();
+                ui.label("笔刷半径:");
+                ui.add(egui::DragValue::new(&mut app.ui.weight_brush_radius).clamp_range(1.0..=128.0).speed(0.5));
+                ui.add(egui::Slider::new(&mut app.ui.weight_brush_strength, 0.01..=1.0).text("强度"));
+                ui.checkbox(&mut app.ui.weight_brush_erase, "减少");
+            }
+        });
+    }
+}
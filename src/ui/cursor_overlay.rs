@@ -143,8 +143,8 @@ impl CursorOverlay {
     }
 
     fn draw_transform_overlay(painter: &Painter, app: &AppState, scale_factor: f32) {
-        if let Some(params) = app.engine.tool_manager().get_transform_params() {
-            let (min_x, min_y, w, h, piv_x, piv_y, off_x, off_y, s_x, s_y, rot): (f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32) = params;
+        if let (Some(params), Some(corners)) = (app.engine.tool_manager().get_transform_params(), app.engine.tool_manager().get_transform_corners()) {
+            let (_, _, _, _, piv_x, piv_y, off_x, off_y, _, _, _): (f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32) = params;
 
             let zoom = app.view.zoom_level as f32;
             let screen_cx = app.view.width / 2.0;
@@ -158,31 +158,8 @@ impl CursorOverlay {
                 Pos2::new(phys_x / scale_factor, phys_y / scale_factor)
             };
 
-            let base_corners = [
-                (min_x, min_y),
-                (min_x + w, min_y),
-                (min_x + w, min_y + h),
-                (min_x, min_y + h),
-            ];
-
-            let cos_t = rot.cos();
-            let sin_t = rot.sin();
-
-            let mut screen_corners = [Pos2::ZERO; 4];
-
-            for i in 0..4 {
-                let (cx, cy) = base_corners[i];
-                let mut x = cx - piv_x;
-                let mut y = cy - piv_y;
-                x *= s_x;
-                y *= s_y;
-                let rx = x * cos_t - y * sin_t;
-                let ry = x * sin_t + y * cos_t;
-                let final_x = rx + piv_x + off_x;
-                let final_y = ry + piv_y + off_y;
-
-                screen_corners[i] = to_screen(final_x, final_y);
-            }
+            // 切变与自由变形后的四角由工具直接给出
+            let screen_corners = corners.map(|(x, y)| to_screen(x, y));
             let stroke = Stroke::new(1.5, Color32::WHITE);
             for i in 0..4 {
                 let p1 = screen_corners[i];
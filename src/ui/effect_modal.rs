@@ -0,0 +1,92 @@
+use egui::{Context, Ui, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::core::color::Color;
+use crate::render::effects::{Connectivity, PixelEffect};
+use rust_i18n::t;
+
+pub struct EffectModal;
+
+impl EffectModal {
+    pub fn show(ctx: &Context, app: &mut AppState) {
+        let Some(mut effect) = app.ui.effect_dialog else { return };
+        let layer_id = match app.engine.store().active_layer_id.clone() {
+            Some(id) => id,
+            None => { app.ui.effect_dialog = None; return; }
+        };
+        let has_selection = app.engine.store().selection.is_active;
+        let mut apply = false;
+        let mut close = false;
+
+        egui::Window::new(effect.label())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                super::scope_label(ui, has_selection);
+                ui.separator();
+                Self::effect_params(ui, &mut effect);
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(format!("✅ {}", t!("dialog.apply"))).clicked() { apply = true; }
+                    if ui.button(format!("❌ {}", t!("dialog.cancel"))).clicked() { close = true; }
+                });
+            });
+
+        if apply { app.enqueue_command(AppCommand::ApplyEffect { layer_id, effect }); }
+        app.ui.effect_dialog = (!apply && !close).then_some(effect);
+    }
+
+    fn effect_params(ui: &mut Ui, effect: &mut PixelEffect) {
+        let color_row = |ui: &mut Ui, color: &mut Color| {
+            ui.horizontal(|ui| {
+                ui.label(t!("effect.color"));
+                let mut arr = [color.r, color.g, color.b];
+                if ui.color_edit_button_srgb(&mut arr).changed() { *color = Color::new(arr[0], arr[1], arr[2], 255); }
+            });
+        };
+        let connectivity_row = |ui: &mut Ui, connectivity: &mut Connectivity| {
+            ui.horizontal(|ui| {
+                for option in [Connectivity::Four, Connectivity::Eight] {
+                    ui.radio_value(connectivity, option, option.label());
+                }
+            });
+        };
+        let offset_row = |ui: &mut Ui, dx: &mut i32, dy: &mut i32, range: std::ops::RangeInclusive<i32>| {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(dx).clamp_range(range.clone()).prefix("X: "));
+                ui.add(egui::DragValue::new(dy).clamp_range(range).prefix("Y: "));
+            });
+        };
+        match effect {
+            PixelEffect::Outline { color, inside, connectivity } => {
+                color_row(ui, color);
+                ui.horizontal(|ui| {
+                    ui.radio_value(inside, false, t!("effect.outside"));
+                    ui.radio_value(inside, true, t!("effect.inside"));
+                });
+                connectivity_row(ui, connectivity);
+            }
+            PixelEffect::DropShadow { color, dx, dy } => {
+                color_row(ui, color);
+                ui.label(t!("effect.offset"));
+                offset_row(ui, dx, dy, -16..=16);
+            }
+            PixelEffect::InnerHighlight { color, dx, dy } => {
+                color_row(ui, color);
+                ui.label(t!("effect.light_direction"));
+                offset_row(ui, dx, dy, -1..=1);
+            }
+            PixelEffect::RemoveOrphans { connectivity } => {
+                ui.label(t!("effect.orphan_neighborhood"));
+                connectivity_row(ui, connectivity);
+            }
+            PixelEffect::FillHoles { color, connectivity } => {
+                color_row(ui, color);
+                ui.label(t!("effect.hole_connectivity"));
+                connectivity_row(ui, connectivity);
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use egui::{Context, Ui, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::app::handlers::filter_handler;
+use crate::render::filters::Adjustment;
+use crate::render::quantize::{ColorMetric, Dither, QuantizeSettings};
+use rust_i18n::t;
+
+pub struct FilterModal;
+
+impl FilterModal {
+    pub fn show(ctx: &Context, app: &mut AppState) {
+        let mut settings = match &app.ui.filter_preview {
+            Some(preview) => preview.settings.clone(),
+            None => return,
+        };
+        let has_selection = app.ui.filter_preview.as_ref().is_some_and(|p| p.mask.is_some());
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new(settings.adjustment.label())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                super::scope_label(ui, has_selection);
+                ui.separator();
+                Self::adjustment_params(ui, &mut settings.adjustment);
+
+                ui.separator();
+                let mut snap = settings.snap_to_palette.is_some();
+                if ui.checkbox(&mut snap, t!("filter.snap_to_palette")).changed() {
+                    settings.snap_to_palette = snap.then(QuantizeSettings::default);
+                }
+                if let Some(quantize) = settings.snap_to_palette.as_mut() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source("filter_snap_metric").selected_text(quantize.metric.label()).show_ui(ui, |ui| {
+                            for metric in ColorMetric::ALL { ui.selectable_value(&mut quantize.metric, metric, metric.label()); }
+                        });
+                        egui::ComboBox::from_id_source("filter_snap_dither").selected_text(quantize.dither.label()).show_ui(ui, |ui| {
+                            for dither in Dither::ALL { ui.selectable_value(&mut quantize.dither, dither, dither.label()); }
+                        });
+                    });
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(format!("✅ {}", t!("dialog.apply"))).clicked() { apply = true; }
+                    if ui.button(format!("❌ {}", t!("dialog.cancel"))).clicked() { cancel = true; }
+                });
+            });
+
+        // 参数或图层像素变化时重新计算预览
+        if app.ui.filter_preview.as_ref().is_some_and(|p| p.settings != settings) || filter_handler::preview_is_stale(app) {
+            app.enqueue_command(AppCommand::UpdateFilterPreview(settings));
+        }
+        if apply { app.enqueue_command(AppCommand::ApplyFilter); }
+        if cancel { app.enqueue_command(AppCommand::CancelFilter); }
+    }
+
+    fn adjustment_params(ui: &mut Ui, adjustment: &mut Adjustment) {
+        let slider = |ui: &mut Ui, value: &mut f32, range: std::ops::RangeInclusive<f32>, text: &str| {
+            ui.add(egui::Slider::new(value, range).text(text));
+        };
+        match adjustment {
+            Adjustment::HueSaturation { hue, saturation, lightness } => {
+                slider(ui, hue, -180.0..=180.0, &t!("filter.hue"));
+                slider(ui, saturation, -1.0..=1.0, &t!("filter.saturation"));
+                slider(ui, lightness, -1.0..=1.0, &t!("filter.lightness"));
+            }
+            Adjustment::BrightnessContrast { brightness, contrast } => {
+                slider(ui, brightness, -1.0..=1.0, &t!("filter.brightness"));
+                slider(ui, contrast, -1.0..=1.0, &t!("filter.contrast"));
+            }
+            Adjustment::Levels { in_black, in_white, gamma, out_black, out_white } => {
+                ui.add(egui::Slider::new(in_black, 0..=254).text(t!("filter.in_black")));
+                ui.add(egui::Slider::new(in_white, 1..=255).text(t!("filter.in_white")));
+                ui.add(egui::Slider::new(gamma, 0.1..=10.0).logarithmic(true).text("Gamma"));
+                ui.add(egui::Slider::new(out_black, 0..=255).text(t!("filter.out_black")));
+                ui.add(egui::Slider::new(out_white, 0..=255).text(t!("filter.out_white")));
+            }
+            Adjustment::Curves(points) => {
+                ui.label(t!("filter.curve_points"));
+                for (input, output) in points.iter_mut() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(input).clamp_range(0..=255));
+                        ui.label("→");
+                        ui.add(egui::DragValue::new(output).clamp_range(0..=255));
+                    });
+                }
+                ui.horizontal(|ui| {
+                    if ui.small_button("➕").clicked() { points.push((128, 128)); }
+                    if points.len() > 2 && ui.small_button("➖").clicked() { points.pop(); }
+                });
+            }
+            Adjustment::Invert | Adjustment::Desaturate => {
+                ui.label(t!("filter.no_params"));
+            }
+            Adjustment::Posterize { levels } => {
+                ui.add(egui::Slider::new(levels, 2..=32).text(t!("filter.levels_count")));
+            }
+            Adjustment::ColorBalance { shadows, midtones, highlights } => {
+                for (name, values) in [(t!("filter.shadows"), shadows), (t!("filter.midtones"), midtones), (t!("filter.highlights"), highlights)] {
+                    ui.label(name);
+                    for (value, channel) in values.iter_mut().zip([t!("filter.cyan_red"), t!("filter.magenta_green"), t!("filter.yellow_blue")]) {
+                        ui.add(egui::Slider::new(value, -1.0..=1.0).text(channel));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -60,6 +60,14 @@ impl Gui {
         });
         }
 
+        if app.mode == AppMode::Animation && !ctx.wants_keyboard_input() {
+            if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::C)) { app.enqueue_command(AppCommand::CopyKeyframes); }
+            if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::V)) {
+                let mirrored = ctx.input(|i| i.modifiers.shift);
+                app.enqueue_command(AppCommand::PasteKeyframes { mirrored });
+            }
+        }
+
         let mut style = (*ctx.style()).clone();
         style.spacing.item_spacing = egui::vec2(6.0, 6.0);
         style.visuals.widgets.active.bg_stroke.width = 2.0;
@@ -136,7 +144,10 @@ impl Gui {
             if response.drag_started() {
                 if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
                     let (cx, cy) = get_canvas_pos(pos);
-                    let _ = app.on_mouse_down(cx, cy);
+                    let secondary = app.mode == AppMode::PixelEdit
+                        && app.engine.tool_manager().active_type.uses_paint_color()
+                        && ctx.input(|i| i.pointer.button_down(egui::PointerButton::Secondary));
+                    let _ = if secondary { app.on_secondary_mouse_down(cx, cy) } else { app.on_mouse_down(cx, cy) };
                 }
             }
 
@@ -168,7 +179,7 @@ impl Gui {
                             app.ui.selected_node_idx = Some(idx);
                             app.ui.show_canvas_menu = true;
                         }
-                    } else if app.engine.store().selection.is_active {
+                    } else if app.engine.store().selection.is_active && !app.engine.tool_manager().active_type.uses_paint_color() {
                         app.ui.canvas_menu_pos = pos;
                         app.ui.show_canvas_menu = true;
                     }
@@ -302,6 +313,39 @@ impl Gui {
                     });
                 });
         }
+        if app.ui.show_wrap_offset_modal {
+            egui::Window::new(t!("dialog.wrap_offset_title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("X:");
+                        ui.add(egui::TextEdit::singleline(&mut app.ui.wrap_offset_x).desired_width(60.0));
+                        ui.label("px");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Y:");
+                        ui.add(egui::TextEdit::singleline(&mut app.ui.wrap_offset_y).desired_width(60.0));
+                        ui.label("px");
+                    });
+
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button(t!("dialog.confirm")).clicked() {
+                            let dx = app.ui.wrap_offset_x.trim().parse::<i32>().unwrap_or(0);
+                            let dy = app.ui.wrap_offset_y.trim().parse::<i32>().unwrap_or(0);
+                            app.enqueue_command(AppCommand::WrapOffsetCanvas(dx, dy));
+                            app.ui.show_wrap_offset_modal = false;
+                        }
+                        if ui.button(t!("dialog.cancel")).clicked() { app.ui.show_wrap_offset_modal = false; }
+                    });
+                });
+        }
+        crate::ui::image_import_modal::ImageImportModal::show(ctx, app);
+        crate::ui::quantize_modal::QuantizeModal::show(ctx, app);
+        crate::ui::filter_modal::FilterModal::show(ctx, app);
+        crate::ui::effect_modal::EffectModal::show(ctx, app);
         if let Some(err_msg) = app.ui.error_message.clone() {
             egui::Window::new(t!("dialog.prompt"))
                 .collapsible(false)
@@ -0,0 +1,84 @@
+use egui::{Context, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::app::image_import::{ImportSizing, ImportTarget};
+use crate::render::pixel_grid::PixelGrid;
+use rust_i18n::t;
+
+pub struct ImageImportModal;
+
+impl ImageImportModal {
+    pub fn show(ctx: &Context, app: &mut AppState) {
+        let (cw, ch) = (app.engine.store().canvas_width, app.engine.store().canvas_height);
+        let mut confirm = false;
+        let mut cancel = false;
+
+        let import = match app.ui.pending_image_import.as_mut() {
+            Some(import) => import,
+            None => return,
+        };
+        egui::Window::new(t!("image_import.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(t!("image_import.source", path = import.source_path));
+                ui.label(t!("image_import.original_size", w = import.width, h = import.height));
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut import.target, ImportTarget::NewLayer, t!("image_import.new_layer"));
+                    ui.radio_value(&mut import.target, ImportTarget::NewCanvas, t!("image_import.new_canvas"));
+                });
+
+                // 放大过的像素画：还原到原生网格
+                let mut snap = import.snap_grid.is_some();
+                let hint = match import.detected_grid {
+                    Some(grid) => t!("image_import.snap_detected", cell = grid.cell).to_string(),
+                    None => t!("image_import.snap_none").to_string(),
+                };
+                if ui.checkbox(&mut snap, hint).changed() {
+                    import.snap_grid = if snap { Some(import.detected_grid.unwrap_or(PixelGrid { cell: 2, offset_x: 0, offset_y: 0 })) } else { None };
+                }
+                if let Some(grid) = import.snap_grid.as_mut() {
+                    ui.horizontal(|ui| {
+                        ui.label(t!("image_import.cell"));
+                        ui.add(egui::DragValue::new(&mut grid.cell).clamp_range(1..=256).suffix(" px"));
+                        ui.label(t!("image_import.offset"));
+                        ui.add(egui::DragValue::new(&mut grid.offset_x).clamp_range(0..=grid.cell.saturating_sub(1)));
+                        ui.add(egui::DragValue::new(&mut grid.offset_y).clamp_range(0..=grid.cell.saturating_sub(1)));
+                    });
+                }
+                let &(_, sw, sh) = import.source_image();
+                ui.label(t!("image_import.result_size", w = sw, h = sh));
+
+                if import.target == ImportTarget::NewLayer {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut import.sizing, ImportSizing::Keep, t!("image_import.keep_size"));
+                        ui.radio_value(&mut import.sizing, ImportSizing::Fit, t!("image_import.fit_canvas"));
+                    });
+                    let &(_, pw, ph) = import.placed_image(cw, ch);
+                    ui.label(t!("image_import.placed_size", w = pw, h = ph, cw = cw, ch = ch));
+                    ui.horizontal(|ui| {
+                        ui.label(t!("image_import.position_x"));
+                        ui.add(egui::DragValue::new(&mut import.offset_x));
+                        ui.label(t!("image_import.position_y"));
+                        ui.add(egui::DragValue::new(&mut import.offset_y));
+                        if ui.button(t!("image_import.center")).clicked() {
+                            (import.offset_x, import.offset_y) = import.centered_offset(cw, ch);
+                        }
+                    });
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("image_import.confirm")).clicked() { confirm = true; }
+                    if ui.button(format!("❌ {}", t!("dialog.cancel"))).clicked() { cancel = true; }
+                });
+            });
+
+        if confirm { app.enqueue_command(AppCommand::ConfirmImageImport); }
+        if cancel { app.enqueue_command(AppCommand::CancelImageImport); }
+    }
+}
@@ -0,0 +1,109 @@
+use egui::Ui;
+use crate::app::state::{AppMode, AppState};
+use crate::app::commands::AppCommand;
+use crate::core::color::Color;
+use crate::core::layer_effect::{Connectivity, LayerEffectKind};
+use rust_i18n::t;
+
+pub struct LayerEffectsPanel;
+
+impl LayerEffectsPanel {
+    // 当前图层的效果栈；任何修改都作为一条 SetLayerEffects 命令提交
+    pub fn show(ui: &mut Ui, app: &mut AppState) {
+        let store = app.engine.store();
+        let Some(layer) = store.active_layer_id.as_ref().and_then(|id| store.get_layer(id)) else { return };
+        let layer_id = layer.id.clone();
+        let original = layer.effects.clone();
+        let (primary, secondary) = (store.primary_color, store.secondary_color);
+        let animating = app.mode == AppMode::Animation && app.animation.project.active_animation_id.is_some();
+        let mut effects = original.clone();
+        let mut commands = Vec::new();
+
+        egui::CollapsingHeader::new(format!("{} ({})", t!("layer.effects"), effects.len()))
+            .id_source("layer_effects")
+            .show(ui, |ui| {
+                let mut move_up = None;
+                let mut remove = None;
+                let count = effects.len();
+                for (i, effect) in effects.iter_mut().enumerate() {
+                    ui.push_id(&effect.id, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut effect.enabled, effect.kind.label());
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("🗑").on_hover_text(t!("effect.delete")).clicked() { remove = Some(i); }
+                                if ui.add_enabled(i + 1 < count, egui::Button::new("⬇").small()).clicked() { move_up = Some(i + 1); }
+                                if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() { move_up = Some(i); }
+                                if animating && effect.kind.amount().is_some()
+                                    && ui.small_button("🔑").on_hover_text(t!("effect.key_amount")).clicked() {
+                                    commands.push(AppCommand::InsertManualKeyframe(effect.id.clone()));
+                                }
+                            });
+                        });
+                        ui.add_enabled_ui(effect.enabled, |ui| Self::effect_params(ui, &mut effect.kind, primary, secondary));
+                        ui.separator();
+                    });
+                }
+                // 按钮所在的效果与上一个交换
+                if let Some(i) = move_up { effects.swap(i - 1, i); }
+                if let Some(i) = remove { effects.remove(i); }
+
+                ui.menu_button(format!("➕ {}", t!("effect.add")), |ui| {
+                    for kind in LayerEffectKind::defaults(primary) {
+                        if ui.button(kind.label()).clicked() {
+                            commands.push(AppCommand::AddLayerEffect(layer_id.clone(), kind));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+
+        if effects != original { app.enqueue_command(AppCommand::SetLayerEffects(layer_id, effects)); }
+        for cmd in commands { app.enqueue_command(cmd); }
+    }
+
+    fn effect_params(ui: &mut Ui, kind: &mut LayerEffectKind, primary: Color, secondary: Color) {
+        let color_button = |ui: &mut Ui, color: &mut Color| {
+            let mut arr = [color.r, color.g, color.b];
+            if ui.color_edit_button_srgb(&mut arr).changed() { *color = Color::new(arr[0], arr[1], arr[2], 255); }
+        };
+        match kind {
+            LayerEffectKind::Outline { color, connectivity } => {
+                ui.horizontal(|ui| {
+                    color_button(ui, color);
+                    for option in [Connectivity::Four, Connectivity::Eight] {
+                        ui.radio_value(connectivity, option, option.label());
+                    }
+                });
+            }
+            LayerEffectKind::DropShadow { color, dx, dy } => {
+                ui.horizontal(|ui| {
+                    color_button(ui, color);
+                    ui.add(egui::DragValue::new(dx).clamp_range(-16..=16).prefix("X: "));
+                    ui.add(egui::DragValue::new(dy).clamp_range(-16..=16).prefix("Y: "));
+                });
+            }
+            LayerEffectKind::ColorOverlay { color, strength } => {
+                ui.horizontal(|ui| {
+                    color_button(ui, color);
+                    ui.add(egui::Slider::new(strength, 0.0..=1.0).text(t!("effect.strength")));
+                });
+            }
+            LayerEffectKind::PaletteSwap { pairs } => {
+                let mut remove = None;
+                for (i, (from, to)) in pairs.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        color_button(ui, from);
+                        ui.label("→");
+                        color_button(ui, to);
+                        if ui.small_button("➖").clicked() { remove = Some(i); }
+                    });
+                }
+                if let Some(i) = remove { pairs.remove(i); }
+                if ui.small_button(format!("➕ {}", t!("effect.add_swap_pair"))).clicked() { pairs.push((primary, secondary)); }
+            }
+            LayerEffectKind::HueShift { degrees } => {
+                ui.add(egui::Slider::new(degrees, -180.0..=180.0).text(t!("effect.hue")).suffix("°"));
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use egui::{Ui, Color32, RichText};
 use crate::app::state::AppState;
 use crate::app::commands::AppCommand;
 use crate::ui::symmetry_panel::SymmetryPanel;
+use crate::ui::layer_effects_panel::LayerEffectsPanel;
 use crate::core::blend_mode::BlendMode;
 use rust_i18n::t;
 
@@ -305,6 +306,9 @@ impl LayerPanel {
                         });
                     });
                 }); 
+
+                ui.add_space(4.0);
+                LayerEffectsPanel::show(ui, app);
                 
                 ui.add_space(4.0);
                 ui.separator();
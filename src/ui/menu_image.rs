@@ -1,6 +1,10 @@
 use egui::Ui;
 use crate::app::state::AppState;
-use crate::app::commands::ResizeAnchor;
+use crate::app::commands::{AppCommand, ResizeAnchor};
+use crate::core::layer::LosslessTransform;
+use crate::render::effects::PixelEffect;
+use crate::render::filters::Adjustment;
+use crate::render::upscale::Upscaler;
 use rust_i18n::t;
 
 pub struct MenuImage;
@@ -17,6 +21,82 @@ impl MenuImage {
                 app.ui.show_resize_modal = true;
                 ui.close_menu();
             }
+
+            if ui.button(t!("menu.wrap_offset")).clicked() {
+                app.ui.wrap_offset_x = (app.engine.store().canvas_width / 2).to_string();
+                app.ui.wrap_offset_y = (app.engine.store().canvas_height / 2).to_string();
+                app.ui.show_wrap_offset_modal = true;
+                ui.close_menu();
+            }
+
+            ui.separator();
+            let canvas_ops = [
+                (LosslessTransform::RotateCw, t!("menu.rotate_cw")),
+                (LosslessTransform::RotateCcw, t!("menu.rotate_ccw")),
+                (LosslessTransform::Rotate180, t!("menu.rotate_180")),
+                (LosslessTransform::FlipHorizontal, t!("menu.flip_horizontal")),
+                (LosslessTransform::FlipVertical, t!("menu.flip_vertical")),
+            ];
+            ui.menu_button(t!("menu.transform_canvas"), |ui| {
+                for (op, label) in &canvas_ops {
+                    if ui.button(label.to_string()).clicked() {
+                        app.enqueue_command(AppCommand::TransformCanvas(*op));
+                        ui.close_menu();
+                    }
+                }
+            });
+            let active_layer = app.engine.store().active_layer_id.clone();
+            ui.add_enabled_ui(active_layer.is_some(), |ui| {
+                ui.menu_button(t!("menu.transform_layer"), |ui| {
+                    for (op, label) in &canvas_ops {
+                        if ui.button(label.to_string()).clicked() {
+                            if let Some(id) = &active_layer {
+                                app.enqueue_command(AppCommand::TransformLayer(id.clone(), *op));
+                            }
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+
+            ui.add_enabled_ui(active_layer.is_some(), |ui| {
+                if ui.button(t!("menu.quantize")).clicked() {
+                    app.ui.show_quantize_modal = true;
+                    ui.close_menu();
+                }
+                ui.menu_button(t!("menu.adjustments"), |ui| {
+                    for adjustment in Adjustment::defaults() {
+                        if ui.button(adjustment.label()).clicked() {
+                            app.enqueue_command(AppCommand::BeginFilter(adjustment));
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button(t!("menu.effects"), |ui| {
+                    for effect in PixelEffect::defaults(app.engine.store().primary_color) {
+                        if ui.button(effect.label()).clicked() {
+                            app.ui.effect_dialog = Some(effect);
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+
+            ui.menu_button(t!("menu.upscale"), |ui| {
+                for mode in Upscaler::ALL {
+                    if ui.button(mode.label()).clicked() {
+                        app.enqueue_command(AppCommand::UpscaleCanvas(mode));
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            ui.separator();
+            let mut tiling = app.engine.store().tiling_mode;
+            if ui.checkbox(&mut tiling, t!("menu.tiling_mode")).clicked() {
+                app.enqueue_command(AppCommand::ToggleTilingMode);
+                ui.close_menu();
+            }
         });
     }
 }
\ No newline at end of file
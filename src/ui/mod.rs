@@ -5,10 +5,20 @@ pub mod symmetry_panel;
 pub mod title_bar;
 pub mod menu_file;
 pub mod menu_image;
+pub mod image_import_modal;
+pub mod quantize_modal;
+pub mod filter_modal;
+pub mod effect_modal;
 pub mod window_controls;
 pub mod palette_panel;
 pub mod layer_panel;
+pub mod layer_effects_panel;
 pub mod timeline;
 pub mod toolbar_pixel;
 pub mod toolbar_anim;
-pub mod bone_transform_panel;
\ No newline at end of file
+pub mod bone_transform_panel;
+
+// 像素操作对话框顶部的作用范围提示：有选区时只处理选区内的像素
+pub fn scope_label(ui: &mut egui::Ui, has_selection: bool) {
+    ui.label(if has_selection { rust_i18n::t!("dialog.scope_selection") } else { rust_i18n::t!("dialog.scope_layer") });
+}
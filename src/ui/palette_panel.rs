@@ -1,7 +1,8 @@
 use egui::{Ui, Color32, Sense, vec2, Stroke, RichText};
 use crate::app::state::AppState;
 use crate::app::commands::AppCommand;
-use crate::core::color::Color;
+use crate::core::color::{Color, ColorModel};
+use crate::core::palette::{generate_ramp, generate_shades, Palette, PaletteSortKey};
 use rust_i18n::t;
 
 const ICON_SAVE: &str    = "\u{f0b2}"; 
@@ -42,17 +43,22 @@ impl PalettePanel {
             });
             ui.add_space(10.0);
 
-            let mut color_arr = [
-                app.engine.store().primary_color.r,
-                app.engine.store().primary_color.g,
-                app.engine.store().primary_color.b,
-            ];
+            let to_rgb = |c: Color| [c.r, c.g, c.b];
+            let mut color_arr = to_rgb(app.engine.store().primary_color);
+            let mut secondary_arr = to_rgb(app.engine.store().secondary_color);
             
             ui.horizontal(|ui| {
                 if ui.color_edit_button_srgb(&mut color_arr).changed() {
                     let new_color = Color::new(color_arr[0], color_arr[1], color_arr[2], 255);
                     app.enqueue_command(AppCommand::SetPrimaryColor(new_color));
                 }
+                if ui.small_button("⇄").on_hover_text(t!("palette.swap_colors")).clicked() {
+                    app.enqueue_command(AppCommand::SwapColors);
+                }
+                if ui.color_edit_button_srgb(&mut secondary_arr).on_hover_text(t!("palette.secondary_color")).changed() {
+                    let new_color = Color::new(secondary_arr[0], secondary_arr[1], secondary_arr[2], 255);
+                    app.enqueue_command(AppCommand::SetSecondaryColor(new_color));
+                }
                 
                 ui.label(RichText::new(t!("palette.main_color").to_string()).size(11.0).color(Color32::LIGHT_GRAY));
                 
@@ -64,50 +70,220 @@ impl PalettePanel {
                 });
             });
 
+            // 最近使用的颜色：左键设为主色，右键设为副色
+            let recent = app.engine.store().recent_colors.clone();
+            if !recent.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing = vec2(2.0, 2.0);
+                    for c in recent { Self::pick_swatch(ui, app, c, 14.0); }
+                });
+            }
+
+            Self::show_picker(ui, app);
+            ui.separator();
+            Self::show_tools(ui, app);
             ui.separator();
 
+            let palette = app.engine.store().palette.clone();
             egui::ScrollArea::vertical()
                 .id_source("palette_scroll")
                 .max_height(200.0)
                 .auto_shrink([false, true])
                 .show(ui, |ui| {
+                // 色带各占一行，其余颜色按调色板顺序排列
+                for ramp in &palette.ramps {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(&ramp.name).size(11.0).color(Color32::LIGHT_GRAY)).context_menu(|ui| {
+                            ui.text_edit_singleline(&mut app.ui.palette_rename_buffer);
+                            if ui.button(t!("palette.rename_ramp")).clicked() {
+                                app.enqueue_command(AppCommand::RenamePaletteRamp(ramp.id, app.ui.palette_rename_buffer.clone()));
+                                ui.close_menu();
+                            }
+                            if ui.button(t!("palette.dissolve_ramp")).clicked() {
+                                app.enqueue_command(AppCommand::RemovePaletteRamp(ramp.id));
+                                ui.close_menu();
+                            }
+                        });
+                    });
+                    ui.horizontal_wrapped(|ui| {
+                        ui.spacing_mut().item_spacing = vec2(4.0, 4.0);
+                        for i in palette.ramp_members(ramp.id) {
+                            Self::swatch(ui, app, &palette, i);
+                        }
+                    });
+                }
                 ui.horizontal_wrapped(|ui| {
                     ui.spacing_mut().item_spacing = vec2(4.0, 4.0);
+                    for i in (0..palette.colors.len()).filter(|&i| palette.ramp_of(i).and_then(|id| palette.ramp(id)).is_none()) {
+                        Self::swatch(ui, app, &palette, i);
+                    }
+                });
+            });
+        });
+    }
 
-                    let cloned_colors = app.engine.store().palette.colors.clone();
-                    
-                    for (i, color) in cloned_colors.into_iter().enumerate() {
-                        let egui_color = Color32::from_rgb(color.r, color.g, color.b);
-                        let is_selected = app.engine.store().primary_color == color;
-                        
-                        let (rect, response) = ui.allocate_exact_size(vec2(20.0, 20.0), Sense::click());
-                        
-                        let stroke = if is_selected {
-                            Stroke::new(2.0, Color32::WHITE)
-                        } else if response.hovered() {
-                            Stroke::new(1.0, Color32::LIGHT_GRAY)
-                        } else {
-                            Stroke::new(1.0, Color32::DARK_GRAY)
-                        };
-                        
-                        ui.painter().rect_filled(rect, 2.0, egui_color);
-                        ui.painter().rect_stroke(rect, 2.0, stroke);
-
-                        if response.clicked() {
-                            app.enqueue_command(AppCommand::SetPrimaryColor(color));
-                        }
+    // 可点击的小色块：左键设为主色，右键设为副色
+    fn pick_swatch(ui: &mut Ui, app: &mut AppState, color: Color, size: f32) {
+        let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::click());
+        ui.painter().rect_filled(rect, 1.0, Color32::from_rgb(color.r, color.g, color.b));
+        if response.hovered() { ui.painter().rect_stroke(rect, 1.0, Stroke::new(1.0, Color32::WHITE)); }
+        if response.clicked() { app.enqueue_command(AppCommand::SetPrimaryColor(color)); }
+        if response.secondary_clicked() { app.enqueue_command(AppCommand::SetSecondaryColor(color)); }
+        response.on_hover_text(format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b));
+    }
 
-                        let response = response.on_hover_text(format!("#{:02X}{:02X}{:02X}\n{}", color.r, color.g, color.b, t!("palette.delete_color")));
+    // 按 HSV / HSL / OKLCH 分量调整主色，并以主色生成色相偏移的明暗色阶
+    fn show_picker(ui: &mut Ui, app: &mut AppState) {
+        egui::CollapsingHeader::new(t!("palette.picker")).id_source("palette_picker").show(ui, |ui| {
+            let primary = app.engine.store().primary_color;
+            ui.horizontal(|ui| {
+                for model in ColorModel::ALL {
+                    ui.selectable_value(&mut app.ui.picker_model, model, model.label());
+                }
+            });
+            let model = app.ui.picker_model;
+            let mut parts = model.components(primary);
+            let mut changed = false;
+            for (value, (name, lo, hi)) in parts.iter_mut().zip(model.ranges()) {
+                changed |= ui.add(egui::Slider::new(value, lo..=hi).text(name)).changed();
+            }
+            if changed {
+                app.enqueue_command(AppCommand::SetPrimaryColor(model.to_color(parts, primary.a)));
+            }
 
-                        response.context_menu(|ui| {
-                            if ui.button(format!("{} {}", ICON_TRASH, t!("palette.delete_btn"))).clicked() {
-                                app.enqueue_command(AppCommand::RemovePaletteColor(i));
-                                ui.close_menu();
-                            }
-                        });
+            ui.horizontal(|ui| {
+                ui.label(t!("palette.shades"));
+                ui.add(egui::DragValue::new(&mut app.ui.shade_count).clamp_range(2..=16));
+                ui.label(t!("palette.hue_shift"));
+                ui.add(egui::DragValue::new(&mut app.ui.shade_hue_shift).clamp_range(-90.0..=90.0).suffix("°"));
+            });
+            let shades = generate_shades(primary, app.ui.shade_count, app.ui.shade_hue_shift);
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = vec2(0.0, 0.0);
+                for c in &shades { Self::pick_swatch(ui, app, *c, 16.0); }
+            });
+            if ui.button(t!("palette.add_as_ramp")).clicked() {
+                let name = t!("palette.ramp_default_name", num = app.engine.store().palette.ramps.len() + 1).to_string();
+                app.enqueue_command(AppCommand::AddPaletteRamp { name, colors: shades });
+            }
+        });
+    }
+
+    // 排序、去重、合并、从文档取色与色带生成器
+    fn show_tools(ui: &mut Ui, app: &mut AppState) {
+        ui.horizontal_wrapped(|ui| {
+            ui.menu_button(t!("palette.sort"), |ui| {
+                for key in PaletteSortKey::ALL {
+                    if ui.button(key.label()).clicked() {
+                        app.enqueue_command(AppCommand::SortPalette(key));
+                        ui.close_menu();
                     }
-                });
+                }
+            });
+            if ui.button(t!("palette.dedupe")).on_hover_text(t!("palette.dedupe_hint")).clicked() {
+                app.enqueue_command(AppCommand::DedupePalette);
+            }
+            if ui.button(t!("palette.merge")).on_hover_text(t!("palette.merge_hint")).clicked() {
+                app.enqueue_command(AppCommand::MergePaletteFromFile);
+            }
+            if ui.button(t!("palette.from_document")).on_hover_text(t!("palette.from_document_hint")).clicked() {
+                app.enqueue_command(AppCommand::CreatePaletteFromUsedColors);
+            }
+        });
+
+        egui::CollapsingHeader::new(t!("palette.ramp_generator")).id_source("palette_ramp_gen").show(ui, |ui| {
+            let to_rgb = |c: Color| [c.r, c.g, c.b];
+            let (mut from, mut to) = (to_rgb(app.ui.ramp_from), to_rgb(app.ui.ramp_to));
+            ui.horizontal(|ui| {
+                if ui.color_edit_button_srgb(&mut from).changed() { app.ui.ramp_from = Color::new(from[0], from[1], from[2], 255); }
+                ui.label("→");
+                if ui.color_edit_button_srgb(&mut to).changed() { app.ui.ramp_to = Color::new(to[0], to[1], to[2], 255); }
+                if ui.small_button(t!("palette.use_primary")).on_hover_text(t!("palette.use_primary_hint")).clicked() {
+                    app.ui.ramp_from = app.engine.store().primary_color;
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(t!("palette.ramp_steps"));
+                ui.add(egui::DragValue::new(&mut app.ui.ramp_steps).clamp_range(2..=32));
+                ui.label(t!("palette.hue_shift"));
+                ui.add(egui::DragValue::new(&mut app.ui.ramp_hue_shift).clamp_range(-90.0..=90.0).suffix("°"));
+            });
+            let colors = generate_ramp(app.ui.ramp_from, app.ui.ramp_to, app.ui.ramp_steps, app.ui.ramp_hue_shift);
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing = vec2(0.0, 0.0);
+                for c in &colors {
+                    let (rect, _) = ui.allocate_exact_size(vec2(14.0, 14.0), Sense::hover());
+                    ui.painter().rect_filled(rect, 0.0, Color32::from_rgb(c.r, c.g, c.b));
+                }
+            });
+            if ui.button(t!("palette.add_as_ramp")).clicked() {
+                let name = t!("palette.ramp_default_name", num = app.engine.store().palette.ramps.len() + 1).to_string();
+                app.enqueue_command(AppCommand::AddPaletteRamp { name, colors });
+            }
+        });
+    }
+
+    fn swatch(ui: &mut Ui, app: &mut AppState, palette: &Palette, i: usize) {
+        let color = palette.colors[i];
+        let egui_color = Color32::from_rgb(color.r, color.g, color.b);
+        let is_selected = app.engine.store().primary_color == color;
+        
+        let (rect, response) = ui.allocate_exact_size(vec2(20.0, 20.0), Sense::click());
+        
+        let stroke = if is_selected {
+            Stroke::new(2.0, Color32::WHITE)
+        } else if response.hovered() {
+            Stroke::new(1.0, Color32::LIGHT_GRAY)
+        } else {
+            Stroke::new(1.0, Color32::DARK_GRAY)
+        };
+        
+        ui.painter().rect_filled(rect, 2.0, egui_color);
+        ui.painter().rect_stroke(rect, 2.0, stroke);
+
+        if response.clicked() {
+            app.enqueue_command(AppCommand::SetPrimaryColor(color));
+        }
+
+        let hex = format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b);
+        let label = match palette.color_name(i) {
+            "" => hex,
+            name => format!("{} {}", name, hex),
+        };
+        let response = response.on_hover_text(format!("{}\n{}", label, t!("palette.delete_color")));
+
+        response.context_menu(|ui| {
+            ui.text_edit_singleline(&mut app.ui.palette_rename_buffer);
+            if ui.button(t!("palette.rename")).clicked() {
+                app.enqueue_command(AppCommand::RenamePaletteColor(i, app.ui.palette_rename_buffer.clone()));
+                ui.close_menu();
+            }
+            ui.menu_button(t!("palette.move_to_ramp"), |ui| {
+                for ramp in &palette.ramps {
+                    if ui.button(&ramp.name).clicked() {
+                        app.enqueue_command(AppCommand::AssignPaletteRamp { indices: vec![i], ramp: Some(ramp.id) });
+                        ui.close_menu();
+                    }
+                }
+                if ui.button(t!("palette.new_ramp")).clicked() {
+                    // 新建色带并移入，作为一步撤销
+                    let name = t!("palette.ramp_default_name", num = palette.ramps.len() + 1).to_string();
+                    let mut new = palette.clone();
+                    let id = new.add_ramp(name, &[]);
+                    new.assign_ramp(&[i], Some(id));
+                    app.enqueue_command(AppCommand::SetPalette(new));
+                    ui.close_menu();
+                }
+                if palette.ramp_of(i).is_some() && ui.button(t!("palette.remove_from_ramp")).clicked() {
+                    app.enqueue_command(AppCommand::AssignPaletteRamp { indices: vec![i], ramp: None });
+                    ui.close_menu();
+                }
             });
+            ui.separator();
+            if ui.button(format!("{} {}", ICON_TRASH, t!("palette.delete_btn"))).clicked() {
+                app.enqueue_command(AppCommand::RemovePaletteColor(i));
+                ui.close_menu();
+            }
         });
     }
-}
\ No newline at end of file
+}
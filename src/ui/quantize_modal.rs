@@ -0,0 +1,79 @@
+use egui::{Context, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::render::quantize::{ColorMetric, Dither, PaletteGenMethod};
+use rust_i18n::t;
+
+pub struct QuantizeModal;
+
+impl QuantizeModal {
+    pub fn show(ctx: &Context, app: &mut AppState) {
+        if !app.ui.show_quantize_modal { return; }
+        let layer_id = match app.engine.store().active_layer_id.clone() {
+            Some(id) => id,
+            None => { app.ui.show_quantize_modal = false; return; }
+        };
+        let palette_len = app.engine.store().palette.colors.len();
+        let has_selection = app.engine.store().selection.is_active;
+        let mut commands = Vec::new();
+        let mut close = false;
+
+        egui::Window::new(t!("quantize.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                super::scope_label(ui, has_selection);
+                ui.separator();
+
+                let settings = &mut app.ui.quantize_settings;
+                egui::Grid::new("quantize_settings").num_columns(2).show(ui, |ui| {
+                    ui.label(t!("quantize.metric"));
+                    egui::ComboBox::from_id_source("quantize_metric").selected_text(settings.metric.label()).show_ui(ui, |ui| {
+                        for metric in ColorMetric::ALL { ui.selectable_value(&mut settings.metric, metric, metric.label()); }
+                    });
+                    ui.end_row();
+                    ui.label(t!("quantize.dither"));
+                    egui::ComboBox::from_id_source("quantize_dither").selected_text(settings.dither.label()).show_ui(ui, |ui| {
+                        for dither in Dither::ALL { ui.selectable_value(&mut settings.dither, dither, dither.label()); }
+                    });
+                    ui.end_row();
+                    ui.label(t!("quantize.alpha_threshold"));
+                    ui.add(egui::Slider::new(&mut settings.alpha_threshold, 0..=255));
+                    ui.end_row();
+                });
+                let settings = *settings;
+
+                ui.add_space(6.0);
+                ui.add_enabled_ui(palette_len > 0, |ui| {
+                    if ui.button(t!("quantize.map_to_palette", count = palette_len)).clicked() {
+                        commands.push(AppCommand::QuantizeLayer { layer_id: layer_id.clone(), settings });
+                    }
+                });
+
+                ui.separator();
+                ui.label(t!("quantize.generate"));
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("palette_gen_method").selected_text(app.ui.palette_gen_method.label()).show_ui(ui, |ui| {
+                        for method in PaletteGenMethod::ALL { ui.selectable_value(&mut app.ui.palette_gen_method, method, method.label()); }
+                    });
+                    ui.add(egui::DragValue::new(&mut app.ui.palette_gen_count).clamp_range(2..=256).suffix(t!("quantize.colors_suffix")));
+                });
+                let (method, count) = (app.ui.palette_gen_method, app.ui.palette_gen_count);
+                ui.horizontal(|ui| {
+                    if ui.button(t!("quantize.generate_only")).clicked() {
+                        commands.push(AppCommand::GeneratePaletteFromLayer { layer_id: layer_id.clone(), method, count, remap: None });
+                    }
+                    if ui.button(t!("quantize.generate_and_map")).clicked() {
+                        commands.push(AppCommand::GeneratePaletteFromLayer { layer_id: layer_id.clone(), method, count, remap: Some(settings) });
+                    }
+                });
+
+                ui.add_space(10.0);
+                if ui.button(t!("dialog.close")).clicked() { close = true; }
+            });
+
+        for cmd in commands { app.enqueue_command(cmd); }
+        if close { app.ui.show_quantize_modal = false; }
+    }
+}
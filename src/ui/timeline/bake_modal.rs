@@ -0,0 +1,50 @@
+use egui::{Ui, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+use crate::animation::bake::BakeTarget;
+use crate::render::resample::ResampleMode;
+
+pub struct BakeModal;
+
+impl BakeModal {
+    pub fn show(ui: &mut Ui, app: &mut AppState) {
+        if app.ui.show_bake_modal {
+            egui::Window::new("烘焙动画 (Bake)")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+                .show(ui.ctx(), |ui| {
+                    let settings = &mut app.ui.bake_settings;
+                    ui.horizontal(|ui| {
+                        ui.label("帧率:");
+                        ui.add(egui::DragValue::new(&mut settings.fps).clamp_range(1..=120).suffix(" fps"));
+                    });
+
+                    ui.label("取样:");
+                    for mode in ResampleMode::ALL {
+                        ui.radio_value(&mut settings.resample, mode, mode.label());
+                    }
+
+                    ui.label("输出:");
+                    ui.radio_value(&mut settings.target, BakeTarget::Layers, "每帧一个图层 (默认隐藏)");
+                    ui.radio_value(&mut settings.target, BakeTarget::SpriteSheet, "精灵表 (PNG)");
+                    if settings.target == BakeTarget::SpriteSheet {
+                        ui.horizontal(|ui| {
+                            ui.label("每行帧数:");
+                            ui.add(egui::DragValue::new(&mut settings.columns).clamp_range(0..=256))
+                                .on_hover_text("0 为自动");
+                        });
+                    }
+
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("✅ 烘焙").clicked() {
+                            app.enqueue_command(AppCommand::BakeAnimation(app.ui.bake_settings));
+                            app.ui.show_bake_modal = false;
+                        }
+                        if ui.button("❌ 取消").clicked() { app.ui.show_bake_modal = false; }
+                    });
+                });
+        }
+    }
+}
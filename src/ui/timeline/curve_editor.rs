@@ -1,10 +1,14 @@
 use egui::{Ui, Color32, Sense, Stroke, Pos2, vec2, Rect, Align2, FontId};
 use crate::app::state::AppState;
-use crate::core::animation::timeline::{TimelineProperty, KeyframeValue, CurveType};
+use crate::core::animation::timeline::{TimelineProperty, CurveType, TangentMode, Timeline};
+use crate::core::animation::graph::{self, TangentSide};
+use crate::core::animation::easing::{EasingFunction, EasingDirection};
 use crate::app::commands::AppCommand;
 
 pub struct CurveEditor;
 
+const AXIS_WIDTH: f32 = 44.0;
+
 impl CurveEditor {
     pub fn show(ui: &mut Ui, app: &mut AppState) {
         let mut show_curve_editor = app.ui.show_curve_editor;
@@ -15,10 +19,51 @@ impl CurveEditor {
                 .default_size(vec2(800.0, 450.0))
                 .show(ui.ctx(), |ui| {
                     let mut pending_commands = Vec::new();
+                    let mut frame_all = false;
+
+                    ui.horizontal(|ui| {
+                        let filter_text = match &app.ui.graph_channel_filter {
+                            None => "全部通道",
+                            Some(TimelineProperty::Rotation) => "旋转",
+                            Some(TimelineProperty::Translation) => "移动",
+                            Some(TimelineProperty::Scale) => "缩放",
+                            Some(_) => "切变",
+                        };
+                        egui::ComboBox::from_id_source("graph_channel_filter").selected_text(filter_text).show_ui(ui, |ui| {
+                            ui.selectable_value(&mut app.ui.graph_channel_filter, None, "全部通道");
+                            ui.selectable_value(&mut app.ui.graph_channel_filter, Some(TimelineProperty::Rotation), "旋转");
+                            ui.selectable_value(&mut app.ui.graph_channel_filter, Some(TimelineProperty::Translation), "移动");
+                            ui.selectable_value(&mut app.ui.graph_channel_filter, Some(TimelineProperty::Scale), "缩放");
+                            ui.selectable_value(&mut app.ui.graph_channel_filter, Some(TimelineProperty::Shear), "切变");
+                        });
+                        ui.checkbox(&mut app.ui.graph_normalized, "归一化").on_hover_text("每条曲线映射到 -1 ~ 1 显示");
+                        if ui.button("⛶ 全部显示").on_hover_text("缩放视图以显示所有关键帧").clicked() { frame_all = true; }
+                        ui.separator();
+
+                        let keys = graph_selection(app);
+                        ui.add_enabled_ui(!keys.is_empty(), |ui| {
+                            ui.label("切线:");
+                            for (mode, label) in [(TangentMode::Auto, "自动"), (TangentMode::Aligned, "对齐"), (TangentMode::Broken, "断开")] {
+                                if ui.button(label).clicked() {
+                                    pending_commands.push(AppCommand::SetKeyframeTangentMode { keys: keys.clone(), mode });
+                                }
+                            }
+                            ui.separator();
+                            ui.label("缩放 时间");
+                            ui.add(egui::DragValue::new(&mut app.ui.graph_time_scale).speed(0.01).clamp_range(0.01..=10.0));
+                            ui.label("数值");
+                            ui.add(egui::DragValue::new(&mut app.ui.graph_value_scale).speed(0.01).clamp_range(-10.0..=10.0));
+                            if ui.button("应用").on_hover_text("以播放头为时间轴心、选中关键帧的数值中心为数值轴心缩放").clicked() {
+                                pending_commands.push(AppCommand::ScaleSelectedKeyframes { time_scale: app.ui.graph_time_scale, value_scale: app.ui.graph_value_scale });
+                            }
+                        });
+                    });
+
                     let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
-                    
-                    if response.dragged() && !ui.input(|i| i.pointer.any_down() && i.modifiers.is_none()) {
-                        app.ui.graph_pan += response.drag_delta(); 
+                    let shift = ui.input(|i| i.modifiers.shift);
+
+                    if response.dragged_by(egui::PointerButton::Middle) || (response.dragged_by(egui::PointerButton::Primary) && ui.input(|i| i.modifiers.alt)) {
+                        app.ui.graph_pan += response.drag_delta();
                     }
                     if response.hovered() {
                         let scroll = ui.input(|i| i.scroll_delta.y);
@@ -28,165 +73,248 @@ impl CurveEditor {
                         }
                     }
 
+                    let curves = match (&app.animation.project.active_animation_id, &app.ui.selected_bone_id) {
+                        (Some(anim_id), Some(bone_id)) => app.animation.project.animations.get(anim_id)
+                            .map(|anim| visible_curves(anim.timelines.iter().filter(|t| &t.target_id == bone_id), &app.ui.graph_channel_filter, app.ui.graph_normalized))
+                            .unwrap_or_default(),
+                        _ => Vec::new(),
+                    };
+
+                    if frame_all {
+                        let mut t_range = (f32::MAX, f32::MIN);
+                        let mut v_range = (f32::MAX, f32::MIN);
+                        for c in &curves {
+                            for (i, kf) in c.timeline.keyframes.iter().enumerate() {
+                                t_range = (t_range.0.min(kf.time), t_range.1.max(kf.time));
+                                let v = c.key_value(i);
+                                v_range = (v_range.0.min(v), v_range.1.max(v));
+                            }
+                        }
+                        if t_range.0 <= t_range.1 {
+                            let ((pan_x, pan_y), (zoom_x, zoom_y)) = graph::frame_view(t_range, v_range, (rect.width() - AXIS_WIDTH, rect.height()), 30.0);
+                            app.ui.graph_pan = vec2(pan_x + AXIS_WIDTH, pan_y);
+                            app.ui.graph_zoom = vec2(zoom_x, zoom_y);
+                        }
+                    }
+
                     let painter = ui.painter_at(rect);
                     painter.rect_filled(rect, 0.0, Color32::from_rgb(45, 45, 48));
 
                     let origin_y = rect.center().y + app.ui.graph_pan.y;
                     let origin_x = rect.min.x + app.ui.graph_pan.x;
+                    let zoom = app.ui.graph_zoom;
+                    let to_screen = |t: f32, v: f32| Pos2::new(origin_x + t * zoom.x, origin_y - v * zoom.y);
 
-                    let val_step = 100.0 * app.ui.graph_zoom.y;
+                    let val_step = 100.0 * zoom.y;
                     let mut y_offset = origin_y % val_step;
                     if y_offset < 0.0 { y_offset += val_step; }
                     let mut current_y = rect.min.y + y_offset;
+                    let mut axis_labels = Vec::new();
                     while current_y <= rect.max.y {
                         painter.hline(rect.x_range(), current_y, Stroke::new(1.0, Color32::from_rgb(30, 30, 32)));
-                        let val = (origin_y - current_y) / app.ui.graph_zoom.y;
-                        painter.text(Pos2::new(rect.max.x - 30.0, current_y - 8.0), Align2::LEFT_TOP, format!("{:.0}", val.round()), FontId::proportional(10.0), Color32::from_gray(140));
+                        axis_labels.push((current_y, (origin_y - current_y) / zoom.y));
                         current_y += val_step;
                     }
 
-                    let fps = 30.0;
-                    let frame_step_x = (10.0 / fps) * app.ui.graph_zoom.x;
+                    let fps = app.animation.project.fps_f32();
+                    let frame_step_x = (10.0 / fps) * zoom.x;
                     let mut x_offset = origin_x % frame_step_x;
                     if x_offset < 0.0 { x_offset += frame_step_x; }
                     let mut current_x = rect.min.x + x_offset;
                     while current_x <= rect.max.x {
                         painter.vline(current_x, rect.y_range(), Stroke::new(1.0, Color32::from_rgb(35, 35, 38)));
-                        let frame = ((current_x - origin_x) / app.ui.graph_zoom.x * fps).round() as i32;
+                        let frame = ((current_x - origin_x) / zoom.x * fps).round() as i32;
                         painter.text(Pos2::new(current_x + 4.0, rect.min.y + 4.0), Align2::LEFT_TOP, format!("{}", frame), FontId::proportional(10.0), Color32::from_gray(150));
                         current_x += frame_step_x;
                     }
 
                     painter.hline(rect.x_range(), origin_y, Stroke::new(1.5, Color32::from_rgb(80, 80, 85)));
 
-                    if let Some(anim_id) = &app.animation.project.active_animation_id {
-                        if let Some(anim) = app.animation.project.animations.get(anim_id) {
-                            if let Some(bone_id) = &app.ui.selected_bone_id {
-                                for tl in &anim.timelines {
-                                    if &tl.target_id == bone_id {
-                                        let (col_x, col_y) = match tl.property {
-                                            TimelineProperty::Rotation => (Color32::from_rgb(0, 255, 0), Color32::from_rgb(0, 255, 0)),
-                                            TimelineProperty::Translation => (Color32::from_rgb(0, 153, 255), Color32::from_rgb(0, 102, 204)),
-                                            TimelineProperty::Scale => (Color32::from_rgb(255, 51, 51), Color32::from_rgb(204, 0, 0)),
-                                            _ => continue,
-                                        };
-
-                                        for i in 0..tl.keyframes.len() {
-                                            let kf = &tl.keyframes[i];
-                                            let screen_x = origin_x + kf.time * app.ui.graph_zoom.x;
-                                            let (val_x, val_y) = match kf.value {
-                                                KeyframeValue::Rotate(r) => (r, r),
-                                                KeyframeValue::Translate(tx, ty) => (tx, ty),
-                                                KeyframeValue::Scale(sx, sy) => (sx * 100.0, sy * 100.0),
-                                                _ => (0.0, 0.0),
-                                            };
-                                            let p_x = Pos2::new(screen_x, origin_y - val_x * app.ui.graph_zoom.y);
-                                            let p_y = Pos2::new(screen_x, origin_y - val_y * app.ui.graph_zoom.y);
-
-                                            let kf_rect = Rect::from_center_size(p_x, vec2(6.0, 14.0));
-                                            let resp = ui.interact(kf_rect, ui.id().with(format!("gkf_{}_{:?}_{}", bone_id, tl.property, kf.time)), Sense::click());
-                                            
-                                            resp.context_menu(|ui| {
-                                                if ui.button("直线 (Linear)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Linear)); ui.close_menu(); }
-                                                if ui.button("平滑 (Bezier)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Bezier(0.33, 0.0, 0.66, 1.0))); ui.close_menu(); }
-                                                if ui.button("阶跃 (Stepped)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Stepped)); ui.close_menu(); }
-                                            });
-
-                                            if i + 1 < tl.keyframes.len() {
-                                                let next_kf = &tl.keyframes[i+1];
-                                                let n_screen_x = origin_x + next_kf.time * app.ui.graph_zoom.x;
-                                                let (n_val_x, n_val_y) = match next_kf.value {
-                                                    KeyframeValue::Rotate(r) => (r, r),
-                                                    KeyframeValue::Translate(tx, ty) => (tx, ty),
-                                                    KeyframeValue::Scale(sx, sy) => (sx * 100.0, sy * 100.0),
-                                                    _ => (0.0, 0.0),
-                                                };
-                                                let n_p_x = Pos2::new(n_screen_x, origin_y - n_val_x * app.ui.graph_zoom.y);
-                                                let n_p_y = Pos2::new(n_screen_x, origin_y - n_val_y * app.ui.graph_zoom.y);
-
-                                                match kf.curve {
-                                                    CurveType::Linear => {
-                                                        painter.line_segment([p_x, n_p_x], Stroke::new(1.5, col_x));
-                                                        if matches!(tl.property, TimelineProperty::Translation | TimelineProperty::Scale) { painter.line_segment([p_y, n_p_y], Stroke::new(1.5, col_y)); }
-                                                    }
-                                                    CurveType::Stepped => {
-                                                        let mid_x = Pos2::new(n_p_x.x, p_x.y);
-                                                        painter.line_segment([p_x, mid_x], Stroke::new(1.5, col_x));
-                                                        painter.line_segment([mid_x, n_p_x], Stroke::new(1.5, col_x));
-                                                    }
-                                                    CurveType::Bezier(cx1, cy1, cx2, cy2) => {
-                                                        let segments = 40;
-                                                        let mut last_px = p_x;
-                                                        let mut last_py = p_y;
-                                                        for s in 1..=segments {
-                                                            let t = s as f32 / segments as f32;
-                                                            let eased_t = crate::core::animation::timeline::Timeline::solve_bezier_y(cx1, cy1, cx2, cy2, t);
-                                                            let cur_sx = p_x.x + (n_p_x.x - p_x.x) * t;
-                                                            let cur_sy_x = p_x.y + (n_p_x.y - p_x.y) * eased_t;
-                                                            let cur_px = Pos2::new(cur_sx, cur_sy_x);
-                                                            painter.line_segment([last_px, cur_px], Stroke::new(1.5, col_x));
-                                                            last_px = cur_px;
-                                                            
-                                                            if matches!(tl.property, TimelineProperty::Translation | TimelineProperty::Scale) {
-                                                                let cur_sy_y = p_y.y + (n_p_y.y - p_y.y) * eased_t;
-                                                                let cur_py = Pos2::new(cur_sx, cur_sy_y);
-                                                                painter.line_segment([last_py, cur_py], Stroke::new(2.0, col_y));
-                                                                last_py = cur_py;
-                                                            }
-                                                        }
-
-                                                        let handle_col = Color32::from_rgb(0, 255, 255);
-                                                        let h1_pos = Pos2::new(p_x.x + cx1 * (n_p_x.x - p_x.x), p_x.y + cy1 * (n_p_x.y - p_x.y));
-                                                        let h2_pos = Pos2::new(p_x.x + cx2 * (n_p_x.x - p_x.x), p_x.y + cy2 * (n_p_x.y - p_x.y));
-
-                                                        painter.line_segment([p_x, h1_pos], Stroke::new(1.0, handle_col.linear_multiply(0.7)));
-                                                        painter.line_segment([n_p_x, h2_pos], Stroke::new(1.0, handle_col.linear_multiply(0.7)));
-
-                                                        let h1_rect = Rect::from_center_size(h1_pos, vec2(12.0, 12.0));
-                                                        let h2_rect = Rect::from_center_size(h2_pos, vec2(12.0, 12.0));
-                                                        let h1_resp = ui.interact(h1_rect, ui.id().with(format!("h1_{}_{:?}_{}", bone_id, tl.property, kf.time)), Sense::drag());
-                                                        let h2_resp = ui.interact(h2_rect, ui.id().with(format!("h2_{}_{:?}_{}", bone_id, tl.property, kf.time)), Sense::drag());
-
-                                                        painter.circle_stroke(h1_pos, 4.0, Stroke::new(1.5, handle_col));
-                                                        painter.circle_stroke(h2_pos, 4.0, Stroke::new(1.5, handle_col));
-                                                        painter.circle_filled(h1_pos, 2.0, Color32::from_rgb(25, 25, 25));
-                                                        painter.circle_filled(h2_pos, 2.0, Color32::from_rgb(25, 25, 25));
-
-                                                        if h1_resp.dragged() || h2_resp.dragged() {
-                                                            let mut new_cx1 = cx1; let mut new_cy1 = cy1;
-                                                            let mut new_cx2 = cx2; let mut new_cy2 = cy2;
-                                                            let dx = n_p_x.x - p_x.x;
-                                                            let dy = n_p_x.y - p_x.y;
-                                                            let safe_dy = if dy.abs() < 0.1 { 100.0 } else { dy }; 
-                                                            
-                                                            if dx.abs() > 0.001 {
-                                                                if let Some(pos) = h1_resp.interact_pointer_pos() { 
-                                                                    new_cx1 = ((pos.x - p_x.x) / dx).clamp(0.0, 1.0); 
-                                                                    new_cy1 = if dy.abs() > 0.1 { (pos.y - p_x.y) / safe_dy } else { 0.0 };
-                                                                }
-                                                                if let Some(pos) = h2_resp.interact_pointer_pos() { 
-                                                                    new_cx2 = ((pos.x - p_x.x) / dx).clamp(0.0, 1.0); 
-                                                                    new_cy2 = if dy.abs() > 0.1 { (pos.y - p_x.y) / safe_dy } else { 0.0 };
-                                                                }
-                                                                pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Bezier(new_cx1, new_cy1, new_cx2, new_cy2)));
-                                                            }
-                                                        }
-                                                    }
-                                                }
+                    let mut key_hits = Vec::new();
+                    for c in &curves {
+                        let tl = &c.timeline;
+                        let bone_id = &tl.target_id;
+                        for i in 0..tl.keyframes.len() {
+                            let kf = &tl.keyframes[i];
+                            let p = to_screen(kf.time, c.key_value(i));
+                            key_hits.push((bone_id.clone(), tl.property.clone(), kf.time, p));
+
+                            if i + 1 < tl.keyframes.len() {
+                                let next_kf = &tl.keyframes[i + 1];
+                                let segments = match kf.curve { CurveType::Linear => 1, _ => 40 };
+                                let mut last = p;
+                                for s in 1..=segments {
+                                    let t = kf.time + (next_kf.time - kf.time) * s as f32 / segments as f32;
+                                    let v = if s == segments { c.key_value(i + 1) } else { c.sample(t) };
+                                    let cur = to_screen(t, v);
+                                    if s == segments && kf.curve == CurveType::Stepped {
+                                        let corner = Pos2::new(cur.x, last.y);
+                                        painter.line_segment([last, corner], Stroke::new(1.5, c.color));
+                                        last = corner;
+                                    }
+                                    painter.line_segment([last, cur], Stroke::new(1.5, c.color));
+                                    last = cur;
+                                }
+                            }
+
+                            let is_selected = is_key_selected(app, bone_id, &tl.property, kf.time);
+                            if c.channel == graph::key_channel(tl, i) {
+                                for side in [TangentSide::In, TangentSide::Out] {
+                                    let seg = match side {
+                                        TangentSide::Out if i + 1 < tl.keyframes.len() => i,
+                                        TangentSide::In if i > 0 => i - 1,
+                                        _ => continue,
+                                    };
+                                    let (cx1, cy1, cx2, cy2) = match graph::segment_handles(&tl.keyframes[seg].curve) {
+                                        Some(h) => h,
+                                        None => continue,
+                                    };
+                                    if !is_selected && !matches!(tl.keyframes[seg].curve, CurveType::Bezier(..)) { continue; }
+                                    let a = to_screen(tl.keyframes[seg].time, c.key_value(seg));
+                                    let b = to_screen(tl.keyframes[seg + 1].time, c.key_value(seg + 1));
+                                    let (hx, hy) = if side == TangentSide::Out { (cx1, cy1) } else { (cx2, cy2) };
+                                    let h_pos = Pos2::new(a.x + hx * (b.x - a.x), a.y + hy * (b.y - a.y));
+
+                                    let handle_col = match kf.tangent {
+                                        TangentMode::Auto => Color32::from_rgb(255, 200, 80),
+                                        TangentMode::Aligned => Color32::from_rgb(0, 255, 255),
+                                        TangentMode::Broken => Color32::from_rgb(255, 120, 120),
+                                    };
+                                    painter.line_segment([p, h_pos], Stroke::new(1.0, handle_col.linear_multiply(0.7)));
+                                    painter.circle_stroke(h_pos, 4.0, Stroke::new(1.5, handle_col));
+                                    painter.circle_filled(h_pos, 2.0, Color32::from_rgb(25, 25, 25));
+
+                                    let h_resp = ui.interact(Rect::from_center_size(h_pos, vec2(12.0, 12.0)), ui.id().with(format!("h_{}_{:?}_{}_{:?}", bone_id, tl.property, kf.time, side)), Sense::drag());
+                                    if h_resp.dragged() {
+                                        if let Some(pos) = h_resp.interact_pointer_pos() {
+                                            let (dx, dy) = (b.x - a.x, b.y - a.y);
+                                            if dx.abs() > 0.001 {
+                                                let x = (pos.x - a.x) / dx;
+                                                let y = if dy.abs() > 0.1 { (pos.y - a.y) / dy } else if side == TangentSide::Out { 0.0 } else { 1.0 };
+                                                pending_commands.push(AppCommand::SetKeyframeHandle {
+                                                    target_id: bone_id.clone(), prop: tl.property.clone(), time: kf.time,
+                                                    side, x, y, merge: !h_resp.drag_started(),
+                                                });
                                             }
+                                        }
+                                    }
+                                }
+                            }
 
-                                            painter.rect_filled(Rect::from_center_size(p_x, vec2(3.0, 12.0)), 0.0, Color32::from_rgb(0, 255, 255));
-                                            if matches!(tl.property, TimelineProperty::Translation | TimelineProperty::Scale) { 
-                                                painter.circle_filled(p_y, 3.0, Color32::from_rgb(0, 255, 255)); 
+                            let kf_rect = Rect::from_center_size(p, vec2(6.0, 14.0));
+                            let resp = ui.interact(kf_rect, ui.id().with(format!("gkf_{}_{:?}_{}_{}", bone_id, tl.property, kf.time, c.channel)), Sense::click());
+                            if resp.clicked() {
+                                let key = (bone_id.clone(), Some(tl.property.clone()), kf.time);
+                                if shift {
+                                    if is_selected { app.ui.selected_keyframes.retain(|k| !(k.0 == key.0 && k.1 == key.1 && (k.2 - key.2).abs() < 0.001)); }
+                                    else { app.ui.selected_keyframes.push(key); }
+                                } else {
+                                    app.ui.selected_keyframes = vec![key];
+                                }
+                            }
+                            resp.context_menu(|ui| {
+                                if ui.button("直线 (Linear)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Linear)); ui.close_menu(); }
+                                if ui.button("平滑 (Bezier)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Bezier(0.33, 0.0, 0.66, 1.0))); ui.close_menu(); }
+                                if ui.button("阶跃 (Stepped)").clicked() { pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Stepped)); ui.close_menu(); }
+                                ui.menu_button("缓动预设", |ui| {
+                                    for function in EasingFunction::ALL {
+                                        ui.menu_button(function.label(), |ui| {
+                                            for direction in EasingDirection::ALL {
+                                                if ui.radio(kf.curve == CurveType::Easing(function, direction), direction.label()).clicked() {
+                                                    pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Easing(function, direction)));
+                                                    ui.close_menu();
+                                                }
                                             }
+                                        });
+                                    }
+                                    ui.separator();
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui::DragValue::new(&mut app.ui.curve_steps).clamp_range(1..=64).prefix("阶梯 "));
+                                        if ui.button("应用").clicked() {
+                                            pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Steps(app.ui.curve_steps)));
+                                            ui.close_menu();
                                         }
+                                    });
+                                });
+                                ui.menu_button("我的预设", |ui| {
+                                    if app.ui.curve_presets.is_empty() { ui.weak("暂无预设"); }
+                                    for preset in &app.ui.curve_presets {
+                                        ui.horizontal(|ui| {
+                                            let [cx1, cy1, cx2, cy2] = preset.handles;
+                                            if ui.button(&preset.name).clicked() {
+                                                pending_commands.push(AppCommand::UpdateKeyframeCurve(bone_id.clone(), tl.property.clone(), kf.time, CurveType::Bezier(cx1, cy1, cx2, cy2)));
+                                                ui.close_menu();
+                                            }
+                                            if ui.small_button("🗑").on_hover_text("删除预设").clicked() {
+                                                pending_commands.push(AppCommand::DeleteCurvePreset(preset.name.clone()));
+                                            }
+                                        });
+                                    }
+                                    if let CurveType::Bezier(cx1, cy1, cx2, cy2) = kf.curve {
+                                        ui.separator();
+                                        ui.horizontal(|ui| {
+                                            ui.add(egui::TextEdit::singleline(&mut app.ui.new_curve_preset_name).hint_text("预设名称").desired_width(100.0));
+                                            if ui.button("保存当前曲线").clicked() {
+                                                pending_commands.push(AppCommand::SaveCurvePreset { name: app.ui.new_curve_preset_name.clone(), handles: [cx1, cy1, cx2, cy2] });
+                                                app.ui.new_curve_preset_name.clear();
+                                            }
+                                        });
+                                    }
+                                });
+                                ui.separator();
+                                for (mode, label) in [(TangentMode::Auto, "自动切线"), (TangentMode::Aligned, "对齐切线"), (TangentMode::Broken, "断开切线")] {
+                                    if ui.radio(kf.tangent == mode, label).clicked() {
+                                        pending_commands.push(AppCommand::SetKeyframeTangentMode { keys: vec![(bone_id.clone(), tl.property.clone(), kf.time)], mode });
+                                        ui.close_menu();
                                     }
                                 }
+                            });
+
+                            let key_col = if is_selected { Color32::from_rgb(255, 220, 60) } else { Color32::from_rgb(0, 255, 255) };
+                            if c.channel == 0 {
+                                painter.rect_filled(Rect::from_center_size(p, vec2(3.0, 12.0)), 0.0, key_col);
+                            } else {
+                                painter.circle_filled(p, 3.0, key_col);
                             }
                         }
                     }
 
-                    let playhead_x = origin_x + app.animation.current_time * app.ui.graph_zoom.x;
+                    // 框选：在空白处左键拖动；点击空白处取消选择
+                    if response.drag_started_by(egui::PointerButton::Primary) && !ui.input(|i| i.modifiers.alt) {
+                        app.ui.graph_box_start = response.interact_pointer_pos();
+                    }
+                    if let Some(start) = app.ui.graph_box_start {
+                        let current = ui.input(|i| i.pointer.hover_pos()).unwrap_or(start);
+                        let box_rect = Rect::from_two_pos(start, current);
+                        painter.rect(box_rect, 0.0, Color32::from_rgba_unmultiplied(0, 200, 255, 30), Stroke::new(1.0, Color32::from_rgb(0, 200, 255)));
+                        if response.drag_released() || !ui.input(|i| i.pointer.primary_down()) {
+                            if !shift { app.ui.selected_keyframes.clear(); }
+                            for (bone_id, prop, time, pos) in &key_hits {
+                                if box_rect.contains(*pos) && !is_key_selected(app, bone_id, prop, *time) {
+                                    app.ui.selected_keyframes.push((bone_id.clone(), Some(prop.clone()), *time));
+                                }
+                            }
+                            app.ui.graph_box_start = None;
+                        }
+                    } else if response.clicked() && !shift {
+                        app.ui.selected_keyframes.clear();
+                    }
+
+                    // 数值轴：旋转/切变为角度，移动为像素，缩放为百分比；归一化时为 -1 ~ 1
+                    let axis_rect = Rect::from_min_size(rect.min, vec2(AXIS_WIDTH, rect.height()));
+                    painter.rect_filled(axis_rect, 0.0, Color32::from_rgb(36, 36, 38));
+                    let unit = match &app.ui.graph_channel_filter {
+                        Some(TimelineProperty::Rotation) | Some(TimelineProperty::Shear) => "°",
+                        Some(TimelineProperty::Translation) => "px",
+                        Some(TimelineProperty::Scale) => "%",
+                        _ => "",
+                    };
+                    for (y, val) in axis_labels {
+                        let text = if app.ui.graph_normalized { format!("{:.1}", val / 100.0) } else { format!("{:.0}{}", val.round(), unit) };
+                        painter.text(Pos2::new(axis_rect.max.x - 4.0, y), Align2::RIGHT_CENTER, text, FontId::proportional(10.0), Color32::from_gray(150));
+                    }
+
+                    let playhead_x = origin_x + app.animation.current_time * zoom.x;
                     painter.vline(playhead_x, rect.y_range(), Stroke::new(1.5, Color32::from_rgb(0, 255, 255)));
                     painter.add(egui::Shape::convex_polygon(
                         vec![
@@ -203,4 +331,75 @@ impl CurveEditor {
         }
         app.ui.show_curve_editor = show_curve_editor;
     }
-}
\ No newline at end of file
+}
+
+// 一条通道曲线 (如移动 X) 的显示数据，数值已换算为显示单位
+struct GraphCurve {
+    timeline: Timeline,
+    channel: usize,
+    color: Color32,
+    factor: f32,
+    range: Option<(f32, f32)>,
+}
+
+impl GraphCurve {
+    fn display(&self, v: f32) -> f32 {
+        let v = v * self.factor;
+        match self.range {
+            Some((min, max)) => graph::normalize_value(v, min, max) * 100.0,
+            None => v,
+        }
+    }
+
+    fn key_value(&self, index: usize) -> f32 {
+        self.display(graph::channel_value(&self.timeline.keyframes[index].value, self.channel).unwrap_or(0.0))
+    }
+
+    fn sample(&self, time: f32) -> f32 {
+        self.display(self.timeline.sample(time).and_then(|v| graph::channel_value(&v, self.channel)).unwrap_or(0.0))
+    }
+}
+
+fn visible_curves<'a>(timelines: impl Iterator<Item = &'a Timeline>, filter: &Option<TimelineProperty>, normalized: bool) -> Vec<GraphCurve> {
+    let mut curves = Vec::new();
+    for tl in timelines {
+        if !graph::is_graph_property(&tl.property) { continue; }
+        if filter.as_ref().is_some_and(|f| f != &tl.property) { continue; }
+        let colors = match tl.property {
+            TimelineProperty::Rotation => [Color32::from_rgb(0, 255, 0), Color32::from_rgb(0, 255, 0)],
+            TimelineProperty::Translation => [Color32::from_rgb(0, 153, 255), Color32::from_rgb(0, 102, 204)],
+            TimelineProperty::Scale => [Color32::from_rgb(255, 51, 51), Color32::from_rgb(204, 0, 0)],
+            _ => [Color32::from_rgb(255, 140, 220), Color32::from_rgb(200, 90, 170)],
+        };
+        let factor = if tl.property == TimelineProperty::Scale { 100.0 } else { 1.0 };
+        for (channel, &color) in colors.iter().enumerate().take(graph::channel_count(&tl.property)) {
+            let range = if normalized {
+                let values = tl.keyframes.iter().filter_map(|k| graph::channel_value(&k.value, channel)).map(|v| v * factor);
+                Some(values.fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v))))
+            } else { None };
+            curves.push(GraphCurve { timeline: tl.clone(), channel, color, factor, range });
+        }
+    }
+    curves
+}
+
+fn is_key_selected(app: &AppState, bone_id: &str, prop: &TimelineProperty, time: f32) -> bool {
+    app.ui.selected_keyframes.iter().any(|k| k.0 == bone_id && k.1.as_ref().is_none_or(|p| p == prop) && (k.2 - time).abs() < 0.001)
+}
+
+// 选中关键帧中属于数值轨道的部分；摄影表中整根骨骼的选择会展开到各条数值轨道
+fn graph_selection(app: &AppState) -> Vec<(String, TimelineProperty, f32)> {
+    let mut keys = Vec::new();
+    for (bone_id, prop, time) in &app.ui.selected_keyframes {
+        match prop {
+            Some(p) if graph::is_graph_property(p) => keys.push((bone_id.clone(), p.clone(), *time)),
+            Some(_) => {}
+            None => {
+                for p in [TimelineProperty::Rotation, TimelineProperty::Translation, TimelineProperty::Scale, TimelineProperty::Shear] {
+                    keys.push((bone_id.clone(), p, *time));
+                }
+            }
+        }
+    }
+    keys
+}
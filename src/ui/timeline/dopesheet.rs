@@ -1,6 +1,6 @@
 use egui::{Ui, Color32, Sense, Stroke, Pos2, vec2, Align2, FontId};
 use crate::app::state::AppState;
-use crate::core::animation::timeline::TimelineProperty;
+use crate::core::animation::timeline::{TimelineProperty, DRAW_ORDER_TARGET};
 use crate::app::commands::AppCommand;
 
 pub struct Dopesheet;
@@ -11,10 +11,11 @@ impl Dopesheet {
         let mut new_seek_time = None;
 
         let row_height = 20.0;
-        let fps = 30.0;
+        let fps = app.animation.project.fps_f32();
+        let fps_frames = app.animation.project.fps.max(1) as i32;
         let frame_width = 10.0 * app.ui.timeline_zoom;
         let current_anim_duration = app.animation.project.animations.get(active_id.as_ref().unwrap_or(&"".to_string())).map(|a| a.duration).unwrap_or(0.0);
-        let max_frames = ((current_anim_duration * fps) as i32 + 60).max(300);
+        let max_frames = ((current_anim_duration * fps) as i32 + 2 * fps_frames).max(10 * fps_frames);
         
         egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
             if active_id.is_some() && app.animation.project.animations.contains_key(active_id.as_ref().unwrap()) {
@@ -44,9 +45,9 @@ impl Dopesheet {
                     
                     for i in 0..=max_frames {
                         let x = rect.min.x + i as f32 * frame_width;
-                        if i % 30 == 0 {
+                        if i % fps_frames == 0 {
                             painter.line_segment([Pos2::new(x, rect.min.y + 10.0), Pos2::new(x, rect.max.y)], Stroke::new(1.5, Color32::GRAY));
-                            painter.text(Pos2::new(x + 2.0, rect.min.y), Align2::LEFT_TOP, format!("{}s", i / 30), FontId::monospace(10.0), Color32::GRAY);
+                            painter.text(Pos2::new(x + 2.0, rect.min.y), Align2::LEFT_TOP, format!("{}s", i / fps_frames), FontId::monospace(10.0), Color32::GRAY);
                         } else if i % 5 == 0 {
                             painter.line_segment([Pos2::new(x, rect.min.y + 18.0), Pos2::new(x, rect.max.y)], Stroke::new(1.0, Color32::from_gray(100)));
                             painter.text(Pos2::new(x + 2.0, rect.min.y + 10.0), Align2::LEFT_TOP, format!("{}", i), FontId::proportional(8.0), Color32::from_gray(100));
@@ -81,6 +82,33 @@ impl Dopesheet {
 
                 let bones_list: Vec<(String, String)> = app.animation.project.skeleton.bones.iter()
                     .map(|b| (b.data.id.clone(), b.data.name.clone())).collect();
+                // 非骨骼行：IK 约束、带显示图层关键帧的插槽、网格变形、图层效果、绘制顺序，各自只有一条轨道
+                let mut extra_rows: Vec<(String, String, TimelineProperty)> = app.animation.project.skeleton.ik_constraints.iter()
+                    .map(|c| (c.data.id.clone(), format!("🦾 {}", c.data.name), TimelineProperty::Ik)).collect();
+                if let Some(anim) = active_id.as_ref().and_then(|id| app.animation.project.animations.get(id)) {
+                    for slot in &app.animation.project.skeleton.slots {
+                        if anim.timelines.iter().any(|t| t.target_id == slot.data.id && t.property == TimelineProperty::Attachment) {
+                            extra_rows.push((slot.data.id.clone(), format!("🖼 {}", slot.data.name), TimelineProperty::Attachment));
+                        }
+                    }
+                    for mesh in &app.animation.project.skeleton.meshes {
+                        if anim.timelines.iter().any(|t| t.target_id == mesh.layer_id && t.property == TimelineProperty::Deform) {
+                            let name = app.engine.store().get_layer(&mesh.layer_id).map(|l| l.name.clone()).unwrap_or_else(|| mesh.layer_id.clone());
+                            extra_rows.push((mesh.layer_id.clone(), format!("◬ {}", name), TimelineProperty::Deform));
+                        }
+                    }
+                    for layer in &app.engine.store().layers {
+                        for effect in &layer.effects {
+                            if anim.timelines.iter().any(|t| t.target_id == effect.id && t.property == TimelineProperty::EffectAmount) {
+                                extra_rows.push((effect.id.clone(), format!("✨ {} · {}", layer.name, effect.kind.label()), TimelineProperty::EffectAmount));
+                            }
+                        }
+                    }
+                    if anim.timelines.iter().any(|t| t.property == TimelineProperty::DrawOrder) {
+                        extra_rows.push((DRAW_ORDER_TARGET.to_string(), "☰ 绘制顺序".to_string(), TimelineProperty::DrawOrder));
+                    }
+                }
+                let extra_start = bones_list.len();
 
                 let mut bone_all_times: std::collections::HashMap<String, Vec<f32>> = std::collections::HashMap::new();
                 let mut bone_prop_times: std::collections::HashMap<(String, TimelineProperty), Vec<f32>> = std::collections::HashMap::new();
@@ -94,20 +122,37 @@ impl Dopesheet {
                     }
                 }
 
-                for (bone_id, bone_name) in bones_list {
-                    let mut tracks_to_draw = vec![
-                        (None, bone_name.clone(), Color32::LIGHT_GRAY, 0.0, bone_all_times.get(&bone_id))
-                    ];
+                let extra_props: Vec<TimelineProperty> = extra_rows.iter().map(|r| r.2.clone()).collect();
+                let rows = bones_list.into_iter().chain(extra_rows.into_iter().map(|(id, name, _)| (id, name)));
+                for (row_idx, (bone_id, bone_name)) in rows.enumerate() {
+                    let extra_prop = row_idx.checked_sub(extra_start).map(|i| extra_props[i].clone());
+                    let is_extra = extra_prop.is_some();
+                    let mut tracks_to_draw = if let Some(prop) = extra_prop {
+                        let color = match prop {
+                            TimelineProperty::Ik => Color32::from_rgb(230, 160, 60),
+                            TimelineProperty::Attachment => Color32::from_rgb(200, 120, 230),
+                            TimelineProperty::Deform => Color32::from_rgb(120, 220, 200),
+                            TimelineProperty::EffectAmount => Color32::from_rgb(240, 140, 180),
+                            _ => Color32::from_rgb(230, 230, 120),
+                        };
+                        let times = bone_prop_times.get(&(bone_id.clone(), prop.clone()));
+                        vec![(Some(prop), bone_name.clone(), color, 10.0, times)]
+                    } else {
+                        vec![(None, bone_name.clone(), Color32::LIGHT_GRAY, 0.0, bone_all_times.get(&bone_id))]
+                    };
                     
-                    if app.ui.timeline_filter.contains(&TimelineProperty::Rotation) {
+                    if !is_extra && app.ui.timeline_filter.contains(&TimelineProperty::Rotation) {
                         tracks_to_draw.push((Some(TimelineProperty::Rotation), "⟳ 旋转".to_string(), Color32::from_rgb(80, 200, 120), 20.0, bone_prop_times.get(&(bone_id.clone(), TimelineProperty::Rotation))));
                     }
-                    if app.ui.timeline_filter.contains(&TimelineProperty::Translation) {
+                    if !is_extra && app.ui.timeline_filter.contains(&TimelineProperty::Translation) {
                         tracks_to_draw.push((Some(TimelineProperty::Translation), "✥ 移动".to_string(), Color32::from_rgb(80, 180, 255), 20.0, bone_prop_times.get(&(bone_id.clone(), TimelineProperty::Translation))));
                     }
-                    if app.ui.timeline_filter.contains(&TimelineProperty::Scale) {
+                    if !is_extra && app.ui.timeline_filter.contains(&TimelineProperty::Scale) {
                         tracks_to_draw.push((Some(TimelineProperty::Scale), "◱ 缩放".to_string(), Color32::from_rgb(255, 100, 100), 20.0, bone_prop_times.get(&(bone_id.clone(), TimelineProperty::Scale))));
                     }
+                    if !is_extra && app.ui.timeline_filter.contains(&TimelineProperty::Shear) {
+                        tracks_to_draw.push((Some(TimelineProperty::Shear), "⧄ 切变".to_string(), Color32::from_rgb(230, 140, 200), 20.0, bone_prop_times.get(&(bone_id.clone(), TimelineProperty::Shear))));
+                    }
 
                     for (prop_opt, label_text, kf_color, indent, times) in tracks_to_draw {
                         ui.horizontal(|ui| {
@@ -205,6 +250,76 @@ impl Dopesheet {
                     }
                 }
 
+                // 事件行：旗标标记事件时间，拖动改变时间，右键编辑负载或删除
+                let events = active_id.as_ref().and_then(|id| app.animation.project.animations.get(id)).map(|a| a.events.clone()).unwrap_or_default();
+                ui.horizontal(|ui| {
+                    let (label_rect, label_resp) = ui.allocate_exact_size(vec2(left_width, row_height), Sense::click());
+                    let label = match app.animation.fired_events.last() {
+                        Some(last) if app.animation.is_playing => format!("⚑ 事件 · {}", last.name),
+                        _ => "⚑ 事件".to_string(),
+                    };
+                    ui.painter().text(label_rect.left_center() + vec2(10.0, 0.0), Align2::LEFT_CENTER, label, FontId::proportional(13.0), Color32::from_rgb(255, 200, 80));
+                    label_resp.context_menu(|ui| {
+                        ui.text_edit_singleline(&mut app.ui.new_event_name);
+                        if ui.button("➕ 在当前时间添加事件").clicked() {
+                            let name = if app.ui.new_event_name.trim().is_empty() { "event".to_string() } else { app.ui.new_event_name.trim().to_string() };
+                            app.enqueue_command(AppCommand::AddAnimationEvent(name));
+                            ui.close_menu();
+                        }
+                    });
+
+                    let (track_rect, _) = ui.allocate_exact_size(vec2(right_width, row_height), Sense::hover());
+                    ui.painter().rect_filled(track_rect, 0.0, Color32::from_gray(35));
+
+                    for (i, event) in events.iter().enumerate() {
+                        let kx = track_rect.min.x + (event.time * fps) * frame_width;
+                        let ev_rect = egui::Rect::from_center_size(Pos2::new(kx, track_rect.center().y), vec2(12.0, row_height));
+                        let resp = ui.interact(ev_rect, ui.id().with(format!("event_{}", i)), Sense::click_and_drag())
+                            .on_hover_text(format!("{}  int: {}  float: {}  \"{}\"", event.name, event.int_value, event.float_value, event.string_value));
+                        let is_selected = app.ui.selected_event == Some(i);
+
+                        if resp.clicked() || resp.drag_started() { app.ui.selected_event = Some(i); }
+                        let mut marker_x = kx;
+                        if resp.dragged() || resp.drag_released() {
+                            if let Some(pos) = ui.input(|inp| inp.pointer.interact_pos()) {
+                                let new_time = ((pos.x - track_rect.min.x) / frame_width).round().max(0.0) / fps;
+                                marker_x = track_rect.min.x + (new_time * fps) * frame_width;
+                                if resp.drag_released() && (new_time - event.time).abs() > 1e-4 {
+                                    let mut moved = event.clone();
+                                    moved.time = new_time;
+                                    app.enqueue_command(AppCommand::UpdateAnimationEvent(i, moved));
+                                }
+                            }
+                        }
+
+                        resp.context_menu(|ui| {
+                            let mut edited = event.clone();
+                            ui.horizontal(|ui| { ui.label("名称:"); ui.text_edit_singleline(&mut edited.name); });
+                            ui.horizontal(|ui| { ui.label("Int:"); ui.add(egui::DragValue::new(&mut edited.int_value)); });
+                            ui.horizontal(|ui| { ui.label("Float:"); ui.add(egui::DragValue::new(&mut edited.float_value).speed(0.01)); });
+                            ui.horizontal(|ui| { ui.label("String:"); ui.text_edit_singleline(&mut edited.string_value); });
+                            if edited != *event {
+                                app.enqueue_command(AppCommand::UpdateAnimationEvent(i, edited));
+                            }
+                            if ui.button("🗑 删除事件").clicked() {
+                                app.enqueue_command(AppCommand::DeleteAnimationEvent(i));
+                                ui.close_menu();
+                            }
+                        });
+
+                        let color = if is_selected { Color32::WHITE } else { Color32::from_rgb(255, 200, 80) };
+                        let top = Pos2::new(marker_x, track_rect.min.y + 2.0);
+                        ui.painter().line_segment([top, Pos2::new(marker_x, track_rect.max.y - 2.0)], Stroke::new(1.5, color));
+                        ui.painter().add(egui::Shape::convex_polygon(
+                            vec![top, Pos2::new(marker_x + 7.0, top.y + 4.0), Pos2::new(marker_x, top.y + 8.0)],
+                            color,
+                            Stroke::NONE,
+                        ));
+                        ui.painter().text(Pos2::new(marker_x + 9.0, track_rect.center().y), Align2::LEFT_CENTER, &event.name, FontId::proportional(10.0), Color32::LIGHT_GRAY);
+                    }
+                });
+
+
                 println!("[DEBUG-DOPESHEET] Total rendered_kfs this frame: {}", rendered_kfs.len());
                 for (id, _, _, rect) in &rendered_kfs {
                     println!("[DEBUG-DOPESHEET] KF {} rendered at rect: {:?}", id, rect);
@@ -0,0 +1,78 @@
+use egui::{Ui, vec2};
+use crate::app::state::AppState;
+use crate::app::commands::AppCommand;
+
+pub struct ImportModal;
+
+impl ImportModal {
+    pub fn show(ui: &mut Ui, app: &mut AppState) {
+        let target_bones: Vec<(String, String)> = app.animation.project.skeleton.bones.iter()
+            .map(|b| (b.data.id.clone(), b.data.name.clone())).collect();
+        let mut confirm = false;
+        let mut cancel = false;
+        let mut remap = false;
+
+        let import = match app.animation.pending_import.as_mut() {
+            Some(import) => import,
+            None => return,
+        };
+        egui::Window::new("导入动画 (Import Animations)")
+            .collapsible(false)
+            .resizable(true)
+            .default_size(vec2(420.0, 480.0))
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("来源: {}", import.source_path));
+                ui.separator();
+
+                ui.label("动画:");
+                for (_, anim, selected) in &mut import.animations {
+                    ui.checkbox(selected, &anim.name);
+                }
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("骨骼映射:");
+                    if ui.button("🔄 自动匹配").on_hover_text("按骨骼名称重新匹配 (忽略大小写与 _ . - 分隔符)").clicked() {
+                        remap = true;
+                    }
+                });
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    egui::Grid::new("import_bone_mapping").striped(true).num_columns(2).show(ui, |ui| {
+                        for m in &mut import.mapping {
+                            ui.label(&m.source_name);
+                            let selected_text = m.target_id.as_ref()
+                                .and_then(|id| target_bones.iter().find(|b| &b.0 == id))
+                                .map(|b| b.1.clone())
+                                .unwrap_or_else(|| "(不导入)".to_string());
+                            egui::ComboBox::from_id_source(("import_map", &m.source_id))
+                                .selected_text(selected_text)
+                                .width(160.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut m.target_id, None, "(不导入)");
+                                    for (id, name) in &target_bones {
+                                        ui.selectable_value(&mut m.target_id, Some(id.clone()), name);
+                                    }
+                                });
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.separator();
+
+                ui.checkbox(&mut import.rescale_translation, "按骨骼长度比例缩放位移");
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("✅ 导入").clicked() { confirm = true; }
+                    if ui.button("❌ 取消").clicked() { cancel = true; }
+                });
+            });
+
+        if remap {
+            import.mapping = crate::core::animation::retarget::auto_map(&import.skeleton, &app.animation.project.skeleton);
+        }
+        if confirm { app.enqueue_command(AppCommand::ConfirmAnimationImport); }
+        if cancel { app.enqueue_command(AppCommand::CancelAnimationImport); }
+    }
+}
@@ -2,6 +2,8 @@ mod toolbar;
 mod dopesheet;
 mod curve_editor;
 mod offset_modal;
+mod import_modal;
+mod bake_modal;
 
 use egui::Ui;
 use crate::app::state::AppState;
@@ -18,5 +20,9 @@ impl TimelinePanel {
         curve_editor::CurveEditor::show(ui, app);
 
         offset_modal::OffsetModal::show(ui, app);
+
+        import_modal::ImportModal::show(ui, app);
+
+        bake_modal::BakeModal::show(ui, app);
     }
 }
\ No newline at end of file
@@ -15,6 +15,7 @@ impl OffsetModal {
                     ui.radio_value(&mut app.ui.offset_mode, 0, "固定偏移 (全体移动 N 帧)");
                     ui.radio_value(&mut app.ui.offset_mode, 1, "基础递增 (N + 1 帧)");
                     ui.radio_value(&mut app.ui.offset_mode, 2, "自定义递增 (N + M 帧)");
+                    ui.label(format!("当前帧率: {} fps", app.animation.project.fps));
 
                     ui.horizontal(|ui| {
                         ui.label("初始偏移(N):");
@@ -2,6 +2,7 @@ use egui::{Ui, Color32};
 use crate::app::state::AppState;
 use crate::app::commands::AppCommand;
 use crate::core::animation::timeline::TimelineProperty;
+use crate::animation::onion_skin::{MAX_ONION_FRAMES, MAX_ONION_STEP};
 use rust_i18n::t;
 
 pub struct Toolbar;
@@ -24,9 +25,9 @@ impl Toolbar {
                 .selected_text(current_name)
                 .width(150.0)
                 .show_ui(ui, |ui| {
-                    for (id, name) in anim_list {
-                        if ui.selectable_label(Some(&id) == active_id.as_ref(), &name).clicked() {
-                            app.enqueue_command(AppCommand::SelectAnimation(id));
+                    for (id, name) in &anim_list {
+                        if ui.selectable_label(Some(id) == active_id.as_ref(), name).clicked() {
+                            app.enqueue_command(AppCommand::SelectAnimation(id.clone()));
                         }
                     }
                 });
@@ -35,17 +36,114 @@ impl Toolbar {
                 app.ui.new_anim_name = format!("anim_{}", anim_count + 1);
                 app.ui.show_new_anim_modal = true;
             }
+            if ui.button("📥 导入动画").on_hover_text("从其它工程 (.pxad) 导入动画并按骨骼名称映射").clicked() {
+                app.enqueue_command(AppCommand::ImportAnimations);
+            }
+            if ui.add_enabled(active_id.is_some(), egui::Button::new("🎞 烘焙")).on_hover_text("把当前动画逐帧栅格化为图层或精灵表").clicked() {
+                app.ui.bake_settings.fps = app.animation.project.fps;
+                app.ui.show_bake_modal = true;
+            }
+
+            ui.separator();
+
+            if app.animation.transition_preview.is_some() {
+                if ui.button("⏹ 停止过渡预览").clicked() {
+                    app.enqueue_command(AppCommand::StopTransitionPreview);
+                }
+            } else {
+                ui.menu_button("🔀 过渡预览", |ui| {
+                    let target_name = app.ui.transition_target_id.as_ref()
+                        .and_then(|id| app.animation.project.animations.get(id))
+                        .map(|a| a.name.clone())
+                        .unwrap_or_else(|| "选择目标动画".to_string());
+                    egui::ComboBox::from_id_source("transition_target")
+                        .selected_text(target_name)
+                        .show_ui(ui, |ui| {
+                            for (id, name) in &anim_list {
+                                if Some(id) == active_id.as_ref() { continue; }
+                                if ui.selectable_label(app.ui.transition_target_id.as_ref() == Some(id), name).clicked() {
+                                    app.ui.transition_target_id = Some(id.clone());
+                                }
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        ui.label("淡入时长:");
+                        ui.add(egui::DragValue::new(&mut app.ui.transition_frames).clamp_range(0..=600).suffix(" 帧"));
+                    });
+                    if ui.add_enabled(app.ui.transition_target_id.is_some(), egui::Button::new("▶ 从当前时间开始过渡")).clicked() {
+                        if let Some(to_id) = app.ui.transition_target_id.clone() {
+                            let duration = app.ui.transition_frames as f32 * app.animation.project.frame_duration();
+                            app.enqueue_command(AppCommand::PreviewTransition(to_id, duration));
+                        }
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            ui.menu_button("📋 关键帧", |ui| {
+                let has_selection = !app.ui.selected_keyframes.is_empty();
+                let has_clipboard = app.animation.keyframe_clipboard.is_some();
+                if ui.add_enabled(has_selection, egui::Button::new("复制选中关键帧").shortcut_text("Ctrl+C")).clicked() {
+                    app.enqueue_command(AppCommand::CopyKeyframes);
+                    ui.close_menu();
+                }
+                if ui.button("复制当前姿势").clicked() {
+                    app.enqueue_command(AppCommand::CopyPose);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_clipboard, egui::Button::new("粘贴到当前时间").shortcut_text("Ctrl+V"))
+                    .on_hover_text("只复制了一根骨骼时，粘贴到当前选中的骨骼").clicked() {
+                    app.enqueue_command(AppCommand::PasteKeyframes { mirrored: false });
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_clipboard, egui::Button::new("镜像粘贴").shortcut_text("Ctrl+Shift+V"))
+                    .on_hover_text("按 _L/_R 等命名约定交换左右骨骼，并相对装配姿势取镜像").clicked() {
+                    app.enqueue_command(AppCommand::PasteKeyframes { mirrored: true });
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.add_enabled(has_selection, egui::Button::new("时间反转")).clicked() {
+                    app.enqueue_command(AppCommand::ReverseSelectedKeyframes);
+                    ui.close_menu();
+                }
+                ui.add_enabled_ui(has_selection, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut app.ui.graph_time_scale).speed(0.01).clamp_range(0.01..=10.0).prefix("时间缩放 ×"));
+                        if ui.button("应用").on_hover_text("以播放头为轴心").clicked() {
+                            app.enqueue_command(AppCommand::ScaleSelectedKeyframes { time_scale: app.ui.graph_time_scale, value_scale: 1.0 });
+                            ui.close_menu();
+                        }
+                    });
+                });
+            });
         });
         
         ui.separator();
         
         ui.horizontal(|ui| {
             ui.label("当前:");
-            let mut current_frame = (app.animation.current_time * 30.0).round() as i32;
-            if ui.add(egui::DragValue::new(&mut current_frame).speed(1.0).clamp_range(0..=300).prefix("    ")).changed() {
-                app.animation.current_time = (current_frame as f32 / 30.0).max(0.0);
+            let fps = app.animation.project.fps;
+            let mut current_frame = (app.animation.current_time * fps as f32).round() as i32;
+            if ui.add(egui::DragValue::new(&mut current_frame).speed(1.0).clamp_range(0..=(10 * fps as i32)).prefix("    ")).changed() {
+                app.animation.current_time = (current_frame as f32 / fps as f32).max(0.0);
                 crate::animation::controller::AnimationController::apply_current_pose(&mut app.animation);
             }
+
+            ui.menu_button(format!("⏱ {} fps", fps), |ui| {
+                for preset in crate::animation::project::FPS_PRESETS {
+                    if ui.radio(fps == preset, format!("{} fps", preset)).clicked() {
+                        app.enqueue_command(AppCommand::SetFrameRate { fps: preset, retime: app.ui.retime_on_fps_change });
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                ui.checkbox(&mut app.ui.retime_on_fps_change, "保持帧号 (重定时关键帧)")
+                    .on_hover_text("开启：关键帧帧号不变，动画速度随帧率改变\n关闭：关键帧秒数不变，对齐到新帧格");
+                let mut snap = app.animation.project.snap_to_frames;
+                if ui.checkbox(&mut snap, "🧲 吸附到帧").clicked() {
+                    app.enqueue_command(AppCommand::ToggleFrameSnap);
+                }
+            });
             
             ui.separator();
 
@@ -76,7 +174,8 @@ impl Toolbar {
                 let props = vec![
                     (TimelineProperty::Translation, "✥ 移动"),
                     (TimelineProperty::Rotation, "⟳ 旋转"),
-                    (TimelineProperty::Scale, "◱ 缩放")
+                    (TimelineProperty::Scale, "◱ 缩放"),
+                    (TimelineProperty::Shear, "⧄ 切变")
                 ];
                 for (prop, label) in props {
                     let mut is_active = app.ui.timeline_filter.contains(&prop);
@@ -86,6 +185,31 @@ impl Toolbar {
                 }
             });
             
+            let mut onion = app.animation.project.onion_skin;
+            ui.toggle_value(&mut onion.enabled, "🧅 洋葱皮").on_hover_text("显示前后帧的半透明残影");
+            ui.menu_button("⚙", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("前帧数:");
+                    ui.add(egui::DragValue::new(&mut onion.frames_before).clamp_range(0..=MAX_ONION_FRAMES));
+                    ui.color_edit_button_srgb(&mut onion.tint_before);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("后帧数:");
+                    ui.add(egui::DragValue::new(&mut onion.frames_after).clamp_range(0..=MAX_ONION_FRAMES));
+                    ui.color_edit_button_srgb(&mut onion.tint_after);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("帧间隔:");
+                    ui.add(egui::DragValue::new(&mut onion.step).clamp_range(1..=MAX_ONION_STEP));
+                });
+                ui.add(egui::Slider::new(&mut onion.opacity, 0..=255).text("不透明度"));
+            });
+            if onion != app.animation.project.onion_skin {
+                app.enqueue_command(AppCommand::SetOnionSkin(onion));
+            }
+
+            ui.separator();
+
             ui.toggle_value(&mut app.ui.show_curve_editor, "📈 曲线");
             ui.toggle_value(&mut app.ui.is_offset_mode_active, "➡️ 偏移模式")
                 .on_hover_text("激活后拖拽帧进行循环偏移 (或使用快捷键: Ctrl+Alt+拖拽)");
@@ -103,6 +227,7 @@ impl Toolbar {
                     app.animation.auto_key_bone(&bone_id, TimelineProperty::Rotation);
                     app.animation.auto_key_bone(&bone_id, TimelineProperty::Translation);
                     app.animation.auto_key_bone(&bone_id, TimelineProperty::Scale);
+                    if app.animation.bone_uses_shear(&bone_id) { app.animation.auto_key_bone(&bone_id, TimelineProperty::Shear); }
                     app.ui.auto_keyframe = old_auto;
                 }
             } else {
@@ -15,6 +15,16 @@ impl ToolbarAnim {
             if ui.toggle_value(&mut is_move, "✥ 移动").clicked() && is_move {
                 app.set_tool(ToolType::BoneTranslate);
             }
+
+            let mut is_mesh = app.engine.tool_manager().active_type == ToolType::MeshEdit;
+            if ui.toggle_value(&mut is_mesh, "◬ 网格").on_hover_text("拖动活动图层网格的顶点").clicked() && is_mesh {
+                app.set_tool(ToolType::MeshEdit);
+            }
+
+            let mut is_weight = app.engine.tool_manager().active_type == ToolType::WeightPaint;
+            if ui.toggle_value(&mut is_weight, "🖌 权重").on_hover_text("为选中骨骼绘制网格权重").clicked() && is_weight {
+                app.set_tool(ToolType::WeightPaint);
+            }
         });
     }
 }
\ No newline at end of file
@@ -78,6 +78,78 @@ impl ToolbarPixel {
             });
         }
 
+        if app.engine.tool_manager().active_type == ToolType::Transform {
+            ui.add_space(5.0);
+            ui.separator();
+            ui.label(t!("transform.resample"));
+            let current = app.engine.tool_manager().transform_resample();
+            egui::ComboBox::from_id_source("transform_resample")
+                .selected_text(current.label())
+                .show_ui(ui, |ui| {
+                    for mode in crate::render::resample::ResampleMode::ALL {
+                        if ui.selectable_label(current == mode, mode.label()).clicked() {
+                            app.enqueue_command(AppCommand::SetTransformResample(mode));
+                        }
+                    }
+                });
+
+            if let Some((params, handle_mode, active)) = app.engine.tool_manager().transform_state() {
+                ui.label(t!("transform.handle_mode"));
+                egui::ComboBox::from_id_source("transform_handle_mode")
+                    .selected_text(handle_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in crate::tools::transform::HandleMode::ALL {
+                            if ui.selectable_label(handle_mode == mode, mode.label()).clicked() {
+                                app.enqueue_command(AppCommand::SetTransformHandleMode(mode));
+                            }
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.small_button("⇔").on_hover_text(t!("transform.flip_horizontal")).clicked() { app.enqueue_command(AppCommand::FlipTransform { horizontal: true }); }
+                    if ui.small_button("⇕").on_hover_text(t!("transform.flip_vertical")).clicked() { app.enqueue_command(AppCommand::FlipTransform { horizontal: false }); }
+                    if active && ui.small_button("⟲").on_hover_text(t!("transform.reset_distort")).clicked() { app.enqueue_command(AppCommand::ResetTransformDistort); }
+                });
+
+                if active {
+                    // 面板以度和百分比显示，提交时换回弧度与比例
+                    let mut p = params;
+                    let mut rotation = p.rotation.to_degrees();
+                    let (mut scale_x, mut scale_y) = (p.scale_x * 100.0, p.scale_y * 100.0);
+                    let (mut skew_x, mut skew_y) = (p.skew_x.to_degrees(), p.skew_y.to_degrees());
+                    let mut changed = false;
+                    egui::Grid::new("transform_numeric").num_columns(3).spacing([4.0, 2.0]).show(ui, |ui| {
+                        ui.label(t!("transform.offset"));
+                        changed |= ui.add(egui::DragValue::new(&mut p.offset_x).speed(1.0)).changed();
+                        changed |= ui.add(egui::DragValue::new(&mut p.offset_y).speed(1.0)).changed();
+                        ui.end_row();
+                        ui.label(t!("transform.angle"));
+                        changed |= ui.add(egui::DragValue::new(&mut rotation).speed(1.0).suffix("°")).changed();
+                        ui.end_row();
+                        ui.label(t!("transform.scale"));
+                        changed |= ui.add(egui::DragValue::new(&mut scale_x).speed(1.0).suffix("%")).changed();
+                        changed |= ui.add(egui::DragValue::new(&mut scale_y).speed(1.0).suffix("%")).changed();
+                        ui.end_row();
+                        ui.label(t!("transform.skew"));
+                        changed |= ui.add(egui::DragValue::new(&mut skew_x).speed(1.0).suffix("°")).changed();
+                        changed |= ui.add(egui::DragValue::new(&mut skew_y).speed(1.0).suffix("°")).changed();
+                        ui.end_row();
+                        ui.label(t!("transform.pivot"));
+                        changed |= ui.add(egui::DragValue::new(&mut p.pivot_x).speed(0.5)).changed();
+                        changed |= ui.add(egui::DragValue::new(&mut p.pivot_y).speed(0.5)).changed();
+                        ui.end_row();
+                    });
+                    if changed {
+                        p.rotation = rotation.to_radians();
+                        p.scale_x = scale_x / 100.0;
+                        p.scale_y = scale_y / 100.0;
+                        p.skew_x = skew_x.to_radians();
+                        p.skew_y = skew_y.to_radians();
+                        app.enqueue_command(AppCommand::SetTransformParams(p));
+                    }
+                }
+            }
+        }
+
         ui.separator();
         ui.add_space(5.0);
         PalettePanel::show(ui, app);
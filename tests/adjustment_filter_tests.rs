@@ -0,0 +1,122 @@
+use pxa_engine_win32::app::state::AppState;
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::render::filters::{Adjustment, FilterSettings};
+use pxa_engine_win32::render::quantize::QuantizeSettings;
+
+const GRAY: Color = Color { r: 100, g: 100, b: 100, a: 255 };
+
+// 在活动图层左上角 4x4 填充灰色
+fn setup_filter_test() -> (AppState, String) {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let (store, _, _) = app.engine.parts_mut();
+    for y in 0..4 { for x in 0..4 { store.force_set_pixel(&layer_id, x, y, GRAY).unwrap(); } }
+    (app, layer_id)
+}
+
+fn layer_pixel(app: &AppState, layer_id: &str, x: u32, y: u32) -> Color {
+    app.engine.store().get_pixel(layer_id, x, y).unwrap_or(Color::transparent())
+}
+
+fn composite_pixel(app: &AppState, x: u32, y: u32) -> Color {
+    let store = app.engine.store();
+    let idx = ((y * store.canvas_width + x) * 4) as usize;
+    let p = &store.composite_cache[idx..idx + 4];
+    Color::new(p[0], p[1], p[2], p[3])
+}
+
+// ---------------------------------------------------------
+// 1. 预览只显示在合成结果中，图层像素不变，取消后不产生历史记录
+// ---------------------------------------------------------
+#[test]
+fn test_filter_preview_and_cancel() {
+    let (mut app, layer_id) = setup_filter_test();
+    let undo_depth = app.engine.history().undo_stack.len();
+
+    CommandHandler::execute(&mut app, AppCommand::BeginFilter(Adjustment::Invert));
+    assert!(app.ui.filter_preview.is_some());
+    assert_eq!(composite_pixel(&app, 0, 0), Color::new(155, 155, 155, 255), "预览立即生效");
+    assert_eq!(layer_pixel(&app, &layer_id, 0, 0), GRAY, "预览不修改图层");
+
+    let settings = FilterSettings { adjustment: Adjustment::Posterize { levels: 2 }, snap_to_palette: None };
+    CommandHandler::execute(&mut app, AppCommand::UpdateFilterPreview(settings));
+    assert_eq!(composite_pixel(&app, 0, 0), Color::new(0, 0, 0, 255), "参数变化时基于原图重新计算");
+
+    CommandHandler::execute(&mut app, AppCommand::CancelFilter);
+    assert!(app.ui.filter_preview.is_none());
+    assert_eq!(composite_pixel(&app, 0, 0), GRAY, "取消后恢复原图");
+    assert_eq!(layer_pixel(&app, &layer_id, 0, 0), GRAY);
+    assert_eq!(app.engine.history().undo_stack.len(), undo_depth, "预览不进入历史记录");
+}
+
+// ---------------------------------------------------------
+// 2. 应用：只作用于选区，并作为一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_filter_apply_in_selection_single_undo() {
+    let (mut app, layer_id) = setup_filter_test();
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        store.selection.set_rect(0, 0, 2, 4);
+        store.selection.is_active = true;
+    }
+    let undo_depth = app.engine.history().undo_stack.len();
+
+    CommandHandler::execute(&mut app, AppCommand::BeginFilter(Adjustment::BrightnessContrast { brightness: 0.0, contrast: 0.0 }));
+    let settings = FilterSettings {
+        adjustment: Adjustment::BrightnessContrast { brightness: 0.5, contrast: 0.0 },
+        snap_to_palette: Some(QuantizeSettings::default()),
+    };
+    CommandHandler::execute(&mut app, AppCommand::UpdateFilterPreview(settings));
+    CommandHandler::execute(&mut app, AppCommand::ApplyFilter);
+
+    assert!(app.ui.filter_preview.is_none());
+    let brightened = layer_pixel(&app, &layer_id, 1, 3);
+    assert!(app.engine.store().palette.colors.contains(&brightened), "调亮后映射到调色板: {:?}", brightened);
+    assert!(brightened.luminance() > GRAY.luminance());
+    assert_eq!(layer_pixel(&app, &layer_id, 2, 0), GRAY, "选区外不变");
+    assert_eq!(app.engine.history().undo_stack.len(), undo_depth + 1, "只产生一步撤销");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(layer_pixel(&app, &layer_id, 1, 3), GRAY);
+}
+
+// ---------------------------------------------------------
+// 3. 预览期间撤销：先恢复原图，再撤销上一步
+// ---------------------------------------------------------
+#[test]
+fn test_undo_during_preview_restores_original() {
+    let (mut app, layer_id) = setup_filter_test();
+    CommandHandler::execute(&mut app, AppCommand::BeginFilter(Adjustment::Desaturate));
+    CommandHandler::execute(&mut app, AppCommand::UpdateFilterPreview(FilterSettings { adjustment: Adjustment::Invert, snap_to_palette: None }));
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(app.ui.filter_preview.is_none());
+    assert_eq!(layer_pixel(&app, &layer_id, 3, 3), GRAY);
+}
+
+// ---------------------------------------------------------
+// 4. 预览期间的绘制直接写入图层，预览随之更新，应用时不被覆盖
+// ---------------------------------------------------------
+#[test]
+fn test_drawing_during_preview_is_kept() {
+    let (mut app, layer_id) = setup_filter_test();
+    CommandHandler::execute(&mut app, AppCommand::BeginFilter(Adjustment::Invert));
+
+    app.engine.set_primary_color(Color::new(255, 0, 0, 255));
+    app.on_mouse_down(8, 8).unwrap(); app.on_mouse_up().unwrap();
+    assert_eq!(layer_pixel(&app, &layer_id, 8, 8), Color::new(255, 0, 0, 255), "绘制作用于图层本身");
+    assert_eq!(composite_pixel(&app, 8, 8), Color::new(255, 0, 0, 255), "过期的预览不再显示");
+
+    let settings = app.ui.filter_preview.as_ref().unwrap().settings.clone();
+    CommandHandler::execute(&mut app, AppCommand::UpdateFilterPreview(settings));
+    assert_eq!(composite_pixel(&app, 8, 8), Color::new(0, 255, 255, 255), "参数不变时也按新像素重新计算");
+
+    CommandHandler::execute(&mut app, AppCommand::ApplyFilter);
+    assert_eq!(layer_pixel(&app, &layer_id, 8, 8), Color::new(0, 255, 255, 255));
+    assert_eq!(layer_pixel(&app, &layer_id, 0, 0), Color::new(155, 155, 155, 255));
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(layer_pixel(&app, &layer_id, 8, 8), Color::new(255, 0, 0, 255), "撤销滤镜后保留预览期间的笔画");
+}
@@ -0,0 +1,138 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::animation::bake::{self, BakeSettings, BakeTarget};
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue, CurveType};
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::core::layer_effect::LayerEffectKind;
+use pxa_engine_win32::render::resample::ResampleMode;
+
+// 图层上画一段横线 (x = 50..56, y = 50)，绑定到位于 (50, 50) 的骨骼；动画时长 1s
+fn setup_bake_app(prop: TimelineProperty, start: KeyframeValue, end: KeyframeValue) -> (AppState, String) {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    for (i, x) in (50..56).enumerate() {
+        let color = if i % 2 == 0 { Color::new(255, 0, 0, 255) } else { Color::new(0, 0, 255, 255) };
+        app.engine.set_primary_color(color);
+        app.on_mouse_down(x, 50).unwrap(); app.on_mouse_up().unwrap();
+    }
+
+    let mut bone = BoneData::new("BoneA".into(), "Arm".into());
+    bone.local_transform.x = 50.0;
+    bone.local_transform.y = 50.0;
+    app.animation.project.skeleton.add_bone(bone);
+    app.animation.project.skeleton.update();
+    CommandHandler::execute(&mut app, AppCommand::BindLayerToBone(layer_id.clone(), "BoneA".into()));
+
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    {
+        let anim = app.animation.project.animations.get_mut(&anim_id).unwrap();
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "BoneA" && t.property == prop).unwrap();
+        tl.add_keyframe(0.0, start, CurveType::Linear);
+        tl.add_keyframe(1.0, end, CurveType::Linear);
+        anim.duration = 1.0;
+    }
+    (app, layer_id)
+}
+
+fn opaque_pixels(app: &AppState, frame: &[u8]) -> Vec<(u32, u32, [u8; 4])> {
+    let w = app.engine.store().canvas_width;
+    frame.chunks_exact(4).enumerate()
+        .filter(|(_, p)| p[3] > 0)
+        .map(|(i, p)| (i as u32 % w, i as u32 / w, [p[0], p[1], p[2], p[3]]))
+        .collect()
+}
+
+// ---------------------------------------------------------
+// 1. 逐帧烘焙为隐藏的新图层，整批一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_bake_to_layers() {
+    let (mut app, _) = setup_bake_app(TimelineProperty::Translation, KeyframeValue::Translate(50.0, 50.0), KeyframeValue::Translate(90.0, 50.0));
+    let layer_count = app.engine.store().layers.len();
+
+    let settings = BakeSettings { fps: 2, resample: ResampleMode::Nearest, target: BakeTarget::Layers, columns: 0 };
+    CommandHandler::execute(&mut app, AppCommand::BakeAnimation(settings));
+
+    let store = app.engine.store();
+    assert_eq!(store.layers.len(), layer_count + 2, "循环动画 1s、2fps 烘焙 2 帧");
+    let baked: Vec<_> = store.layers[layer_count..].iter().collect();
+    assert_eq!(baked[0].name, "Action_000");
+    assert_eq!(baked[1].name, "Action_001");
+    assert!(baked.iter().all(|l| !l.visible), "烘焙图层默认隐藏");
+    assert_eq!(baked[0].get_pixel(50, 50).map(|c| c.r), Some(255), "第 0 帧在原位置");
+    assert_eq!(baked[1].get_pixel(70, 50).map(|c| c.r), Some(255), "第 1 帧 (0.5s) 右移 20 像素");
+    assert!(baked[1].get_pixel(50, 50).is_none_or(|c| c.a == 0));
+
+    app.engine.undo().unwrap();
+    assert_eq!(app.engine.store().layers.len(), layer_count, "一步撤销全部烘焙图层");
+}
+
+// ---------------------------------------------------------
+// 2. RotSprite 取样：旋转后不产生新颜色，90° 时与最近邻一致
+// ---------------------------------------------------------
+#[test]
+fn test_bake_rotsprite_keeps_palette() {
+    let (app, _) = setup_bake_app(TimelineProperty::Rotation, KeyframeValue::Rotate(0.0), KeyframeValue::Rotate(180.0));
+    let nearest = app.bake_animation_frames(&BakeSettings { fps: 4, resample: ResampleMode::Nearest, target: BakeTarget::Layers, columns: 0 });
+    let rotsprite = app.bake_animation_frames(&BakeSettings { fps: 4, resample: ResampleMode::RotSprite, target: BakeTarget::Layers, columns: 0 });
+    assert_eq!(rotsprite.len(), 4);
+
+    // 第 2 帧为 90°：整数角度下两种取样结果相同
+    assert_eq!(opaque_pixels(&app, &nearest[2]), opaque_pixels(&app, &rotsprite[2]));
+    assert_eq!(opaque_pixels(&app, &rotsprite[0]).len(), 6, "0° 时保持原样");
+
+    // 第 1 帧为 45°：只出现源图层中已有的颜色
+    let pixels = opaque_pixels(&app, &rotsprite[1]);
+    assert!(!pixels.is_empty());
+    for (x, y, c) in pixels {
+        assert!(c == [255, 0, 0, 255] || c == [0, 0, 255, 255], "({}, {}) 出现了新颜色 {:?}", x, y, c);
+    }
+}
+
+// ---------------------------------------------------------
+// 3. 精灵表按行排列帧
+// ---------------------------------------------------------
+#[test]
+fn test_sprite_sheet_layout() {
+    let frames: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 2 * 2 * 4]).collect();
+    let (sheet, w, h) = bake::sprite_sheet(&frames, 2, 2, 0);
+    assert_eq!((w, h), (6, 4), "5 帧自动排成 3 列 2 行");
+    let at = |x: u32, y: u32| sheet[((y * w + x) * 4) as usize];
+    assert_eq!(at(0, 0), 0);
+    assert_eq!(at(5, 1), 2);
+    assert_eq!(at(2, 3), 4);
+    assert_eq!(at(5, 3), 0, "最后一行空余的格子保持透明");
+
+    let (_, w, h) = bake::sprite_sheet(&frames, 2, 2, 5);
+    assert_eq!((w, h), (10, 2));
+}
+
+// ---------------------------------------------------------
+// 4. 放大取样按每帧的效果数值重新生成：效果动画不会被烘焙成固定值
+// ---------------------------------------------------------
+#[test]
+fn test_bake_rotsprite_follows_effect_amounts() {
+    let (mut app, layer_id) = setup_bake_app(TimelineProperty::Rotation, KeyframeValue::Rotate(0.0), KeyframeValue::Rotate(90.0));
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::HueShift { degrees: 0.0 }));
+    let effect_id = app.engine.store().get_layer(&layer_id).unwrap().effects[0].id.clone();
+
+    app.animation.current_time = 0.0;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(effect_id.clone()));
+    let mut stack = app.engine.store().get_layer(&layer_id).unwrap().effects.clone();
+    stack[0].kind.set_amount(120.0);
+    CommandHandler::execute(&mut app, AppCommand::SetLayerEffects(layer_id.clone(), stack));
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(effect_id));
+
+    let frames = app.bake_animation_frames(&BakeSettings { fps: 2, resample: ResampleMode::RotSprite, target: BakeTarget::Layers, columns: 0 });
+    assert_eq!(frames.len(), 2);
+    assert_ne!(frames[0], frames[1], "两帧的色相偏移不同");
+    assert!(opaque_pixels(&app, &frames[0]).iter().any(|(_, _, c)| *c == [255, 0, 0, 255]), "0 秒处为原色");
+    let shifted = opaque_pixels(&app, &frames[1]);
+    assert!(shifted.iter().any(|(_, _, c)| *c == [255, 255, 0, 255]), "0.5 秒 (45°) 处红色偏移 60° 为黄色");
+    assert!(shifted.iter().all(|(_, _, c)| *c != [255, 0, 0, 255]));
+}
@@ -0,0 +1,120 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::animation::controller::AnimationController;
+use pxa_engine_win32::core::animation::event::AnimationEvent;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// 创建动画并在 1 秒处打一个关键帧，使动画时长为 1 秒
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    let root = app.animation.project.skeleton.bones[0].data.id.clone();
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(root));
+    app.animation.current_time = 0.0;
+    app
+}
+
+fn events(app: &AppState) -> Vec<AnimationEvent> {
+    let id = app.animation.project.active_animation_id.clone().unwrap();
+    app.animation.project.animations[&id].events.clone()
+}
+
+fn add_event_at(app: &mut AppState, name: &str, time: f32) {
+    app.animation.current_time = time;
+    CommandHandler::execute(app, AppCommand::AddAnimationEvent(name.into()));
+}
+
+// ---------------------------------------------------------
+// 1. 添加、修改、删除事件均可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_event_edit_with_undo() {
+    let mut app = setup_env();
+    add_event_at(&mut app, "footstep", 0.5);
+    assert_eq!(events(&app).len(), 1, "应添加一个事件");
+    assert!((events(&app)[0].time - 0.5).abs() < 1e-4);
+
+    let mut edited = events(&app)[0].clone();
+    edited.int_value = 7;
+    edited.string_value = "left".into();
+    edited.time = 0.25;
+    CommandHandler::execute(&mut app, AppCommand::UpdateAnimationEvent(0, edited));
+    assert_eq!(events(&app)[0].int_value, 7, "负载应被修改");
+    let snapped = app.animation.project.snap_time(0.25);
+    assert!((events(&app)[0].time - snapped).abs() < 1e-4, "时间应被移动并吸附到帧");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(events(&app)[0].int_value, 0, "撤销后负载应恢复");
+    assert!((events(&app)[0].time - 0.5).abs() < 1e-4);
+
+    CommandHandler::execute(&mut app, AppCommand::DeleteAnimationEvent(0));
+    assert!(events(&app).is_empty(), "事件应被删除");
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(events(&app).len(), 1, "撤销删除后事件应恢复");
+    CommandHandler::execute(&mut app, AppCommand::Redo);
+    assert!(events(&app).is_empty(), "重做后事件应再次删除");
+}
+
+// ---------------------------------------------------------
+// 2. 播放时回调按顺序触发，循环回绕时不丢事件
+// ---------------------------------------------------------
+#[test]
+fn test_listeners_fire_across_loop_wrap() {
+    let mut app = setup_env();
+    add_event_at(&mut app, "start", 0.0);
+    add_event_at(&mut app, "hit", 0.5);
+    add_event_at(&mut app, "end", 0.9);
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let sink = log.clone();
+    app.animation.event_listeners.push(Box::new(move |e: &AnimationEvent| sink.borrow_mut().push(e.name.clone())));
+
+    app.animation.current_time = 0.4;
+    app.animation.is_playing = true;
+    app.animation.is_looping = true;
+    AnimationController::update(&mut app.animation, Duration::from_millis(200));
+    assert_eq!(*log.borrow(), vec!["hit"], "0.4 -> 0.6 应只触发 hit");
+
+    log.borrow_mut().clear();
+    AnimationController::update(&mut app.animation, Duration::from_millis(500));
+    assert_eq!(*log.borrow(), vec!["end", "start"], "0.6 -> 1.1 跨越结尾应依次触发 end 与 start");
+
+    log.borrow_mut().clear();
+    app.animation.is_playing = false;
+    AnimationController::update(&mut app.animation, Duration::from_millis(500));
+    assert!(log.borrow().is_empty(), "暂停时不应触发事件");
+    assert_eq!(app.animation.fired_events.last().map(|e| e.name.as_str()), Some("start"));
+}
+
+// ---------------------------------------------------------
+// 3. 事件随动画复制与保存
+// ---------------------------------------------------------
+#[test]
+fn test_events_survive_clone_and_save() {
+    let mut app = setup_env();
+    add_event_at(&mut app, "footstep", 0.5);
+    let mut edited = events(&app)[0].clone();
+    edited.float_value = 1.5;
+    edited.string_value = "grass".into();
+    CommandHandler::execute(&mut app, AppCommand::UpdateAnimationEvent(0, edited.clone()));
+
+    let id = app.animation.project.active_animation_id.clone().unwrap();
+    let cloned = app.animation.project.animations[&id].clone();
+    assert_eq!(cloned.events, vec![edited.clone()], "复制动画应保留事件");
+
+    let path = std::env::temp_dir().join(format!("pxa_events_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(anim.active_animation_id.as_ref(), Some(&id), "应恢复当前动画");
+    let restored = &anim.animations[&id];
+    assert_eq!(restored.events, vec![edited], "事件及负载应被读回");
+    assert_eq!(restored.timelines, app.animation.project.animations[&id].timelines, "轨道应被读回");
+}
@@ -1,7 +1,7 @@
 use pxa_engine_win32::app::state::{AppState, AppMode};
 use pxa_engine_win32::app::commands::AppCommand;
 use pxa_engine_win32::app::command_handler::CommandHandler;
-use pxa_engine_win32::core::animation::timeline::{TimelineProperty, CurveType};
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, CurveType, KeyframeValue};
 use pxa_engine_win32::core::animation::bone::BoneData;
 
 fn setup_env() -> AppState {
@@ -73,4 +73,36 @@ fn test_curve_editor_update_command() {
     let tl = anim.timelines.iter().find(|t| t.target_id == "Root" && t.property == TimelineProperty::Translation).unwrap();
     
     assert_eq!(tl.keyframes[0].curve, new_curve, "关键帧的曲线属性必须被正确更新并推入历史记录");
-}
\ No newline at end of file
+}
+#[test]
+fn test_transition_preview_commands() {
+    let mut app = setup_env();
+    let idle_id = app.animation.project.active_animation_id.clone().unwrap();
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Run".into()));
+    let run_id = app.animation.project.active_animation_id.clone().unwrap();
+
+    for (id, x) in [(&idle_id, 0.0), (&run_id, 40.0)] {
+        let anim = app.animation.project.animations.get_mut(id).unwrap();
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Translation).unwrap();
+        tl.add_keyframe(0.0, KeyframeValue::Translate(x, 0.0), CurveType::Linear);
+        tl.add_keyframe(2.0, KeyframeValue::Translate(x, 0.0), CurveType::Linear);
+    }
+
+    CommandHandler::execute(&mut app, AppCommand::SelectAnimation(idle_id.clone()));
+    CommandHandler::execute(&mut app, AppCommand::PreviewTransition(run_id.clone(), 1.0));
+    assert!(app.animation.transition_preview.is_some(), "应进入过渡预览");
+    assert!(app.animation.is_playing, "过渡预览应自动开始播放");
+
+    // 拖动时间轴即可查看过渡的任意时刻
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.5));
+    let x = app.animation.project.skeleton.bones[0].local_transform.x;
+    assert!((x - 20.0).abs() < 0.01, "过渡中段应为两个动画的混合: {}", x);
+
+    CommandHandler::execute(&mut app, AppCommand::StopTransitionPreview);
+    assert!(app.animation.transition_preview.is_none());
+    assert!(app.animation.project.skeleton.bones[0].local_transform.x.abs() < 0.01, "停止后恢复当前动画");
+
+    CommandHandler::execute(&mut app, AppCommand::PreviewTransition(run_id, 1.0));
+    CommandHandler::execute(&mut app, AppCommand::SelectAnimation(idle_id));
+    assert!(app.animation.transition_preview.is_none(), "切换动画时应退出过渡预览");
+}
@@ -0,0 +1,109 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{CurveType, KeyframeValue, Timeline, TimelineProperty};
+
+fn add_bone(app: &mut AppState, id: &str, name: &str, length: f32, x: f32) {
+    let mut bone = BoneData::new(id.into(), name.into());
+    bone.parent_id = Some("root".into());
+    bone.length = length;
+    bone.local_transform.x = x;
+    app.animation.project.skeleton.add_bone(bone);
+    app.animation.project.skeleton.update();
+}
+
+// 源工程：Leg_L (长 10，装配 x = 2) 与 Tail，Walk 动画给两根骨骼打帧，保存为 .pxad
+fn save_source_project(tag: &str) -> std::path::PathBuf {
+    let mut src = AppState::new();
+    src.mode = AppMode::Animation;
+    add_bone(&mut src, "src_leg", "Leg_L", 10.0, 2.0);
+    add_bone(&mut src, "src_tail", "Tail", 5.0, 0.0);
+    CommandHandler::execute(&mut src, AppCommand::CreateAnimation("Walk".into()));
+    let anim_id = src.animation.project.active_animation_id.clone().unwrap();
+    let anim = src.animation.project.animations.get_mut(&anim_id).unwrap();
+    let mut leg_pos = Timeline::new("src_leg".into(), TimelineProperty::Translation);
+    leg_pos.add_keyframe(0.0, KeyframeValue::Translate(2.0, 0.0), CurveType::Linear);
+    leg_pos.add_keyframe(0.5, KeyframeValue::Translate(5.0, 1.0), CurveType::Linear);
+    let mut tail_rot = Timeline::new("src_tail".into(), TimelineProperty::Rotation);
+    tail_rot.add_keyframe(0.5, KeyframeValue::Rotate(45.0), CurveType::Linear);
+    anim.timelines.retain(|t| t.target_id != "src_leg" && t.target_id != "src_tail");
+    anim.timelines.push(leg_pos);
+    anim.timelines.push(tail_rot);
+    anim.recalculate_duration();
+
+    let path = std::env::temp_dir().join(format!("pxa_retarget_{}_{}.pxad", tag, std::process::id()));
+    IoService::save_project(path.clone(), src.engine.store(), src.engine.symmetry(), &src.view, &src.animation.project).unwrap();
+    path
+}
+
+// 目标骨架：leg.l (长 20，装配 x = 4)，没有尾巴；已有一个同名的 Walk 动画
+fn setup_target() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    add_bone(&mut app, "dst_leg", "leg.l", 20.0, 4.0);
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Walk".into()));
+    app
+}
+
+fn active_timeline(app: &AppState, bone_id: &str, prop: TimelineProperty) -> Option<Timeline> {
+    let anim = &app.animation.project.animations[app.animation.project.active_animation_id.as_ref().unwrap()];
+    anim.timelines.iter().find(|t| t.target_id == bone_id && t.property == prop).cloned()
+}
+
+// ---------------------------------------------------------
+// 1. 按名称自动匹配骨骼，确认后导入并换算到目标装配姿势
+// ---------------------------------------------------------
+#[test]
+fn test_import_with_auto_mapping() {
+    let path = save_source_project("auto");
+    let mut app = setup_target();
+    app.begin_animation_import(path.clone());
+    let _ = std::fs::remove_file(&path);
+
+    let import = app.animation.pending_import.as_ref().expect("应进入等待确认的导入状态");
+    let leg = import.mapping.iter().find(|m| m.source_id == "src_leg").unwrap();
+    assert_eq!(leg.target_id.as_deref(), Some("dst_leg"), "Leg_L 与 leg.l 视为同名");
+    let tail = import.mapping.iter().find(|m| m.source_id == "src_tail").unwrap();
+    assert_eq!(tail.target_id, None, "目标骨架没有尾巴");
+
+    CommandHandler::execute(&mut app, AppCommand::ConfirmAnimationImport);
+    assert!(app.animation.pending_import.is_none());
+    assert_eq!(app.animation.project.animations.len(), 2);
+    let active = &app.animation.project.animations[app.animation.project.active_animation_id.as_ref().unwrap()];
+    assert_eq!(active.name, "Walk (2)", "重名时追加序号");
+
+    let pos = active_timeline(&app, "dst_leg", TimelineProperty::Translation).unwrap();
+    let values: Vec<KeyframeValue> = pos.keyframes.iter().map(|k| k.value.clone()).collect();
+    assert_eq!(values, vec![KeyframeValue::Translate(4.0, 0.0), KeyframeValue::Translate(7.0, 1.0)], "位移相对装配姿势换算");
+    assert!(active_timeline(&app, "src_tail", TimelineProperty::Rotation).is_none(), "未映射骨骼的轨道被丢弃");
+    assert!(active_timeline(&app, "src_leg", TimelineProperty::Translation).is_none());
+}
+
+// ---------------------------------------------------------
+// 2. 手动修改映射并按骨骼长度比例缩放位移；取消不写入任何动画
+// ---------------------------------------------------------
+#[test]
+fn test_manual_mapping_and_length_rescale() {
+    let path = save_source_project("manual");
+    let mut app = setup_target();
+    app.begin_animation_import(path.clone());
+    CommandHandler::execute(&mut app, AppCommand::CancelAnimationImport);
+    assert!(app.animation.pending_import.is_none());
+    assert_eq!(app.animation.project.animations.len(), 1, "取消后不导入");
+
+    app.begin_animation_import(path.clone());
+    let _ = std::fs::remove_file(&path);
+    {
+        let import = app.animation.pending_import.as_mut().unwrap();
+        import.rescale_translation = true;
+        import.mapping.iter_mut().find(|m| m.source_id == "src_tail").unwrap().target_id = Some("root".into());
+    }
+    CommandHandler::execute(&mut app, AppCommand::ConfirmAnimationImport);
+
+    let pos = active_timeline(&app, "dst_leg", TimelineProperty::Translation).unwrap();
+    assert_eq!(pos.keyframes[1].value, KeyframeValue::Translate(10.0, 2.0), "目标骨骼长度为两倍，位移偏移量加倍");
+    let root_rot = active_timeline(&app, "root", TimelineProperty::Rotation).unwrap();
+    assert_eq!(root_rot.keyframes[0].value, KeyframeValue::Rotate(45.0), "尾巴的轨道映射到手动指定的骨骼");
+}
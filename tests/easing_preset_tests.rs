@@ -0,0 +1,78 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::easing::{EasingFunction, EasingDirection};
+use pxa_engine_win32::core::animation::timeline::{CurveType, KeyframeValue, TimelineProperty};
+
+// root 骨骼旋转 0s -> 0°，1s -> 90°；曲线预设写入临时目录
+fn setup_env(tag: &str) -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    app.ui.curve_presets_path = std::env::temp_dir().join(format!("pxa_{}_{}", tag, std::process::id())).join("curve_presets.txt");
+    app.ui.curve_presets.clear();
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Swing".into()));
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let tl = app.animation.project.animations.get_mut(&anim_id).unwrap()
+        .timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap();
+    tl.add_keyframe(0.0, KeyframeValue::Rotate(0.0), CurveType::Linear);
+    tl.add_keyframe(1.0, KeyframeValue::Rotate(90.0), CurveType::Linear);
+    app
+}
+
+fn sample(app: &AppState, time: f32) -> f32 {
+    let anim = &app.animation.project.animations[app.animation.project.active_animation_id.as_ref().unwrap()];
+    let tl = anim.timelines.iter().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap();
+    match tl.sample(time) {
+        Some(KeyframeValue::Rotate(r)) => r,
+        other => panic!("采样结果错误: {:?}", other),
+    }
+}
+
+// ---------------------------------------------------------
+// 1. 预设缓动与阶梯曲线按解析式采样，并随工程保存
+// ---------------------------------------------------------
+#[test]
+fn test_easing_presets_sample_and_persist() {
+    let mut app = setup_env("ease");
+    let curve = CurveType::Easing(EasingFunction::Bounce, EasingDirection::Out);
+    CommandHandler::execute(&mut app, AppCommand::UpdateKeyframeCurve("root".into(), TimelineProperty::Rotation, 0.0, curve));
+    assert!((sample(&app, 1.0 / 2.75) - 90.0).abs() < 1e-3, "弹跳缓出第一次落地应到达终点");
+
+    CommandHandler::execute(&mut app, AppCommand::UpdateKeyframeCurve("root".into(), TimelineProperty::Rotation, 0.0, CurveType::Steps(3)));
+    assert_eq!(sample(&app, 0.3), 0.0);
+    assert_eq!(sample(&app, 0.5), 30.0, "三级阶梯每级 30°");
+
+    let path = std::env::temp_dir().join(format!("pxa_ease_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    assert_eq!(anim.animations[&anim_id].timelines, app.animation.project.animations[&anim_id].timelines, "缓动类型应被读回");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!((sample(&app, 1.0 / 2.75) - 90.0).abs() < 1e-3, "撤销回到弹跳缓动");
+}
+
+// ---------------------------------------------------------
+// 2. 自定义贝塞尔预设写入用户文件，重名覆盖，可删除
+// ---------------------------------------------------------
+#[test]
+fn test_custom_curve_presets_file() {
+    let mut app = setup_env("presets");
+    CommandHandler::execute(&mut app, AppCommand::SaveCurvePreset { name: "snappy".into(), handles: [0.1, 0.9, 0.2, 1.0] });
+    CommandHandler::execute(&mut app, AppCommand::SaveCurvePreset { name: "lazy".into(), handles: [0.6, 0.0, 0.9, 0.4] });
+    CommandHandler::execute(&mut app, AppCommand::SaveCurvePreset { name: "snappy".into(), handles: [0.05, 0.95, 0.2, 1.0] });
+    CommandHandler::execute(&mut app, AppCommand::SaveCurvePreset { name: "  ".into(), handles: [0.0; 4] });
+    assert!(app.ui.error_message.take().is_some(), "空名称应报错");
+
+    let loaded = pxa_engine_win32::format::curve_presets::load_presets(&app.ui.curve_presets_path).unwrap();
+    assert_eq!(loaded, app.ui.curve_presets, "文件内容与内存一致");
+    assert_eq!(loaded.len(), 2, "重名预设应覆盖");
+    assert_eq!(loaded[0].handles, [0.05, 0.95, 0.2, 1.0]);
+
+    CommandHandler::execute(&mut app, AppCommand::DeleteCurvePreset("snappy".into()));
+    let loaded = pxa_engine_win32::format::curve_presets::load_presets(&app.ui.curve_presets_path).unwrap();
+    assert_eq!(loaded.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["lazy"]);
+    let _ = std::fs::remove_dir_all(app.ui.curve_presets_path.parent().unwrap());
+}
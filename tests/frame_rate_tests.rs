@@ -0,0 +1,106 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue, CurveType};
+
+fn setup_env() -> (AppState, String) {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    (app, anim_id)
+}
+
+fn rotation_key_times(app: &AppState, anim_id: &str) -> Vec<f32> {
+    let anim = app.animation.project.animations.get(anim_id).unwrap();
+    let tl = anim.timelines.iter().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap();
+    tl.keyframes.iter().map(|k| k.time).collect()
+}
+
+// ---------------------------------------------------------
+// 1. 单帧步进按工程帧率推进
+// ---------------------------------------------------------
+#[test]
+fn test_step_frame_respects_fps() {
+    let (mut app, _) = setup_env();
+    assert_eq!(app.animation.project.fps, 30, "默认帧率为 30");
+
+    CommandHandler::execute(&mut app, AppCommand::SetFrameRate { fps: 12, retime: false });
+    assert_eq!(app.animation.project.fps, 12);
+
+    CommandHandler::execute(&mut app, AppCommand::StepFrame(3));
+    assert!((app.animation.current_time - 0.25).abs() < 1e-5, "12fps 下 3 帧应为 0.25 秒");
+    CommandHandler::execute(&mut app, AppCommand::StepFrame(-1));
+    assert!((app.animation.current_time - 2.0 / 12.0).abs() < 1e-5);
+}
+
+// ---------------------------------------------------------
+// 2. 吸附：新插入的关键帧落在帧边界上
+// ---------------------------------------------------------
+#[test]
+fn test_keyframe_snaps_to_frame() {
+    let (mut app, anim_id) = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::SetFrameRate { fps: 8, retime: false });
+
+    app.animation.current_time = 0.3;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe("root".into()));
+    let times = rotation_key_times(&app, &anim_id);
+    assert!(times.iter().any(|t| (t - 0.25).abs() < 1e-5), "0.3 秒应吸附到 8fps 的第 2 帧 (0.25 秒): {:?}", times);
+
+    CommandHandler::execute(&mut app, AppCommand::ToggleFrameSnap);
+    assert!(!app.animation.project.snap_to_frames);
+    app.animation.current_time = 0.3;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe("root".into()));
+    assert!(rotation_key_times(&app, &anim_id).iter().any(|t| (t - 0.3).abs() < 1e-5), "关闭吸附后保留原始时间");
+}
+
+// ---------------------------------------------------------
+// 3. 改变帧率：重定时保持帧号，否则保持秒数；均可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_change_fps_retime_and_undo() {
+    let (mut app, anim_id) = setup_env();
+    {
+        let anim = app.animation.project.animations.get_mut(&anim_id).unwrap();
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap();
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(0.0), CurveType::Linear);
+        tl.add_keyframe(10.0 / 30.0, KeyframeValue::Rotate(45.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Rotate(90.0), CurveType::Linear);
+        anim.recalculate_duration();
+    }
+
+    // 重定时：第 10 帧在 12fps 下仍为第 10 帧
+    CommandHandler::execute(&mut app, AppCommand::SetFrameRate { fps: 12, retime: true });
+    let times = rotation_key_times(&app, &anim_id);
+    assert!((times[1] - 10.0 / 12.0).abs() < 1e-5, "重定时应保持帧号: {:?}", times);
+    assert!((app.animation.project.animations[&anim_id].duration - 2.5).abs() < 1e-4, "时长应随帧率同比缩放");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(app.animation.project.fps, 30, "撤销应还原帧率");
+    assert!((rotation_key_times(&app, &anim_id)[1] - 10.0 / 30.0).abs() < 1e-5, "撤销应还原关键帧时间");
+
+    // 不重定时：保持秒数，对齐到 8fps 帧格
+    CommandHandler::execute(&mut app, AppCommand::SetFrameRate { fps: 8, retime: false });
+    let times = rotation_key_times(&app, &anim_id);
+    assert!((times[1] - 3.0 / 8.0).abs() < 1e-5, "1/3 秒应对齐到 8fps 的第 3 帧: {:?}", times);
+    assert!((times[2] - 1.0).abs() < 1e-5);
+}
+
+// ---------------------------------------------------------
+// 4. 帧率与吸附设置随工程保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_frame_rate_persists_in_project() {
+    let (mut app, _) = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::SetFrameRate { fps: 24, retime: false });
+    CommandHandler::execute(&mut app, AppCommand::ToggleFrameSnap);
+
+    let path = std::env::temp_dir().join(format!("pxa_frame_rate_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(anim.fps, 24, "读取的帧率应与保存时一致");
+    assert!(!anim.snap_to_frames);
+}
@@ -0,0 +1,120 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::graph::{self, TangentSide};
+use pxa_engine_win32::core::animation::timeline::{CurveType, KeyframeValue, TangentMode, Timeline, TimelineProperty, DRAW_ORDER_TARGET};
+
+// root 骨骼的旋转轨道：0s -> 0°，1s -> 10°，2s -> 30°
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Swing".into()));
+    let tl = rotation(&mut app);
+    for (t, r) in [(0.0, 0.0), (1.0, 10.0), (2.0, 30.0)] {
+        tl.add_keyframe(t, KeyframeValue::Rotate(r), CurveType::Linear);
+    }
+    app
+}
+
+fn rotation(app: &mut AppState) -> &mut Timeline {
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    app.animation.project.animations.get_mut(&anim_id).unwrap()
+        .timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap()
+}
+
+// ---------------------------------------------------------
+// 1. 自动切线平滑曲线，撤销后恢复直线
+// ---------------------------------------------------------
+#[test]
+fn test_auto_tangent_with_undo() {
+    let mut app = setup_env();
+    let keys = vec![("root".to_string(), TimelineProperty::Rotation, 1.0)];
+    CommandHandler::execute(&mut app, AppCommand::SetKeyframeTangentMode { keys, mode: TangentMode::Auto });
+
+    let tl = rotation(&mut app).clone();
+    assert_eq!(tl.keyframes[1].tangent, TangentMode::Auto);
+    assert!((graph::in_slope(&tl, 1).unwrap() - 15.0).abs() < 1e-3, "自动切线取相邻关键帧连线斜率");
+    assert!((graph::out_slope(&tl, 1).unwrap() - 15.0).abs() < 1e-3);
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let tl = rotation(&mut app);
+    assert_eq!(tl.keyframes[1].tangent, TangentMode::Broken, "撤销恢复切线模式");
+    assert_eq!(tl.keyframes[0].curve, CurveType::Linear, "撤销恢复直线");
+}
+
+// ---------------------------------------------------------
+// 2. 拖动对齐手柄同步另一侧，连续拖动只产生一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_aligned_handle_drag_merges_undo() {
+    let mut app = setup_env();
+    rotation(&mut app).keyframes[1].tangent = TangentMode::Aligned;
+    for (y, merge) in [(0.5, false), (0.75, true), (1.0, true)] {
+        CommandHandler::execute(&mut app, AppCommand::SetKeyframeHandle {
+            target_id: "root".into(), prop: TimelineProperty::Rotation, time: 1.0,
+            side: TangentSide::Out, x: 1.0 / 3.0, y, merge,
+        });
+    }
+
+    let tl = rotation(&mut app).clone();
+    let out = graph::out_slope(&tl, 1).unwrap();
+    assert!((out - 60.0).abs() < 1e-3, "出切线斜率应为 60，实际 {}", out);
+    assert!((graph::in_slope(&tl, 1).unwrap() - out).abs() < 1e-3, "对齐切线两侧斜率一致");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let tl = rotation(&mut app);
+    assert_eq!(tl.keyframes[0].curve, CurveType::Linear, "一次撤销即回到拖动前");
+    assert_eq!(tl.keyframes[1].curve, CurveType::Linear);
+}
+
+// ---------------------------------------------------------
+// 3. 按播放头与数值中心缩放选中关键帧，选区随之更新
+// ---------------------------------------------------------
+#[test]
+fn test_scale_selected_keyframes() {
+    let mut app = setup_env();
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let mut order = Timeline::new(DRAW_ORDER_TARGET.into(), TimelineProperty::DrawOrder);
+    order.add_keyframe(2.0, KeyframeValue::DrawOrder(Vec::new()), CurveType::Stepped);
+    app.animation.project.animations.get_mut(&anim_id).unwrap().timelines.push(order);
+
+    app.animation.current_time = 1.0;
+    app.ui.selected_keyframes = vec![
+        ("root".into(), Some(TimelineProperty::Rotation), 1.0),
+        ("root".into(), Some(TimelineProperty::Rotation), 2.0),
+        (DRAW_ORDER_TARGET.into(), Some(TimelineProperty::DrawOrder), 2.0),
+    ];
+    CommandHandler::execute(&mut app, AppCommand::ScaleSelectedKeyframes { time_scale: 2.0, value_scale: 2.0 });
+
+    let keys: Vec<_> = rotation(&mut app).keyframes.iter().map(|k| (k.time, k.value.clone())).collect();
+    assert_eq!(keys, vec![(0.0, KeyframeValue::Rotate(0.0)), (1.0, KeyframeValue::Rotate(0.0)), (3.0, KeyframeValue::Rotate(40.0))]);
+    assert_eq!(app.ui.selected_keyframes[1].2, 3.0, "选区应跟随新的时间");
+    let order = app.animation.project.animations[&anim_id].timelines.iter().find(|t| t.property == TimelineProperty::DrawOrder).unwrap();
+    assert_eq!(order.keyframes[0].time, 2.0, "曲线编辑器的缩放不影响非数值轨道");
+
+    CommandHandler::execute(&mut app, AppCommand::ScaleSelectedKeyframes { time_scale: 0.0, value_scale: 1.0 });
+    assert!(app.ui.error_message.is_some(), "时间缩放为 0 应报错");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let times: Vec<f32> = rotation(&mut app).keyframes.iter().map(|k| k.time).collect();
+    assert_eq!(times, vec![0.0, 1.0, 2.0], "撤销恢复原关键帧");
+}
+
+// ---------------------------------------------------------
+// 4. 切线模式随工程保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_tangent_mode_persists() {
+    let mut app = setup_env();
+    let keys = vec![("root".to_string(), TimelineProperty::Rotation, 1.0)];
+    CommandHandler::execute(&mut app, AppCommand::SetKeyframeTangentMode { keys, mode: TangentMode::Auto });
+
+    let path = std::env::temp_dir().join(format!("pxa_graph_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    assert_eq!(anim.animations[&anim_id].timelines, app.animation.project.animations[&anim_id].timelines, "切线模式与曲线应被读回");
+}
@@ -0,0 +1,119 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue};
+
+fn add_bone(app: &mut AppState, id: &str, parent: Option<&str>, x: f32, y: f32, length: f32) {
+    let mut bone = BoneData::new(id.into(), id.into());
+    bone.parent_id = parent.map(|p| p.to_string());
+    bone.local_transform.x = x;
+    bone.local_transform.y = y;
+    bone.length = length;
+    app.animation.project.skeleton.add_bone(bone);
+}
+
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    add_bone(&mut app, "upper", Some("root"), 0.0, 0.0, 10.0);
+    add_bone(&mut app, "lower", Some("upper"), 10.0, 0.0, 10.0);
+    add_bone(&mut app, "target", Some("root"), 12.0, 8.0, 0.0);
+    app.animation.project.skeleton.update();
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    app
+}
+
+fn lower_tip(app: &AppState) -> (f32, f32) {
+    let bone = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == "lower").unwrap();
+    let m = bone.world_matrix;
+    (m[4] + m[0] * bone.data.length, m[5] + m[1] * bone.data.length)
+}
+
+fn add_arm_ik(app: &mut AppState) -> String {
+    CommandHandler::execute(app, AppCommand::AddIkConstraint { tip_bone_id: "lower".into(), target_bone_id: "target".into(), chain_length: 2 });
+    app.animation.project.skeleton.ik_constraints.last().unwrap().data.id.clone()
+}
+
+// ---------------------------------------------------------
+// 1. 创建约束：链从链尾向上取父骨骼，链尾到达目标，可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_add_ik_constraint_and_undo() {
+    let mut app = setup_env();
+    add_arm_ik(&mut app);
+
+    let ik = &app.animation.project.skeleton.ik_constraints[0];
+    assert_eq!(ik.data.bone_ids, vec!["upper".to_string(), "lower".to_string()], "骨骼链应从链根排列到链尾");
+    let (tx, ty) = lower_tip(&app);
+    assert!((tx - 12.0).abs() < 0.01 && (ty - 8.0).abs() < 0.01, "链尾应到达目标骨骼: ({}, {})", tx, ty);
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(app.animation.project.skeleton.ik_constraints.is_empty(), "撤销应移除约束");
+
+    CommandHandler::execute(&mut app, AppCommand::AddIkConstraint { tip_bone_id: "lower".into(), target_bone_id: "upper".into(), chain_length: 2 });
+    assert!(app.animation.project.skeleton.ik_constraints.is_empty(), "目标位于链中时不应创建");
+    assert!(app.ui.error_message.is_some());
+}
+
+// ---------------------------------------------------------
+// 2. 拖动目标骨骼时骨骼链跟随
+// ---------------------------------------------------------
+#[test]
+fn test_dragging_target_poses_chain() {
+    let mut app = setup_env();
+    add_arm_ik(&mut app);
+
+    let target = app.animation.project.skeleton.bones.iter_mut().find(|b| b.data.id == "target").unwrap();
+    target.local_transform.x = 0.0;
+    target.local_transform.y = 15.0;
+    app.animation.project.skeleton.update();
+
+    let (tx, ty) = lower_tip(&app);
+    assert!(tx.abs() < 0.01 && (ty - 15.0).abs() < 0.01, "链尾应跟随目标移动: ({}, {})", tx, ty);
+}
+
+// ---------------------------------------------------------
+// 3. 混合度与弯曲方向可记录为关键帧并在播放时生效
+// ---------------------------------------------------------
+#[test]
+fn test_ik_mix_is_keyable() {
+    let mut app = setup_env();
+    let ik_id = add_arm_ik(&mut app);
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+
+    app.ui.auto_keyframe = true;
+    CommandHandler::execute(&mut app, AppCommand::SetIkConstraint { id: ik_id.clone(), mix: 1.0, bend_positive: false });
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(&mut app, AppCommand::SetIkConstraint { id: ik_id.clone(), mix: 0.0, bend_positive: false });
+
+    let anim = &app.animation.project.animations[&anim_id];
+    let tl = anim.timelines.iter().find(|t| t.target_id == ik_id && t.property == TimelineProperty::Ik).expect("应生成 IK 轨道");
+    assert_eq!(tl.keyframes.len(), 2);
+    assert_eq!(tl.sample(0.5), Some(KeyframeValue::Ik(0.5, false)), "混合度应线性插值");
+
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.5));
+    let ik = &app.animation.project.skeleton.ik_constraints[0];
+    assert!((ik.mix - 0.5).abs() < 1e-4 && !ik.bend_positive, "跳转时间后应应用关键帧值");
+}
+
+// ---------------------------------------------------------
+// 4. 约束随骨骼一起保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_ik_constraints_persist_with_skeleton() {
+    let mut app = setup_env();
+    let ik_id = add_arm_ik(&mut app);
+
+    let path = std::env::temp_dir().join(format!("pxa_ik_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(anim.skeleton.bones.len(), app.animation.project.skeleton.bones.len(), "骨骼应完整读回");
+    assert_eq!(anim.skeleton.slots.len(), app.animation.project.skeleton.slots.len());
+    let ik = anim.skeleton.ik_constraints.iter().find(|c| c.data.id == ik_id).expect("IK 约束应被读回");
+    assert_eq!(ik.data.target_id, "target");
+    assert_eq!(ik.data.bone_ids.len(), 2);
+}
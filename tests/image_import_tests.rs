@@ -0,0 +1,147 @@
+use pxa_engine_win32::app::state::AppState;
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::image_import::{ImageImport, ImportSizing, ImportTarget};
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::mesh::MeshAttachment;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::render::pixel_grid::PixelGrid;
+use pxa_engine_win32::render::upscale::Upscaler;
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+// 4x2 的原生像素图：左右两半分别为红、蓝，右下角透明
+fn native_sprite() -> Vec<u8> {
+    [RED, RED, BLUE, BLUE, RED, RED, BLUE, [0; 4]].concat()
+}
+
+// 每个像素放大为 factor x factor
+fn upscaled(src: &[u8], w: u32, h: u32, factor: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    for y in 0..h * factor {
+        for x in 0..w * factor {
+            let idx = (((y / factor) * w + x / factor) * 4) as usize;
+            out.extend_from_slice(&src[idx..idx + 4]);
+        }
+    }
+    out
+}
+
+fn pixel(app: &AppState, x: u32, y: u32) -> Option<Color> {
+    let store = app.engine.store();
+    store.get_layer(store.active_layer_id.as_ref().unwrap()).unwrap().get_pixel(x, y).filter(|c| c.a > 0)
+}
+
+// ---------------------------------------------------------
+// 1. 保持原尺寸导入为新图层：按偏移放置，不拉伸
+// ---------------------------------------------------------
+#[test]
+fn test_import_keep_size_with_offset() {
+    let mut app = AppState::new();
+    let layer_count = app.engine.store().layers.len();
+    let mut import = ImageImport::new("sprite.png".into(), native_sprite(), 4, 2);
+    assert_eq!(import.detected_grid, None, "原生像素图不应检测出网格");
+    (import.offset_x, import.offset_y) = (10, 20);
+    app.ui.pending_image_import = Some(import);
+    CommandHandler::execute(&mut app, AppCommand::ConfirmImageImport);
+
+    assert!(app.ui.pending_image_import.is_none());
+    assert_eq!(app.engine.store().layers.len(), layer_count + 1);
+    assert_eq!(pixel(&app, 10, 20), Some(Color::new(255, 0, 0, 255)));
+    assert_eq!(pixel(&app, 13, 20), Some(Color::new(0, 0, 255, 255)));
+    assert_eq!(pixel(&app, 13, 21), None, "透明像素保持透明");
+    assert_eq!(pixel(&app, 14, 20), None, "图片外不应被拉伸填满");
+
+    app.engine.undo().unwrap();
+    assert_eq!(app.engine.store().layers.len(), layer_count, "导入可撤销");
+}
+
+// ---------------------------------------------------------
+// 2. 适应画布：放大取整数倍，缩小保持宽高比；超出画布的部分被裁掉
+// ---------------------------------------------------------
+#[test]
+fn test_import_fit_and_clip() {
+    let mut import = ImageImport::new("sprite.png".into(), native_sprite(), 4, 2);
+    import.sizing = ImportSizing::Fit;
+    let &(_, w, h) = import.placed_image(30, 30);
+    assert_eq!((w, h), (28, 14), "整数倍放大 (7x)，保持宽高比");
+    assert_eq!(import.centered_offset(30, 30), (1, 8));
+
+    let &(_, w, h) = import.placed_image(2, 2);
+    assert_eq!((w, h), (2, 1), "缩小时按比例");
+
+    import.sizing = ImportSizing::Keep;
+    (import.offset_x, import.offset_y) = (-2, 0);
+    let layer = import.to_layer(8, 8, "l".into(), "l".into());
+    assert_eq!((layer.width, layer.height), (8, 8));
+    assert_eq!(layer.get_pixel(0, 0).map(|c| c.b), Some(255), "左侧两列被裁掉");
+    assert!(layer.get_pixel(2, 0).is_none_or(|c| c.a == 0));
+}
+
+// ---------------------------------------------------------
+// 3. 放大过的伪像素画：检测网格并还原，导入为新画布
+// ---------------------------------------------------------
+#[test]
+fn test_import_snapped_as_new_canvas() {
+    let mut app = AppState::new();
+    let palette = app.engine.store().palette.clone();
+    let mut import = ImageImport::new("fake.png".into(), upscaled(&native_sprite(), 4, 2, 6), 24, 12);
+    assert_eq!(import.detected_grid, Some(PixelGrid { cell: 6, offset_x: 0, offset_y: 0 }));
+    assert_eq!(import.snap_grid, import.detected_grid, "检测到网格时默认还原");
+    assert_eq!(*import.source_image(), (native_sprite(), 4, 2));
+
+    import.target = ImportTarget::NewCanvas;
+    app.ui.pending_image_import = Some(import);
+    CommandHandler::execute(&mut app, AppCommand::ConfirmImageImport);
+
+    let store = app.engine.store();
+    assert_eq!((store.canvas_width, store.canvas_height), (4, 2), "以还原后的尺寸新建画布");
+    assert_eq!(store.layers.len(), 1);
+    assert_eq!(store.palette.colors, palette.colors, "保留当前调色板");
+    assert_eq!(pixel(&app, 2, 1), Some(Color::new(0, 0, 255, 255)));
+    assert_eq!(app.animation.project.skeleton.slots.len(), 1, "为新图层建立插槽");
+}
+
+// ---------------------------------------------------------
+// 4. 放大画布：尺寸、图层偏移、选区与骨架一同放大，一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_upscale_canvas() {
+    let mut app = AppState::new();
+    let (w, h) = (app.engine.store().canvas_width, app.engine.store().canvas_height);
+    let import = ImageImport::new("sprite.png".into(), native_sprite(), 4, 2);
+    app.ui.pending_image_import = Some(import);
+    CommandHandler::execute(&mut app, AppCommand::ConfirmImageImport);
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        store.selection.set_rect(1, 1, 2, 2);
+        store.selection.is_active = true;
+    }
+    let mut arm = BoneData::new("arm".into(), "arm".into());
+    arm.parent_id = Some("root".into());
+    arm.local_transform.x = 2.0;
+    arm.local_transform.y = 1.0;
+    arm.length = 2.0;
+    app.animation.project.skeleton.add_bone(arm);
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let mesh = MeshAttachment::from_layer(app.engine.store().get_layer(&layer_id).unwrap(), 2).unwrap();
+    app.animation.project.skeleton.meshes.push(mesh);
+
+    CommandHandler::execute(&mut app, AppCommand::UpscaleCanvas(Upscaler::Scale3x));
+    let store = app.engine.store();
+    assert_eq!((store.canvas_width, store.canvas_height), (w * 3, h * 3));
+    assert!(store.layers.iter().all(|l| l.width == w * 3 && l.height == h * 3));
+    assert!(store.selection.contains(3, 3) && store.selection.contains(8, 8) && !store.selection.contains(9, 9), "选区按倍率放大");
+    assert_eq!(pixel(&app, 0, 0), Some(Color::new(255, 0, 0, 255)));
+    assert_eq!(pixel(&app, 11, 0), Some(Color::new(0, 0, 255, 255)));
+    assert_eq!(pixel(&app, 11, 5), None, "透明像素放大后仍透明");
+    let arm = &app.animation.project.skeleton.bones[1].data;
+    assert_eq!((arm.local_transform.x, arm.local_transform.y, arm.length), (6.0, 3.0, 6.0), "骨骼位置与长度按倍率放大");
+    let corner = app.animation.project.skeleton.meshes[0].vertices.iter().find(|v| v.u > 0.0 && v.v > 0.0).unwrap();
+    assert_eq!((corner.x, corner.y, corner.u, corner.v), (6.0, 6.0, 6.0, 6.0), "网格顶点与纹理坐标按倍率放大");
+
+    app.engine.undo().unwrap();
+    assert_eq!((app.engine.store().canvas_width, app.engine.store().canvas_height), (w, h));
+    assert_eq!(pixel(&app, 3, 0), Some(Color::new(0, 0, 255, 255)));
+}
@@ -0,0 +1,142 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{CurveType, KeyframeValue, Timeline, TimelineProperty};
+
+// 左右对称的两只手臂：arm_L 装配旋转 30°、x = -10，arm_R 装配旋转 -30°、x = 10
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    for (id, rotation, x) in [("arm_L", 30.0, -10.0), ("arm_R", -30.0, 10.0)] {
+        let mut bone = BoneData::new(id.into(), id.into());
+        bone.parent_id = Some("root".into());
+        bone.local_transform.rotation = rotation;
+        bone.local_transform.x = x;
+        bone.length = 10.0;
+        app.animation.project.skeleton.add_bone(bone);
+    }
+    app.animation.project.skeleton.update();
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Wave".into()));
+    app
+}
+
+fn timeline(app: &AppState, bone_id: &str, prop: TimelineProperty) -> Option<Timeline> {
+    let anim = &app.animation.project.animations[app.animation.project.active_animation_id.as_ref().unwrap()];
+    anim.timelines.iter().find(|t| t.target_id == bone_id && t.property == prop).cloned()
+}
+
+fn key_rotation(app: &mut AppState, bone_id: &str, time: f32, rotation: f32, curve: CurveType) {
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let anim = app.animation.project.animations.get_mut(&anim_id).unwrap();
+    match anim.timelines.iter_mut().find(|t| t.target_id == bone_id && t.property == TimelineProperty::Rotation) {
+        Some(tl) => tl.add_keyframe(time, KeyframeValue::Rotate(rotation), curve),
+        None => {
+            let mut tl = Timeline::new(bone_id.into(), TimelineProperty::Rotation);
+            tl.add_keyframe(time, KeyframeValue::Rotate(rotation), curve);
+            anim.timelines.push(tl);
+        }
+    }
+    anim.recalculate_duration();
+}
+
+fn rotations(tl: &Timeline) -> Vec<(f32, KeyframeValue)> {
+    tl.keyframes.iter().map(|k| (k.time, k.value.clone())).collect()
+}
+
+// ---------------------------------------------------------
+// 1. 复制的关键帧可粘贴到其它时间与其它骨骼，一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_copy_paste_to_other_bone() {
+    let mut app = setup_env();
+    key_rotation(&mut app, "arm_L", 0.0, 30.0, CurveType::Stepped);
+    key_rotation(&mut app, "arm_L", 0.5, 60.0, CurveType::Linear);
+    app.ui.selected_keyframes = vec![("arm_L".into(), None, 0.0), ("arm_L".into(), None, 0.5)];
+    CommandHandler::execute(&mut app, AppCommand::CopyKeyframes);
+
+    app.ui.selected_bone_id = Some("arm_R".into());
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(&mut app, AppCommand::PasteKeyframes { mirrored: false });
+
+    let tl = timeline(&app, "arm_R", TimelineProperty::Rotation).expect("目标骨骼应有旋转轨道");
+    assert_eq!(rotations(&tl), vec![(1.0, KeyframeValue::Rotate(30.0)), (1.5, KeyframeValue::Rotate(60.0))]);
+    assert_eq!(tl.keyframes[0].curve, CurveType::Stepped, "曲线类型随关键帧复制");
+    assert_eq!(app.ui.selected_keyframes.len(), 2, "选区切换到粘贴出的关键帧");
+    assert_eq!(rotations(&timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap()).len(), 2, "源骨骼不变");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(timeline(&app, "arm_R", TimelineProperty::Rotation).is_none_or(|tl| tl.keyframes.is_empty()), "撤销移除粘贴的关键帧");
+}
+
+// ---------------------------------------------------------
+// 2. 剪贴板在动画之间共享
+// ---------------------------------------------------------
+#[test]
+fn test_paste_into_other_animation() {
+    let mut app = setup_env();
+    key_rotation(&mut app, "arm_L", 0.25, 45.0, CurveType::Linear);
+    app.ui.selected_keyframes = vec![("arm_L".into(), Some(TimelineProperty::Rotation), 0.25)];
+    CommandHandler::execute(&mut app, AppCommand::CopyKeyframes);
+
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Idle".into()));
+    CommandHandler::execute(&mut app, AppCommand::PasteKeyframes { mirrored: false });
+    let tl = timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap();
+    assert_eq!(rotations(&tl), vec![(0.0, KeyframeValue::Rotate(45.0))], "相对时间从播放头开始");
+}
+
+// ---------------------------------------------------------
+// 3. 镜像粘贴姿势：按 _L/_R 交换骨骼，相对装配姿势取镜像
+// ---------------------------------------------------------
+#[test]
+fn test_paste_pose_mirrored() {
+    let mut app = setup_env();
+    {
+        let skel = &mut app.animation.project.skeleton;
+        let left = skel.bones.iter_mut().find(|b| b.data.id == "arm_L").unwrap();
+        left.local_transform.rotation = 50.0;
+        left.local_transform.x = -15.0;
+    }
+    CommandHandler::execute(&mut app, AppCommand::CopyPose);
+    CommandHandler::execute(&mut app, AppCommand::PasteKeyframes { mirrored: true });
+
+    let right_rot = timeline(&app, "arm_R", TimelineProperty::Rotation).unwrap();
+    assert_eq!(rotations(&right_rot), vec![(0.0, KeyframeValue::Rotate(-50.0))], "左臂多转 20° 对应右臂反向 20°");
+    let right_pos = timeline(&app, "arm_R", TimelineProperty::Translation).unwrap();
+    assert_eq!(right_pos.keyframes[0].value, KeyframeValue::Translate(15.0, 0.0), "水平位移取镜像");
+    let left_rot = timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap();
+    assert_eq!(left_rot.keyframes[0].value, KeyframeValue::Rotate(30.0), "右臂的装配姿势镜像回左臂");
+    let root_rot = timeline(&app, "root", TimelineProperty::Rotation).unwrap();
+    assert_eq!(root_rot.keyframes.len(), 1, "没有对侧的骨骼映射到自身");
+    let right_bone = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == "arm_R").unwrap();
+    assert_eq!(right_bone.local_transform.rotation, -50.0, "粘贴后立即应用到姿势");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(timeline(&app, "arm_R", TimelineProperty::Rotation).unwrap().keyframes.is_empty(), "一步撤销整个粘贴");
+}
+
+// ---------------------------------------------------------
+// 4. 选中关键帧的时间反转与时间缩放
+// ---------------------------------------------------------
+#[test]
+fn test_reverse_and_time_scale_selection() {
+    let mut app = setup_env();
+    key_rotation(&mut app, "arm_L", 0.0, 0.0, CurveType::Linear);
+    key_rotation(&mut app, "arm_L", 0.25, 10.0, CurveType::Linear);
+    key_rotation(&mut app, "arm_L", 1.0, 40.0, CurveType::Linear);
+    app.ui.selected_keyframes = vec![("arm_L".into(), None, 0.0), ("arm_L".into(), None, 0.25), ("arm_L".into(), None, 1.0)];
+
+    CommandHandler::execute(&mut app, AppCommand::ReverseSelectedKeyframes);
+    let tl = timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap();
+    assert_eq!(rotations(&tl), vec![(0.0, KeyframeValue::Rotate(40.0)), (0.75, KeyframeValue::Rotate(10.0)), (1.0, KeyframeValue::Rotate(0.0))]);
+
+    app.animation.current_time = 0.0;
+    CommandHandler::execute(&mut app, AppCommand::ScaleSelectedKeyframes { time_scale: 2.0, value_scale: 1.0 });
+    let tl = timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap();
+    assert_eq!(tl.keyframes.iter().map(|k| k.time).collect::<Vec<_>>(), vec![0.0, 1.5, 2.0]);
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let tl = timeline(&app, "arm_L", TimelineProperty::Rotation).unwrap();
+    assert_eq!(rotations(&tl), vec![(0.0, KeyframeValue::Rotate(0.0)), (0.25, KeyframeValue::Rotate(10.0)), (1.0, KeyframeValue::Rotate(40.0))], "两步撤销恢复原样");
+}
@@ -0,0 +1,129 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::core::layer_effect::{Connectivity, LayerEffectKind};
+
+const RED: Color = Color { r: 255, g: 0, b: 0, a: 255 };
+const INK: Color = Color { r: 0, g: 0, b: 0, a: 255 };
+
+// 在活动图层 (10, 10) 画一个红色像素
+fn setup_layer_effect_test() -> (AppState, String) {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    app.engine.set_primary_color(RED);
+    app.on_mouse_down(10, 10).unwrap(); app.on_mouse_up().unwrap();
+    (app, layer_id)
+}
+
+fn composite_pixel(app: &AppState, x: u32, y: u32) -> [u8; 4] {
+    let store = app.engine.store();
+    let idx = ((y * store.canvas_width + x) * 4) as usize;
+    store.composite_cache[idx..idx + 4].try_into().unwrap()
+}
+
+fn effects(app: &AppState, layer_id: &str) -> Vec<pxa_engine_win32::core::layer_effect::LayerEffect> {
+    app.engine.store().get_layer(layer_id).unwrap().effects.clone()
+}
+
+// ---------------------------------------------------------
+// 1. 效果只影响合成结果，源像素不变；添加可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_outline_effect_is_non_destructive() {
+    let (mut app, layer_id) = setup_layer_effect_test();
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::Outline { color: INK, connectivity: Connectivity::Four }));
+
+    assert_eq!(composite_pixel(&app, 9, 10), [0, 0, 0, 255], "合成结果带描边");
+    assert_eq!(composite_pixel(&app, 10, 10), [255, 0, 0, 255]);
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 9, 10), Some(Color::transparent()), "源像素不变");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(effects(&app, &layer_id).is_empty());
+    assert_eq!(composite_pixel(&app, 9, 10)[3], 0, "撤销后描边消失");
+}
+
+// ---------------------------------------------------------
+// 2. 缓存按图层 version 与效果参数失效
+// ---------------------------------------------------------
+#[test]
+fn test_effect_cache_follows_version() {
+    let (mut app, layer_id) = setup_layer_effect_test();
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::HueShift { degrees: 120.0 }));
+    assert_eq!(composite_pixel(&app, 10, 10), [0, 255, 0, 255], "红色偏移 120° 为绿色");
+
+    let serial = app.engine.store().effect_cache_serial;
+    app.engine.update_render_cache(None);
+    assert_eq!(app.engine.store().effect_cache_serial, serial, "未变化时复用缓存");
+
+    app.on_mouse_down(12, 10).unwrap(); app.on_mouse_up().unwrap();
+    assert!(app.engine.store().effect_cache_serial > serial, "绘制后重新计算");
+    assert_eq!(composite_pixel(&app, 12, 10), [0, 255, 0, 255]);
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 12, 10), Some(RED));
+}
+
+// ---------------------------------------------------------
+// 3. 开关与排序：效果按栈顺序求值
+// ---------------------------------------------------------
+#[test]
+fn test_toggle_and_reorder_effects() {
+    let (mut app, layer_id) = setup_layer_effect_test();
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::ColorOverlay { color: Color::new(0, 0, 255, 255), strength: 1.0 }));
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::PaletteSwap { pairs: vec![(Color::new(0, 0, 255, 255), Color::new(0, 255, 0, 255))] }));
+    assert_eq!(composite_pixel(&app, 10, 10), [0, 255, 0, 255], "先叠加为蓝色再替换为绿色");
+
+    let mut stack = effects(&app, &layer_id);
+    stack.swap(0, 1);
+    CommandHandler::execute(&mut app, AppCommand::SetLayerEffects(layer_id.clone(), stack.clone()));
+    assert_eq!(composite_pixel(&app, 10, 10), [0, 0, 255, 255], "替换在前时不匹配，最终为叠加色");
+
+    stack[1].enabled = false;
+    CommandHandler::execute(&mut app, AppCommand::SetLayerEffects(layer_id.clone(), stack));
+    assert_eq!(composite_pixel(&app, 10, 10), [255, 0, 0, 255], "关闭叠加");
+}
+
+// ---------------------------------------------------------
+// 4. 效果数值可由动画轨道驱动
+// ---------------------------------------------------------
+#[test]
+fn test_effect_amount_timeline() {
+    let (mut app, layer_id) = setup_layer_effect_test();
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::HueShift { degrees: 0.0 }));
+    let effect_id = effects(&app, &layer_id)[0].id.clone();
+
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Flash".into()));
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(effect_id.clone()));
+
+    let mut stack = effects(&app, &layer_id);
+    stack[0].kind.set_amount(120.0);
+    CommandHandler::execute(&mut app, AppCommand::SetLayerEffects(layer_id.clone(), stack));
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(effect_id.clone()));
+
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.0));
+    assert_eq!(composite_pixel(&app, 10, 10), [255, 0, 0, 255], "0 秒处为 0°");
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.5));
+    assert_eq!(app.engine.store().effect_amounts.get(&effect_id), Some(&60.0));
+    assert_eq!(composite_pixel(&app, 10, 10), [255, 255, 0, 255], "0.5 秒处为 60°");
+    assert_eq!(effects(&app, &layer_id)[0].kind.amount(), Some(120.0), "动画不修改图层上的参数");
+}
+
+// ---------------------------------------------------------
+// 5. 局部重绘覆盖效果扩展到笔画以外的像素
+// ---------------------------------------------------------
+#[test]
+fn test_partial_redraw_includes_effect_reach() {
+    let (mut app, layer_id) = setup_layer_effect_test();
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::DropShadow { color: INK, dx: 3, dy: 0 }));
+    CommandHandler::execute(&mut app, AppCommand::AddLayerEffect(layer_id.clone(), LayerEffectKind::Outline { color: INK, connectivity: Connectivity::Four }));
+
+    app.on_mouse_down(20, 20).unwrap(); app.on_mouse_up().unwrap();
+    assert_eq!(composite_pixel(&app, 23, 20), [0, 0, 0, 255], "投影落在笔画矩形之外");
+    assert_eq!(composite_pixel(&app, 24, 20), [0, 0, 0, 255], "投影的描边也被重绘");
+
+    app.engine.set_primary_color(Color::transparent());
+    app.on_mouse_down(20, 20).unwrap(); app.on_mouse_up().unwrap();
+    assert_eq!(composite_pixel(&app, 23, 20)[3], 0, "擦除后投影消失");
+    assert_eq!(composite_pixel(&app, 24, 20)[3], 0);
+}
@@ -0,0 +1,172 @@
+use pxa_engine_win32::app::state::{AppState, AppMode, ToolType};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::mesh::MeshAttachment;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue};
+use pxa_engine_win32::core::color::Color;
+
+// L1 图层上 (40..56, 40..48) 为不透明像素，左右各一根骨骼
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    {
+        let layer = app.engine.parts_mut().0.get_layer_mut("L1").unwrap();
+        for y in 40..48 {
+            for x in 40..56 {
+                layer.set_pixel_raw(x, y, Color::new(255, 0, 0, 255)).unwrap();
+            }
+        }
+    }
+    for (id, x) in [("left", 40.0), ("right", 48.0)] {
+        let mut bone = BoneData::new(id.into(), id.into());
+        bone.parent_id = Some("root".into());
+        bone.local_transform.x = x;
+        bone.local_transform.y = 44.0;
+        bone.length = 8.0;
+        app.animation.project.skeleton.add_bone(bone);
+    }
+    app.animation.project.skeleton.update();
+    app
+}
+
+fn vertex_index(app: &AppState, x: f32, y: f32) -> usize {
+    let mesh = app.animation.project.skeleton.mesh("L1").unwrap();
+    mesh.vertices.iter().position(|v| v.x == x && v.y == y).expect("应有该顶点")
+}
+
+fn composite_alpha(app: &AppState, x: u32, y: u32) -> u8 {
+    let store = app.engine.store();
+    store.composite_cache[((y * store.canvas_width + x) * 4 + 3) as usize]
+}
+
+// ---------------------------------------------------------
+// 1. 生成网格：覆盖不透明区域、自动权重，且可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_create_mesh_with_undo() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::CreateMesh { layer_id: "L1".into(), cell_size: 8 });
+
+    let mesh = app.animation.project.skeleton.mesh("L1").expect("应生成网格");
+    assert_eq!(mesh.vertices.len(), 6, "两个 8x8 格子共 6 个顶点");
+    assert_eq!(mesh.triangles.len(), 4);
+    for v in &mesh.vertices {
+        assert!(!v.weights.is_empty() && v.weights.len() <= 4, "每个顶点最多 4 个权重");
+        assert!((v.weights.iter().map(|w| w.1).sum::<f32>() - 1.0).abs() < 1e-4, "权重之和应为 1");
+    }
+    let left = &mesh.vertices[vertex_index(&app, 40.0, 48.0)];
+    assert_eq!(left.weights[0].0, "left", "靠近左骨骼的顶点主要受左骨骼影响");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert!(app.animation.project.skeleton.meshes.is_empty(), "撤销后网格应被移除");
+}
+
+// ---------------------------------------------------------
+// 2. 顶点按权重混合多根骨骼的变换，合成结果随网格变形
+// ---------------------------------------------------------
+#[test]
+fn test_weighted_skinning_and_composite() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::CreateMesh { layer_id: "L1".into(), cell_size: 8 });
+    for v in &mut app.animation.project.skeleton.mesh_mut("L1").unwrap().vertices {
+        v.weights = match v.x as i32 {
+            40 => vec![("left".into(), 1.0)],
+            48 => vec![("left".into(), 0.5), ("right".into(), 0.5)],
+            _ => vec![("right".into(), 1.0)],
+        };
+    }
+
+    app.animation.project.skeleton.bones[2].local_transform.y += 10.0;
+    app.animation.project.skeleton.update();
+    let deformed = app.animation.project.skeleton.deformed_mesh("L1").unwrap();
+    for (x, dy) in [(40.0, 0.0), (48.0, 5.0), (56.0, 10.0)] {
+        let p = deformed.positions[vertex_index(&app, x, 40.0)];
+        assert!((p[1] - (40.0 + dy)).abs() < 1e-4, "x = {} 的顶点应下移 {}, 实际 y = {}", x, dy, p[1]);
+    }
+
+    // 两根骨骼一起移动时整个网格平移
+    app.animation.project.skeleton.bones[1].local_transform.y += 10.0;
+    app.animation.project.skeleton.update();
+    app.sync_animation_to_layers();
+    app.engine.update_render_cache(None);
+    assert!(app.engine.store().layer_meshes.contains_key("L1"), "网格图层应由蒙皮结果合成");
+    assert_eq!(composite_alpha(&app, 44, 55), 255, "像素应随网格下移");
+    assert_eq!(composite_alpha(&app, 44, 42), 0, "原位置应已清空");
+}
+
+// ---------------------------------------------------------
+// 3. 自动关键帧下拖动顶点写入变形轨道，并在关键帧间插值
+// ---------------------------------------------------------
+#[test]
+fn test_deform_timeline_from_vertex_drag() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::CreateMesh { layer_id: "L1".into(), cell_size: 8 });
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Wobble".into()));
+    app.ui.auto_keyframe = true;
+    app.set_tool(ToolType::MeshEdit);
+    let idx = vertex_index(&app, 40.0, 40.0);
+
+    app.on_mouse_down(40, 40).unwrap();
+    assert_eq!(app.ui.selected_mesh_vertex, Some(idx), "应选中最近的顶点");
+    app.on_mouse_move(43, 40).unwrap();
+    app.on_mouse_up().unwrap();
+
+    let mesh = app.animation.project.skeleton.mesh("L1").unwrap();
+    assert_eq!(mesh.deform_offset(idx), (3.0, 0.0));
+    assert_eq!(mesh.vertices[idx].x, 40.0, "记录变形时不修改装配位置");
+
+    app.animation.current_time = 1.0;
+    app.on_mouse_down(43, 40).unwrap();
+    app.on_mouse_move(40, 40).unwrap();
+    app.on_mouse_up().unwrap();
+
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let anim = app.animation.project.animations[&anim_id].clone();
+    let timeline = anim.timelines.iter().find(|t| t.target_id == "L1" && t.property == TimelineProperty::Deform).expect("应创建变形轨道");
+    assert_eq!(timeline.keyframes.len(), 2);
+    match timeline.sample(0.5) {
+        Some(KeyframeValue::Deform(offsets)) => assert!((offsets[idx].0 - 1.5).abs() < 1e-4, "变形偏移应线性插值"),
+        other => panic!("采样结果错误: {:?}", other),
+    }
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let anim = &app.animation.project.animations[&anim_id];
+    let timeline = anim.timelines.iter().find(|t| t.target_id == "L1" && t.property == TimelineProperty::Deform).unwrap();
+    assert_eq!(timeline.keyframes.len(), 1, "撤销应移除第二次拖动的关键帧");
+}
+
+// ---------------------------------------------------------
+// 4. 权重绘制只影响笔刷内的顶点，并随工程保存
+// ---------------------------------------------------------
+#[test]
+fn test_weight_paint_and_persistence() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::CreateMesh { layer_id: "L1".into(), cell_size: 8 });
+    let idx = vertex_index(&app, 40.0, 40.0);
+    let far = vertex_index(&app, 56.0, 48.0);
+    let before = app.animation.project.skeleton.mesh("L1").unwrap().clone();
+    let right_weight = |mesh: &MeshAttachment| mesh.vertices[idx].weights.iter().find(|w| w.0 == "right").map(|w| w.1).unwrap_or(0.0);
+
+    app.set_tool(ToolType::WeightPaint);
+    app.ui.selected_bone_id = Some("right".into());
+    app.ui.weight_brush_radius = 3.0;
+    app.ui.weight_brush_strength = 0.5;
+    app.on_mouse_down(40, 40).unwrap();
+    app.on_mouse_up().unwrap();
+
+    assert!(right_weight(app.animation.project.skeleton.mesh("L1").unwrap()) > right_weight(&before) + 0.2, "笔刷下的顶点应增加右骨骼权重");
+    assert_eq!(app.animation.project.skeleton.mesh("L1").unwrap().vertices[far], before.vertices[far], "笔刷外的顶点不变");
+    assert_eq!(app.ui.selected_bone_id.as_deref(), Some("right"), "绘制权重不应改变选中的骨骼");
+
+    let path = std::env::temp_dir().join(format!("pxa_mesh_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(anim.skeleton.meshes, app.animation.project.skeleton.meshes, "网格与权重应被读回");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(app.animation.project.skeleton.mesh("L1").unwrap(), &before, "撤销恢复原权重");
+}
+
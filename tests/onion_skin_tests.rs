@@ -0,0 +1,151 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::animation::onion_skin::OnionSkinSettings;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue, CurveType};
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::render::compositor::{Compositor, Viewport};
+
+// 图层绑定到骨骼，0s 处位移 (0, 0)，1s 处位移 (30, 0)，时间停在 0.5s
+fn setup_onion_app() -> (AppState, String) {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+
+    app.engine.set_primary_color(Color::new(255, 0, 0, 255));
+    app.on_mouse_down(50, 50).unwrap(); app.on_mouse_up().unwrap();
+
+    app.animation.project.skeleton.add_bone(BoneData::new("BoneA".into(), "Arm".into()));
+    CommandHandler::execute(&mut app, AppCommand::BindLayerToBone(layer_id.clone(), "BoneA".into()));
+
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    {
+        let anim = app.animation.project.animations.get_mut(&anim_id).unwrap();
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "BoneA" && t.property == TimelineProperty::Translation).unwrap();
+        tl.add_keyframe(0.0, KeyframeValue::Translate(0.0, 0.0), CurveType::Linear);
+        tl.add_keyframe(1.0, KeyframeValue::Translate(30.0, 0.0), CurveType::Linear);
+        anim.duration = 2.0;
+    }
+    app.animation.is_looping = false;
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.5));
+    (app, layer_id)
+}
+
+// ---------------------------------------------------------
+// 1. 开启洋葱皮后，前后帧的残影写入 onion_cache
+// ---------------------------------------------------------
+#[test]
+fn test_onion_skin_builds_ghost_cache() {
+    let (mut app, _) = setup_onion_app();
+    assert!(app.update_onion_skin().is_empty(), "默认关闭洋葱皮");
+    assert!(app.engine.store().onion_cache.is_empty());
+
+    let settings = OnionSkinSettings { enabled: true, frames_before: 1, frames_after: 1, step: 15, ..Default::default() };
+    CommandHandler::execute(&mut app, AppCommand::SetOnionSkin(settings));
+    assert!(app.is_dirty, "修改洋葱皮设置应标记工程已修改");
+
+    let ghosts = app.update_onion_skin();
+    assert_eq!(ghosts.len(), 2);
+
+    let store = app.engine.store();
+    let w = store.canvas_width as usize;
+    let pixel = |x: usize, y: usize| { let i = (y * w + x) * 4; [store.onion_cache[i], store.onion_cache[i + 1], store.onion_cache[i + 2], store.onion_cache[i + 3]] };
+
+    let before = pixel(50, 50);
+    assert!(before[3] > 0, "0s 处的残影应位于原位置");
+    assert!(before[0] > before[1], "前帧残影应带有前帧色调");
+    let after = pixel(80, 50);
+    assert!(after[3] > 0, "1s 处的残影应位于 (80, 50)");
+    assert!(after[1] > before[1], "后帧残影应带有后帧色调");
+    assert_eq!(pixel(65, 50)[3], 0, "当前帧不应出现在残影缓存中");
+    assert_eq!(store.get_composite_pixel(65, 50).r, 255, "当前帧仍正常合成");
+}
+
+// ---------------------------------------------------------
+// 2. 残影显示在画面上，且切回像素编辑模式后消失
+// ---------------------------------------------------------
+#[test]
+fn test_onion_skin_render_and_mode_switch() {
+    let (mut app, _) = setup_onion_app();
+    CommandHandler::execute(&mut app, AppCommand::SetOnionSkin(OnionSkinSettings { enabled: true, frames_before: 1, frames_after: 0, step: 15, ..Default::default() }));
+    app.update_onion_skin();
+
+    let store = app.engine.store();
+    let (w, h) = (store.canvas_width, store.canvas_height);
+    let view = Viewport { screen_width: w, screen_height: h, zoom: 1.0, pan_x: 0.0, pan_y: 0.0 };
+    let mut frame = vec![0u8; (w * h * 4) as usize];
+    let idx = ((50 * w + 50) * 4) as usize;
+    let empty_idx = ((10 * w + 10) * 4) as usize;
+
+    Compositor::render(store, &mut frame, view);
+    assert!(frame[idx] > frame[idx + 1], "残影处应显示为带色调的半透明像素");
+    assert_eq!(frame[empty_idx], frame[empty_idx + 1], "无残影处保持棋盘格背景");
+
+    app.mode = AppMode::PixelEdit;
+    assert!(app.update_onion_skin().is_empty());
+    assert!(app.engine.store().onion_cache.is_empty(), "像素编辑模式下不显示洋葱皮");
+}
+
+// ---------------------------------------------------------
+// 3. 洋葱皮设置随工程保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_onion_skin_settings_persist_in_project() {
+    let mut app = AppState::new();
+    let settings = OnionSkinSettings {
+        enabled: true,
+        frames_before: 4,
+        frames_after: 1,
+        step: 2,
+        tint_before: [1, 2, 3],
+        tint_after: [4, 5, 6],
+        opacity: 90,
+    };
+    let path = std::env::temp_dir().join(format!("pxa_onion_skin_{}.pxad", std::process::id()));
+
+    app.animation.project.onion_skin = settings;
+
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (store, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(anim.onion_skin, settings, "读取的洋葱皮设置应与保存时一致");
+    assert_eq!(store.layers.len(), app.engine.store().layers.len());
+}
+
+// ---------------------------------------------------------
+// 4. 残影缓存在输入不变时复用，时间或图层像素变化后重新合成
+// ---------------------------------------------------------
+#[test]
+fn test_onion_skin_cache_invalidation() {
+    let (mut app, layer_id) = setup_onion_app();
+    CommandHandler::execute(&mut app, AppCommand::SetOnionSkin(OnionSkinSettings { enabled: true, frames_before: 1, frames_after: 1, step: 15, ..Default::default() }));
+    app.update_onion_skin();
+
+    // 在空白处写入标记：复用缓存时标记保留，重新合成后被清除
+    let mark = |app: &mut AppState| { app.engine.parts_mut().0.onion_cache[..4].copy_from_slice(&[1, 2, 3, 4]); };
+    let marked = |app: &AppState| app.engine.store().onion_cache[..4] == [1, 2, 3, 4];
+
+    mark(&mut app);
+    app.update_onion_skin();
+    assert!(marked(&app), "输入未变化时不重新合成");
+
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.6));
+    app.update_onion_skin();
+    assert!(!marked(&app), "时间变化后重新合成");
+
+    mark(&mut app);
+    app.engine.parts_mut().0.force_set_pixel(&layer_id, 51, 50, Color::new(0, 0, 255, 255)).unwrap();
+    app.update_onion_skin();
+    assert!(!marked(&app), "图层像素变化后重新合成");
+
+    mark(&mut app);
+    let mut settings = app.animation.project.onion_skin;
+    settings.opacity = 60;
+    CommandHandler::execute(&mut app, AppCommand::SetOnionSkin(settings));
+    app.update_onion_skin();
+    assert!(!marked(&app), "设置变化后重新合成");
+}
@@ -3,6 +3,10 @@ use pxa_engine_win32::app::commands::AppCommand;
 use pxa_engine_win32::app::command_handler::CommandHandler;
 use pxa_engine_win32::core::color::Color;
 use pxa_engine_win32::format::hex_palette::{load_from_hex, save_to_hex};
+use pxa_engine_win32::format::palette_io::{load_palette, save_palette};
+use pxa_engine_win32::format::payload::{serialize_palette, deserialize_palette};
+use pxa_engine_win32::core::palette::{Palette, PaletteSortKey};
+use pxa_engine_win32::render::quantize::{ColorMetric, Dither, PaletteGenMethod, QuantizeSettings};
 use std::env;
 use std::fs;
 
@@ -82,4 +86,154 @@ INVALID_STRING_FORMAT
     // 清理临时文件
     let _ = fs::remove_file(path);
     let _ = fs::remove_file(export_path);
-}
\ No newline at end of file
+}
+// ---------------------------------------------------------
+// 7. 按文件头识别格式 (扩展名不可靠时) & 8. 图片色条 & 9. 工程中保存颜色名称
+// ---------------------------------------------------------
+#[test]
+fn test_palette_formats_detected_from_files() {
+    let dir = env::temp_dir();
+
+    // 扩展名为 .txt 的 GPL 文件仍按 GPL 读取，保留名称
+    let gpl_path = dir.join("test_detect_palette.txt");
+    fs::write(&gpl_path, "GIMP Palette\nName: Tiny\n#\n255   0   0\tBlood Red\n  0   0 255\tSky\n").unwrap();
+    let gpl = load_palette(&gpl_path).expect("GPL 加载失败");
+    assert_eq!(gpl.name, "Tiny");
+    assert_eq!(gpl.colors, vec![Color::new(255, 0, 0, 255), Color::new(0, 0, 255, 255)]);
+    assert_eq!(gpl.color_name(0), "Blood Red");
+
+    // 8 倍放大的 Lospec 色条：按从左到右的顺序去重
+    let png_path = dir.join("test_detect_palette_strip.png");
+    let strip: Vec<u8> = [[0u8, 0, 0, 255], [255, 255, 255, 255], [255, 0, 77, 255]].iter()
+        .flat_map(|c| std::iter::repeat_n(*c, 8)).flatten().collect();
+    let strip: Vec<u8> = (0..8).flat_map(|_| strip.clone()).collect();
+    image::save_buffer(&png_path, &strip, 24, 8, image::ColorType::Rgba8).unwrap();
+    let from_png = load_palette(&png_path).expect("图片加载失败");
+    assert_eq!(from_png.name, "test_detect_palette_strip", "名称回退为文件名");
+    assert_eq!(from_png.colors, vec![Color::new(0, 0, 0, 255), Color::new(255, 255, 255, 255), Color::new(255, 0, 77, 255)]);
+
+    // 导出按扩展名选择格式
+    let ase_path = dir.join("test_detect_palette.ase");
+    save_palette(&ase_path, &gpl).unwrap();
+    assert!(fs::read(&ase_path).unwrap().starts_with(b"ASEF"));
+    assert_eq!(load_palette(&ase_path).unwrap().color_name(1), "Sky");
+
+    // 颜色名称随工程的 PALT 块保存；旧版无名称的数据仍可读取
+    let restored = deserialize_palette(&serialize_palette(&gpl)).unwrap();
+    assert_eq!(restored.names, gpl.names);
+    let old = deserialize_palette(&serialize_palette(&Palette::default_pico8())).unwrap();
+    assert!(old.names.is_empty());
+
+    for path in [gpl_path, png_path, ase_path] { let _ = fs::remove_file(path); }
+}
+
+// ---------------------------------------------------------
+// 10. 调色板整理可撤销 & 11. 从文档取色 & 12. 色带随 PALT 块保存
+// ---------------------------------------------------------
+#[test]
+fn test_palette_management_undo_and_save() {
+    let mut app = setup_palette_test();
+    let original = app.engine.store().palette.clone();
+
+    CommandHandler::execute(&mut app, AppCommand::AddPaletteRamp { name: "Sky".into(), colors: vec![Color::new(20, 40, 90, 255), Color::new(90, 160, 230, 255)] });
+    CommandHandler::execute(&mut app, AppCommand::RenamePaletteColor(0, "Ink".into()));
+    CommandHandler::execute(&mut app, AppCommand::SortPalette(PaletteSortKey::Luminance));
+    let palette = app.engine.store().palette.clone();
+    assert_eq!(palette.ramps.len(), 1);
+    assert_eq!(palette.colors.len(), original.colors.len() + 2);
+    assert_eq!(palette.color_name(0), "Ink", "黑色亮度最低，排序后仍在首位");
+
+    // 色带与名称随工程保存
+    let restored = deserialize_palette(&serialize_palette(&palette)).unwrap();
+    assert_eq!((restored.colors, restored.names, restored.ramps, restored.ramp_ids), (palette.colors.clone(), palette.names.clone(), palette.ramps.clone(), palette.ramp_ids.clone()));
+
+    for _ in 0..3 { app.engine.undo().unwrap(); }
+    assert_eq!(app.engine.store().palette, original, "三步操作依次撤销");
+    app.engine.redo().unwrap();
+    assert_eq!(app.engine.store().palette.ramps.len(), 1, "可以重做");
+
+    // 删除颜色也可撤销
+    CommandHandler::execute(&mut app, AppCommand::RemovePaletteColor(0));
+    app.engine.undo().unwrap();
+    assert_eq!(app.engine.store().palette.colors[0], original.colors[0]);
+
+    // 从文档取色：按使用次数排序，次数写在名称中
+    app.engine.set_primary_color(Color::new(200, 10, 10, 255));
+    for x in 0..3 { app.on_mouse_down(x, 0).unwrap(); app.on_mouse_up().unwrap(); }
+    app.engine.set_primary_color(Color::new(10, 200, 10, 255));
+    app.on_mouse_down(5, 5).unwrap(); app.on_mouse_up().unwrap();
+    CommandHandler::execute(&mut app, AppCommand::CreatePaletteFromUsedColors);
+    let used = &app.engine.store().palette;
+    assert_eq!(used.colors, vec![Color::new(200, 10, 10, 255), Color::new(10, 200, 10, 255)]);
+    assert_eq!(used.names, vec!["3 px", "1 px"]);
+    app.engine.undo().unwrap();
+    assert_eq!(app.engine.store().palette.colors[0], original.colors[0], "恢复为原调色板");
+}
+
+fn active_pixel(app: &AppState, x: u32, y: u32) -> Color {
+    let store = app.engine.store();
+    store.get_pixel(store.active_layer_id.as_ref().unwrap(), x, y).unwrap_or(Color::transparent())
+}
+
+// ---------------------------------------------------------
+// 13. 图层映射到调色板 (只改选区内)，一步撤销
+// ---------------------------------------------------------
+#[test]
+fn test_quantize_layer_to_palette() {
+    let mut app = setup_palette_test();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    CommandHandler::execute(&mut app, AppCommand::SetPalette(Palette::from_colors("bw".into(), vec![Color::new(0, 0, 0, 255), Color::new(255, 255, 255, 255)])));
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        for x in 0..4 {
+            store.force_set_pixel(&layer_id, x, 0, Color::new(230, 220, 210, 255)).unwrap();
+            store.force_set_pixel(&layer_id, x, 1, Color::new(40, 30, 20, 60)).unwrap();
+        }
+        store.selection.set_rect(0, 0, 2, 2);
+        store.selection.is_active = true;
+    }
+
+    let settings = QuantizeSettings { metric: ColorMetric::OkLab, dither: Dither::None, alpha_threshold: 128 };
+    CommandHandler::execute(&mut app, AppCommand::QuantizeLayer { layer_id: layer_id.clone(), settings });
+    assert_eq!(active_pixel(&app, 0, 0), Color::new(255, 255, 255, 255), "选区内映射为最接近的颜色");
+    assert_eq!(active_pixel(&app, 1, 1).a, 0, "低于透明阈值的像素被清除");
+    assert_eq!(active_pixel(&app, 3, 0), Color::new(230, 220, 210, 255), "选区外不变");
+
+    app.engine.undo().unwrap();
+    assert_eq!(active_pixel(&app, 0, 0), Color::new(230, 220, 210, 255), "可撤销");
+    assert_eq!(active_pixel(&app, 1, 1), Color::new(40, 30, 20, 60));
+}
+
+// ---------------------------------------------------------
+// 14. 从图层生成调色板并映射：调色板与像素一起撤销
+// ---------------------------------------------------------
+#[test]
+fn test_generate_palette_from_layer() {
+    let mut app = setup_palette_test();
+    let original = app.engine.store().palette.clone();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        for x in 0..8u32 {
+            store.force_set_pixel(&layer_id, x, 0, Color::new(200 + x as u8, 20, 20, 255)).unwrap();
+            store.force_set_pixel(&layer_id, x, 1, Color::new(20, 20, 200 + x as u8, 255)).unwrap();
+        }
+    }
+
+    CommandHandler::execute(&mut app, AppCommand::GeneratePaletteFromLayer {
+        layer_id: layer_id.clone(), method: PaletteGenMethod::KMeans, count: 2, remap: Some(QuantizeSettings::default()),
+    });
+    let palette = app.engine.store().palette.clone();
+    assert_eq!(palette.colors.len(), 2);
+    assert!(palette.colors.contains(&active_pixel(&app, 0, 0)) && palette.colors.contains(&active_pixel(&app, 7, 1)), "像素被映射到新调色板");
+    assert_eq!(active_pixel(&app, 0, 0), active_pixel(&app, 7, 0), "同一簇的颜色合并");
+
+    app.engine.undo().unwrap();
+    assert_eq!(app.engine.store().palette, original, "调色板恢复");
+    assert_eq!(active_pixel(&app, 7, 0), Color::new(207, 20, 20, 255), "像素恢复");
+
+    // 仅生成调色板时不改像素
+    CommandHandler::execute(&mut app, AppCommand::GeneratePaletteFromLayer { layer_id, method: PaletteGenMethod::MedianCut, count: 4, remap: None });
+    assert_eq!(app.engine.store().palette.colors.len(), 4);
+    assert_eq!(active_pixel(&app, 7, 0), Color::new(207, 20, 20, 255));
+}
@@ -196,4 +196,35 @@ fn test_brush_size_shortcut_simulation() {
         CommandHandler::execute(&mut app, cmd_inc);
     }
     assert_eq!(app.engine.store().brush_size, 20, "笔刷尺寸上限应限制为 20");
-}
\ No newline at end of file
+}
+// ---------------------------------------------------------
+// 8. 右键用副色绘制 (橡皮擦右键涂副色) & 9. X 交换主副色 & 10. 最近使用的颜色
+// ---------------------------------------------------------
+#[test]
+fn test_secondary_color_drawing_and_history() {
+    let mut app = setup_app();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let (red, blue) = (Color::new(255, 0, 0, 255), Color::new(0, 0, 255, 255));
+    app.engine.set_primary_color(red);
+    app.engine.set_secondary_color(blue);
+
+    app.set_tool(ToolType::Pencil);
+    let _ = app.on_mouse_down(1, 1); let _ = app.on_mouse_up();
+    let _ = app.on_secondary_mouse_down(2, 1); let _ = app.on_mouse_up();
+    let _ = app.on_mouse_down(3, 1); let _ = app.on_mouse_up();
+    let pixel = |app: &AppState, x| app.engine.store().get_pixel(&layer_id, x, 1).unwrap();
+    assert_eq!(pixel(&app, 1), red);
+    assert_eq!(pixel(&app, 2), blue, "右键使用副色");
+    assert_eq!(pixel(&app, 3), red, "松开后恢复主色");
+
+    app.set_tool(ToolType::Eraser);
+    let _ = app.on_secondary_mouse_down(1, 1); let _ = app.on_mouse_up();
+    assert_eq!(pixel(&app, 1), blue, "橡皮擦右键涂副色");
+    let _ = app.on_mouse_down(3, 1); let _ = app.on_mouse_up();
+    assert_eq!(pixel(&app, 3).a, 0, "橡皮擦左键仍然擦除");
+    assert_eq!(app.engine.store().recent_colors, vec![blue, red], "最近使用的颜色，最新的在前");
+
+    let cmd = app.shortcuts.handle_text_input("x", AppMode::PixelEdit).unwrap();
+    CommandHandler::execute(&mut app, cmd);
+    assert_eq!((app.engine.store().primary_color, app.engine.store().secondary_color), (blue, red), "按 X 交换主副色");
+}
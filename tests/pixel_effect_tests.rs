@@ -0,0 +1,85 @@
+use pxa_engine_win32::app::state::AppState;
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::render::effects::{Connectivity, PixelEffect};
+
+const GRAY: Color = Color { r: 100, g: 100, b: 100, a: 255 };
+const INK: Color = Color { r: 20, g: 10, b: 30, a: 255 };
+
+// 在活动图层 (4,4) 起填充 3x3 灰色方块
+fn setup_effect_test() -> (AppState, String) {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let (store, _, _) = app.engine.parts_mut();
+    for y in 4..7 { for x in 4..7 { store.force_set_pixel(&layer_id, x, y, GRAY).unwrap(); } }
+    (app, layer_id)
+}
+
+fn layer_pixel(app: &AppState, layer_id: &str, x: u32, y: u32) -> Color {
+    app.engine.store().get_pixel(layer_id, x, y).unwrap_or(Color::transparent())
+}
+
+// ---------------------------------------------------------
+// 1. 外描边作为一步撤销，撤销后恢复
+// ---------------------------------------------------------
+#[test]
+fn test_outline_single_undo() {
+    let (mut app, layer_id) = setup_effect_test();
+    let undo_depth = app.engine.history().undo_stack.len();
+
+    let effect = PixelEffect::Outline { color: INK, inside: false, connectivity: Connectivity::Four };
+    CommandHandler::execute(&mut app, AppCommand::ApplyEffect { layer_id: layer_id.clone(), effect });
+    assert_eq!(layer_pixel(&app, &layer_id, 3, 5), INK, "左侧描边");
+    assert_eq!(layer_pixel(&app, &layer_id, 5, 7), INK, "下方描边");
+    assert_eq!(layer_pixel(&app, &layer_id, 3, 3), Color::transparent(), "4 邻域不描对角");
+    assert_eq!(layer_pixel(&app, &layer_id, 5, 5), GRAY, "形状本身不变");
+    assert_eq!(app.engine.history().undo_stack.len(), undo_depth + 1, "只产生一步撤销");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(layer_pixel(&app, &layer_id, 3, 5), Color::transparent());
+    CommandHandler::execute(&mut app, AppCommand::Redo);
+    assert_eq!(layer_pixel(&app, &layer_id, 7, 5), INK);
+}
+
+// ---------------------------------------------------------
+// 2. 选区限制改动范围，但形状仍按整个图层判断
+// ---------------------------------------------------------
+#[test]
+fn test_effect_limited_to_selection() {
+    let (mut app, layer_id) = setup_effect_test();
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        store.selection.set_rect(0, 0, 5, 16);
+        store.selection.is_active = true;
+    }
+
+    let effect = PixelEffect::DropShadow { color: INK, dx: 1, dy: 1 };
+    CommandHandler::execute(&mut app, AppCommand::ApplyEffect { layer_id: layer_id.clone(), effect });
+    assert_eq!(layer_pixel(&app, &layer_id, 5, 7), Color::transparent(), "选区外不变");
+    assert_eq!(layer_pixel(&app, &layer_id, 7, 7), Color::transparent(), "选区外不变");
+
+    let effect = PixelEffect::Outline { color: INK, inside: true, connectivity: Connectivity::Four };
+    CommandHandler::execute(&mut app, AppCommand::ApplyEffect { layer_id: layer_id.clone(), effect });
+    assert_eq!(layer_pixel(&app, &layer_id, 4, 5), INK, "选区内的边缘");
+    assert_eq!(layer_pixel(&app, &layer_id, 6, 5), GRAY, "选区外的边缘不变");
+}
+
+// ---------------------------------------------------------
+// 3. 没有变化时不产生历史记录；锁定图层报错
+// ---------------------------------------------------------
+#[test]
+fn test_noop_and_locked_layer() {
+    let (mut app, layer_id) = setup_effect_test();
+    let undo_depth = app.engine.history().undo_stack.len();
+
+    let effect = PixelEffect::RemoveOrphans { connectivity: Connectivity::Eight };
+    CommandHandler::execute(&mut app, AppCommand::ApplyEffect { layer_id: layer_id.clone(), effect });
+    assert_eq!(app.engine.history().undo_stack.len(), undo_depth, "没有孤立像素");
+
+    app.engine.parts_mut().0.get_layer_mut(&layer_id).unwrap().locked = true;
+    let effect = PixelEffect::FillHoles { color: INK, connectivity: Connectivity::Four };
+    CommandHandler::execute(&mut app, AppCommand::ApplyEffect { layer_id: layer_id.clone(), effect });
+    assert!(app.ui.error_message.is_some());
+    assert_eq!(app.engine.history().undo_stack.len(), undo_depth);
+}
@@ -0,0 +1,106 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::bone::BoneData;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue};
+
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    let mut bone = BoneData::new("arm".into(), "arm".into());
+    bone.parent_id = Some("root".into());
+    bone.length = 10.0;
+    app.animation.project.skeleton.add_bone(bone);
+    app.animation.project.skeleton.update();
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    app
+}
+
+fn set_shear(app: &mut AppState, x: f32, y: f32) {
+    let bone = app.animation.project.skeleton.bones.iter_mut().find(|b| b.data.id == "arm").unwrap();
+    bone.local_transform.shear_x = x;
+    bone.local_transform.shear_y = y;
+}
+
+fn shear_of(app: &AppState) -> (f32, f32) {
+    let t = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == "arm").unwrap().local_transform;
+    (t.shear_x, t.shear_y)
+}
+
+fn shear_keys(app: &AppState) -> Vec<KeyframeValue> {
+    let id = app.animation.project.active_animation_id.clone().unwrap();
+    app.animation.project.animations[&id].timelines.iter()
+        .find(|t| t.target_id == "arm" && t.property == TimelineProperty::Shear)
+        .map(|t| t.keyframes.iter().map(|k| k.value.clone()).collect())
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------
+// 1. 自动打帧写入切变关键帧，播放时插值并影响世界矩阵
+// ---------------------------------------------------------
+#[test]
+fn test_shear_auto_key_and_playback() {
+    let mut app = setup_env();
+    app.animation.current_time = 0.0;
+    app.animation.auto_key_bone("arm", TimelineProperty::Shear);
+    app.animation.current_time = 1.0;
+    set_shear(&mut app, 20.0, -10.0);
+    app.animation.auto_key_bone("arm", TimelineProperty::Shear);
+    assert_eq!(shear_keys(&app), vec![KeyframeValue::Shear(0.0, 0.0), KeyframeValue::Shear(20.0, -10.0)], "应生成两个切变关键帧");
+
+    CommandHandler::execute(&mut app, AppCommand::SetTime(0.5));
+    let (sx, sy) = shear_of(&app);
+    assert!((sx - 10.0).abs() < 1e-3 && (sy + 5.0).abs() < 1e-3, "0.5 秒时切变应为中间值: {:?}", (sx, sy));
+
+    let m = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == "arm").unwrap().world_matrix;
+    let dot = m[0] * m[2] + m[1] * m[3];
+    assert!(dot.abs() > 1e-3, "切变后世界矩阵的两轴不再正交");
+}
+
+// ---------------------------------------------------------
+// 2. 手动打帧：只有使用切变的骨骼才会写入切变轨道
+// ---------------------------------------------------------
+#[test]
+fn test_manual_key_includes_shear_only_when_used() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe("arm".into()));
+    assert!(shear_keys(&app).is_empty(), "未切变的骨骼不应生成切变关键帧");
+
+    set_shear(&mut app, 5.0, 0.0);
+    app.animation.current_time = 0.5;
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe("arm".into()));
+    assert_eq!(shear_keys(&app), vec![KeyframeValue::Shear(5.0, 0.0)], "切变骨骼应写入切变关键帧");
+}
+
+// ---------------------------------------------------------
+// 3. 摄影表筛选可切换切变轨道
+// ---------------------------------------------------------
+#[test]
+fn test_shear_filter_toggle() {
+    let mut app = setup_env();
+    assert!(!app.ui.timeline_filter.contains(&TimelineProperty::Shear), "默认不展开切变轨道");
+    CommandHandler::execute(&mut app, AppCommand::ToggleTimelineFilter(TimelineProperty::Shear));
+    assert!(app.ui.timeline_filter.contains(&TimelineProperty::Shear));
+    CommandHandler::execute(&mut app, AppCommand::ToggleTimelineFilter(TimelineProperty::Shear));
+    assert!(!app.ui.timeline_filter.contains(&TimelineProperty::Shear));
+}
+
+// ---------------------------------------------------------
+// 4. 切变轨道随项目保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_shear_timeline_persists() {
+    let mut app = setup_env();
+    set_shear(&mut app, 12.5, 3.0);
+    app.animation.auto_key_bone("arm", TimelineProperty::Shear);
+
+    let path = std::env::temp_dir().join(format!("pxa_shear_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let id = app.animation.project.active_animation_id.clone().unwrap();
+    let tl = anim.animations[&id].timelines.iter().find(|t| t.property == TimelineProperty::Shear).expect("切变轨道应被读回");
+    assert_eq!(tl.keyframes[0].value, KeyframeValue::Shear(12.5, 3.0));
+}
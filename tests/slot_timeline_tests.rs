@@ -0,0 +1,146 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::animation::timeline::{TimelineProperty, KeyframeValue, DRAW_ORDER_TARGET};
+use pxa_engine_win32::core::color::Color;
+
+// 两个图层在 (10, 10) 处分别画红色与蓝色，蓝色图层在上
+fn setup_env() -> (AppState, String, String) {
+    let mut app = AppState::new();
+    let red = app.engine.store().active_layer_id.clone().unwrap();
+    app.engine.set_primary_color(Color::new(255, 0, 0, 255));
+    app.on_mouse_down(10, 10).unwrap(); app.on_mouse_up().unwrap();
+
+    app.add_new_layer();
+    let blue = app.engine.store().active_layer_id.clone().unwrap();
+    app.engine.set_primary_color(Color::new(0, 0, 255, 255));
+    app.on_mouse_down(10, 10).unwrap(); app.on_mouse_up().unwrap();
+
+    app.mode = AppMode::Animation;
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    app.ui.auto_keyframe = true;
+    (app, red, blue)
+}
+
+fn pixel(app: &AppState) -> [u8; 4] {
+    let store = app.engine.store();
+    let idx = ((10 * store.canvas_width + 10) * 4) as usize;
+    store.composite_cache[idx..idx + 4].try_into().unwrap()
+}
+
+// 在 1 秒处补一个关键帧，使动画时长为 1 秒 (时间等于时长时会回绕到 0)
+fn key_end(app: &mut AppState, id: &str) {
+    app.animation.current_time = 1.0;
+    CommandHandler::execute(app, AppCommand::InsertManualKeyframe(id.into()));
+}
+
+fn set_time(app: &mut AppState, t: f32) {
+    CommandHandler::execute(app, AppCommand::SetTime(t));
+}
+
+// ---------------------------------------------------------
+// 1. 默认按图层顺序叠放
+// ---------------------------------------------------------
+#[test]
+fn test_default_stacking_follows_layers() {
+    let (mut app, red, blue) = setup_env();
+    set_time(&mut app, 0.0);
+    assert_eq!(app.engine.store().layer_draw_order, Some(vec![red, blue]), "未设置绘制顺序时应保持图层顺序");
+    assert_eq!(pixel(&app)[2], 255, "蓝色图层应在最上层");
+}
+
+// ---------------------------------------------------------
+// 2. 绘制顺序轨道：随时间改变图层叠放
+// ---------------------------------------------------------
+#[test]
+fn test_draw_order_timeline_reorders_layers() {
+    let (mut app, _, blue) = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(DRAW_ORDER_TARGET.into()));
+    app.animation.current_time = 0.5;
+    CommandHandler::execute(&mut app, AppCommand::MoveSlotDrawOrder { slot_id: blue.clone(), delta: -1 });
+    key_end(&mut app, DRAW_ORDER_TARGET);
+
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let tl = app.animation.project.animations[&anim_id].timelines.iter()
+        .find(|t| t.property == TimelineProperty::DrawOrder).expect("应生成绘制顺序轨道");
+    assert_eq!(tl.keyframes.len(), 3);
+
+    set_time(&mut app, 0.25);
+    assert_eq!(pixel(&app)[2], 255, "0.25 秒时仍为原顺序");
+    set_time(&mut app, 0.6);
+    assert_eq!(pixel(&app)[0], 255, "0.5 秒后红色图层应移到上层");
+    let order = app.engine.store().layer_draw_order.clone().unwrap();
+    assert_eq!(order.last(), Some(&app.animation.project.skeleton.draw_order_slots().last().unwrap().data.id), "GPU 与 CPU 的绘制顺序应一致");
+}
+
+// ---------------------------------------------------------
+// 3. 显示图层轨道：切换插槽显示的图层 (如眨眼)
+// ---------------------------------------------------------
+#[test]
+fn test_attachment_timeline_swaps_layer() {
+    let (mut app, red, blue) = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::InsertManualKeyframe(blue.clone()));
+    app.animation.current_time = 0.5;
+    CommandHandler::execute(&mut app, AppCommand::SetSlotAttachment { slot_id: blue.clone(), attachment: None });
+    key_end(&mut app, &blue);
+
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let tl = app.animation.project.animations[&anim_id].timelines.iter()
+        .find(|t| t.target_id == blue && t.property == TimelineProperty::Attachment).unwrap();
+    assert_eq!(tl.keyframes[1].value, KeyframeValue::Attachment(None));
+
+    set_time(&mut app, 0.4);
+    assert_eq!(pixel(&app)[2], 255);
+    set_time(&mut app, 0.6);
+    assert_eq!(pixel(&app)[0], 255, "蓝色图层被隐藏后应露出红色");
+    assert_eq!(app.engine.store().layer_draw_order, Some(vec![red.clone()]));
+
+    // 切换到另一图层：蓝色插槽改为显示红色图层，红色图层只绘制在该插槽的层级
+    CommandHandler::execute(&mut app, AppCommand::SetSlotAttachment { slot_id: blue.clone(), attachment: Some(red.clone()) });
+    assert_eq!(app.engine.store().layer_draw_order, Some(vec![red.clone(), red]));
+}
+
+// ---------------------------------------------------------
+// 4. 未开启自动关键帧时修改装配姿态，可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_setup_attachment_undo() {
+    let (mut app, _, blue) = setup_env();
+    app.ui.auto_keyframe = false;
+    CommandHandler::execute(&mut app, AppCommand::SetSlotAttachment { slot_id: blue.clone(), attachment: None });
+    let slot = app.animation.project.skeleton.slots.iter().find(|s| s.data.id == blue).unwrap();
+    assert_eq!(slot.data.attachment, None, "应修改装配姿态的显示图层");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let slot = app.animation.project.skeleton.slots.iter().find(|s| s.data.id == blue).unwrap();
+    assert_eq!(slot.data.attachment.as_deref(), Some(blue.as_str()));
+}
+
+// ---------------------------------------------------------
+// 5. 自动关键帧记录的显示图层与绘制顺序可撤销；未开启时拒绝调整绘制顺序
+// ---------------------------------------------------------
+#[test]
+fn test_keyed_slot_changes_undo() {
+    let (mut app, _, blue) = setup_env();
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    let undo_depth = app.animation.history.undo_stack.len();
+    let timelines = app.animation.project.animations[&anim_id].timelines.len();
+
+    CommandHandler::execute(&mut app, AppCommand::SetSlotAttachment { slot_id: blue.clone(), attachment: None });
+    CommandHandler::execute(&mut app, AppCommand::MoveSlotDrawOrder { slot_id: blue.clone(), delta: -1 });
+    assert_eq!(app.animation.project.animations[&anim_id].timelines.len(), timelines + 2);
+    assert_eq!(app.animation.history.undo_stack.len(), undo_depth + 2, "每次记录关键帧都是一步撤销");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(app.animation.project.animations[&anim_id].timelines.len(), timelines);
+    let skel = &app.animation.project.skeleton;
+    let slot = skel.slots.iter().find(|s| s.data.id == blue).unwrap();
+    assert_eq!(slot.current_attachment.as_deref(), Some(blue.as_str()), "撤销后恢复原显示与顺序");
+    assert_eq!(skel.draw_order, None);
+
+    app.ui.auto_keyframe = false;
+    CommandHandler::execute(&mut app, AppCommand::MoveSlotDrawOrder { slot_id: blue, delta: -1 });
+    assert!(app.ui.error_message.is_some());
+    assert_eq!(app.animation.project.skeleton.draw_order, None, "不应写入无法保存的运行时顺序");
+}
@@ -0,0 +1,126 @@
+use pxa_engine_win32::app::state::{AppState, ToolType};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::core::layer::Layer;
+use pxa_engine_win32::core::store::PixelStore;
+use pxa_engine_win32::render::compositor::{Compositor, Viewport};
+
+fn setup_tiling_app() -> AppState {
+    let mut app = AppState::new();
+    CommandHandler::execute(&mut app, AppCommand::ToggleTilingMode);
+    assert!(app.engine.store().tiling_mode);
+    app
+}
+
+// ---------------------------------------------------------
+// 1. 铅笔越过画布边缘时从对侧继续绘制
+// ---------------------------------------------------------
+#[test]
+fn test_pencil_wraps_across_edges() {
+    let mut app = setup_tiling_app();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let w = app.engine.store().canvas_width;
+    let h = app.engine.store().canvas_height;
+
+    app.engine.set_primary_color(Color::new(255, 0, 0, 255));
+    app.set_tool(ToolType::Pencil);
+    // 画布外 (-2, 5) 经 gui 传入时为 i32 as u32
+    let _ = app.on_mouse_down((-2i32) as u32, 5);
+    let _ = app.on_mouse_move(1, 5);
+    let _ = app.on_mouse_up();
+
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, w - 2, 5).unwrap().r, 255, "左侧越界部分应绘制在右边缘");
+    assert_eq!(store.get_pixel(&layer_id, w - 1, 5).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 0, 5).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 1, 5).unwrap().r, 255);
+
+    let _ = app.on_mouse_down(3, h + 1);
+    let _ = app.on_mouse_up();
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 3, 1).unwrap().r, 255, "下方越界应绘制在顶部");
+
+    app.undo();
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 3, 1).unwrap().a, 0, "撤销应还原环绕绘制的像素");
+}
+
+// ---------------------------------------------------------
+// 2. 油漆桶在平铺模式下跨越边缘连通
+// ---------------------------------------------------------
+#[test]
+fn test_bucket_fill_wraps_across_edges() {
+    let mut app = setup_tiling_app();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let w = app.engine.store().canvas_width;
+    let h = app.engine.store().canvas_height;
+
+    // 竖线 x=2 与 x=w-3 将画布分为中间区域和经边缘相连的两侧区域
+    app.engine.set_primary_color(Color::new(255, 255, 255, 255));
+    app.set_tool(ToolType::Pencil);
+    let _ = app.on_mouse_down(2, 0); let _ = app.on_mouse_move(2, h - 1); let _ = app.on_mouse_up();
+    let _ = app.on_mouse_down(w - 3, 0); let _ = app.on_mouse_move(w - 3, h - 1); let _ = app.on_mouse_up();
+
+    app.engine.set_primary_color(Color::new(0, 0, 255, 255));
+    app.set_tool(ToolType::Bucket);
+    let _ = app.on_mouse_down(0, 0);
+    let _ = app.on_mouse_up();
+
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, 1, 10).unwrap().b, 255);
+    assert_eq!(store.get_pixel(&layer_id, w - 1, 10).unwrap().b, 255, "右边缘区域应经环绕被填充");
+    assert_eq!(store.get_pixel(&layer_id, w - 2, h - 1).unwrap().b, 255);
+    assert_eq!(store.get_pixel(&layer_id, w / 2, h / 2).unwrap().a, 0, "被竖线隔开的中间区域不应被填充");
+
+    app.undo();
+    assert_eq!(app.engine.store().get_pixel(&layer_id, w - 1, 10).unwrap().a, 0);
+}
+
+// ---------------------------------------------------------
+// 3. 循环位移：移出边缘的像素从对侧出现，且可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_wrap_offset_command() {
+    let mut app = AppState::new();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let w = app.engine.store().canvas_width;
+    let h = app.engine.store().canvas_height;
+
+    app.engine.set_primary_color(Color::new(255, 0, 0, 255));
+    app.set_tool(ToolType::Pencil);
+    let _ = app.on_mouse_down(w - 1, h - 1);
+    let _ = app.on_mouse_up();
+
+    CommandHandler::execute(&mut app, AppCommand::WrapOffsetCanvas(2, 3));
+    let store = app.engine.store();
+    assert_eq!(store.canvas_width, w, "循环位移不应改变画布尺寸");
+    assert_eq!(store.get_pixel(&layer_id, 1, 2).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, w - 1, h - 1).unwrap().a, 0);
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, w - 1, h - 1).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 1, 2).unwrap().a, 0);
+}
+
+// ---------------------------------------------------------
+// 4. 平铺预览：画布四周以 3x3 重复显示
+// ---------------------------------------------------------
+#[test]
+fn test_compositor_tiling_preview() {
+    let mut store = PixelStore::new(2, 2);
+    store.add_layer(Layer::new("L".into(), "L".into(), 2, 2));
+    store.mut_set_pixel("L", 0, 0, Color::new(255, 0, 0, 255)).unwrap();
+    Compositor::update_composite_cache(&mut store, None);
+    let view = Viewport { screen_width: 6, screen_height: 6, zoom: 1.0, pan_x: 0.0, pan_y: 0.0 };
+    let mut frame = vec![0u8; 6 * 6 * 4];
+
+    Compositor::render(&store, &mut frame, view);
+    assert_eq!(frame[0], 20, "关闭平铺时画布外为空背景");
+
+    store.tiling_mode = true;
+    Compositor::render(&store, &mut frame, view);
+    for (sx, sy) in [(0usize, 0usize), (2, 2), (4, 4), (4, 0)] {
+        let idx = (sy * 6 + sx) * 4;
+        assert_eq!(&frame[idx..idx + 4], &[255, 0, 0, 255], "({}, {}) 应显示重复的画布像素", sx, sy);
+    }
+}
@@ -0,0 +1,61 @@
+use pxa_engine_win32::app::state::{AppState, AppMode};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::app::io_service::IoService;
+use pxa_engine_win32::core::animation::bone::{BoneData, TransformMode};
+
+fn setup_env() -> AppState {
+    let mut app = AppState::new();
+    app.mode = AppMode::Animation;
+    let mut arm = BoneData::new("arm".into(), "arm".into());
+    arm.parent_id = Some("root".into());
+    arm.local_transform.x = 10.0;
+    arm.length = 10.0;
+    app.animation.project.skeleton.add_bone(arm);
+    app.animation.project.skeleton.bones[0].local_transform.rotation = 90.0;
+    app.animation.project.skeleton.update();
+    app
+}
+
+fn arm_axis(app: &AppState) -> (f32, f32) {
+    let m = app.animation.project.skeleton.bones.iter().find(|b| b.data.id == "arm").unwrap().world_matrix;
+    (m[0], m[1])
+}
+
+// ---------------------------------------------------------
+// 1. 修改继承方式立即影响世界矩阵，且可撤销
+// ---------------------------------------------------------
+#[test]
+fn test_set_transform_mode_with_undo() {
+    let mut app = setup_env();
+    let (ax, ay) = arm_axis(&app);
+    assert!(ax.abs() < 1e-4 && (ay - 1.0).abs() < 1e-4, "默认继承父骨骼旋转");
+
+    CommandHandler::execute(&mut app, AppCommand::SetBoneTransformMode { bone_id: "arm".into(), mode: TransformMode::OnlyTranslation });
+    let (ax, ay) = arm_axis(&app);
+    assert!((ax - 1.0).abs() < 1e-4 && ay.abs() < 1e-4, "仅平移时不随父骨骼旋转");
+    let bone = &app.animation.project.skeleton.bones[1].data;
+    assert!(!bone.inherit_rotation && !bone.inherit_scale, "标志位应同步更新");
+
+    CommandHandler::execute(&mut app, AppCommand::Undo);
+    assert_eq!(app.animation.project.skeleton.bones[1].data.transform_mode(), TransformMode::Normal, "撤销后恢复完整继承");
+    let (ax, ay) = arm_axis(&app);
+    assert!(ax.abs() < 1e-4 && (ay - 1.0).abs() < 1e-4, "撤销后世界矩阵恢复");
+}
+
+// ---------------------------------------------------------
+// 2. 继承方式随骨骼保存与读取
+// ---------------------------------------------------------
+#[test]
+fn test_transform_mode_persists() {
+    let mut app = setup_env();
+    CommandHandler::execute(&mut app, AppCommand::SetBoneTransformMode { bone_id: "arm".into(), mode: TransformMode::NoScaleOrReflection });
+
+    let path = std::env::temp_dir().join(format!("pxa_inherit_{}.pxad", std::process::id()));
+    IoService::save_project(path.clone(), app.engine.store(), app.engine.symmetry(), &app.view, &app.animation.project).unwrap();
+    let (_, _, _, _, _, anim) = IoService::load_project(path.clone()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let arm = anim.skeleton.bones.iter().find(|b| b.data.id == "arm").expect("骨骼应被读回");
+    assert_eq!(arm.data.transform_mode(), TransformMode::NoScaleOrReflection);
+}
@@ -0,0 +1,216 @@
+use pxa_engine_win32::app::state::{AppState, ToolType};
+use pxa_engine_win32::app::commands::AppCommand;
+use pxa_engine_win32::app::command_handler::CommandHandler;
+use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::render::resample::ResampleMode;
+use pxa_engine_win32::tools::transform::TransformTool;
+
+const RED: Color = Color { r: 255, g: 0, b: 0, a: 255 };
+const BLUE: Color = Color { r: 0, g: 0, b: 255, a: 255 };
+
+// 四角切成斜边的描边横条 (红色外框、蓝色内部)：斜边的阶梯是各放大算法产生差异的地方
+const SPRITE: [&str; 8] = [
+    "...RRRRRRRR...",
+    "..RBBBBBBBBR..",
+    ".RBBRBBBBBBBR.",
+    "RBBBBRBBBBBBBR",
+    "RBBBBBRBBBBBBR",
+    ".RBBBBBBRBBBR.",
+    "..RBBBBBBBBR..",
+    "...RRRRRRRR...",
+];
+
+// 100x100 画布上从 (44, 48) 开始画 SPRITE，选中后切到变换工具；shaped 时选区只含不透明像素
+fn setup_sprite(shaped: bool) -> AppState {
+    let mut app = AppState::new();
+    let (store, _, _) = app.engine.parts_mut();
+    store.canvas_width = 100;
+    store.canvas_height = 100;
+    let layer_id = store.active_layer_id.clone().unwrap();
+    if let Some(layer) = store.get_layer_mut(&layer_id) {
+        layer.width = 100;
+        layer.height = 100;
+    }
+
+    app.set_tool(ToolType::Pencil);
+    for (y, row) in SPRITE.iter().enumerate() {
+        for (x, ch) in row.chars().enumerate() {
+            let color = match ch { 'R' => RED, 'B' => BLUE, _ => continue };
+            app.engine.set_primary_color(color);
+            app.on_mouse_down(44 + x as u32, 48 + y as u32).unwrap();
+            app.on_mouse_up().unwrap();
+        }
+    }
+
+    app.set_tool(ToolType::RectSelect);
+    app.on_mouse_down(44, 48).unwrap();
+    app.on_mouse_move(43 + SPRITE[0].len() as u32, 47 + SPRITE.len() as u32).unwrap();
+    app.on_mouse_up().unwrap();
+    if shaped {
+        let (store, _, _) = app.engine.parts_mut();
+        let width = store.selection.width;
+        for (y, row) in SPRITE.iter().enumerate() {
+            for (x, _) in row.chars().enumerate().filter(|&(_, ch)| ch == '.') {
+                store.selection.mask[((48 + y as u32) * width + 44 + x as u32) as usize] = false;
+            }
+        }
+    }
+
+    app.set_tool(ToolType::Transform);
+    // 在选区外点击一次以提取像素，不拖动
+    app.on_mouse_down(0, 0).unwrap();
+    app.on_mouse_up().unwrap();
+    app
+}
+
+fn rotate_and_commit(mode: ResampleMode, degrees: f32, shaped: bool) -> AppState {
+    let mut app = setup_sprite(shaped);
+    {
+        let tool = app.engine.tool_manager_mut().tools.get_mut(&ToolType::Transform).unwrap();
+        let transform = tool.as_any_mut().downcast_mut::<TransformTool>().unwrap();
+        transform.rotation = degrees.to_radians();
+    }
+    // 切换算法会按当前参数刷新预览
+    CommandHandler::execute(&mut app, AppCommand::SetTransformResample(mode));
+    CommandHandler::execute(&mut app, AppCommand::CommitCurrentTool);
+    app
+}
+
+// 不透明像素的包围盒按字符画输出：. 透明、R 红、B 蓝、? 其它颜色
+fn ascii_art(app: &AppState) -> String {
+    let store = app.engine.store();
+    let layer = store.get_layer(store.active_layer_id.as_ref().unwrap()).unwrap();
+    let pixel = |x: u32, y: u32| layer.get_pixel(x, y).filter(|c| c.a > 0);
+    let opaque: Vec<(u32, u32)> = (0..100).flat_map(|y| (0..100).map(move |x| (x, y))).filter(|&(x, y)| pixel(x, y).is_some()).collect();
+    let min_x = opaque.iter().map(|p| p.0).min().unwrap();
+    let max_x = opaque.iter().map(|p| p.0).max().unwrap();
+    let min_y = opaque.iter().map(|p| p.1).min().unwrap();
+    let max_y = opaque.iter().map(|p| p.1).max().unwrap();
+
+    let mut art = String::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            art.push(match pixel(x, y) {
+                None => '.',
+                Some(c) if c == RED => 'R',
+                Some(c) if c == BLUE => 'B',
+                Some(_) => '?',
+            });
+        }
+        art.push('\n');
+    }
+    art
+}
+
+// 与 tests/golden 下的字符画比较；设置 UPDATE_GOLDEN=1 时重新生成
+fn check_golden(name: &str, art: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, art).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("缺少基准图 {}", path.display()));
+    assert_eq!(art, expected.replace("\r\n", "\n"), "{} 与基准图不一致，实际结果:\n{}", name, art);
+}
+
+fn mode_name(mode: ResampleMode) -> &'static str {
+    match mode {
+        ResampleMode::Nearest => "nearest",
+        ResampleMode::RotSprite => "rotsprite2x",
+        ResampleMode::RotSprite3x => "rotsprite3x",
+        ResampleMode::CleanEdge => "cleanedge",
+    }
+}
+
+// ---------------------------------------------------------
+// 1. 30° 与 45° 旋转的基准图，同一角度下各算法的结果互不相同
+// ---------------------------------------------------------
+#[test]
+fn test_rotation_golden_images() {
+    for degrees in [30.0, 45.0] {
+        let mut arts: Vec<(ResampleMode, String)> = Vec::new();
+        for mode in ResampleMode::ALL {
+            let art = ascii_art(&rotate_and_commit(mode, degrees, false));
+            assert!(!art.contains('?'), "{:?} 旋转 {}° 不应产生新颜色", mode, degrees);
+            check_golden(&format!("rotate_{}_{}", mode_name(mode), degrees as u32), &art);
+            if let Some((other, _)) = arts.iter().find(|(_, a)| *a == art) {
+                panic!("{:?} 与 {:?} 旋转 {}° 的结果相同，基准图无法区分算法", mode, other, degrees);
+            }
+            arts.push((mode, art));
+        }
+    }
+}
+
+// ---------------------------------------------------------
+// 2. 放大取样的外框保持连贯：每一行的红色外框都与上一行相接
+// ---------------------------------------------------------
+#[test]
+fn test_rotsprite_outline_has_no_gaps() {
+    let app = rotate_and_commit(ResampleMode::RotSprite, 30.0, false);
+    let store = app.engine.store();
+    let layer = store.get_layer(store.active_layer_id.as_ref().unwrap()).unwrap();
+    let is_red = |x: i32, y: i32| x >= 0 && y >= 0 && layer.get_pixel(x as u32, y as u32).is_some_and(|c| c == RED);
+    let red: Vec<(i32, i32)> = (0..100).flat_map(|y| (0..100).map(move |x| (x, y))).filter(|&(x, y)| is_red(x, y)).collect();
+    assert!(!red.is_empty());
+    for &(x, y) in &red {
+        let neighbours = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0) && is_red(x + dx, y + dy))
+            .count();
+        assert!(neighbours >= 1, "外框像素 ({}, {}) 孤立", x, y);
+    }
+}
+
+// ---------------------------------------------------------
+// 3. 变换进行中切换算法即刷新预览，取消后恢复原图
+// ---------------------------------------------------------
+#[test]
+fn test_switching_mode_updates_preview() {
+    let mut app = setup_sprite(false);
+    {
+        let tool = app.engine.tool_manager_mut().tools.get_mut(&ToolType::Transform).unwrap();
+        tool.as_any_mut().downcast_mut::<TransformTool>().unwrap().rotation = 30f32.to_radians();
+    }
+    CommandHandler::execute(&mut app, AppCommand::SetTransformResample(ResampleMode::Nearest));
+    let nearest = ascii_art(&app);
+    CommandHandler::execute(&mut app, AppCommand::SetTransformResample(ResampleMode::RotSprite));
+    assert_eq!(app.engine.tool_manager().transform_resample(), ResampleMode::RotSprite);
+    assert_ne!(ascii_art(&app), nearest, "切换算法后预览应改变");
+
+    CommandHandler::execute(&mut app, AppCommand::CancelCurrentTool);
+    let art = ascii_art(&app);
+    assert_eq!(art, SPRITE.map(|row| format!("{}\n", row)).concat(), "取消后恢复未旋转的横条");
+}
+
+// ---------------------------------------------------------
+// 4. 覆盖范围由所选算法决定，不被最近邻的形状裁剪 (选区贴合不透明像素)
+// ---------------------------------------------------------
+#[test]
+fn test_rotsprite_coverage_is_not_clipped_to_nearest() {
+    let opaque = |app: &AppState| -> Vec<(u32, u32)> {
+        let store = app.engine.store();
+        let layer = store.get_layer(store.active_layer_id.as_ref().unwrap()).unwrap();
+        (0..100).flat_map(|y| (0..100).map(move |x| (x, y))).filter(|&(x, y)| layer.get_pixel(x, y).is_some_and(|c| c.a > 0)).collect()
+    };
+    let nearest_45 = opaque(&rotate_and_commit(ResampleMode::Nearest, 45.0, true));
+    assert_ne!(opaque(&rotate_and_commit(ResampleMode::RotSprite, 45.0, true)), nearest_45, "45° 时 RotSprite 与最近邻的形状不同");
+
+    let nearest = opaque(&rotate_and_commit(ResampleMode::Nearest, 30.0, true));
+    let app = rotate_and_commit(ResampleMode::RotSprite, 30.0, true);
+    let rotsprite = opaque(&app);
+    assert!(rotsprite.iter().any(|p| !nearest.contains(p)), "RotSprite 补齐的斜边超出最近邻的形状");
+
+    let store = app.engine.store();
+    let width = store.selection.width;
+    assert!(rotsprite.iter().all(|&(x, y)| store.selection.mask[(y * width + x) as usize]), "选区覆盖所有取样得到的像素");
+}
+
+// ---------------------------------------------------------
+// 5. 选中的透明像素在放大取样时仍留在新选区内，与最近邻一致
+// ---------------------------------------------------------
+#[test]
+fn test_rotsprite_keeps_transparent_selection() {
+    let selected = |app: &AppState| app.engine.store().selection.mask.clone();
+    let nearest = selected(&rotate_and_commit(ResampleMode::Nearest, 30.0, false));
+    let rotsprite = selected(&rotate_and_commit(ResampleMode::RotSprite, 30.0, false));
+    assert!(nearest.iter().zip(&rotsprite).all(|(&n, &r)| !n || r), "最近邻选区内的像素 (包括透明角) 都在新选区内");
+}
@@ -2,6 +2,8 @@ use pxa_engine_win32::app::state::{AppState, ToolType};
 use pxa_engine_win32::app::commands::AppCommand;
 use pxa_engine_win32::app::command_handler::CommandHandler;
 use pxa_engine_win32::core::color::Color;
+use pxa_engine_win32::core::layer::LosslessTransform;
+use pxa_engine_win32::tools::transform::HandleMode;
 
 /// 初始化测试环境：100x100 画布，中间 20x20 红色方块，并建立等大的矩形选区
 fn setup_transform_test() -> AppState {
@@ -120,4 +122,216 @@ fn test_transform_selection_update_and_cancel() {
     // 验证选区也回到了原位
     assert!(store.selection.contains(50, 50), "取消变换后，选区应回到原位");
     assert!(!store.selection.contains(70, 70), "取消变换后，选区不应留在拖拽位置");
-}
\ No newline at end of file
+}
+fn transform_params(app: &AppState) -> pxa_engine_win32::tools::transform::TransformParams {
+    app.engine.tool_manager().transform_state().unwrap().0
+}
+
+// ---------------------------------------------------------
+// 8. 水平翻转 & 数值输入 (偏移、轴心、角度)
+// ---------------------------------------------------------
+#[test]
+fn test_transform_flip_and_numeric_input() {
+    let mut app = setup_transform_test();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    let blue = Color::new(0, 0, 255, 255);
+
+    // 左上角做一个蓝色标记，翻转后应出现在右上角
+    app.set_tool(ToolType::Pencil);
+    app.engine.set_primary_color(blue);
+    app.on_mouse_down(41, 41).unwrap(); app.on_mouse_up().unwrap();
+    app.set_tool(ToolType::Transform);
+
+    // 未开始拖拽时直接翻转：自动提取选区
+    CommandHandler::execute(&mut app, AppCommand::FlipTransform { horizontal: true });
+    CommandHandler::execute(&mut app, AppCommand::CommitCurrentTool);
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, 58, 41), Some(blue), "翻转后标记应在镜像位置");
+    assert_eq!(store.get_pixel(&layer_id, 41, 41).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 40, 40).unwrap().r, 255, "翻转不应产生一像素的偏移");
+    assert_eq!(store.get_pixel(&layer_id, 60, 50).unwrap().a, 0);
+
+    // 数值输入：先把轴心移到左上角 (图像不动)，再输入 90°
+    app.on_mouse_down(0, 0).unwrap(); app.on_mouse_up().unwrap();
+    let mut params = transform_params(&app);
+    params.pivot_x = 40.0;
+    params.pivot_y = 40.0;
+    CommandHandler::execute(&mut app, AppCommand::SetTransformParams(params));
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 58, 41), Some(blue), "移动轴心不应改变图像");
+
+    let mut params = transform_params(&app);
+    params.rotation = std::f32::consts::FRAC_PI_2;
+    CommandHandler::execute(&mut app, AppCommand::SetTransformParams(params));
+    CommandHandler::execute(&mut app, AppCommand::CommitCurrentTool);
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, 30, 50).unwrap().r, 255, "绕左上角顺时针旋转 90° 后方块位于轴心左侧");
+    assert_eq!(store.get_pixel(&layer_id, 50, 50).unwrap().a, 0);
+    assert_eq!(store.get_pixel(&layer_id, 38, 58), Some(blue));
+}
+
+// ---------------------------------------------------------
+// 9. 切变控制柄 & 自由变形 (透视)
+// ---------------------------------------------------------
+#[test]
+fn test_transform_skew_and_distort_handles() {
+    let mut app = setup_transform_test();
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+
+    CommandHandler::execute(&mut app, AppCommand::SetTransformHandleMode(HandleMode::Skew));
+    app.on_mouse_down(0, 0).unwrap(); app.on_mouse_up().unwrap();
+
+    // 上边中点 (50, 40) 向右拖 10 像素：顶边右移，底边左移
+    app.on_mouse_down(50, 40).unwrap();
+    app.on_mouse_move(60, 40).unwrap();
+    app.on_mouse_up().unwrap();
+    let params = transform_params(&app);
+    assert!((params.skew_x.to_degrees() + 45.0).abs() < 0.01, "轴心到顶边 10 像素，位移 10 像素即 45° 切变");
+    assert_eq!(params.skew_y, 0.0);
+
+    CommandHandler::execute(&mut app, AppCommand::CommitCurrentTool);
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, 65, 41).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 41, 41).unwrap().a, 0);
+    assert_eq!(store.get_pixel(&layer_id, 41, 58).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 55, 58).unwrap().a, 0);
+    app.undo();
+
+    // 自由变形：只把右上角拉到 (70, 30)
+    CommandHandler::execute(&mut app, AppCommand::SetTransformHandleMode(HandleMode::Distort));
+    app.on_mouse_down(0, 0).unwrap(); app.on_mouse_up().unwrap();
+    app.on_mouse_down(60, 40).unwrap();
+    app.on_mouse_move(70, 30).unwrap();
+    app.on_mouse_up().unwrap();
+    let corners = app.engine.tool_manager().get_transform_corners().unwrap();
+    assert_eq!(corners, [(40.0, 40.0), (70.0, 30.0), (60.0, 60.0), (40.0, 60.0)], "其余三角保持不动");
+
+    CommandHandler::execute(&mut app, AppCommand::CommitCurrentTool);
+    let store = app.engine.store();
+    assert_eq!(store.get_pixel(&layer_id, 64, 37).unwrap().r, 255, "右上角被拉出的区域");
+    assert_eq!(store.get_pixel(&layer_id, 45, 45).unwrap().r, 255);
+    assert_eq!(store.get_pixel(&layer_id, 62, 58).unwrap().a, 0);
+
+    app.undo();
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 64, 37).unwrap().a, 0, "撤销后恢复原图");
+}
+
+// ---------------------------------------------------------
+// 10. 整个画布 / 图层的无损旋转与翻转
+// ---------------------------------------------------------
+#[test]
+fn test_lossless_canvas_and_layer_transform() {
+    let mut app = AppState::new();
+    let red = Color::new(255, 0, 0, 255);
+    let layer_id = app.engine.store().active_layer_id.clone().unwrap();
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        store.canvas_width = 8;
+        store.canvas_height = 4;
+        store.selection = pxa_engine_win32::core::selection::SelectionData::new(8, 4);
+        let layer = store.get_layer_mut(&layer_id).unwrap();
+        layer.width = 8;
+        layer.height = 4;
+        layer.set_pixel(7, 0, red).unwrap();
+        layer.set_pixel(4, 1, red).unwrap();
+    }
+
+    CommandHandler::execute(&mut app, AppCommand::TransformCanvas(LosslessTransform::RotateCw));
+    let store = app.engine.store();
+    assert_eq!((store.canvas_width, store.canvas_height), (4, 8), "旋转 90° 后画布宽高互换");
+    assert_eq!(store.get_pixel(&layer_id, 3, 7), Some(red), "右上角转到右下角");
+    app.undo();
+    let store = app.engine.store();
+    assert_eq!((store.canvas_width, store.canvas_height), (8, 4));
+    assert_eq!(store.get_pixel(&layer_id, 7, 0), Some(red), "撤销后恢复");
+
+    CommandHandler::execute(&mut app, AppCommand::TransformLayer(layer_id.clone(), LosslessTransform::FlipVertical));
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 7, 3), Some(red));
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 4, 2), Some(red));
+    app.undo();
+
+    // 非正方形图层旋转 90°：尺寸不变，以中心对齐并裁掉超出部分
+    CommandHandler::execute(&mut app, AppCommand::TransformLayer(layer_id.clone(), LosslessTransform::RotateCw));
+    let store = app.engine.store();
+    let layer = store.get_layer(&layer_id).unwrap();
+    assert_eq!((layer.width, layer.height), (8, 4));
+    assert_eq!(layer.get_pixel(4, 2), Some(red), "中心附近的像素绕中心旋转");
+    assert_eq!(layer.chunks.values().flat_map(|c| c.data.chunks_exact(4)).filter(|p| p[3] > 0).count(), 1, "转出图层范围的像素被裁掉");
+
+    CommandHandler::execute(&mut app, AppCommand::ToggleLayerLock(layer_id.clone()));
+    CommandHandler::execute(&mut app, AppCommand::TransformLayer(layer_id.clone(), LosslessTransform::FlipHorizontal));
+    assert!(app.ui.error_message.is_some(), "锁定的图层不能旋转或翻转");
+    assert_eq!(app.engine.store().get_pixel(&layer_id, 4, 2), Some(red));
+}
+
+// ---------------------------------------------------------
+// 11. 画布旋转与翻转时骨架、关键帧随画布变换
+// ---------------------------------------------------------
+// 装配姿势下骨骼的起点与末端 (画布坐标)
+fn bone_tip(app: &AppState, id: &str) -> ((f32, f32), (f32, f32)) {
+    let skeleton = &app.animation.project.skeleton;
+    let idx = skeleton.bones.iter().position(|b| b.data.id == id).unwrap();
+    let m = skeleton.setup_world_matrices()[idx];
+    let len = skeleton.bones[idx].data.length;
+    ((m[4], m[5]), (m[0] * len + m[4], m[1] * len + m[5]))
+}
+
+fn assert_near(actual: (f32, f32), expected: (f32, f32), msg: &str) {
+    assert!((actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3, "{}: {:?} != {:?}", msg, actual, expected);
+}
+
+#[test]
+fn test_canvas_transform_moves_skeleton() {
+    use pxa_engine_win32::core::animation::bone::BoneData;
+    use pxa_engine_win32::core::animation::timeline::{CurveType, KeyframeValue, TimelineProperty};
+
+    let mut app = AppState::new();
+    {
+        let (store, _, _) = app.engine.parts_mut();
+        store.canvas_width = 8;
+        store.canvas_height = 4;
+        store.selection = pxa_engine_win32::core::selection::SelectionData::new(8, 4);
+        let layer_id = store.active_layer_id.clone().unwrap();
+        let layer = store.get_layer_mut(&layer_id).unwrap();
+        layer.width = 8;
+        layer.height = 4;
+    }
+    let mut arm = BoneData::new("arm".into(), "arm".into());
+    arm.parent_id = Some("root".into());
+    arm.local_transform.x = 2.0;
+    arm.local_transform.y = 1.0;
+    arm.length = 3.0;
+    app.animation.project.skeleton.add_bone(arm);
+    app.animation.project.skeleton.update();
+
+    CommandHandler::execute(&mut app, AppCommand::CreateAnimation("Action".into()));
+    let anim_id = app.animation.project.active_animation_id.clone().unwrap();
+    {
+        let anim = app.animation.project.animations.get_mut(&anim_id).unwrap();
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Translation).unwrap();
+        tl.add_keyframe(0.0, KeyframeValue::Translate(1.0, 0.0), CurveType::Linear);
+        let tl = anim.timelines.iter_mut().find(|t| t.target_id == "root" && t.property == TimelineProperty::Rotation).unwrap();
+        tl.add_keyframe(0.0, KeyframeValue::Rotate(30.0), CurveType::Linear);
+    }
+
+    CommandHandler::execute(&mut app, AppCommand::TransformCanvas(LosslessTransform::RotateCw));
+    let root = &app.animation.project.skeleton.bones[0].data.local_transform;
+    assert_near((root.x, root.y), (4.0, 0.0), "根骨骼位置随画布旋转");
+    assert!((root.rotation - 90.0).abs() < 1e-3, "根骨骼朝向随画布旋转");
+    let (origin, tip) = bone_tip(&app, "arm");
+    assert_near(origin, (3.0, 2.0), "子骨骼起点跟随");
+    assert_near(tip, (3.0, 5.0), "子骨骼末端跟随");
+    let anim = &app.animation.project.animations[&anim_id];
+    let key = |prop: TimelineProperty| anim.timelines.iter().find(|t| t.target_id == "root" && t.property == prop).unwrap().keyframes[0].value.clone();
+    assert_eq!(key(TimelineProperty::Translation), KeyframeValue::Translate(4.0, 1.0), "位移关键帧随画布旋转");
+    assert_eq!(key(TimelineProperty::Rotation), KeyframeValue::Rotate(120.0), "旋转关键帧随画布旋转");
+
+    app.engine.undo().unwrap();
+    app.animation.history.undo(&mut app.animation.project);
+    assert_near(bone_tip(&app, "arm").1, (5.0, 1.0), "撤销后骨骼复原");
+
+    // 镜像：子骨骼经继承一同镜像，长度方向反转
+    CommandHandler::execute(&mut app, AppCommand::TransformCanvas(LosslessTransform::FlipHorizontal));
+    let (origin, tip) = bone_tip(&app, "arm");
+    assert_near(origin, (6.0, 1.0), "水平翻转后子骨骼起点");
+    assert_near(tip, (3.0, 1.0), "水平翻转后子骨骼指向左侧");
+}